@@ -119,6 +119,7 @@ pub fn display_baseline_summary(result: &BaselineCreationResult) {
                 MigrationStep::Extension(_) => "Extension",
                 MigrationStep::Grant(_) => "Grant",
                 MigrationStep::Comment(_) => "Comment",
+                MigrationStep::Data(_) => "Data",
             };
             *object_counts.entry(step_type).or_insert(0) += 1;
         }
@@ -4,9 +4,10 @@
 //! Loading lives in `catalog::raw::aggregate`.
 
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
 /// Represents a PostgreSQL aggregate function
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Aggregate {
     pub schema: String,
     pub name: String,
@@ -3,12 +3,13 @@
 //! Loading lives in `catalog::raw::cast`.
 
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
 /// A user-defined PostgreSQL cast (`CREATE CAST`).
 ///
 /// Casts are not schema-scoped; their identity is the (source type, target type)
 /// pair. `source` and `target` are canonical `format_type` names.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Cast {
     pub source: String,
     pub target: String,
@@ -3,9 +3,10 @@
 //! Loading lives in `catalog::raw::collation`.
 
 use crate::catalog::{DependsOn, id::DbObjectId};
+use serde::{Deserialize, Serialize};
 
 /// The provider backing a collation (`pg_collation.collprovider`).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CollationProvider {
     /// 'c' — operating system libc locales
     Libc,
@@ -20,7 +21,7 @@ pub enum CollationProvider {
 ///
 /// Same-named collations can exist in different schemas, so the bare `collname`
 /// is not a usable identity.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CollationRef {
     pub schema: String,
     pub name: String,
@@ -43,7 +44,7 @@ impl CollationRef {
 /// machine and ICU build, so including it in equality would produce spurious
 /// diffs between dev, shadow, and target databases. `collencoding` is likewise
 /// excluded — user-created collations are always encoding-agnostic (-1).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Collation {
     pub schema: String,
     pub name: String,
@@ -4,10 +4,11 @@
 //! Loading lives in `catalog::raw::constraint`.
 
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
 /* ---------- Data structures ---------- */
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConstraintType {
     Unique {
         columns: Vec<String>,
@@ -34,7 +35,7 @@ pub enum ConstraintType {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Constraint {
     pub schema: String,
     pub table_name: String,
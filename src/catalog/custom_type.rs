@@ -6,10 +6,11 @@
 
 use super::collation::CollationRef;
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
 /* ---------- Data structures ---------- */
 
-#[derive(Hash, PartialEq, Debug, Clone)]
+#[derive(Hash, PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub enum TypeKind {
     Enum,
     Composite,
@@ -32,12 +33,12 @@ impl TypeKind {
 /// `pg_enum.enumsortorder` float that produced it is physical — two databases
 /// reach the same label order through different floats once a label has been
 /// added with `ADD VALUE BEFORE` — and dies in the converter.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnumValue {
     pub name: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompositeAttribute {
     pub name: String,
     /// The attribute's type as the server renders it, with modifiers and array
@@ -51,7 +52,7 @@ pub struct CompositeAttribute {
     pub comment: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomType {
     pub schema: String,
     pub name: String,
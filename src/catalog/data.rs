@@ -0,0 +1,234 @@
+//! Seed data: the rows pgmt manages in reference tables.
+//!
+//! Tables with a file in the data directory (see `seed_data`) have their rows
+//! loaded alongside the catalog, keyed by primary key, so `diff::data` can
+//! turn row differences into `INSERT` / `UPDATE` / `DELETE` steps. Values are
+//! kept in their text form, which is also how they are written back: an
+//! untyped literal is coerced to the column's type on the way in. The text
+//! form of dates, times, floats and the like depends on session settings, so
+//! rows are read under [`PINNED_SETTINGS`]: the shadow and the dev database
+//! then agree whatever their connections were configured with.
+
+use super::constraint::{Constraint, ConstraintType};
+use super::id::DbObjectId;
+use crate::render::quote_ident;
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, PgConnection, PgPool};
+use std::collections::BTreeSet;
+
+/// A table's `(schema, name)`
+pub type TableName = (String, String);
+
+/// A table whose rows are managed, and which of its columns are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeededTable {
+    pub schema: String,
+    pub table: String,
+    /// The managed columns; `None` for every column. Columns left out (say, a
+    /// `created_at` filled by a default) are never compared or written.
+    pub columns: Option<Vec<String>>,
+}
+
+/// The managed rows of one table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableData {
+    pub schema: String,
+    pub table: String,
+    /// Primary key columns, in key order
+    pub key_columns: Vec<String>,
+    /// Key columns first, then the other managed columns the table has
+    pub columns: Vec<String>,
+    /// One text value per column, in primary key order
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+impl TableData {
+    pub fn id(&self) -> DbObjectId {
+        DbObjectId::TableData {
+            schema: self.schema.clone(),
+            table: self.table.clone(),
+        }
+    }
+
+    /// The table the rows live in
+    pub fn table_id(&self) -> DbObjectId {
+        DbObjectId::Table {
+            schema: self.schema.clone(),
+            name: self.table.clone(),
+        }
+    }
+
+    /// The table and managed columns these rows were loaded for
+    pub fn seeded(&self) -> SeededTable {
+        SeededTable {
+            schema: self.schema.clone(),
+            table: self.table.clone(),
+            columns: Some(self.columns.clone()),
+        }
+    }
+
+    /// A row's primary key values, in `key_columns` order
+    pub fn key_of(&self, row: &[Option<String>]) -> Vec<Option<String>> {
+        row[..self.key_columns.len()].to_vec()
+    }
+}
+
+/// The settings rows are read under, for every setting that changes how a
+/// value prints as text.
+const PINNED_SETTINGS: &str = "SET LOCAL TimeZone = 'UTC';
+     SET LOCAL DateStyle = 'ISO, MDY';
+     SET LOCAL IntervalStyle = 'postgres';
+     SET LOCAL extra_float_digits = 1;
+     SET LOCAL bytea_output = 'hex';
+     SET LOCAL lc_monetary = 'C';
+     SET LOCAL search_path = ''";
+
+/// Load the managed rows of each of `seeded` that exists in the database.
+pub async fn load(pool: &PgPool, seeded: &[SeededTable]) -> Result<Vec<TableData>> {
+    let mut loaded = Vec::new();
+    if seeded.is_empty() {
+        return Ok(loaded);
+    }
+    // Read-only; the transaction only scopes the settings.
+    let mut tx = pool.begin().await?;
+    tx.execute(PINNED_SETTINGS).await?;
+    for table in seeded {
+        if let Some(data) = load_table(&mut tx, table).await? {
+            loaded.push(data);
+        }
+    }
+    tx.rollback().await?;
+    Ok(loaded)
+}
+
+async fn load_table(conn: &mut PgConnection, seeded: &SeededTable) -> Result<Option<TableData>> {
+    // (column, position in the primary key), in column order
+    let attributes: Vec<(String, Option<i32>)> = sqlx::query_as(
+        "SELECT a.attname::text, array_position(pk.indkey::int2[], a.attnum)
+         FROM pg_attribute a
+         JOIN pg_class c ON c.oid = a.attrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         LEFT JOIN pg_index pk ON pk.indrelid = c.oid AND pk.indisprimary
+         WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'p')
+           AND a.attnum > 0 AND NOT a.attisdropped AND a.attgenerated = ''
+         ORDER BY a.attnum",
+    )
+    .bind(&seeded.schema)
+    .bind(&seeded.table)
+    .fetch_all(&mut *conn)
+    .await?;
+    if attributes.is_empty() {
+        return Ok(None);
+    }
+
+    let mut keyed: Vec<(i32, String)> = attributes
+        .iter()
+        .filter_map(|(name, position)| position.map(|p| (p, name.clone())))
+        .collect();
+    if keyed.is_empty() {
+        bail!(
+            "Seed data table {}.{} has no primary key; seed rows are matched by it",
+            seeded.schema,
+            seeded.table
+        );
+    }
+    keyed.sort();
+    let key_columns: Vec<String> = keyed.into_iter().map(|(_, name)| name).collect();
+
+    let mut columns = key_columns.clone();
+    columns.extend(
+        attributes
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| !key_columns.contains(name))
+            .filter(|name| {
+                seeded
+                    .columns
+                    .as_ref()
+                    .is_none_or(|managed| managed.contains(name))
+            }),
+    );
+
+    let select = columns
+        .iter()
+        .map(|column| format!("{}::text", quote_ident(column)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let order = key_columns
+        .iter()
+        .map(|column| quote_ident(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rows: Vec<Vec<Option<String>>> = sqlx::query_scalar(sqlx::AssertSqlSafe(format!(
+        "SELECT ARRAY[{}] FROM {}.{} ORDER BY {}",
+        select,
+        quote_ident(&seeded.schema),
+        quote_ident(&seeded.table),
+        order
+    )))
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Some(TableData {
+        schema: seeded.schema.clone(),
+        table: seeded.table.clone(),
+        key_columns,
+        columns,
+        rows,
+    }))
+}
+
+/// The foreign keys among `tables`, as `(referenced, referencing)` pairs.
+/// Self-references are left out.
+pub fn references_between<'a>(
+    tables: &BTreeSet<TableName>,
+    constraints: impl IntoIterator<Item = &'a Constraint>,
+) -> BTreeSet<(TableName, TableName)> {
+    let mut references = BTreeSet::new();
+    for constraint in constraints {
+        if let ConstraintType::ForeignKey {
+            referenced_schema,
+            referenced_table,
+            ..
+        } = &constraint.constraint_type
+        {
+            let referenced = (referenced_schema.clone(), referenced_table.clone());
+            let referencing = (constraint.schema.clone(), constraint.table_name.clone());
+            if referenced != referencing
+                && tables.contains(&referenced)
+                && tables.contains(&referencing)
+            {
+                references.insert((referenced, referencing));
+            }
+        }
+    }
+    references
+}
+
+/// `tables` with every referenced table before the tables referencing it.
+/// Tables in a reference cycle follow in name order.
+pub fn order_by_references(
+    tables: &BTreeSet<TableName>,
+    references: &BTreeSet<(TableName, TableName)>,
+) -> Vec<TableName> {
+    let mut order = Vec::with_capacity(tables.len());
+    let mut remaining = tables.clone();
+    while let Some(first) = remaining.iter().next().cloned() {
+        let ready: Vec<TableName> = remaining
+            .iter()
+            .filter(|table| {
+                !references.iter().any(|(referenced, referencing)| {
+                    referencing == *table && remaining.contains(referenced)
+                })
+            })
+            .cloned()
+            .collect();
+        let next = if ready.is_empty() { vec![first] } else { ready };
+        for table in next {
+            remaining.remove(&table);
+            order.push(table);
+        }
+    }
+    order
+}
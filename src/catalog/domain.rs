@@ -6,16 +6,17 @@
 
 use super::collation::CollationRef;
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
 /// A CHECK constraint on a domain
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DomainCheckConstraint {
     pub name: String,
     pub expression: String,
 }
 
 /// Represents a PostgreSQL domain
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Domain {
     pub schema: String,
     pub name: String,
@@ -4,9 +4,10 @@
 //! Loading lives in `catalog::raw::extension`.
 
 use crate::catalog::{DependsOn, id::DbObjectId};
+use serde::{Deserialize, Serialize};
 
 /// Represents a PostgreSQL extension
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Extension {
     pub name: String,
     pub schema: String,
@@ -4,21 +4,22 @@
 //! Loading lives in `catalog::raw::function`.
 
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FunctionKind {
     Function,
     Procedure,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionParam {
     pub name: Option<String>,
     pub data_type: String,
     pub mode: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Function {
     pub schema: String,
     pub name: String,
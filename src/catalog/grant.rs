@@ -7,14 +7,15 @@
 //! index.
 use super::id::{DbObjectId, DependsOn};
 use super::target::AttrTarget;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GranteeType {
     Role(String),
     Public,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Grant {
     pub grantee: GranteeType,
     pub target: AttrTarget,
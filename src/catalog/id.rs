@@ -1,7 +1,8 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// A globally unique identifier for any database object in pgmt.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum DbObjectId {
     Schema {
         name: String,
@@ -98,6 +99,12 @@ pub enum DbObjectId {
         table: String,
         column: String,
     },
+    /// The seed rows pgmt manages in a table (see `catalog::data`). Not a
+    /// database object of its own: it orders data steps after their table.
+    TableData {
+        schema: String,
+        table: String,
+    },
 }
 
 impl DbObjectId {
@@ -121,13 +128,44 @@ impl DbObjectId {
             | DbObjectId::Policy { schema, .. }
             | DbObjectId::Aggregate { schema, .. }
             | DbObjectId::Operator { schema, .. }
-            | DbObjectId::Column { schema, .. } => Some(schema.as_str()),
+            | DbObjectId::Column { schema, .. }
+            | DbObjectId::TableData { schema, .. } => Some(schema.as_str()),
             DbObjectId::Grant { .. } | DbObjectId::Extension { .. } | DbObjectId::Cast { .. } => {
                 None
             }
             DbObjectId::Comment { object_id } => object_id.schema(),
         }
     }
+
+    /// Rename schema `from` to `to` in this identity. Argument and type
+    /// strings are left as they are.
+    pub fn rename_schema(&mut self, from: &str, to: &str) {
+        match self {
+            DbObjectId::Schema { name: schema }
+            | DbObjectId::Table { schema, .. }
+            | DbObjectId::View { schema, .. }
+            | DbObjectId::Type { schema, .. }
+            | DbObjectId::Domain { schema, .. }
+            | DbObjectId::Collation { schema, .. }
+            | DbObjectId::Function { schema, .. }
+            | DbObjectId::Procedure { schema, .. }
+            | DbObjectId::Sequence { schema, .. }
+            | DbObjectId::Index { schema, .. }
+            | DbObjectId::Constraint { schema, .. }
+            | DbObjectId::Trigger { schema, .. }
+            | DbObjectId::Policy { schema, .. }
+            | DbObjectId::Aggregate { schema, .. }
+            | DbObjectId::Operator { schema, .. }
+            | DbObjectId::Column { schema, .. }
+            | DbObjectId::TableData { schema, .. } => {
+                if schema == from {
+                    *schema = to.to_string();
+                }
+            }
+            DbObjectId::Comment { object_id } => object_id.rename_schema(from, to),
+            DbObjectId::Grant { .. } | DbObjectId::Extension { .. } | DbObjectId::Cast { .. } => {}
+        }
+    }
 }
 
 impl fmt::Display for DbObjectId {
@@ -185,6 +223,7 @@ impl fmt::Display for DbObjectId {
                 table,
                 column,
             } => write!(f, "column {schema}.{table}.{column}"),
+            Self::TableData { schema, table } => write!(f, "data in {schema}.{table}"),
         }
     }
 }
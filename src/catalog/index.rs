@@ -5,10 +5,11 @@
 
 use super::collation::CollationRef;
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
 /* ---------- Data structures ---------- */
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum IndexType {
     Btree,
     Hash,
@@ -47,7 +48,7 @@ impl std::fmt::Display for IndexType {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexColumn {
     pub expression: String, // Column name or expression
     /// Explicit per-key collation from `pg_index.indcollation`, recorded only
@@ -61,7 +62,7 @@ pub struct IndexColumn {
     pub nulls_ordering: Option<String>, // NULLS FIRST/LAST
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Index {
     pub schema: String,
     pub name: String,
@@ -0,0 +1,53 @@
+//! The kinds of object a [`super::Catalog`] stores, one per vector, so a
+//! caller that knows which kinds a change touched can reload only those (see
+//! [`super::Catalog::reload_kinds`]).
+
+use super::id::DbObjectId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CatalogKind {
+    Schema,
+    Table,
+    View,
+    Type,
+    Domain,
+    Collation,
+    Function,
+    Aggregate,
+    Operator,
+    Cast,
+    Sequence,
+    Index,
+    Constraint,
+    Trigger,
+    Policy,
+    Extension,
+}
+
+impl CatalogKind {
+    /// The kind whose vector holds `id`. Comments and columns are stored on
+    /// their parent, so they resolve to its kind. Grants have no kind of their
+    /// own: they are reloaded along with the objects they are on.
+    pub fn of(id: &DbObjectId) -> Option<Self> {
+        Some(match id {
+            DbObjectId::Schema { .. } => CatalogKind::Schema,
+            DbObjectId::Table { .. } | DbObjectId::Column { .. } => CatalogKind::Table,
+            DbObjectId::View { .. } => CatalogKind::View,
+            DbObjectId::Type { .. } => CatalogKind::Type,
+            DbObjectId::Domain { .. } => CatalogKind::Domain,
+            DbObjectId::Collation { .. } => CatalogKind::Collation,
+            DbObjectId::Function { .. } | DbObjectId::Procedure { .. } => CatalogKind::Function,
+            DbObjectId::Aggregate { .. } => CatalogKind::Aggregate,
+            DbObjectId::Operator { .. } => CatalogKind::Operator,
+            DbObjectId::Cast { .. } => CatalogKind::Cast,
+            DbObjectId::Sequence { .. } => CatalogKind::Sequence,
+            DbObjectId::Index { .. } => CatalogKind::Index,
+            DbObjectId::Constraint { .. } => CatalogKind::Constraint,
+            DbObjectId::Trigger { .. } => CatalogKind::Trigger,
+            DbObjectId::Policy { .. } => CatalogKind::Policy,
+            DbObjectId::Extension { .. } => CatalogKind::Extension,
+            DbObjectId::Comment { object_id } => return Self::of(object_id),
+            DbObjectId::Grant { .. } | DbObjectId::TableData { .. } => return None,
+        })
+    }
+}
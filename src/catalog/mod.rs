@@ -1,5 +1,6 @@
 use crate::catalog::file_dependencies::FileDependencyAugmentation;
 use crate::catalog::id::{DbObjectId, DependsOn};
+use crate::catalog::kind::CatalogKind;
 use crate::diff::operations::MigrationStep;
 use crate::diff::{
     aggregates as aggregates_diff, casts as casts_diff, collations as collations_diff,
@@ -8,8 +9,9 @@ use crate::diff::{
     policies as policies_diff, sequences as sequences_diff, tables as tables_diff,
     triggers as triggers_diff, views as views_diff,
 };
+use serde::{Deserialize, Serialize};
 use sqlx::{Acquire, PgPool};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 pub mod aggregate;
 pub mod attached;
@@ -17,6 +19,7 @@ pub mod cast;
 pub mod collation;
 pub mod constraint;
 pub mod custom_type;
+pub mod data;
 pub mod domain;
 pub mod extension;
 pub mod file_dependencies;
@@ -25,18 +28,20 @@ pub mod grant;
 pub mod id;
 pub mod identity;
 pub mod index;
+pub mod kind;
 pub mod operator;
 pub mod policy;
 pub mod raw;
 pub mod schema;
 pub mod sequence;
+pub mod snapshot_file;
 pub mod table;
 pub mod target;
 pub mod triggers;
 pub mod utils;
 pub mod view;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Catalog {
     pub schemas: Vec<schema::Schema>,
     pub tables: Vec<table::Table>,
@@ -55,8 +60,15 @@ pub struct Catalog {
     pub policies: Vec<policy::Policy>,
     pub extensions: Vec<extension::Extension>,
     pub grants: Vec<grant::Grant>,
+    /// Seed rows of the tables with files in the data directory. Only loaded
+    /// where it is asked for (see [`Self::with_data`]); absent in older
+    /// snapshots.
+    #[serde(default)]
+    pub data: Vec<data::TableData>,
 
+    #[serde(with = "snapshot_file::dep_map")]
     pub forward_deps: BTreeMap<DbObjectId, Vec<DbObjectId>>,
+    #[serde(with = "snapshot_file::dep_map")]
     pub reverse_deps: BTreeMap<DbObjectId, Vec<DbObjectId>>,
 }
 
@@ -80,11 +92,69 @@ impl Catalog {
         Self::load_with_file_dependencies(pool, None).await
     }
 
+    /// Load the raw physical catalog as if schema `from` were named `to`.
+    ///
+    /// The schema is renamed inside the load's transaction, which then rolls
+    /// back, so the database is left as it was. Renaming in the catalog rather
+    /// than in the loaded text moves everything Postgres stores by reference
+    /// along with the identities: column types and defaults, view queries,
+    /// constraint and index expressions, `BEGIN ATOMIC` bodies. Function bodies
+    /// written as strings keep their text. Used by schema-per-tenant mode (see
+    /// `crate::tenants`).
+    pub async fn load_unfiltered_renamed(
+        pool: &PgPool,
+        from: &str,
+        to: &str,
+    ) -> anyhow::Result<Self> {
+        Self::load_kinds_as(pool, None, &BTreeSet::new(), Some((from, to))).await
+    }
+
     /// Load catalog with optional file-based dependency augmentation
-    #[allow(clippy::explicit_auto_deref)] // Required for PoolConnection -> PgConnection deref
     pub async fn load_with_file_dependencies(
         pool: &PgPool,
         file_augmentation: Option<&FileDependencyAugmentation>,
+    ) -> anyhow::Result<Self> {
+        let mut catalog = Self::load_kinds(pool, None, &BTreeSet::new()).await?;
+        if let Some(augmentation) = file_augmentation {
+            catalog.apply_file_augmentation(augmentation);
+        }
+        Ok(catalog)
+    }
+
+    /// Reload only `kinds` from the database, keeping every other kind as it
+    /// is in `self`. Grants are reloaded for the objects of those kinds and
+    /// kept for the rest. The dependency maps are rebuilt from scratch, so any
+    /// file augmentation on `self` has to be applied again.
+    ///
+    /// For callers that know which kinds a change could have touched, such as
+    /// watch mode re-running a few schema files; anything else should load
+    /// the whole catalog.
+    pub async fn reload_kinds(
+        self,
+        pool: &PgPool,
+        kinds: &BTreeSet<CatalogKind>,
+    ) -> anyhow::Result<Self> {
+        Self::load_kinds(pool, Some(self), kinds).await
+    }
+
+    /// The shared loader: every kind when there is no `previous` catalog,
+    /// otherwise `kinds` with the rest taken from `previous`.
+    async fn load_kinds(
+        pool: &PgPool,
+        previous: Option<Catalog>,
+        kinds: &BTreeSet<CatalogKind>,
+    ) -> anyhow::Result<Self> {
+        Self::load_kinds_as(pool, previous, kinds, None).await
+    }
+
+    /// [`Self::load_kinds`], with `rename`'s schema renamed for the duration of
+    /// the load (see [`Self::load_unfiltered_renamed`]).
+    #[allow(clippy::explicit_auto_deref)] // Required for PoolConnection -> PgConnection deref
+    async fn load_kinds_as(
+        pool: &PgPool,
+        mut previous: Option<Catalog>,
+        kinds: &BTreeSet<CatalogKind>,
+        rename: Option<(&str, &str)>,
     ) -> anyhow::Result<Self> {
         // Acquire a single connection to ensure consistent search_path across all fetches.
         // This is critical because pg_get_function_identity_arguments() output depends on
@@ -106,10 +176,29 @@ impl Catalog {
         //
         // The transaction rolls back if a fetch fails (the guard is dropped
         // unfinished); it is only committed once every fetch has succeeded.
+        //
+        // A renaming load can't be read-only: the rename is its first
+        // statement, and the rollback at the end undoes it.
         let mut tx = conn.begin().await?;
-        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
-            .execute(&mut *tx)
-            .await?;
+        match rename {
+            None => {
+                sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            Some((from, to)) => {
+                sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(sqlx::AssertSqlSafe(format!(
+                    "ALTER SCHEMA {} RENAME TO {}",
+                    crate::render::quote_ident(from),
+                    crate::render::quote_ident(to)
+                )))
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
 
         // The cross-cutting state (namespace map, extension ownership,
         // comments) every converted kind resolves against. It must be fetched
@@ -121,53 +210,113 @@ impl Catalog {
         // with one index rather than fifteen that expire inside their converter.
         let mut oid_indexes = Vec::new();
 
-        let schemas =
-            raw::schema::load_with_exclusions(&shared)?.collect_into("schema", &mut oid_indexes);
-        let tables = raw::table::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("table", &mut oid_indexes);
-        let views = raw::view::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("view", &mut oid_indexes);
-        let types = raw::custom_type::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("type", &mut oid_indexes);
-        let domains = raw::domain::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("domain", &mut oid_indexes);
-        let collations = raw::collation::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("collation", &mut oid_indexes);
-        let functions = raw::function::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("function", &mut oid_indexes);
-        let aggregates = raw::aggregate::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("aggregate", &mut oid_indexes);
-        let operators = raw::operator::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("operator", &mut oid_indexes);
-        let casts = raw::cast::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("cast", &mut oid_indexes);
-        let sequences = raw::sequence::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("sequence", &mut oid_indexes);
-        let indexes = raw::index::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("index", &mut oid_indexes);
-        let constraints = raw::constraint::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("constraint", &mut oid_indexes);
-        let triggers = raw::trigger::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("trigger", &mut oid_indexes);
-        let policies = raw::policy::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("policy", &mut oid_indexes);
-        let extensions = raw::extension::load_with_exclusions(&mut tx, &shared)
-            .await?
-            .collect_into("extension", &mut oid_indexes);
+        // With a previous catalog, a kind outside `kinds` is taken from it
+        // rather than fetched; its OID index is then absent from the merge.
+        macro_rules! load_kind {
+            ($kind:expr, $field:ident, $label:literal, $load:expr) => {
+                match previous.as_mut().filter(|_| !kinds.contains(&$kind)) {
+                    Some(previous) => std::mem::take(&mut previous.$field),
+                    None => $load.collect_into($label, &mut oid_indexes),
+                }
+            };
+        }
+
+        let schemas = load_kind!(
+            CatalogKind::Schema,
+            schemas,
+            "schema",
+            raw::schema::load_with_exclusions(&shared)?
+        );
+        let tables = load_kind!(
+            CatalogKind::Table,
+            tables,
+            "table",
+            raw::table::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let views = load_kind!(
+            CatalogKind::View,
+            views,
+            "view",
+            raw::view::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let types = load_kind!(
+            CatalogKind::Type,
+            types,
+            "type",
+            raw::custom_type::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let domains = load_kind!(
+            CatalogKind::Domain,
+            domains,
+            "domain",
+            raw::domain::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let collations = load_kind!(
+            CatalogKind::Collation,
+            collations,
+            "collation",
+            raw::collation::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let functions = load_kind!(
+            CatalogKind::Function,
+            functions,
+            "function",
+            raw::function::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let aggregates = load_kind!(
+            CatalogKind::Aggregate,
+            aggregates,
+            "aggregate",
+            raw::aggregate::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let operators = load_kind!(
+            CatalogKind::Operator,
+            operators,
+            "operator",
+            raw::operator::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let casts = load_kind!(
+            CatalogKind::Cast,
+            casts,
+            "cast",
+            raw::cast::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let sequences = load_kind!(
+            CatalogKind::Sequence,
+            sequences,
+            "sequence",
+            raw::sequence::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let indexes = load_kind!(
+            CatalogKind::Index,
+            indexes,
+            "index",
+            raw::index::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let constraints = load_kind!(
+            CatalogKind::Constraint,
+            constraints,
+            "constraint",
+            raw::constraint::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let triggers = load_kind!(
+            CatalogKind::Trigger,
+            triggers,
+            "trigger",
+            raw::trigger::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let policies = load_kind!(
+            CatalogKind::Policy,
+            policies,
+            "policy",
+            raw::policy::load_with_exclusions(&mut tx, &shared).await?
+        );
+        let extensions = load_kind!(
+            CatalogKind::Extension,
+            extensions,
+            "extension",
+            raw::extension::load_with_exclusions(&mut tx, &shared).await?
+        );
 
         // The whole load's OID → identity index. Grants are loaded through it
         // rather than through filters of their own: an ACL row on an OID no
@@ -175,10 +324,27 @@ impl Catalog {
         // is dropped for that one reason. The index is deliberately not stored
         // on `Catalog` — an OID reaching a logical struct is what breaks
         // cross-database comparison, so it dies here, at the firewall.
+        //
+        // On a partial reload the index covers only the reloaded kinds, so the
+        // previous grants on every other kind are kept alongside.
         let oid_index = raw::merge_indexes(oid_indexes)?;
-        let grants = raw::grant::load(&mut tx, &oid_index).await?;
+        let mut grants = raw::grant::load(&mut tx, &oid_index).await?;
+        if let Some(previous) = previous.as_mut() {
+            grants.extend(
+                std::mem::take(&mut previous.grants)
+                    .into_iter()
+                    .filter(|grant| {
+                        CatalogKind::of(&grant.target.object)
+                            .is_some_and(|kind| !kinds.contains(&kind))
+                    }),
+            );
+        }
 
-        tx.commit().await?;
+        if rename.is_some() {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
 
         let mut forward = BTreeMap::new();
         let mut reverse = BTreeMap::new();
@@ -234,6 +400,7 @@ impl Catalog {
             policies,
             extensions,
             grants,
+            data: previous.map(|previous| previous.data).unwrap_or_default(),
             forward_deps: forward,
             reverse_deps: reverse,
         };
@@ -244,10 +411,6 @@ impl Catalog {
         // user-declared `-- require:` edges layer on top.
         catalog.apply_cast_function_routing();
 
-        if let Some(augmentation) = file_augmentation {
-            catalog.apply_file_augmentation(augmentation);
-        }
-
         Ok(catalog)
     }
 
@@ -467,6 +630,29 @@ impl Catalog {
             .find(|c| c.source == source && c.target == target)
     }
 
+    pub fn find_table_data(&self, schema: &str, table: &str) -> Option<&data::TableData> {
+        self.data
+            .iter()
+            .find(|d| d.schema == schema && d.table == table)
+    }
+
+    /// The tables this catalog carries seed rows for
+    pub fn seeded_tables(&self) -> Vec<data::SeededTable> {
+        self.data.iter().map(data::TableData::seeded).collect()
+    }
+
+    /// This catalog with the rows of `seeded` tables loaded from `pool`, so a
+    /// diff against it covers their seed data. Tables that don't exist in the
+    /// database are left out.
+    pub async fn with_data(
+        mut self,
+        pool: &PgPool,
+        seeded: &[data::SeededTable],
+    ) -> anyhow::Result<Self> {
+        self.data = data::load(pool, seeded).await?;
+        Ok(self)
+    }
+
     /// Every object that carries attached state (comments). Enumerated in ONE
     /// place via an exhaustive destructure: adding a field to `Catalog` fails to
     /// compile here until you decide whether the new object type is `Attached`.
@@ -492,6 +678,7 @@ impl Catalog {
             // Not object-attached comment state: grants are their own diff, and
             // the dep maps are derived. A new object field belongs above, not here.
             grants: _,
+            data: _,
             forward_deps: _,
             reverse_deps: _,
         } = self;
@@ -673,7 +860,8 @@ impl Catalog {
             | DbObjectId::Extension { .. }
             | DbObjectId::Grant { .. }
             | DbObjectId::Comment { .. }
-            | DbObjectId::Column { .. } => return None,
+            | DbObjectId::Column { .. }
+            | DbObjectId::TableData { .. } => return None,
         }
 
         // No ACL here — it's re-stated centrally for every recreated object; see
@@ -701,6 +889,7 @@ impl Catalog {
             policies: Vec::new(),
             extensions: Vec::new(),
             grants: Vec::new(),
+            data: Vec::new(),
             forward_deps: BTreeMap::new(),
             reverse_deps: BTreeMap::new(),
         }
@@ -758,6 +947,9 @@ impl Catalog {
             DbObjectId::Comment { object_id } => self.contains_id(object_id),
             // Column resolves to its parent table for containment checks
             DbObjectId::Column { schema, table, .. } => self.find_table(schema, table).is_some(),
+            DbObjectId::TableData { schema, table } => {
+                self.find_table_data(schema, table).is_some()
+            }
         }
     }
 
@@ -796,9 +988,50 @@ impl Catalog {
         ids.extend(self.policies.iter().map(DependsOn::id));
         ids.extend(self.extensions.iter().map(DependsOn::id));
         ids.extend(self.grants.iter().map(DependsOn::id));
+        ids.extend(self.data.iter().map(data::TableData::id));
         ids
     }
 
+    /// This catalog without `ids` and the grants on them: the state a
+    /// database is in once those objects are dropped.
+    pub fn without_objects(&self, ids: &HashSet<DbObjectId>) -> Catalog {
+        let mut catalog = self.clone();
+        fn retain<T: DependsOn>(items: &mut Vec<T>, ids: &HashSet<DbObjectId>) {
+            items.retain(|item| !ids.contains(&item.id()));
+        }
+        catalog.schemas.retain(|s| {
+            !ids.contains(&DbObjectId::Schema {
+                name: s.name.clone(),
+            })
+        });
+        retain(&mut catalog.tables, ids);
+        retain(&mut catalog.views, ids);
+        retain(&mut catalog.types, ids);
+        retain(&mut catalog.domains, ids);
+        retain(&mut catalog.collations, ids);
+        retain(&mut catalog.functions, ids);
+        retain(&mut catalog.aggregates, ids);
+        retain(&mut catalog.operators, ids);
+        retain(&mut catalog.casts, ids);
+        retain(&mut catalog.sequences, ids);
+        retain(&mut catalog.indexes, ids);
+        retain(&mut catalog.constraints, ids);
+        retain(&mut catalog.triggers, ids);
+        retain(&mut catalog.policies, ids);
+        retain(&mut catalog.extensions, ids);
+        catalog
+            .grants
+            .retain(|grant| !ids.contains(&grant.target.object));
+        catalog.data.retain(|data| !ids.contains(&data.table_id()));
+
+        let present = catalog.object_ids();
+        catalog
+            .forward_deps
+            .retain(|id, _| Self::id_present_in(&present, id));
+        catalog.rebuild_reverse_deps();
+        catalog
+    }
+
     /// [`Self::contains_id`]'s verdict, answered against a set from
     /// [`Self::object_ids`].
     ///
@@ -4,13 +4,14 @@
 //! Loading lives in `catalog::raw::operator`.
 
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
 /// A user-defined PostgreSQL operator (`CREATE OPERATOR`).
 ///
 /// An operator is identified by its schema, symbol, and the types of its two
 /// operands. Prefix operators have no left operand; their left operand type is
 /// recorded as `NONE` (matching the `DROP`/`COMMENT` `(left, right)` syntax).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Operator {
     pub schema: String,
     /// The operator symbol, e.g. `===` or `@>`.
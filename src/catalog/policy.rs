@@ -4,9 +4,10 @@
 //! Loading lives in `catalog::raw::policy`.
 
 use crate::catalog::{DependsOn, id::DbObjectId};
+use serde::{Deserialize, Serialize};
 
 /// Command type for RLS policies
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PolicyCommand {
     All,    // 'a' or '*' - applies to all commands
     Select, // 'r' - SELECT only
@@ -16,7 +17,7 @@ pub enum PolicyCommand {
 }
 
 /// Represents a PostgreSQL Row-Level Security policy
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Policy {
     pub schema: String,
     pub table_name: String,
@@ -3,7 +3,9 @@
 //! Loading lives in `catalog::raw::schema`, which converts the shared namespace
 //! map into these.
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Schema {
     pub name: String,
     /// Comment on the schema.
@@ -4,8 +4,9 @@
 //! Loading lives in `catalog::raw::sequence`.
 
 use crate::catalog::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sequence {
     pub schema: String,
     pub name: String,
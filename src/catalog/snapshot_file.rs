@@ -0,0 +1,158 @@
+//! Catalog snapshot files: a whole [`Catalog`] — every object and the
+//! dependency graph — as JSON, so a desired or live state captured where a
+//! database is available can be diffed later where none is.
+//!
+//! The file is the catalog exactly as loaded, plus a header. `format` is
+//! bumped whenever the catalog model changes shape; a file in another format
+//! is refused rather than half-read, and the fix is to take the snapshot
+//! again with this pgmt.
+
+use super::Catalog;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Version of the snapshot file layout this pgmt reads and writes.
+pub const SNAPSHOT_FORMAT: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CatalogSnapshot {
+    pub format: u32,
+    /// The pgmt that wrote the file, for the error when `format` differs.
+    pub pgmt_version: String,
+    /// What was captured, e.g. "schema files" or "dev database".
+    pub source: String,
+    pub catalog: Catalog,
+}
+
+/// Write `catalog` to `path` as a snapshot of `source`.
+pub fn write_snapshot(path: &Path, catalog: &Catalog, source: &str) -> Result<()> {
+    let snapshot = CatalogSnapshot {
+        format: SNAPSHOT_FORMAT,
+        pgmt_version: env!("CARGO_PKG_VERSION").to_string(),
+        source: source.to_string(),
+        catalog: catalog.clone(),
+    };
+    let mut json = serde_json::to_string_pretty(&snapshot)?;
+    json.push('\n');
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    std::fs::write(path, json)
+        .with_context(|| format!("Failed to write snapshot {}", path.display()))
+}
+
+/// Read a snapshot written by [`write_snapshot`].
+pub fn read_snapshot(path: &Path) -> Result<CatalogSnapshot> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+
+    // Check the header before the catalog, so a file from another pgmt gets
+    // the format error rather than whatever field it trips over first.
+    #[derive(Deserialize)]
+    struct Header {
+        format: Option<u32>,
+        pgmt_version: Option<String>,
+    }
+    let header: Header = serde_json::from_str(&text)
+        .with_context(|| format!("{} is not a pgmt catalog snapshot", path.display()))?;
+    match header.format {
+        Some(SNAPSHOT_FORMAT) => {}
+        Some(format) => bail!(
+            "{} is a format {} snapshot (written by pgmt {}); this pgmt reads format {}. \
+             Take the snapshot again with `pgmt catalog snapshot`",
+            path.display(),
+            format,
+            header.pgmt_version.as_deref().unwrap_or("unknown"),
+            SNAPSHOT_FORMAT
+        ),
+        None => bail!("{} is not a pgmt catalog snapshot", path.display()),
+    }
+
+    serde_json::from_str(&text)
+        .with_context(|| format!("Failed to parse snapshot {}", path.display()))
+}
+
+/// The dependency maps as `[object, [dependencies…]]` pairs: their keys are
+/// object ids, which JSON object keys can't hold.
+pub(super) mod dep_map {
+    use crate::catalog::id::DbObjectId;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(
+        map: &BTreeMap<DbObjectId, Vec<DbObjectId>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        map.iter().collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<DbObjectId, Vec<DbObjectId>>, D::Error> {
+        Ok(
+            Vec::<(DbObjectId, Vec<DbObjectId>)>::deserialize(deserializer)?
+                .into_iter()
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::id::DbObjectId;
+    use crate::catalog::schema::Schema;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut catalog = Catalog::empty();
+        catalog.schemas.push(Schema {
+            name: "app".to_string(),
+            comment: Some("application".to_string()),
+        });
+        let schema = DbObjectId::Schema {
+            name: "app".to_string(),
+        };
+        let table = DbObjectId::Table {
+            schema: "app".to_string(),
+            name: "users".to_string(),
+        };
+        catalog
+            .forward_deps
+            .insert(table.clone(), vec![schema.clone()]);
+        catalog.reverse_deps.insert(schema, vec![table]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/schema.snapshot.json");
+        write_snapshot(&path, &catalog, "schema files").unwrap();
+        let snapshot = read_snapshot(&path).unwrap();
+
+        assert_eq!(snapshot.source, "schema files");
+        assert_eq!(snapshot.catalog.schemas.len(), 1);
+        assert_eq!(
+            snapshot.catalog.schemas[0].comment.as_deref(),
+            Some("application")
+        );
+        assert_eq!(snapshot.catalog.forward_deps, catalog.forward_deps);
+        assert_eq!(snapshot.catalog.reverse_deps, catalog.reverse_deps);
+    }
+
+    #[test]
+    fn test_snapshot_format_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("old.json");
+        std::fs::write(&path, r#"{"format": 99, "pgmt_version": "9.0.0"}"#).unwrap();
+        let err = read_snapshot(&path).unwrap_err().to_string();
+        assert!(
+            err.contains("format 99 snapshot (written by pgmt 9.0.0)"),
+            "{}",
+            err
+        );
+
+        std::fs::write(&path, r#"{"tables": []}"#).unwrap();
+        let err = read_snapshot(&path).unwrap_err().to_string();
+        assert!(err.contains("is not a pgmt catalog snapshot"), "{}", err);
+    }
+}
@@ -6,8 +6,9 @@
 
 use super::collation::CollationRef;
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Column {
     pub name: String,
     pub data_type: String,
@@ -29,7 +30,7 @@ pub struct Column {
 }
 
 /// Which identity flavor a column uses (`pg_attribute.attidentity`)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IdentityKind {
     /// `GENERATED ALWAYS AS IDENTITY` (attidentity = 'a')
     Always,
@@ -54,14 +55,14 @@ impl IdentityKind {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PrimaryKey {
     pub name: String,
     pub columns: Vec<String>,
     pub comment: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Table {
     pub schema: String,
     pub name: String,
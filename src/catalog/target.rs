@@ -8,8 +8,9 @@
 //! vs a GRANT.
 
 use super::id::DbObjectId;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SubObject {
     /// A column of a relation (table, view) or attribute of a composite type.
     /// Keyed by name only — `attnum` is a physical coordinate that is not stable
@@ -17,7 +18,7 @@ pub enum SubObject {
     Column { name: String },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct AttrTarget {
     pub object: DbObjectId,
     pub sub: Option<SubObject>,
@@ -103,9 +104,10 @@ impl AttrTarget {
             DbObjectId::Extension { name } => (String::new(), name.clone()),
             // Casts have no schema; report the source→target pair as the name.
             DbObjectId::Cast { source, target } => (String::new(), format!("{source} AS {target}")),
-            DbObjectId::Grant { .. } | DbObjectId::Comment { .. } | DbObjectId::Column { .. } => {
-                (String::new(), String::new())
-            }
+            DbObjectId::Grant { .. }
+            | DbObjectId::Comment { .. }
+            | DbObjectId::Column { .. }
+            | DbObjectId::TableData { .. } => (String::new(), String::new()),
         }
     }
 }
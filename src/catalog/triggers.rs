@@ -4,9 +4,10 @@
 //! Loading lives in `catalog::raw::trigger`.
 
 use crate::catalog::{DependsOn, id::DbObjectId};
+use serde::{Deserialize, Serialize};
 
 /// Represents a PostgreSQL trigger
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Trigger {
     pub schema: String,
     pub table_name: String,
@@ -3,15 +3,16 @@
 //! Views are read through `catalog::raw::view`, which fetches the OID-keyed
 //! rows and converts them into these structs.
 use super::id::{DbObjectId, DependsOn};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ViewColumn {
     pub name: String,
     pub type_: Option<String>, // PostgreSQL doesn't always expose this directly
     pub comment: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct View {
     pub schema: String,
     pub name: String,
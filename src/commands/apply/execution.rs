@@ -4,19 +4,22 @@ use sqlx::PgPool;
 use tracing::info;
 
 use crate::catalog::Catalog;
-use crate::config::Config;
+use crate::config::{Config, DevUrl};
 use crate::diff::operations::{MigrationStep, SqlRenderer};
 use crate::render::{RenderedSql, Safety};
 
 use super::ApplyOutcome;
 use super::ExecutionMode;
 use super::execution_helpers;
+use super::snapshot::snapshot_before_destructive;
 use super::user_interaction;
 
-/// Execute migration plan based on execution mode
+/// Execute migration plan based on execution mode. Destructive steps that
+/// will run are snapshotted first (see `snapshot`).
 pub async fn execute_plan(
     steps: &[MigrationStep],
-    dev_pool: &PgPool,
+    dev: &DevUrl,
+    dev_pool: &mut PgPool,
     mode: ExecutionMode,
     expected_catalog: &Catalog,
     config: &Config,
@@ -39,6 +42,7 @@ pub async fn execute_plan(
 
         ExecutionMode::Force => {
             info!("Applying all migration steps without confirmation...");
+            snapshot_before_destructive(config, dev, dev_pool, &rendered).await?;
             let outcome = execution_helpers::apply_all_rendered_steps(
                 &rendered,
                 dev_pool,
@@ -113,6 +117,7 @@ pub async fn execute_plan(
                 user_interaction::execute_with_user_control(
                     &rendered,
                     steps,
+                    dev,
                     dev_pool,
                     expected_catalog,
                     config,
@@ -0,0 +1,780 @@
+//! Incremental desired state for `apply --watch`.
+//!
+//! A full build applies every schema file to a fresh shadow. Watch mode keeps
+//! its shadow between saves instead, together with the objects each file
+//! created, so a save only redoes the files it affects: the changed files,
+//! the files that `-- require:` them, the files owning objects that depend on
+//! theirs, and the files on either side of a statement that changes another
+//! file's object (`ALTER`, `GRANT`/`REVOKE`, `COMMENT ON`, rows written into
+//! its table). Those files' objects are dropped (planned by the diff engine,
+//! as if the files had been deleted), the files that still exist run again in
+//! load order, the seed files of the tables recreated load again, and only
+//! the catalog kinds their objects belong to are reloaded.
+//!
+//! Anything this can't vouch for gets a full build on a fresh shadow instead:
+//! a closure covering more than [`WATCH_INCREMENTAL_MAX_PERCENT`] of the
+//! files, a statement whose target can't be told (a `DO` block, say), a
+//! changed file that changed an object no schema file creates (dropping
+//! can't undo that), a change to the seed files, and any error on the way.
+
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::catalog::Catalog;
+use crate::catalog::data::TableName;
+use crate::catalog::file_dependencies::{FileToObjectMapping, create_dependency_augmentation};
+use crate::catalog::id::DbObjectId;
+use crate::catalog::kind::CatalogKind;
+use crate::config::{Config, ObjectFilter, ShadowDatabase};
+use crate::constants::WATCH_INCREMENTAL_MAX_PERCENT;
+use crate::db::branch::drop_branch;
+use crate::diff::operations::SqlRenderer;
+use crate::diff::plan;
+use crate::migration::lint::split_statements;
+use crate::schema_loader::SchemaFile;
+use crate::schema_ops::{clean_shadow_for_schema, schema_processor};
+
+/// A shadow kept built across watch iterations.
+pub struct WarmShadow<'a> {
+    config: &'a Config,
+    root_dir: &'a Path,
+    shadow: &'a ShadowDatabase,
+    built: Option<Built>,
+}
+
+struct Built {
+    pool: PgPool,
+    /// The schema files as last applied, in load order.
+    files: Vec<SchemaFile>,
+    mapping: FileToObjectMapping,
+    /// The shadow's catalog, unfiltered and without file augmentation, with
+    /// the seeded rows.
+    catalog: Catalog,
+    /// The seed files as last loaded: path and content.
+    seeds: Vec<(PathBuf, String)>,
+}
+
+/// How [`WarmShadow::update`] brought the shadow up to date.
+#[derive(Debug, PartialEq)]
+pub enum Rebuild {
+    /// The shadow was built for the first time.
+    Initial,
+    /// No schema file changed.
+    Unchanged,
+    /// Only these files ran again.
+    Incremental(Vec<String>),
+    /// Every file ran again on a fresh shadow, for this reason.
+    Full(String),
+}
+
+/// The files a change affects, or why it needs a full build.
+enum Closure {
+    Unchanged,
+    Files(BTreeSet<String>),
+    TooBroad(String),
+}
+
+impl<'a> WarmShadow<'a> {
+    /// An unbuilt shadow; the first [`Self::update`] builds it.
+    pub fn new(config: &'a Config, root_dir: &'a Path, shadow: &'a ShadowDatabase) -> Self {
+        Self {
+            config,
+            root_dir,
+            shadow,
+            built: None,
+        }
+    }
+
+    /// Bring the shadow up to date with the schema files on disk.
+    pub async fn update(&mut self) -> Result<Rebuild> {
+        let Some(built) = &self.built else {
+            self.full_rebuild().await?;
+            return Ok(Rebuild::Initial);
+        };
+
+        let files =
+            schema_processor(self.config, &built.pool).load_schema_files(&self.schema_dir())?;
+        if read_seeds(self.config, self.root_dir)? != built.seeds {
+            self.full_rebuild().await?;
+            return Ok(Rebuild::Full("the seed data files changed".to_string()));
+        }
+        let closure = match built.closure(&files) {
+            Closure::Unchanged => return Ok(Rebuild::Unchanged),
+            Closure::TooBroad(reason) => {
+                self.full_rebuild().await?;
+                return Ok(Rebuild::Full(reason));
+            }
+            Closure::Files(closure) => closure,
+        };
+
+        let built = self.built.as_mut().expect("checked above");
+        match built
+            .rerun(self.config, self.root_dir, files, &closure)
+            .await
+        {
+            Ok(()) => Ok(Rebuild::Incremental(closure.into_iter().collect())),
+            Err(e) => {
+                warn!("Incremental rebuild failed, rebuilding in full: {:#}", e);
+                self.full_rebuild().await?;
+                Ok(Rebuild::Full(format!("incremental rebuild failed: {}", e)))
+            }
+        }
+    }
+
+    /// The desired state: the shadow's managed catalog, with file dependencies
+    /// applied when the config asks for them (as [`crate::schema_ops::build_desired_state`]
+    /// does).
+    pub fn desired_state(&self) -> Result<Catalog> {
+        let built = self
+            .built
+            .as_ref()
+            .context("The shadow database has not been built")?;
+        let mut catalog = built.catalog.clone();
+        if self.config.schema.augment_dependencies_from_files {
+            let augmentation = create_dependency_augmentation(&built.mapping, &built.files)?;
+            catalog = catalog.with_file_dependencies_augmented(augmentation);
+        }
+        Ok(ObjectFilter::from_config(self.config).filter_catalog(catalog))
+    }
+
+    /// Reclaim the shadow's branch.
+    pub async fn close(mut self) -> Result<()> {
+        match self.built.take() {
+            Some(built) => drop_branch(built.pool).await,
+            None => Ok(()),
+        }
+    }
+
+    fn schema_dir(&self) -> PathBuf {
+        self.root_dir.join(&self.config.directories.schema)
+    }
+
+    async fn full_rebuild(&mut self) -> Result<()> {
+        if let Some(built) = self.built.take() {
+            drop_branch(built.pool).await?;
+        }
+
+        let pool = self.shadow.connect_fresh().await?;
+        match self.build_on(&pool).await {
+            Ok((files, mapping, catalog, seeds)) => {
+                self.built = Some(Built {
+                    pool,
+                    files,
+                    mapping,
+                    catalog,
+                    seeds,
+                });
+                Ok(())
+            }
+            Err(e) => {
+                drop_branch(pool).await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn build_on(
+        &self,
+        pool: &PgPool,
+    ) -> Result<(
+        Vec<SchemaFile>,
+        FileToObjectMapping,
+        Catalog,
+        Vec<(PathBuf, String)>,
+    )> {
+        clean_shadow_for_schema(self.config, self.root_dir, pool).await?;
+        let processor = schema_processor(self.config, pool);
+        let files = processor.load_schema_files(&self.schema_dir())?;
+        let mapping = processor.apply_schema_files(&files).await?;
+        let mut catalog = Catalog::load_unfiltered(pool)
+            .await
+            .context("Failed to load the shadow catalog")?;
+        let seeds = read_seeds(self.config, self.root_dir)?;
+        catalog.data =
+            crate::seed_data::load_seed_files(self.config, self.root_dir, pool, &catalog).await?;
+        Ok((files, mapping, catalog, seeds))
+    }
+}
+
+impl Built {
+    /// The files to run again for the schema files now on disk.
+    fn closure(&self, files: &[SchemaFile]) -> Closure {
+        let previous: HashMap<&str, &SchemaFile> = self
+            .files
+            .iter()
+            .map(|f| (f.relative_path.as_str(), f))
+            .collect();
+        let current: HashSet<&str> = files.iter().map(|f| f.relative_path.as_str()).collect();
+
+        let mut changed: BTreeSet<String> = files
+            .iter()
+            .filter(|f| {
+                previous
+                    .get(f.relative_path.as_str())
+                    .is_none_or(|p| p.content != f.content)
+            })
+            .map(|f| f.relative_path.clone())
+            .collect();
+        changed.extend(
+            self.files
+                .iter()
+                .filter(|f| !current.contains(f.relative_path.as_str()))
+                .map(|f| f.relative_path.clone()),
+        );
+        if changed.is_empty() {
+            return Closure::Unchanged;
+        }
+
+        // The files each file's statements reach into, by the objects' owners.
+        // A changed file counts with what it did before and does now: the
+        // owners of what it used to change run again to lose those changes.
+        let owners = self.owners_by_name();
+        let mut touches: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        let mut touched_by: HashMap<&str, Vec<&str>> = HashMap::new();
+        let versions = files.iter().chain(
+            self.files
+                .iter()
+                .filter(|f| changed.contains(&f.relative_path)),
+        );
+        for file in versions {
+            let path = file.relative_path.as_str();
+            let Some(names) = touched_names(&file.content) else {
+                return Closure::TooBroad(format!(
+                    "{} changes objects in a way watch can't follow",
+                    path
+                ));
+            };
+            for name in names {
+                match owners.get(&name) {
+                    Some(files) => {
+                        for owner in files {
+                            if *owner != path && touches.entry(path).or_default().insert(owner) {
+                                touched_by.entry(owner).or_default().push(path);
+                            }
+                        }
+                    }
+                    None if changed.contains(path) => {
+                        return Closure::TooBroad(format!(
+                            "{} changes {}, which no schema file creates",
+                            path,
+                            name.to_lowercase()
+                        ));
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        let mut requirers: HashMap<&str, Vec<&str>> = HashMap::new();
+        for file in files {
+            for dependency in &file.dependencies {
+                requirers
+                    .entry(dependency.as_str())
+                    .or_default()
+                    .push(&file.relative_path);
+            }
+        }
+
+        let mut closure = changed.clone();
+        let mut queue: Vec<String> = changed.into_iter().collect();
+        let mut visited: HashSet<&DbObjectId> = HashSet::new();
+        while let Some(path) = queue.pop() {
+            let mut affected: Vec<&str> = requirers.get(path.as_str()).cloned().unwrap_or_default();
+            affected.extend(touches.get(path.as_str()).into_iter().flatten());
+            affected.extend(touched_by.get(path.as_str()).into_iter().flatten());
+
+            // Files owning objects that depend, directly or not, on this
+            // file's: dropping its objects takes theirs with it.
+            let mut objects: Vec<&DbObjectId> = self
+                .mapping
+                .file_objects
+                .get(&path)
+                .map(|objects| objects.iter().collect())
+                .unwrap_or_default();
+            while let Some(object) = objects.pop() {
+                for dependent in self.catalog.reverse_deps.get(object).into_iter().flatten() {
+                    if visited.insert(dependent) {
+                        objects.push(dependent);
+                        if let Some(owner) = self.mapping.object_files.get(dependent) {
+                            affected.push(owner);
+                        }
+                    }
+                }
+            }
+
+            for file in affected {
+                if closure.insert(file.to_string()) {
+                    queue.push(file.to_string());
+                }
+            }
+        }
+
+        let total = files.len().max(self.files.len());
+        if closure.len() * 100 > total * WATCH_INCREMENTAL_MAX_PERCENT {
+            return Closure::TooBroad(format!(
+                "the change affects {} of {} schema files",
+                closure.len(),
+                total
+            ));
+        }
+        Closure::Files(closure)
+    }
+
+    /// The files creating the objects a statement could name, by the names
+    /// it could use (upper-cased, as [`touched_names`] returns them): the
+    /// qualified name, and the bare one for any schema.
+    fn owners_by_name(&self) -> HashMap<String, Vec<&str>> {
+        let mut owners: HashMap<String, Vec<&str>> = HashMap::new();
+        for (object, file) in &self.mapping.object_files {
+            let Some((schema, name)) = object_name(object) else {
+                continue;
+            };
+            let mut names = vec![name.to_uppercase()];
+            if let Some(schema) = schema {
+                names.push(format!("{}.{}", schema, name).to_uppercase());
+            }
+            for name in names {
+                owners.entry(name).or_default().push(file);
+            }
+        }
+        owners
+    }
+
+    /// Drop the objects `closure`'s files created, run those still in `files`
+    /// again, load the seed files of the tables that were recreated, and
+    /// reload the kinds of object involved.
+    async fn rerun(
+        &mut self,
+        config: &Config,
+        root_dir: &Path,
+        files: Vec<SchemaFile>,
+        closure: &BTreeSet<String>,
+    ) -> Result<()> {
+        let dropped: HashSet<DbObjectId> = closure
+            .iter()
+            .filter_map(|path| self.mapping.file_objects.get(path))
+            .flatten()
+            .cloned()
+            .collect();
+        let remaining = self.catalog.without_objects(&dropped);
+        for step in plan(&self.catalog, &remaining)? {
+            for rendered in step.to_sql() {
+                sqlx::raw_sql(sqlx::AssertSqlSafe(rendered.sql.clone()))
+                    .execute(&self.pool)
+                    .await
+                    .with_context(|| format!("Failed to drop objects: {}", rendered.sql))?;
+            }
+        }
+
+        let rerun: Vec<SchemaFile> = files
+            .iter()
+            .filter(|f| closure.contains(&f.relative_path))
+            .cloned()
+            .collect();
+        info!("Re-running {} schema file(s)", rerun.len());
+        let created = schema_processor(config, &self.pool)
+            .apply_schema_files(&rerun)
+            .await?;
+
+        for path in closure {
+            for object in self.mapping.file_objects.remove(path).unwrap_or_default() {
+                self.mapping.object_files.remove(&object);
+            }
+        }
+        let mut kinds: BTreeSet<CatalogKind> = dropped.iter().filter_map(CatalogKind::of).collect();
+        for (path, objects) in created.file_objects {
+            for object in objects {
+                kinds.extend(CatalogKind::of(&object));
+                self.mapping.add_object(path.clone(), object);
+            }
+        }
+
+        let recreated: BTreeSet<TableName> = dropped
+            .iter()
+            .filter_map(|object| match object {
+                DbObjectId::Table { schema, name } => Some((schema.clone(), name.clone())),
+                _ => None,
+            })
+            .collect();
+        let previous = std::mem::replace(&mut self.catalog, Catalog::empty());
+        self.catalog = previous.reload_kinds(&self.pool, &kinds).await?;
+        self.catalog.data = crate::seed_data::reload_seed_files(
+            config,
+            root_dir,
+            &self.pool,
+            &self.catalog,
+            &recreated,
+        )
+        .await?;
+        self.files = files;
+        Ok(())
+    }
+}
+
+/// The seed files' paths and contents, to notice when they change.
+fn read_seeds(config: &Config, root_dir: &Path) -> Result<Vec<(PathBuf, String)>> {
+    crate::seed_data::discover(&root_dir.join(&config.directories.data))?
+        .into_iter()
+        .map(|file| {
+            let content = std::fs::read_to_string(&file.path)
+                .with_context(|| format!("Failed to read seed file {}", file.path.display()))?;
+            Ok((file.path, content))
+        })
+        .collect()
+}
+
+/// An object's schema and name, for matching the names statements use.
+fn object_name(object: &DbObjectId) -> Option<(Option<&str>, &str)> {
+    match object {
+        DbObjectId::Schema { name } | DbObjectId::Extension { name } => Some((None, name)),
+        DbObjectId::Table { schema, name }
+        | DbObjectId::View { schema, name }
+        | DbObjectId::Type { schema, name }
+        | DbObjectId::Domain { schema, name }
+        | DbObjectId::Collation { schema, name }
+        | DbObjectId::Function { schema, name, .. }
+        | DbObjectId::Procedure { schema, name, .. }
+        | DbObjectId::Aggregate { schema, name, .. }
+        | DbObjectId::Sequence { schema, name }
+        | DbObjectId::Index { schema, name } => Some((Some(schema), name)),
+        _ => None,
+    }
+}
+
+/// Kinds `ALTER` and `COMMENT ON` name an object of by its own name.
+const NAMED_KINDS: &[&str] = &[
+    "TABLE",
+    "VIEW",
+    "SEQUENCE",
+    "FUNCTION",
+    "PROCEDURE",
+    "ROUTINE",
+    "AGGREGATE",
+    "TYPE",
+    "DOMAIN",
+    "INDEX",
+    "SCHEMA",
+    "COLLATION",
+    "EXTENSION",
+];
+
+/// Kinds named on a table: `ALTER POLICY p ON t`, `COMMENT ON TRIGGER t ON t`.
+const ON_TABLE_KINDS: &[&str] = &["POLICY", "TRIGGER", "RULE", "CONSTRAINT"];
+
+/// Statements that change nothing already there, besides what they create.
+const CREATING: &[&str] = &[
+    "CREATE", "SET", "RESET", "BEGIN", "COMMIT", "START", "END", "ANALYZE",
+];
+
+/// The names of the objects `sql`'s statements change without creating
+/// them, upper-cased as written (`APP.USERS` or `USERS`); `None` when a
+/// statement's target can't be told from its text.
+fn touched_names(sql: &str) -> Option<Vec<String>> {
+    let mut names = Vec::new();
+    for statement in split_statements(sql) {
+        let tokens = statement.tokens();
+        names.extend(statement_targets(&tokens)?.into_iter().map(str::to_string));
+    }
+    Some(names)
+}
+
+fn statement_targets<'t>(tokens: &[&'t str]) -> Option<Vec<&'t str>> {
+    let Some((&first, rest)) = tokens.split_first() else {
+        return Some(Vec::new());
+    };
+    match first {
+        "ALTER" => match rest {
+            [kind, target @ ..] if ON_TABLE_KINDS.contains(kind) => name_after_on(target),
+            ["MATERIALIZED", "VIEW", target @ ..] | ["FOREIGN", "TABLE", target @ ..] => {
+                first_name(target)
+            }
+            [kind, target @ ..] if NAMED_KINDS.contains(kind) => first_name(target),
+            _ => None,
+        },
+        "COMMENT" | "SECURITY" => {
+            let on = rest.iter().position(|t| *t == "ON")?;
+            match &rest[on + 1..] {
+                ["COLUMN", column, ..] => Some(vec![column.rsplit_once('.')?.0]),
+                [kind, target @ ..] if ON_TABLE_KINDS.contains(kind) => name_after_on(target),
+                ["MATERIALIZED", "VIEW", name, ..] | ["FOREIGN", "TABLE", name, ..] => {
+                    Some(vec![name])
+                }
+                [kind, name, ..] if NAMED_KINDS.contains(kind) => Some(vec![name]),
+                _ => None,
+            }
+        }
+        "GRANT" | "REVOKE" => {
+            // Without ON it grants a role, not an object.
+            let Some(on) = rest.iter().position(|t| *t == "ON") else {
+                return Some(Vec::new());
+            };
+            let end = rest
+                .iter()
+                .position(|t| matches!(*t, "TO" | "FROM"))
+                .unwrap_or(rest.len());
+            let target = rest.get(on + 1..end)?;
+            match target {
+                ["ALL", ..] => {
+                    let schema = target.iter().position(|t| *t == "SCHEMA")?;
+                    Some(names_in(&target[schema + 1..]))
+                }
+                [kind, names @ ..] if NAMED_KINDS.contains(kind) => Some(names_in(names)),
+                // Objects outside the schema files'.
+                [
+                    "DATABASE" | "LANGUAGE" | "TABLESPACE" | "FOREIGN" | "LARGE" | "PARAMETER",
+                    ..,
+                ] => None,
+                // A bare name is a table.
+                _ => Some(names_in(target)),
+            }
+        }
+        "INSERT" => {
+            let into = rest.iter().position(|t| *t == "INTO")?;
+            first_name(&rest[into + 1..])
+        }
+        "UPDATE" => first_name(rest),
+        "DELETE" => {
+            let from = rest.iter().position(|t| *t == "FROM")?;
+            first_name(&rest[from + 1..])
+        }
+        "TRUNCATE" => {
+            let end = rest
+                .iter()
+                .position(|t| matches!(*t, "CASCADE" | "RESTRICT" | "RESTART" | "CONTINUE"))
+                .unwrap_or(rest.len());
+            let names = rest[..end].strip_prefix(&["TABLE"]).unwrap_or(&rest[..end]);
+            Some(names_in(names))
+        }
+        _ if CREATING.contains(&first) => Some(Vec::new()),
+        _ => None,
+    }
+}
+
+/// The object name starting `tokens`, past `IF EXISTS` and `ONLY`.
+fn first_name<'t>(tokens: &[&'t str]) -> Option<Vec<&'t str>> {
+    let name = tokens
+        .iter()
+        .find(|t| !matches!(**t, "IF" | "EXISTS" | "ONLY"))?;
+    (*name != "ALL").then(|| vec![*name])
+}
+
+/// The table named after `ON` in `p ON t`.
+fn name_after_on<'t>(tokens: &[&'t str]) -> Option<Vec<&'t str>> {
+    let on = tokens.iter().position(|t| *t == "ON")?;
+    first_name(&tokens[on + 1..])
+}
+
+/// The comma-separated names in `tokens`, skipping argument lists.
+fn names_in<'t>(tokens: &[&'t str]) -> Vec<&'t str> {
+    let mut names = Vec::new();
+    let mut depth = 0usize;
+    for token in tokens {
+        match *token {
+            "(" => depth += 1,
+            ")" => depth = depth.saturating_sub(1),
+            "," | "ONLY" => {}
+            name if depth == 0 => names.push(name),
+            _ => {}
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(sql: &str) -> Option<Vec<String>> {
+        touched_names(sql)
+    }
+
+    fn strings(names: &[&str]) -> Option<Vec<String>> {
+        Some(names.iter().map(|n| n.to_string()).collect())
+    }
+
+    fn file(path: &str, content: &str) -> SchemaFile {
+        SchemaFile {
+            relative_path: path.to_string(),
+            content: content.to_string(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    fn table(name: &str) -> DbObjectId {
+        DbObjectId::Table {
+            schema: "app".to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    /// A shadow built from `files` plus filler files, so small closures stay
+    /// under the incremental limit; `created` names the table each creates.
+    /// The pool never connects, but needs a runtime to be made.
+    fn built(files: &[(&str, &str)], created: &[(&str, &str)]) -> Built {
+        let mut schema_files: Vec<SchemaFile> = files.iter().map(|(p, c)| file(p, c)).collect();
+        schema_files.extend((0..10).map(|i| file(&format!("filler_{}.sql", i), "")));
+        let mut mapping = FileToObjectMapping::new();
+        for (path, name) in created {
+            mapping
+                .file_objects
+                .entry(path.to_string())
+                .or_default()
+                .push(table(name));
+            mapping.object_files.insert(table(name), path.to_string());
+        }
+        Built {
+            pool: PgPool::connect_lazy("postgres://localhost/unused").unwrap(),
+            files: schema_files,
+            mapping,
+            catalog: Catalog::empty(),
+            seeds: Vec::new(),
+        }
+    }
+
+    /// `built`'s files with `path`'s content replaced.
+    fn edited(built: &Built, path: &str, content: &str) -> Vec<SchemaFile> {
+        built
+            .files
+            .iter()
+            .map(|f| {
+                if f.relative_path == path {
+                    file(path, content)
+                } else {
+                    f.clone()
+                }
+            })
+            .collect()
+    }
+
+    fn closure_files(closure: Closure) -> Vec<String> {
+        match closure {
+            Closure::Files(files) => files.into_iter().collect(),
+            Closure::Unchanged => panic!("expected files, got Unchanged"),
+            Closure::TooBroad(reason) => panic!("expected files, got TooBroad({})", reason),
+        }
+    }
+
+    #[test]
+    fn test_alter_and_comment_on_a_table_name_it() {
+        assert_eq!(
+            names("ALTER TABLE IF EXISTS ONLY app.users ADD COLUMN x int;"),
+            strings(&["APP.USERS"])
+        );
+        assert_eq!(
+            names("ALTER POLICY p ON app.users USING (true);"),
+            strings(&["APP.USERS"])
+        );
+        assert_eq!(
+            names("COMMENT ON TRIGGER t ON \"users\" IS 'audit';"),
+            strings(&["USERS"])
+        );
+        assert_eq!(
+            names("COMMENT ON COLUMN app.users.email IS 'login';"),
+            strings(&["APP.USERS"])
+        );
+        assert_eq!(
+            names("COMMENT ON FUNCTION app.f(int, text) IS 'f';"),
+            strings(&["APP.F"])
+        );
+    }
+
+    #[test]
+    fn test_grant_and_revoke_name_their_objects() {
+        assert_eq!(
+            names("GRANT SELECT, INSERT ON app.users, app.posts TO reader;"),
+            strings(&["APP.USERS", "APP.POSTS"])
+        );
+        assert_eq!(
+            names("REVOKE EXECUTE ON FUNCTION app.f(int), app.g() FROM PUBLIC;"),
+            strings(&["APP.F", "APP.G"])
+        );
+        assert_eq!(
+            names("GRANT USAGE ON ALL SEQUENCES IN SCHEMA app TO reader;"),
+            strings(&["APP"])
+        );
+        // Role membership names no object.
+        assert_eq!(names("GRANT reader TO writer;"), strings(&[]));
+        // Objects no schema file can create.
+        assert_eq!(names("GRANT CONNECT ON DATABASE app TO reader;"), None);
+    }
+
+    #[test]
+    fn test_row_writes_name_their_table() {
+        assert_eq!(
+            names("INSERT INTO app.tags (name) VALUES ('a');"),
+            strings(&["APP.TAGS"])
+        );
+        assert_eq!(
+            names("UPDATE ONLY tags SET name = 'b'; DELETE FROM app.tags WHERE false;"),
+            strings(&["TAGS", "APP.TAGS"])
+        );
+        assert_eq!(
+            names("TRUNCATE app.a, app.b CASCADE;"),
+            strings(&["APP.A", "APP.B"])
+        );
+    }
+
+    #[test]
+    fn test_creating_statements_name_nothing_and_unknown_ones_none() {
+        assert_eq!(
+            names("CREATE TABLE app.users (id int); SET search_path = app;"),
+            strings(&[])
+        );
+        assert_eq!(names("DO $$ BEGIN PERFORM 1; END $$;"), None);
+        assert_eq!(names("SELECT app.setup();"), None);
+    }
+
+    #[tokio::test]
+    async fn test_closure_follows_statements_into_other_files() {
+        let grants = "GRANT SELECT ON app.users TO reader;";
+        let seed = "INSERT INTO app.users VALUES (1);";
+        let shadow = built(
+            &[
+                ("users.sql", "CREATE TABLE app.users (id int);"),
+                ("grants.sql", grants),
+                ("seed.sql", seed),
+            ],
+            &[("users.sql", "users")],
+        );
+
+        // Recreating users.sql loses the grant and rows the others added.
+        let files = edited(&shadow, "users.sql", "CREATE TABLE app.users (id bigint);");
+        assert_eq!(
+            closure_files(shadow.closure(&files)),
+            ["grants.sql", "seed.sql", "users.sql"]
+        );
+
+        // Undoing a grant means recreating what it was on.
+        let files = edited(&shadow, "grants.sql", "GRANT ALL ON app.users TO reader;");
+        assert_eq!(
+            closure_files(shadow.closure(&files)),
+            ["grants.sql", "seed.sql", "users.sql"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_closure_falls_back_to_a_full_build() {
+        let shadow = built(
+            &[
+                ("users.sql", "CREATE TABLE app.users (id int);"),
+                ("setup.sql", ""),
+            ],
+            &[("users.sql", "users")],
+        );
+
+        let files = edited(&shadow, "setup.sql", "DO $$ BEGIN PERFORM 1; END $$;");
+        assert!(matches!(
+            shadow.closure(&files),
+            Closure::TooBroad(reason) if reason.contains("can't follow")
+        ));
+
+        let files = edited(&shadow, "setup.sql", "ALTER TABLE app.elsewhere ADD x int;");
+        assert!(matches!(
+            shadow.closure(&files),
+            Closure::TooBroad(reason) if reason.contains("which no schema file creates")
+        ));
+    }
+}
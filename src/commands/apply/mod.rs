@@ -1,13 +1,16 @@
 pub mod execution;
 pub mod execution_helpers;
+pub mod incremental;
 pub mod lock;
 pub mod shutdown;
+pub mod snapshot;
 pub mod user_interaction;
 pub mod verification;
 pub mod watch;
 
 pub use lock::ApplyLock;
 pub use shutdown::ShutdownSignal;
+pub use snapshot::cmd_apply_undo;
 
 /// Execution mode for apply operations
 #[derive(Clone)]
@@ -64,7 +67,7 @@ pub async fn cmd_apply(
     _lock.acquire()?;
 
     info!("Connecting to development database...");
-    let dev_pool =
+    let mut dev_pool =
         crate::db::connection::connect_to_database(dev.as_str(), "development database").await?;
 
     info!("Processing schema to shadow database...");
@@ -74,7 +77,9 @@ pub async fn cmd_apply(
     let filter = ObjectFilter::from_config(config);
     let old = Catalog::load_managed(&dev_pool, &filter)
         .await
-        .context("Failed to load catalog from development database")?;
+        .context("Failed to load catalog from development database")?
+        .with_data(&dev_pool, &new.seeded_tables())
+        .await?;
 
     info!("Computing schema differences...");
     let ordered = plan(&old, &new)?;
@@ -98,8 +103,15 @@ pub async fn cmd_apply(
             return Ok(ApplyOutcome::Cancelled);
         }
 
-        match execution::execute_plan(&ordered, &dev_pool, execution_mode.clone(), &new, config)
-            .await
+        match execution::execute_plan(
+            &ordered,
+            dev,
+            &mut dev_pool,
+            execution_mode.clone(),
+            &new,
+            config,
+        )
+        .await
         {
             Ok(outcome) => {
                 final_outcome = outcome;
@@ -112,7 +124,10 @@ pub async fn cmd_apply(
                 let new_filtered = apply_current_schema_to_shadow(config, root_dir, shadow).await?;
 
                 info!("Re-analyzing database catalogs...");
-                let old_filtered = Catalog::load_managed(&dev_pool, &filter).await?;
+                let old_filtered = Catalog::load_managed(&dev_pool, &filter)
+                    .await?
+                    .with_data(&dev_pool, &new_filtered.seeded_tables())
+                    .await?;
 
                 info!("Re-computing schema differences...");
                 let new_ordered = plan(&old_filtered, &new_filtered)?;
@@ -145,7 +160,8 @@ pub async fn cmd_apply(
                     match user_interaction::execute_with_user_control(
                         &rendered,
                         &new_ordered,
-                        &dev_pool,
+                        dev,
+                        &mut dev_pool,
                         &new_filtered,
                         config,
                     )
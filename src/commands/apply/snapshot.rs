@@ -0,0 +1,86 @@
+//! Dev database snapshots around `pgmt apply`: one is taken before a plan's
+//! destructive steps run, once they're confirmed, and `pgmt apply --undo`
+//! restores the latest.
+
+use anyhow::{Result, bail};
+use sqlx::PgPool;
+use std::path::Path;
+
+use super::ApplyLock;
+use crate::config::{Config, DevUrl};
+use crate::db::connection::connect_to_database;
+use crate::db::snapshot::{list_snapshots, restore_snapshot, snapshot_and_prune};
+use crate::render::{RenderedSql, Safety};
+
+/// Longest statement preview recorded as a snapshot's reason.
+const REASON_PREVIEW_CHARS: usize = 80;
+
+/// Snapshot the dev database before the destructive statements among
+/// `to_run` run, unless `dev.snapshot_retention` is 0. Called once they are
+/// sure to run, after any confirmation.
+///
+/// The copy needs the dev database to itself, so `dev_pool` is closed and
+/// replaced by a new connection. A snapshot that can't be taken (another
+/// session is connected, the role lacks CREATEDB) is reported, and the apply
+/// goes ahead without one.
+pub async fn snapshot_before_destructive<'r>(
+    config: &Config,
+    dev: &DevUrl,
+    dev_pool: &mut PgPool,
+    to_run: impl IntoIterator<Item = &'r RenderedSql>,
+) -> Result<()> {
+    if config.dev.snapshot_retention == 0 {
+        return Ok(());
+    }
+    let destructive: Vec<String> = to_run
+        .into_iter()
+        .filter(|rendered| rendered.safety == Safety::Destructive)
+        .map(|rendered| rendered.sql.lines().next().unwrap_or_default().to_string())
+        .collect();
+    let Some(first) = destructive.first() else {
+        return Ok(());
+    };
+
+    let mut reason: String = first.chars().take(REASON_PREVIEW_CHARS).collect();
+    if destructive.len() > 1 {
+        reason.push_str(&format!(" (+{} more)", destructive.len() - 1));
+    }
+
+    dev_pool.close().await;
+    let snapshot = snapshot_and_prune(dev.as_str(), &reason, config.dev.snapshot_retention).await;
+    *dev_pool = connect_to_database(dev.as_str(), "development database").await?;
+
+    match snapshot {
+        Ok(snapshot) => println!(
+            "📸 Snapshot {} taken before destructive changes (pgmt apply --undo restores it)",
+            snapshot.name
+        ),
+        Err(e) => println!(
+            "⚠️  {:#}\n   Applying without a snapshot; set dev.snapshot_retention: 0 to stop trying",
+            e
+        ),
+    }
+    Ok(())
+}
+
+/// Restore the dev database from its latest snapshot, which is used up: a
+/// second `--undo` goes back one snapshot further.
+pub async fn cmd_apply_undo(root_dir: &Path, dev: &DevUrl) -> Result<()> {
+    let _lock = ApplyLock::new(root_dir);
+    _lock.acquire()?;
+
+    let Some(latest) = list_snapshots(dev.as_str()).await?.into_iter().next() else {
+        bail!("No dev database snapshots to undo to (see pgmt dev snapshots list)");
+    };
+    restore_snapshot(dev.as_str(), &latest.name, true).await?;
+
+    println!(
+        "⏪ Restored the dev database to before: {} (snapshot from {})",
+        latest.reason,
+        latest
+            .created_at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S")
+    );
+    Ok(())
+}
@@ -5,19 +5,21 @@ use sqlx::PgPool;
 use tracing::{debug, info};
 
 use crate::catalog::Catalog;
-use crate::config::Config;
+use crate::config::{Config, DevUrl};
 use crate::diff::operations::MigrationStep;
 use crate::render::{RenderedSql, Safety};
 
 use super::ApplyOutcome;
 use super::execution_helpers;
+use super::snapshot::snapshot_before_destructive;
 use super::verification::verify_final_state;
 
 /// Execute migration steps with enhanced user control and recovery options
 pub async fn execute_with_user_control(
     rendered: &[RenderedSql],
     steps: &[MigrationStep],
-    dev_pool: &PgPool,
+    dev: &DevUrl,
+    dev_pool: &mut PgPool,
     expected_catalog: &Catalog,
     config: &Config,
 ) -> Result<ApplyOutcome> {
@@ -67,6 +69,7 @@ pub async fn execute_with_user_control(
             0 => {
                 // Apply all steps
                 info!("Applying all migration steps...");
+                snapshot_before_destructive(config, dev, dev_pool, rendered).await?;
                 let outcome = execution_helpers::apply_all_rendered_steps(
                     rendered,
                     dev_pool,
@@ -99,6 +102,7 @@ pub async fn execute_with_user_control(
                 return review_destructive_steps(
                     rendered,
                     steps,
+                    dev,
                     dev_pool,
                     expected_catalog,
                     config,
@@ -139,7 +143,8 @@ pub async fn execute_with_user_control(
 async fn review_destructive_steps(
     rendered: &[RenderedSql],
     _steps: &[MigrationStep],
-    dev_pool: &PgPool,
+    dev: &DevUrl,
+    dev_pool: &mut PgPool,
     expected_catalog: &Catalog,
     config: &Config,
 ) -> Result<ApplyOutcome> {
@@ -209,6 +214,7 @@ async fn review_destructive_steps(
         println!("ℹ️  No steps to apply");
         return Ok(ApplyOutcome::Cancelled);
     }
+    snapshot_before_destructive(config, dev, dev_pool, to_apply.iter().copied()).await?;
 
     let show_progress = tracing::enabled!(tracing::Level::INFO);
     let executor = crate::db::schema_executor::ApplyStepExecutor::new(
@@ -7,7 +7,7 @@ use std::time::Instant;
 use tracing::{error, info};
 
 use crate::catalog::Catalog;
-use crate::config::{Config, ObjectFilter};
+use crate::config::{Config, DevUrl, ObjectFilter};
 use crate::diff::operations::SqlRenderer;
 use crate::diff::plan;
 use crate::render::{RenderedSql, Safety};
@@ -16,6 +16,7 @@ use super::ApplyOutcome;
 use super::ExecutionMode;
 use super::execution;
 use super::execution_helpers;
+use super::incremental::{Rebuild, WarmShadow};
 use super::lock::ApplyLock;
 use super::shutdown::ShutdownSignal;
 use super::user_interaction;
@@ -43,12 +44,27 @@ pub async fn cmd_apply_watch_impl(
 
     // Set up persistent database connections
     info!("Connecting to development database...");
-    let dev_pool =
+    let mut dev_pool =
         crate::db::connection::connect_to_database(dev.as_str(), "development database").await?;
 
+    // The shadow stays built between saves, so each save only re-runs the
+    // schema files it affects
+    let mut warm = WarmShadow::new(config, root_dir, shadow);
+
     // Perform initial apply
     println!("\n🚀 Performing initial schema apply...");
-    perform_single_apply(config, root_dir, &dev_pool, shadow, execution_mode.clone()).await?;
+    if let Err(e) = rebuild_and_apply(
+        &mut warm,
+        dev,
+        &mut dev_pool,
+        config,
+        execution_mode.clone(),
+    )
+    .await
+    {
+        warm.close().await?;
+        return Err(e);
+    }
 
     // Set up file watching
     let schema_dir = root_dir.join(&config.directories.schema);
@@ -68,6 +84,11 @@ pub async fn cmd_apply_watch_impl(
     )?;
 
     watcher.watch(&schema_dir, RecursiveMode::Recursive)?;
+    let data_dir = root_dir.join(&config.directories.data);
+    if data_dir.is_dir() {
+        println!("👁️  Watching for changes in: {}", data_dir.display());
+        watcher.watch(&data_dir, RecursiveMode::NonRecursive)?;
+    }
 
     let mut last_apply = Instant::now();
 
@@ -100,11 +121,11 @@ pub async fn cmd_apply_watch_impl(
                         .display();
                     println!("\n🔄 Change detected: {}", file_display);
 
-                    match perform_single_apply(
+                    match rebuild_and_apply(
+                        &mut warm,
+                        dev,
+                        &mut dev_pool,
                         config,
-                        root_dir,
-                        &dev_pool,
-                        shadow,
                         execution_mode.clone(),
                     )
                     .await
@@ -154,6 +175,7 @@ pub async fn cmd_apply_watch_impl(
         }
     }
 
+    warm.close().await?;
     println!("👋 Watch mode stopped");
     Ok(ApplyOutcome::Cancelled)
 }
@@ -163,35 +185,64 @@ fn get_changed_sql_file(event: &Event) -> Option<&Path> {
     // Only care about write events and creation/deletion
     match event.kind {
         EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
-            // Find the first .sql file in the paths
+            // Find the first .sql (or seed .csv) file in the paths
             event
                 .paths
                 .iter()
-                .find(|path| path.extension().is_some_and(|ext| ext == "sql"))
+                .find(|path| {
+                    path.extension()
+                        .is_some_and(|ext| ext == "sql" || ext == "csv")
+                })
                 .map(|p| p.as_path())
         }
         _ => None,
     }
 }
 
-/// Perform a single apply operation (shared by regular and watch mode)
-async fn perform_single_apply(
+/// Bring the warm shadow up to date with the schema files, then apply its
+/// state to the development database
+async fn rebuild_and_apply(
+    warm: &mut WarmShadow<'_>,
+    dev: &DevUrl,
+    dev_pool: &mut PgPool,
     config: &Config,
-    root_dir: &Path,
-    dev_pool: &PgPool,
-    shadow: &crate::config::ShadowDatabase,
     execution_mode: ExecutionMode,
 ) -> Result<ApplyOutcome> {
-    // Process schema to shadow database. Each apply provisions and reclaims its
-    // own fresh branch — reusing one across watch iterations would collide
-    // (branch cleans are no-ops) and leak a branch per file-save.
-    let new = crate::schema_ops::apply_current_schema_to_shadow(config, root_dir, shadow).await?;
+    let started = Instant::now();
+    match warm.update().await? {
+        Rebuild::Unchanged => info!("Schema files unchanged, shadow database kept"),
+        Rebuild::Initial => info!("Built shadow database in {:.1?}", started.elapsed()),
+        Rebuild::Incremental(files) => println!(
+            "⚡ Re-ran {} in {:.1?}",
+            files.join(", "),
+            started.elapsed()
+        ),
+        Rebuild::Full(reason) => println!(
+            "🔨 Rebuilt shadow database ({}) in {:.1?}",
+            reason,
+            started.elapsed()
+        ),
+    }
+    let new = warm.desired_state()?;
+    perform_single_apply(config, dev, dev_pool, &new, execution_mode).await
+}
 
+/// Apply the desired state to the development database
+async fn perform_single_apply(
+    config: &Config,
+    dev: &DevUrl,
+    dev_pool: &mut PgPool,
+    new: &Catalog,
+    execution_mode: ExecutionMode,
+) -> Result<ApplyOutcome> {
     // Analyze differences
     let filter = ObjectFilter::from_config(config);
-    let old = Catalog::load_managed(dev_pool, &filter).await?;
+    let old = Catalog::load_managed(dev_pool, &filter)
+        .await?
+        .with_data(dev_pool, &new.seeded_tables())
+        .await?;
 
-    let ordered = plan(&old, &new)?;
+    let ordered = plan(&old, new)?;
 
     if ordered.is_empty() {
         return Ok(ApplyOutcome::NoChanges);
@@ -204,13 +255,14 @@ async fn perform_single_apply(
     );
 
     // Execute the migration plan
-    execute_plan_watch_aware(&ordered, dev_pool, execution_mode, &new, config).await
+    execute_plan_watch_aware(&ordered, dev, dev_pool, execution_mode, new, config).await
 }
 
 /// Execute plan with watch mode optimizations
 async fn execute_plan_watch_aware(
     steps: &[crate::diff::operations::MigrationStep],
-    dev_pool: &PgPool,
+    dev: &DevUrl,
+    dev_pool: &mut PgPool,
     mode: ExecutionMode,
     expected_catalog: &Catalog,
     config: &Config,
@@ -233,6 +285,7 @@ async fn execute_plan_watch_aware(
 
         ExecutionMode::Force => {
             // Auto-apply everything in watch mode
+            super::snapshot::snapshot_before_destructive(config, dev, dev_pool, &rendered).await?;
             let outcome = execution_helpers::apply_all_rendered_steps(
                 &rendered,
                 dev_pool,
@@ -305,6 +358,7 @@ async fn execute_plan_watch_aware(
                 user_interaction::execute_with_user_control(
                     &rendered,
                     steps,
+                    dev,
                     dev_pool,
                     expected_catalog,
                     config,
@@ -135,6 +135,7 @@ pub async fn cmd_migrate_baseline(
         )?;
         std::fs::write(&result.path, render_sectioned_migration(&sections))?;
     }
+    crate::schema_ops::placeholder_restorer(config, root_dir)?.restore_file(&result.path)?;
 
     if tracing::enabled!(tracing::Level::DEBUG) {
         debug!(
@@ -165,6 +166,7 @@ pub async fn cmd_migrate_baseline(
                     crate::diff::operations::MigrationStep::Extension(_) => "Extension",
                     crate::diff::operations::MigrationStep::Grant(_) => "Grant",
                     crate::diff::operations::MigrationStep::Comment(_) => "Comment",
+                    crate::diff::operations::MigrationStep::Data(_) => "Data",
                 };
 
                 let dependencies = catalog
@@ -314,7 +316,7 @@ pub async fn cmd_baseline_list(config: &Config, root_dir: &std::path::Path) -> R
     Ok(())
 }
 
-fn format_size(bytes: u64) -> String {
+pub(crate) fn format_size(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
     let mut size = bytes as f64;
     let mut unit_index = 0;
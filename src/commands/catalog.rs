@@ -0,0 +1,194 @@
+//! `pgmt catalog snapshot`, and the `--from-snapshot` / `--to-snapshot` flags
+//! that let `diff`, `migrate diff` and `validate` compare snapshot files
+//! instead of connecting (see `catalog::snapshot_file`).
+
+use crate::catalog::Catalog;
+use crate::catalog::snapshot_file::{read_snapshot, write_snapshot};
+use crate::config::{
+    Config, DevUrl, DevUrlArgs, ObjectFilter, ShadowDatabase, ShadowUrlArgs, TargetUrl,
+    TargetUrlArgs,
+};
+use crate::schema_ops::apply_current_schema_to_shadow;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Catalog subcommands
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum CatalogCommands {
+    /// Save a catalog (every object and its dependencies) to a file that
+    /// `--from-snapshot` / `--to-snapshot` can diff without a database
+    Snapshot {
+        /// File to write, e.g. schema.snapshot.json
+        #[arg(long, value_name = "FILE")]
+        out: PathBuf,
+
+        /// What to capture
+        #[arg(long, value_enum, default_value = "schema")]
+        source: SnapshotSource,
+
+        #[command(flatten)]
+        dev: DevUrlArgs,
+
+        #[command(flatten)]
+        target: TargetUrlArgs,
+
+        #[command(flatten)]
+        shadow: ShadowUrlArgs,
+    },
+}
+
+/// What `pgmt catalog snapshot` captures
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum SnapshotSource {
+    /// The schema files, built on the shadow database
+    #[default]
+    Schema,
+    /// The dev database
+    Dev,
+    /// The target database
+    Target,
+}
+
+/// A resolved [`SnapshotSource`].
+pub enum SnapshotOf {
+    SchemaFiles(ShadowDatabase),
+    Dev(DevUrl),
+    Target(TargetUrl),
+}
+
+/// Snapshot flags shared by the commands that compare a database with the
+/// schema files.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct SnapshotArgs {
+    /// Read the database side from this catalog snapshot instead of
+    /// connecting
+    #[arg(long, value_name = "FILE")]
+    pub from_snapshot: Option<PathBuf>,
+
+    /// Read the schema files side from this catalog snapshot instead of
+    /// building the schema files on the shadow database
+    #[arg(long, value_name = "FILE")]
+    pub to_snapshot: Option<PathBuf>,
+}
+
+impl SnapshotArgs {
+    /// The database side: the snapshot if given, otherwise `live()` — so a
+    /// connection is only resolved (and required) when it's used.
+    pub fn database_side<T>(&self, live: impl FnOnce() -> Result<T>) -> Result<CatalogSource<T>> {
+        CatalogSource::pick(&self.from_snapshot, live)
+    }
+
+    /// The schema files side, like [`SnapshotArgs::database_side`].
+    pub fn schema_side(
+        &self,
+        live: impl FnOnce() -> Result<ShadowDatabase>,
+    ) -> Result<CatalogSource<ShadowDatabase>> {
+        CatalogSource::pick(&self.to_snapshot, live)
+    }
+}
+
+/// One side of a comparison: a live source, or a snapshot file taken earlier.
+pub enum CatalogSource<T> {
+    Live(T),
+    Snapshot(PathBuf),
+}
+
+impl<T> CatalogSource<T> {
+    fn pick(snapshot: &Option<PathBuf>, live: impl FnOnce() -> Result<T>) -> Result<Self> {
+        Ok(match snapshot {
+            Some(path) => CatalogSource::Snapshot(path.clone()),
+            None => CatalogSource::Live(live()?),
+        })
+    }
+
+    /// How reports name this side: `live` or the snapshot file.
+    pub fn label(&self, live: &str) -> String {
+        match self {
+            CatalogSource::Live(_) => live.to_string(),
+            CatalogSource::Snapshot(path) => format!("snapshot {}", path.display()),
+        }
+    }
+}
+
+/// The schema files' managed catalog: built on the shadow, or read from a
+/// snapshot.
+pub async fn load_schema_side(
+    config: &Config,
+    root_dir: &Path,
+    source: &CatalogSource<ShadowDatabase>,
+) -> Result<Catalog> {
+    match source {
+        CatalogSource::Live(shadow) => {
+            apply_current_schema_to_shadow(config, root_dir, shadow).await
+        }
+        CatalogSource::Snapshot(path) => load_snapshot(config, path),
+    }
+}
+
+/// A database's managed catalog: loaded from `url`, or read from a snapshot.
+pub async fn load_database_side<T>(
+    config: &Config,
+    source: &CatalogSource<T>,
+    url: impl FnOnce(&T) -> &str,
+    label: &str,
+) -> Result<Catalog> {
+    match source {
+        CatalogSource::Live(database) => {
+            let pool = crate::db::connection::connect_to_database(url(database), label).await?;
+            let catalog = Catalog::load_managed(&pool, &ObjectFilter::from_config(config)).await;
+            pool.close().await;
+            catalog
+        }
+        CatalogSource::Snapshot(path) => load_snapshot(config, path),
+    }
+}
+
+/// A snapshot's catalog, scoped by this project's objects config like a live
+/// load.
+fn load_snapshot(config: &Config, path: &Path) -> Result<Catalog> {
+    let snapshot = read_snapshot(path)?;
+    Ok(ObjectFilter::from_config(config).filter_catalog(snapshot.catalog))
+}
+
+/// Capture `of` into `out`.
+pub async fn cmd_catalog_snapshot(
+    config: &Config,
+    root_dir: &Path,
+    of: &SnapshotOf,
+    out: &Path,
+) -> Result<()> {
+    let (catalog, source) = match of {
+        SnapshotOf::SchemaFiles(shadow) => {
+            eprintln!("Loading schema files...");
+            (
+                apply_current_schema_to_shadow(config, root_dir, shadow).await?,
+                "schema files",
+            )
+        }
+        SnapshotOf::Dev(dev) => {
+            eprintln!("Loading dev database...");
+            let source = CatalogSource::Live(dev);
+            let catalog =
+                load_database_side(config, &source, |d| d.as_str(), "development database").await?;
+            (catalog, "dev database")
+        }
+        SnapshotOf::Target(target) => {
+            eprintln!("Loading target database...");
+            let source = CatalogSource::Live(target);
+            let catalog =
+                load_database_side(config, &source, |t| t.as_str(), "target database").await?;
+            (catalog, "target database")
+        }
+    };
+
+    write_snapshot(out, &catalog, source)?;
+    println!(
+        "Wrote snapshot of {} to {} ({} tables, {} views, {} functions)",
+        source,
+        out.display(),
+        catalog.tables.len(),
+        catalog.views.len(),
+        catalog.functions.len()
+    );
+    Ok(())
+}
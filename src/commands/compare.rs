@@ -0,0 +1,80 @@
+//! pgmt compare - Compare two live databases
+//!
+//! Shows how one database differs from another (say staging from
+//! production), independent of the schema files. Both sides are loaded under
+//! the project's objects config, so the comparison covers what pgmt manages.
+
+use crate::catalog::Catalog;
+use crate::config::{Config, ObjectFilter};
+use crate::db::connection::{connect_to_database, mask_url_password};
+use crate::diff::plan;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+use super::diff_output::{DiffContext, DiffFormat, has_differences, output_diff, render_sql};
+
+/// Arguments for the compare command
+#[derive(Debug)]
+pub struct CompareArgs {
+    pub from_url: String,
+    pub to_url: String,
+    pub format: DiffFormat,
+    pub output_sql: Option<String>,
+    /// Also write the SQL that brings the `to` database back in line with
+    /// the `from` database.
+    pub sync_sql: Option<PathBuf>,
+}
+
+/// Compare the `from` database with the `to` database
+///
+/// The report reads as the changes that take `from` to `to`; `--sync-sql`
+/// writes the opposite direction.
+pub async fn cmd_compare(config: &Config, args: CompareArgs) -> Result<()> {
+    let from_label = mask_url_password(&args.from_url);
+    let to_label = mask_url_password(&args.to_url);
+    eprintln!("Comparing {} with {}...\n", from_label, to_label);
+
+    eprintln!("Loading {}...", from_label);
+    let from_catalog = load(config, &args.from_url, "--from-url database").await?;
+    eprintln!("Loading {}...", to_label);
+    let to_catalog = load(config, &args.to_url, "--to-url database").await?;
+
+    eprintln!("Computing differences...\n");
+    let ordered_steps = plan(&from_catalog, &to_catalog)?;
+
+    let context = DiffContext::new(&from_label, &to_label);
+    output_diff(
+        &ordered_steps,
+        &args.format,
+        &context,
+        &from_catalog,
+        &to_catalog,
+        args.output_sql.as_deref(),
+    )?;
+
+    if let Some(path) = &args.sync_sql {
+        let sync_steps = plan(&to_catalog, &from_catalog)?;
+        let sql = render_sql(&sync_steps, &DiffContext::new(&to_label, &from_label));
+        std::fs::write(path, sql).with_context(|| format!("Failed to write {}", path.display()))?;
+        eprintln!(
+            "SQL to bring {} in sync with {} saved to {}",
+            to_label,
+            from_label,
+            path.display()
+        );
+    }
+
+    // Exit with code 1 if differences found
+    if has_differences(&ordered_steps) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+async fn load(config: &Config, url: &str, label: &str) -> Result<Catalog> {
+    let pool = connect_to_database(url, label).await?;
+    let catalog = Catalog::load_managed(&pool, &ObjectFilter::from_config(config)).await;
+    pool.close().await;
+    catalog
+}
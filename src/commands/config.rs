@@ -125,6 +125,8 @@ fn get_config_value(config: &Config, file_input: &ConfigInput, key: &str) -> Res
         ["directories", "migrations"] => Ok(config.directories.migrations.clone()),
         ["directories", "baselines"] => Ok(config.directories.baselines.clone()),
         ["directories", "roles"] => Ok(config.directories.roles.clone()),
+        ["directories", "data"] => Ok(config.directories.data.clone()),
+        ["directories", "tests"] => Ok(config.directories.tests.clone()),
 
         ["migration", "default_mode"] => Ok(config.migration.default_mode.clone()),
         ["migration", "validate_baseline_consistency"] => {
@@ -145,6 +147,8 @@ fn get_config_value(config: &Config, file_input: &ConfigInput, key: &str) -> Res
             Ok(config.docker.check_system_identifier.to_string())
         }
 
+        ["dev", "snapshot_retention"] => Ok(config.dev.snapshot_retention.to_string()),
+
         _ => Err(anyhow!("Unknown configuration key: {}", key)),
     }
 }
@@ -197,6 +201,18 @@ fn set_config_value(config_file: &str, key: &str, value: &str) -> Result<()> {
                 .get_or_insert_with(Default::default)
                 .baselines_dir = Some(value.to_string());
         }
+        ["directories", "data"] => {
+            config_input
+                .directories
+                .get_or_insert_with(Default::default)
+                .data_dir = Some(value.to_string());
+        }
+        ["directories", "tests"] => {
+            config_input
+                .directories
+                .get_or_insert_with(Default::default)
+                .tests_dir = Some(value.to_string());
+        }
 
         ["migration", "default_mode"] => {
             config_input
@@ -260,6 +276,16 @@ fn set_config_value(config_file: &str, key: &str, value: &str) -> Result<()> {
                 .check_system_identifier = Some(bool_val);
         }
 
+        ["dev", "snapshot_retention"] => {
+            let count = value
+                .parse::<usize>()
+                .map_err(|_| anyhow!("Invalid snapshot count: {}", value))?;
+            config_input
+                .dev
+                .get_or_insert_with(Default::default)
+                .snapshot_retention = Some(count);
+        }
+
         _ => return Err(anyhow!("Unknown or unsupported configuration key: {}", key)),
     }
 
@@ -287,6 +313,8 @@ fn list_config_values(
             "migrations": config.directories.migrations,
             "baselines": config.directories.baselines,
             "roles": config.directories.roles,
+            "data": config.directories.data,
+            "tests": config.directories.tests,
         },
         "migration": {
             "default_mode": config.migration.default_mode,
@@ -310,7 +338,14 @@ fn list_config_values(
         "docker": {
             "auto_cleanup": config.docker.auto_cleanup,
             "check_system_identifier": config.docker.check_system_identifier,
-        }
+        },
+        "dev": {
+            "snapshot_retention": config.dev.snapshot_retention,
+        },
+        "variables": config
+            .variables
+            .iter()
+            .collect::<std::collections::BTreeMap<_, _>>(),
     });
 
     match format {
@@ -112,6 +112,10 @@ pub enum ObjectIdJson {
         table: String,
         column: String,
     },
+    TableData {
+        schema: String,
+        table: String,
+    },
 }
 
 impl From<&DbObjectId> for ObjectIdJson {
@@ -227,6 +231,10 @@ impl From<&DbObjectId> for ObjectIdJson {
                 table: table.clone(),
                 column: column.clone(),
             },
+            DbObjectId::TableData { schema, table } => ObjectIdJson::TableData {
+                schema: schema.clone(),
+                table: table.clone(),
+            },
         }
     }
 }
@@ -269,7 +277,7 @@ pub async fn cmd_debug_dependencies(
 
     // Apply roles file before schema (if it exists)
     let roles_file = root_dir.join(&config.directories.roles);
-    apply_roles_file(&shadow_pool, &roles_file).await?;
+    apply_roles_file(&shadow_pool, &roles_file, &config.variables).await?;
 
     // Process schema with file dependency tracking (skip cleaning since we already did it)
     let processor = SchemaProcessor::new(
@@ -278,6 +286,7 @@ pub async fn cmd_debug_dependencies(
             verbose: false,
             clean_before_apply: false,
             objects: config.objects.clone(),
+            variables: config.variables.clone(),
         },
     );
 
@@ -523,5 +532,8 @@ fn format_object_id(obj: &ObjectIdJson) -> String {
         } => {
             format!("Column: {}.{}.{}", schema, table, column)
         }
+        ObjectIdJson::TableData { schema, table } => {
+            format!("Data: {}.{}", schema, table)
+        }
     }
 }
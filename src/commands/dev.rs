@@ -0,0 +1,99 @@
+//! `pgmt dev snapshots`: the dev database snapshots `pgmt apply` takes before
+//! destructive changes (see `db::snapshot`).
+
+use crate::config::{Config, DevUrl, DevUrlArgs};
+use crate::db::snapshot::{list_snapshots, prune_snapshots, restore_snapshot};
+use anyhow::Result;
+
+use super::baseline::format_size;
+
+/// Dev subcommands
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum DevCommands {
+    /// Snapshots of the dev database taken before destructive applies
+    Snapshots {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+}
+
+/// `pgmt dev snapshots` subcommands
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum SnapshotCommands {
+    /// List the dev database's snapshots, newest first
+    List {
+        #[command(flatten)]
+        dev: DevUrlArgs,
+    },
+
+    /// Replace the dev database with a copy of a snapshot (the snapshot is
+    /// kept)
+    Restore {
+        /// Snapshot name, as shown by `pgmt dev snapshots list`
+        name: String,
+
+        #[command(flatten)]
+        dev: DevUrlArgs,
+    },
+
+    /// Drop all but the newest snapshots
+    Prune {
+        /// How many snapshots to keep [default: dev.snapshot_retention]
+        #[arg(long)]
+        keep: Option<usize>,
+
+        #[command(flatten)]
+        dev: DevUrlArgs,
+    },
+}
+
+/// List the dev database's snapshots
+pub async fn cmd_dev_snapshots_list(dev: &DevUrl) -> Result<()> {
+    let snapshots = list_snapshots(dev.as_str()).await?;
+    if snapshots.is_empty() {
+        println!("No snapshots of the dev database");
+        return Ok(());
+    }
+
+    println!("Found {} snapshot(s), newest first:", snapshots.len());
+    println!();
+    for snapshot in &snapshots {
+        println!(
+            "  {} ({}, {})",
+            snapshot.name,
+            snapshot
+                .created_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S"),
+            format_size(snapshot.size_bytes.max(0) as u64)
+        );
+        println!("    Before: {}", snapshot.reason);
+    }
+    Ok(())
+}
+
+/// Replace the dev database with a copy of snapshot `name`
+pub async fn cmd_dev_snapshots_restore(dev: &DevUrl, name: &str) -> Result<()> {
+    restore_snapshot(dev.as_str(), name, false).await?;
+    println!("⏪ Restored the dev database from {}", name);
+    Ok(())
+}
+
+/// Drop all but the `keep` newest snapshots
+pub async fn cmd_dev_snapshots_prune(
+    config: &Config,
+    dev: &DevUrl,
+    keep: Option<usize>,
+) -> Result<()> {
+    let keep = keep.unwrap_or(config.dev.snapshot_retention);
+    let dropped = prune_snapshots(dev.as_str(), keep).await?;
+    if dropped.is_empty() {
+        println!("Nothing to prune (keeping up to {} snapshot(s))", keep);
+    } else {
+        for name in &dropped {
+            println!("🗑️  Dropped {}", name);
+        }
+        println!("Pruned {} snapshot(s)", dropped.len());
+    }
+    Ok(())
+}
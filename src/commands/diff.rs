@@ -2,13 +2,18 @@
 //!
 //! This command shows what changes would be applied by `pgmt apply`.
 //! It compares your schema files (source of truth) to the dev database.
+//! Either side can come from a catalog snapshot instead, and
+//! `--from-ref`/`--to-ref` compare the schema files at two git revisions.
 
 use crate::catalog::Catalog;
-use crate::config::{Config, ObjectFilter};
+use crate::commands::catalog::{CatalogSource, load_database_side, load_schema_side};
+use crate::config::{Config, DevUrl, ShadowDatabase};
 use crate::diff::plan;
 use crate::schema_ops::apply_current_schema_to_shadow;
-use anyhow::Result;
-use std::path::Path;
+use anyhow::{Context, Result, bail};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 use super::diff_output::{DiffContext, has_differences, output_diff};
 
@@ -39,28 +44,28 @@ pub async fn cmd_diff(
     config: &Config,
     root_dir: &Path,
     args: DiffArgs,
-    dev: &crate::config::DevUrl,
-    shadow: &crate::config::ShadowDatabase,
+    dev: &CatalogSource<DevUrl>,
+    shadow: &CatalogSource<ShadowDatabase>,
 ) -> Result<()> {
-    eprintln!("Comparing schema files with dev database...\n");
+    let dev_label = dev.label("dev database");
+    let schema_label = shadow.label("schema files");
+    eprintln!("Comparing {} with {}...\n", schema_label, dev_label);
 
     // Load schema into shadow database
-    eprintln!("Loading schema files...");
-    let schema_catalog = apply_current_schema_to_shadow(config, root_dir, shadow).await?;
+    eprintln!("Loading {}...", schema_label);
+    let schema_catalog = load_schema_side(config, root_dir, shadow).await?;
 
     // Load dev database catalog
-    eprintln!("Loading dev database...");
-    let dev_pool =
-        crate::db::connection::connect_to_database(dev.as_str(), "development database").await?;
-    let filter = ObjectFilter::from_config(config);
-    let dev_catalog = Catalog::load_managed(&dev_pool, &filter).await?;
+    eprintln!("Loading {}...", dev_label);
+    let dev_catalog =
+        load_database_side(config, dev, DevUrl::as_str, "development database").await?;
 
     // Compute differences (dev -> schema, so SQL shows how to update dev)
     eprintln!("Computing differences...\n");
     let ordered_steps = plan(&dev_catalog, &schema_catalog)?;
 
     // Output results
-    let context = DiffContext::new("dev database", "schema files");
+    let context = DiffContext::new(&dev_label, &schema_label);
     output_diff(
         &ordered_steps,
         &args.format,
@@ -77,3 +82,175 @@ pub async fn cmd_diff(
 
     Ok(())
 }
+
+/// Compare the schema files at git revision `from_ref` with those at `to_ref`
+/// (or in the catalog snapshot `to_snapshot`, or in the working tree)
+///
+/// Each side is built on its own pristine shadow, so the output is the DDL
+/// the change between the revisions amounts to. Both sides use the current
+/// pgmt.yaml.
+pub async fn cmd_diff_refs(
+    config: &Config,
+    root_dir: &Path,
+    args: DiffArgs,
+    from_ref: &str,
+    to_ref: Option<&str>,
+    to_snapshot: Option<&Path>,
+    shadow: &ShadowDatabase,
+) -> Result<()> {
+    let from_label = format!("schema files at {}", from_ref);
+    let to_source = match to_snapshot {
+        Some(path) => CatalogSource::Snapshot(path.to_path_buf()),
+        None => CatalogSource::Live(shadow.clone()),
+    };
+    let to_label = match to_ref {
+        Some(rev) => format!("schema files at {}", rev),
+        None => to_source.label("schema files"),
+    };
+    eprintln!("Comparing {} with {}...\n", to_label, from_label);
+
+    eprintln!("Loading {}...", from_label);
+    let from_catalog = catalog_at_rev(config, root_dir, from_ref, shadow).await?;
+
+    eprintln!("Loading {}...", to_label);
+    let to_catalog = match to_ref {
+        Some(rev) => catalog_at_rev(config, root_dir, rev, shadow).await?,
+        None => load_schema_side(config, root_dir, &to_source).await?,
+    };
+
+    eprintln!("Computing differences...\n");
+    let ordered_steps = plan(&from_catalog, &to_catalog)?;
+
+    let context = DiffContext::new(&from_label, &to_label);
+    output_diff(
+        &ordered_steps,
+        &args.format,
+        &context,
+        &from_catalog,
+        &to_catalog,
+        args.output_sql.as_deref(),
+    )?;
+
+    if has_differences(&ordered_steps) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// The desired state described by the schema files at `rev`.
+async fn catalog_at_rev(
+    config: &Config,
+    root_dir: &Path,
+    rev: &str,
+    shadow: &ShadowDatabase,
+) -> Result<Catalog> {
+    let tree = SchemaTree::at_rev(config, root_dir, rev)?;
+    apply_current_schema_to_shadow(config, &tree.root, shadow).await
+}
+
+/// The schema directory, roles file and seed data directory as of a git
+/// revision, extracted into a temporary directory laid out like the project
+/// root. Removed on drop.
+struct SchemaTree {
+    root: PathBuf,
+}
+
+impl SchemaTree {
+    fn at_rev(config: &Config, root_dir: &Path, rev: &str) -> Result<Self> {
+        let commit = git(
+            root_dir,
+            &["rev-parse", "--verify", &format!("{}^{{commit}}", rev)],
+        )
+        .with_context(|| format!("Unknown git revision '{}'", rev))?;
+
+        let tree = SchemaTree {
+            root: std::env::temp_dir().join(format!("pgmt-diff-{}", uuid::Uuid::new_v4().simple())),
+        };
+        // The schema directory may not exist yet at `rev`: that's an empty schema.
+        let schema_dir = tree.root.join(&config.directories.schema);
+        std::fs::create_dir_all(&schema_dir)
+            .with_context(|| format!("Failed to create {}", schema_dir.display()))?;
+
+        // Paths are relative to the project root, which needn't be the
+        // repository root; git resolves them against `-C root_dir`.
+        let tracked = git(
+            root_dir,
+            &[
+                "ls-tree",
+                "-r",
+                "--name-only",
+                &commit,
+                "--",
+                &config.directories.schema,
+                &config.directories.roles,
+                &config.directories.data,
+            ],
+        )?;
+        if tracked.is_empty() {
+            return Ok(tree);
+        }
+
+        let archive = Command::new("git")
+            .arg("-C")
+            .arg(root_dir)
+            .args(["archive", "--format=tar", &commit, "--"])
+            .args(tracked.lines())
+            .output()
+            .context("Failed to run git: diff --from-ref needs git to read the schema files")?;
+        if !archive.status.success() {
+            bail!(
+                "git archive {} failed: {}",
+                rev,
+                String::from_utf8_lossy(&archive.stderr).trim()
+            );
+        }
+
+        let mut tar = Command::new("tar")
+            .arg("-x")
+            .arg("-C")
+            .arg(&tree.root)
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run tar to extract the schema files")?;
+        tar.stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(&archive.stdout)?;
+        let extracted = tar.wait_with_output()?;
+        if !extracted.status.success() {
+            bail!(
+                "Failed to extract the schema files at {}: {}",
+                rev,
+                String::from_utf8_lossy(&extracted.stderr).trim()
+            );
+        }
+
+        Ok(tree)
+    }
+}
+
+impl Drop for SchemaTree {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Run git in the project root and return its trimmed stdout.
+fn git(root_dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root_dir)
+        .args(args)
+        .output()
+        .context("Failed to run git: diff --from-ref needs git to read the schema files")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
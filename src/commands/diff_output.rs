@@ -66,6 +66,21 @@ fn output_sql_format(
     context: &DiffContext,
     output_file: Option<&str>,
 ) -> Result<()> {
+    let output = render_sql(steps, context);
+
+    if let Some(file_path) = output_file {
+        std::fs::write(file_path, &output)?;
+        println!("SQL saved to {}", file_path);
+    } else {
+        println!("{}", output);
+    }
+
+    Ok(())
+}
+
+/// The SQL that brings `context.from_description` in sync with
+/// `context.to_description`, with a header naming both.
+pub fn render_sql(steps: &[MigrationStep], context: &DiffContext) -> String {
     let mut output = String::new();
     output.push_str(&format!(
         "-- SQL to bring {} in sync with {}\n",
@@ -80,14 +95,7 @@ fn output_sql_format(
         }
     }
 
-    if let Some(file_path) = output_file {
-        std::fs::write(file_path, &output)?;
-        println!("SQL saved to {}", file_path);
-    } else {
-        println!("{}", output);
-    }
-
-    Ok(())
+    output
 }
 
 fn output_summary_format(steps: &[MigrationStep]) {
@@ -121,6 +129,7 @@ fn output_summary_format(steps: &[MigrationStep]) {
             MigrationStep::Grant(_) => "Grants",
             MigrationStep::Constraint(_) => "Constraints",
             MigrationStep::Comment(_) => "Comments",
+            MigrationStep::Data(_) => "Seed data",
         };
 
         *counts.entry(type_name).or_insert(0) += 1;
@@ -0,0 +1,380 @@
+//! `pgmt init --adopt-from` — switch a database managed by another migration
+//! tool over to pgmt without re-running anything.
+//!
+//! The other tool's history table says the database is in a clean state (no
+//! failed or dirty migration); the live catalog must then match both the
+//! schema files and the latest baseline plus the migrations after it, replayed
+//! on the shadow. Once all of that holds, the latest baseline and every
+//! migration after it are recorded as applied, each section `satisfied`, so
+//! the next `migrate apply` starts from there. The other tool's tables are
+//! left in place.
+
+use crate::catalog::Catalog;
+use crate::commands::migrate::status::relation_exists;
+use crate::config::filter::ObjectFilter;
+use crate::config::{Config, ShadowDatabase, TargetUrl};
+use crate::migration::parsing::ParsedBaseline;
+use crate::migration::{
+    BaselineConfig, discover_migrations, find_latest_baseline, get_migration_starting_state,
+    parse_migration_sections_with_variables,
+};
+use crate::migration_tracking::{
+    MigrationLock, TrackingStore, calculate_checksum, ensure_section_tracking_table,
+    ensure_tracking_table_exists, register_adopted,
+};
+use crate::validation::{
+    ValidationConfig, validate_catalogs, validate_database_against_schema_files,
+};
+use anyhow::{Context, Result, bail};
+use sqlx::PgPool;
+use std::path::Path;
+
+/// Migration tools whose history `pgmt init --adopt-from` can take over
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum AdoptFrom {
+    /// Flyway (`flyway_schema_history`)
+    Flyway,
+    /// golang-migrate (`schema_migrations` with a dirty flag)
+    GolangMigrate,
+    /// Rails / ActiveRecord (`schema_migrations` and `ar_internal_metadata`)
+    Rails,
+    /// Sqitch (the `sqitch` registry schema)
+    Sqitch,
+}
+
+impl AdoptFrom {
+    fn name(self) -> &'static str {
+        match self {
+            AdoptFrom::Flyway => "Flyway",
+            AdoptFrom::GolangMigrate => "golang-migrate",
+            AdoptFrom::Rails => "Rails",
+            AdoptFrom::Sqitch => "Sqitch",
+        }
+    }
+
+    /// The history table, resolved through the search path.
+    fn history_table(self) -> &'static str {
+        match self {
+            AdoptFrom::Flyway => "flyway_schema_history",
+            AdoptFrom::GolangMigrate | AdoptFrom::Rails => "schema_migrations",
+            AdoptFrom::Sqitch => "sqitch.changes",
+        }
+    }
+
+    /// Schemas and tables that belong to the tool rather than the
+    /// application, left out of the comparison with the schema files.
+    fn own_objects(self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self {
+            AdoptFrom::Flyway => (&[], &["flyway_schema_history"]),
+            AdoptFrom::GolangMigrate => (&[], &["schema_migrations"]),
+            AdoptFrom::Rails => (&[], &["schema_migrations", "ar_internal_metadata"]),
+            AdoptFrom::Sqitch => (&["sqitch"], &[]),
+        }
+    }
+}
+
+/// What the other tool's history says is applied.
+struct ForeignHistory {
+    applied: i64,
+    latest: String,
+}
+
+pub async fn cmd_init_adopt(
+    config: &Config,
+    root_dir: &Path,
+    target: &TargetUrl,
+    tool: AdoptFrom,
+    shadow: &ShadowDatabase,
+) -> Result<()> {
+    if config.modules.is_enabled() {
+        bail!(
+            "--adopt-from is not supported on module projects: adoption records every \
+             baseline section as applied, and module establishment has to be chosen per \
+             target with `pgmt migrate provision --modules`"
+        );
+    }
+
+    let baselines_dir = root_dir.join(&config.directories.baselines);
+    let Some(baseline) = find_latest_baseline(&baselines_dir)? else {
+        bail!(
+            "No baseline in {}: adoption records one as already applied. Create it from the \
+             schema files with `pgmt migrate baseline`, then re-run.",
+            baselines_dir.display()
+        );
+    };
+
+    let pool =
+        crate::db::connection::connect_to_database(target.as_str(), "target database").await?;
+    let lock = MigrationLock::acquire(target.as_str(), &config.migration.tracking_table).await?;
+    let result = adopt(config, root_dir, &pool, tool, shadow, &baseline).await;
+    lock.release().await?;
+    result
+}
+
+async fn adopt(
+    config: &Config,
+    root_dir: &Path,
+    pool: &PgPool,
+    tool: AdoptFrom,
+    shadow: &ShadowDatabase,
+    baseline: &ParsedBaseline,
+) -> Result<()> {
+    let tracking_table = &config.migration.tracking_table;
+    let store = TrackingStore::new(pool, tracking_table)?;
+    if relation_exists(pool, store.main_table()).await? && !store.main_table_is_empty().await? {
+        bail!(
+            "this database is already tracked by pgmt ({} has rows); there is nothing to adopt",
+            store.main_table()
+        );
+    }
+
+    let history = read_foreign_history(pool, tool).await?;
+    println!(
+        "{} history: {} migration(s) applied, latest {}",
+        tool.name(),
+        history.applied,
+        history.latest
+    );
+
+    // The tool's own tables aren't in the schema files; leave them out of
+    // the comparison.
+    let mut adopt_config = config.clone();
+    let (schemas, tables) = tool.own_objects();
+    adopt_config
+        .objects
+        .exclude
+        .schemas
+        .extend(schemas.iter().map(|s| s.to_string()));
+    adopt_config
+        .objects
+        .exclude
+        .tables
+        .extend(tables.iter().map(|t| t.to_string()));
+
+    println!("Comparing the database with the schema files...");
+    let live = Catalog::load_managed(pool, &ObjectFilter::from_config(&adopt_config)).await?;
+    let result = validate_database_against_schema_files(
+        &live,
+        &adopt_config,
+        root_dir,
+        &ValidationConfig {
+            show_differences: true,
+            verbose: false,
+        },
+        shadow,
+    )
+    .await?;
+    if !result.passed {
+        println!("{}", result.message);
+        bail!(
+            "the database differs from the schema files ({} difference(s)); adopting would \
+             record history that isn't true here. Bring the schema files (or the database) \
+             in line, then re-run",
+            result.differences.len()
+        );
+    }
+
+    // Matching the schema files isn't enough: the history about to be
+    // recorded must itself reproduce the database, or `migrate new` (which
+    // replays it) would diff against a state this target isn't in.
+    println!(
+        "Replaying baseline {} and the migrations after it...",
+        baseline.version
+    );
+    let replay_pool = shadow.connect_fresh().await?;
+    let replayed = get_migration_starting_state(
+        &replay_pool,
+        &root_dir.join(&config.directories.baselines),
+        &root_dir.join(&config.directories.migrations),
+        &root_dir.join(&config.directories.roles),
+        &BaselineConfig {
+            validate_consistency: false,
+            verbose: false,
+        },
+        &adopt_config,
+    )
+    .await;
+    crate::db::branch::drop_branch(replay_pool).await?;
+    let replayed = replayed.context("Failed to replay the baseline and migrations")?;
+    let result = validate_catalogs(
+        &live,
+        &replayed,
+        &adopt_config,
+        &ValidationConfig {
+            show_differences: true,
+            verbose: false,
+        },
+    )?;
+    if !result.passed {
+        println!("{}", result.message);
+        bail!(
+            "baseline {} plus the migrations after it don't reproduce the database ({} \
+             difference(s)); adopting would record history that isn't true here. Regenerate \
+             the baseline or fix the migrations, then re-run",
+            baseline.version,
+            result.differences.len()
+        );
+    }
+
+    ensure_tracking_table_exists(pool, tracking_table).await?;
+    ensure_section_tracking_table(pool, tracking_table).await?;
+
+    let baseline_sql = std::fs::read_to_string(&baseline.path)
+        .with_context(|| format!("Failed to read baseline file: {}", baseline.path.display()))?;
+    let sections = numbered_sections(config, &baseline.path, &baseline_sql)?;
+    // One transaction: a half-recorded adoption would read as pgmt-managed
+    // with migrations pending that are already in the database.
+    let mut tx = pool.begin().await?;
+    register_adopted(
+        &mut tx,
+        tracking_table,
+        baseline.version,
+        "baseline",
+        &calculate_checksum(&baseline_sql),
+        true,
+        &sections,
+    )
+    .await?;
+    println!("  Recorded baseline {} as applied", baseline.version);
+
+    // The replay matches, so migrations after the baseline are in the
+    // database too.
+    let migrations = discover_migrations(&root_dir.join(&config.directories.migrations))?;
+    for migration in migrations.iter().filter(|m| m.version > baseline.version) {
+        let sql = std::fs::read_to_string(&migration.path).with_context(|| {
+            format!(
+                "Failed to read migration file: {}",
+                migration.path.display()
+            )
+        })?;
+        let sections = numbered_sections(config, &migration.path, &sql)?;
+        register_adopted(
+            &mut tx,
+            tracking_table,
+            migration.version,
+            &migration.description,
+            &calculate_checksum(&sql),
+            false,
+            &sections,
+        )
+        .await?;
+        println!(
+            "  Recorded migration {} - {} as applied",
+            migration.version, migration.description
+        );
+    }
+
+    tx.commit()
+        .await
+        .context("Failed to record the adopted history")?;
+
+    println!("✅ Adopted from {}; nothing was run.", tool.name());
+    let (schemas, tables) = tool.own_objects();
+    let leftovers: Vec<&str> = schemas.iter().chain(tables).copied().collect();
+    println!(
+        "   {}'s {} stay in place: drop them once nothing uses {}, or add them to \
+         objects.exclude so pgmt leaves them alone.",
+        tool.name(),
+        leftovers.join(", "),
+        tool.name()
+    );
+    Ok(())
+}
+
+fn numbered_sections(
+    config: &Config,
+    path: &Path,
+    sql: &str,
+) -> Result<Vec<(i32, crate::migration::section_parser::MigrationSection)>> {
+    let sections = parse_migration_sections_with_variables(path, sql, &config.variables)?;
+    crate::migration::validate_sections(&sections)
+        .with_context(|| format!("Invalid section configuration in {}", path.display()))?;
+    Ok(sections
+        .into_iter()
+        .enumerate()
+        .map(|(i, s)| (i as i32, s))
+        .collect())
+}
+
+/// Read the tool's history, refusing a database it left mid-migration.
+async fn read_foreign_history(pool: &PgPool, tool: AdoptFrom) -> Result<ForeignHistory> {
+    let table = tool.history_table();
+    if !relation_exists(pool, table).await? {
+        bail!(
+            "no {} history here: {} does not exist. Check the target URL and --adopt-from",
+            tool.name(),
+            table
+        );
+    }
+
+    let (applied, latest): (i64, Option<String>) = match tool {
+        AdoptFrom::Flyway => {
+            let failed: i64 =
+                sqlx::query_scalar("SELECT count(*) FROM flyway_schema_history WHERE NOT success")
+                    .fetch_one(pool)
+                    .await?;
+            if failed > 0 {
+                bail!(
+                    "flyway_schema_history records {} failed migration(s); fix them and run \
+                     `flyway repair` before adopting",
+                    failed
+                );
+            }
+            sqlx::query_as(
+                "SELECT count(*),
+                        (SELECT coalesce(version, description) FROM flyway_schema_history
+                         WHERE type <> 'SCHEMA' ORDER BY installed_rank DESC LIMIT 1)
+                 FROM flyway_schema_history WHERE type <> 'SCHEMA'",
+            )
+            .fetch_one(pool)
+            .await?
+        }
+        AdoptFrom::GolangMigrate => {
+            let row: Option<(i64, bool)> =
+                sqlx::query_as("SELECT version, dirty FROM schema_migrations LIMIT 1")
+                    .fetch_optional(pool)
+                    .await?;
+            if let Some((version, true)) = row {
+                bail!(
+                    "schema_migrations is dirty at version {}: a migration failed part-way. \
+                     Fix the database and run `migrate force {}` before adopting",
+                    version,
+                    version
+                );
+            }
+            (
+                i64::from(row.is_some()),
+                row.map(|(version, _)| version.to_string()),
+            )
+        }
+        AdoptFrom::Rails => {
+            sqlx::query_as(
+                "SELECT count(*),
+                        (SELECT version::text FROM schema_migrations
+                         ORDER BY length(version::text) DESC, version::text DESC LIMIT 1)
+                 FROM schema_migrations",
+            )
+            .fetch_one(pool)
+            .await?
+        }
+        AdoptFrom::Sqitch => {
+            sqlx::query_as(
+                "SELECT count(*),
+                        (SELECT change FROM sqitch.changes ORDER BY committed_at DESC LIMIT 1)
+                 FROM sqitch.changes",
+            )
+            .fetch_one(pool)
+            .await?
+        }
+    };
+
+    let Some(latest) = latest.filter(|_| applied > 0) else {
+        bail!(
+            "{} is empty: {} never applied anything here. Use `pgmt migrate provision` to set \
+             up an empty database",
+            table,
+            tool.name()
+        );
+    };
+    Ok(ForeignHistory { applied, latest })
+}
@@ -878,7 +878,7 @@ async fn validate_schema_files_impl(
     if let Some(roles_path) = roles_file
         && roles_path.exists()
     {
-        crate::schema_ops::apply_roles_file(&pool, roles_path).await?;
+        crate::schema_ops::apply_roles_file(&pool, roles_path, &Default::default()).await?;
     }
 
     // Process schema directory (loads, orders, and applies all files)
@@ -29,7 +29,7 @@ pub async fn import_from_sql_file(
         && roles_path.exists()
     {
         tracing::debug!("Applying roles from: {}", roles_path.display());
-        crate::schema_ops::apply_roles_file(&pool, roles_path).await?;
+        crate::schema_ops::apply_roles_file(&pool, roles_path, &Default::default()).await?;
     }
 
     // Configure SQL executor for import scenario (quiet mode for init)
@@ -1,3 +1,4 @@
+pub mod adopt;
 pub mod commands;
 pub mod import;
 pub mod project;
@@ -6,6 +7,7 @@ pub mod prompts;
 use clap::Parser;
 
 // Re-export command functions and types
+pub use adopt::{AdoptFrom, cmd_init_adopt};
 pub use commands::{
     BaselineCreationConfig, DatabaseState, InitOptions, ObjectManagementConfig, cmd_init_with_args,
 };
@@ -77,4 +79,14 @@ pub struct InitArgs {
     /// Force fresh initialization (overwrite existing config)
     #[clap(long)]
     pub fresh: bool,
+
+    /// Take over an existing database from another migration tool: check it
+    /// matches the schema files, then record the latest baseline and later
+    /// migrations as applied. Runs in an existing project.
+    #[clap(long, value_enum, conflicts_with = "fresh")]
+    pub adopt_from: Option<AdoptFrom>,
+
+    /// Database to adopt with --adopt-from
+    #[clap(flatten)]
+    pub target: crate::config::TargetUrlArgs,
 }
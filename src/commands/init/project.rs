@@ -135,6 +135,10 @@ fn gathered_config_input(options: &super::InitOptions) -> crate::config::types::
             migrations_dir: Some(options.migrations_dir.clone()),
             baselines_dir: Some(options.baselines_dir.clone()),
             roles_file: options.roles_file.clone(),
+            repeatable_dir: None,
+            hooks_dir: None,
+            data_dir: None,
+            tests_dir: None,
         }),
         ..Default::default()
     }
@@ -1,18 +1,26 @@
+use crate::commands::migrate::hooks::{HookContext, HookPoint, MigrationHooks};
+use crate::commands::migrate::repeatable::{
+    apply_repeatable_migrations, discover_repeatable_migrations,
+};
 use crate::commands::migrate::section_executor::{ExecutionMode, SectionExecutor};
 use crate::config::Config;
+use crate::migration::parsing::find_migration_by_version;
 use crate::migration::{
-    ParsedMigration, discover_migrations, parse_migration_sections, validate_sections,
+    ParsedMigration, discover_migrations, parse_migration_sections_with_variables,
+    validate_sections,
 };
 use crate::migration_tracking::section_tracking::{
-    RecordedSections, section_statuses, validate_and_sync_section_checksums,
+    RecordedSections, SectionStatus, section_statuses, validate_and_sync_section_checksums,
 };
 use crate::migration_tracking::{
-    MigrationLock, calculate_checksum, ensure_section_tracking_table, ensure_tracking_table_exists,
-    format_tracking_table_name, initialize_sections, register_migration_start, version_from_db,
+    AuditLog, MigrationLock, calculate_checksum, ensure_repeatable_tracking_table,
+    ensure_section_tracking_table, ensure_tracking_table_exists, format_tracking_table_name,
+    initialize_sections, register_migration_start, version_from_db,
 };
 use crate::modules::{ModuleRuntime, ModuleSelection, SectionClassification, SkipNotice};
 use crate::progress::SectionReporter;
-use anyhow::{Context, Result};
+use crate::schema_loader::SchemaFile;
+use anyhow::{Context, Result, anyhow, bail};
 use sqlx::PgPool;
 use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
@@ -24,6 +32,8 @@ pub async fn cmd_migrate_apply(
     root_dir: &Path,
     target: &crate::config::TargetUrl,
     selection: ModuleSelection,
+    limit: ApplyLimit,
+    deploy_id: Option<String>,
 ) -> Result<()> {
     println!("Applying migrations to target database");
 
@@ -39,7 +49,10 @@ pub async fn cmd_migrate_apply(
     // directory.
     let migrations = discover_migrations(&migrations_dir)?;
     let has_baselines = !crate::migration::discover_baselines(&baselines_dir)?.is_empty();
-    if !migrations_dir.exists() && !has_baselines {
+    let repeatables = discover_repeatable_migrations(config, root_dir)?;
+    let hooks = MigrationHooks::load(config, root_dir)?;
+    let audit = AuditLog::from_config(config, deploy_id);
+    if !migrations_dir.exists() && !has_baselines && repeatables.is_empty() {
         println!("No migrations directory found - nothing to apply");
         return Ok(());
     }
@@ -53,19 +66,104 @@ pub async fn cmd_migrate_apply(
     // otherwise).
     let lock = MigrationLock::acquire(target.as_str(), &config.migration.tracking_table).await?;
 
-    let result = apply_with_module_guard(config, root_dir, &pool, &migrations, &selection).await;
+    let result = apply_with_module_guard(
+        config,
+        root_dir,
+        &pool,
+        &migrations,
+        &repeatables,
+        &selection,
+        &hooks,
+        &limit,
+        audit.as_ref(),
+    )
+    .await;
     lock.release().await?;
     result
 }
 
+/// Where `migrate apply` stops short of everything pending: `--to V` runs
+/// nothing above V, `--count N` stops once N migrations have run (resumed
+/// ones included). The default has no limit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApplyLimit {
+    pub to: Option<u64>,
+    pub count: Option<usize>,
+}
+
+impl ApplyLimit {
+    /// Resolve `--to` (a version or unique version prefix, `V` optional)
+    /// against the migration files.
+    pub fn resolve(migrations_dir: &Path, to: Option<&str>, count: Option<usize>) -> Result<Self> {
+        let to = match to {
+            Some(version_str) => Some(
+                find_migration_by_version(migrations_dir, version_str)?
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Migration '{}' not found. Use 'pgmt migrate status' to see \
+                             pending migrations.",
+                            version_str
+                        )
+                    })?
+                    .version,
+            ),
+            None => None,
+        };
+        Ok(Self { to, count })
+    }
+
+    /// Whether `version` is past the limit, with `applied` migrations run so
+    /// far.
+    fn excludes(&self, version: u64, applied: usize) -> bool {
+        self.to.is_some_and(|to| version > to) || self.count.is_some_and(|count| applied >= count)
+    }
+}
+
+/// Pending migrations older than the newest applied one: no migration row,
+/// above the newest recorded baseline, and below some recorded migration.
+/// `migrate apply` only runs these with `migration.allow_out_of_order`.
+pub(crate) fn out_of_order_migrations(
+    migrations: &[ParsedMigration],
+    baseline_version: Option<u64>,
+    applied: impl IntoIterator<Item = u64>,
+) -> Vec<&ParsedMigration> {
+    let applied: BTreeSet<u64> = applied.into_iter().collect();
+    let Some(&newest) = applied.last() else {
+        return Vec::new();
+    };
+    migrations
+        .iter()
+        .filter(|m| {
+            m.version < newest
+                && !baseline_version.is_some_and(|bv| m.version <= bv)
+                && !applied.contains(&m.version)
+        })
+        .collect()
+}
+
+/// What [`apply_pending_migrations`] did.
+pub(crate) struct ApplyOutcome {
+    /// At least one migration ran or resumed.
+    pub applied_any: bool,
+    /// The first pending or unfinished migration the [`ApplyLimit`] held
+    /// back, if any.
+    pub stopped_before: Option<u64>,
+}
+
 /// The module-aware body of `migrate apply`, split out so the advisory lock
 /// wraps every path (including the guards' refusals) with a single release.
-async fn apply_with_module_guard(
+/// `migrate apply --rehearse` runs it unlocked, on its branch.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn apply_with_module_guard(
     config: &Config,
     root_dir: &Path,
     pool: &PgPool,
     migrations: &[ParsedMigration],
+    repeatables: &[SchemaFile],
     selection: &ModuleSelection,
+    hooks: &MigrationHooks,
+    limit: &ApplyLimit,
+    audit: Option<&AuditLog>,
 ) -> Result<()> {
     // A baseline row whose registered sections aren't all completed is a
     // crashed/incomplete `provision`. Its version must NOT be blindly trusted as
@@ -91,6 +189,10 @@ async fn apply_with_module_guard(
     //   selection actually names one of the half-built modules.
     ensure_tracking_table_exists(pool, &config.migration.tracking_table).await?;
     ensure_section_tracking_table(pool, &config.migration.tracking_table).await?;
+    ensure_repeatable_tracking_table(pool, &config.migration.tracking_table).await?;
+    if let Some(audit) = audit {
+        audit.ensure_table(pool).await?;
+    }
     let store =
         crate::migration_tracking::TrackingStore::new(pool, &config.migration.tracking_table)?;
 
@@ -233,12 +335,55 @@ async fn apply_with_module_guard(
         runtime.record_adopted(&to_adopt).await?;
     }
 
-    let applied_any =
-        apply_pending_migrations(pool, config, migrations, selection, &mut runtime).await?;
-    if !applied_any {
-        println!("Nothing to apply — up to date.");
+    // Hooks bracket the whole run, including an up-to-date one, and only run
+    // once the guards above have let it proceed.
+    let hook_reporter = SectionReporter::new(0, false);
+    hooks
+        .run(
+            pool,
+            HookPoint::BeforeMigrate,
+            HookContext::default(),
+            &hook_reporter,
+        )
+        .await?;
+
+    let outcome = apply_pending_migrations(
+        pool,
+        config,
+        migrations,
+        selection,
+        &mut runtime,
+        hooks,
+        limit,
+        audit,
+    )
+    .await?;
+
+    // Repeatable migrations run last, against the fully migrated schema. They
+    // are not module-scoped: every apply re-runs whichever ones changed. A
+    // limited apply that left migrations pending isn't fully migrated, so they
+    // wait for the apply that finishes the job.
+    if let Some(next) = outcome.stopped_before {
+        println!(
+            "\nStopped before migration {next}; repeatable migrations run once nothing is \
+             pending. See what remains with `pgmt migrate status`."
+        );
+    } else {
+        let applied_repeatable =
+            apply_repeatable_migrations(pool, &store, repeatables, &config.variables).await?;
+        if !outcome.applied_any && !applied_repeatable {
+            println!("Nothing to apply — up to date.");
+        }
     }
-    Ok(())
+
+    hooks
+        .run(
+            pool,
+            HookPoint::AfterMigrate,
+            HookContext::default(),
+            &hook_reporter,
+        )
+        .await
 }
 
 /// Apply migration files to a database, skipping any already recorded in the
@@ -264,18 +409,32 @@ async fn apply_with_module_guard(
 /// is the strong membrane: nothing at or beyond V — base sections included —
 /// executes.
 ///
-/// Returns `true` if at least one migration actually ran or resumed here, so
+/// `hooks` runs `after_each_section` after every executed section; provision
+/// passes none.
+///
+/// `limit` stops the loop early (`migrate apply --to/--count`); provision
+/// passes the default, which applies everything. Migrations past the limit are
+/// left alone, and so are re-anchors past it: the final sweep only crosses
+/// through the limit.
+///
+/// Reports whether at least one migration actually ran or resumed here, so
 /// the caller can emit a "nothing to apply" closing line when the target was
-/// already up to date.
+/// already up to date, and where a limit held back pending migrations.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn apply_pending_migrations(
     pool: &PgPool,
     config: &Config,
     migrations: &[ParsedMigration],
     selection: &ModuleSelection,
     runtime: &mut ModuleRuntime,
-) -> Result<bool> {
+    hooks: &MigrationHooks,
+    limit: &ApplyLimit,
+    audit: Option<&AuditLog>,
+) -> Result<ApplyOutcome> {
     let tracking_table_name = format_tracking_table_name(&config.migration.tracking_table)?;
-    let mut applied_any = false;
+    let mut applied = 0;
+    let mut processed_through = None;
+    let mut stopped_before = None;
 
     // All tracking rows: version + checksum + is_baseline.
     let rows: Vec<(i64, String, bool)> = sqlx::query_as(sqlx::AssertSqlSafe(format!(
@@ -303,6 +462,37 @@ pub(crate) async fn apply_pending_migrations(
         .map(|(v, checksum, _)| (version_from_db(v), checksum))
         .collect();
 
+    // Pending migrations older than the newest applied one: a migration merged
+    // (e.g. from a hotfix branch) after newer ones already ran here. Applying
+    // it runs history in a different order than every other replay, so that
+    // takes an explicit opt-in.
+    let out_of_order = out_of_order_migrations(
+        migrations,
+        baseline_version,
+        applied_migrations.keys().copied(),
+    );
+    if !out_of_order.is_empty() {
+        let listed: Vec<String> = out_of_order
+            .iter()
+            .map(|m| format!("{} ({})", m.version, m.description))
+            .collect();
+        if !config.migration.allow_out_of_order {
+            bail!(
+                "{} pending migration(s) are older than the newest applied migration: {}. \
+                 Apply them anyway with --allow-out-of-order (or `migration.allow_out_of_order: \
+                 true`), and check with 'pgmt migrate validate' that the result still matches \
+                 the schema files",
+                out_of_order.len(),
+                listed.join(", ")
+            );
+        }
+        println!(
+            "Applying {} migration(s) out of order: {}",
+            out_of_order.len(),
+            listed.join(", ")
+        );
+    }
+
     // Every recorded migration section row in ONE query, rather than one read
     // per migration file. The snapshot's precondition holds here: the loop
     // reads a version's rows within that version's own iteration and before
@@ -312,6 +502,39 @@ pub(crate) async fn apply_pending_migrations(
 
     // Apply unapplied migrations
     for migration in migrations {
+        if limit.excludes(migration.version, applied) {
+            // Past the limit: nothing here runs. Keep looking only to name
+            // what was actually held back — the first pending migration, and
+            // any started one left unfinished (status reports those too, so
+            // a limited apply must not pass over them in silence).
+            if baseline_version.is_some_and(|bv| migration.version <= bv) {
+                continue;
+            }
+            if !applied_migrations.contains_key(&migration.version) {
+                stopped_before.get_or_insert(migration.version);
+                continue;
+            }
+            let unfinished = recorded_sections
+                .for_version(migration.version)
+                .iter()
+                .filter(|s| {
+                    !s.status
+                        .parse::<SectionStatus>()
+                        .is_ok_and(|status| status.is_covered())
+                })
+                .count();
+            if unfinished > 0 {
+                println!(
+                    "Migration {} ({}) is INCOMPLETE: {} section(s) pending or failed — past \
+                     the limit, so it was not resumed",
+                    migration.version, migration.description, unfinished
+                );
+                stopped_before.get_or_insert(migration.version);
+            }
+            continue;
+        }
+        processed_through = Some(migration.version);
+
         // Crossing loop, phase order: consume every re-anchor STRICTLY
         // below this version single-shot (their versions are settled). A
         // re-anchor AT this version is two-phase — gate-checked now (before
@@ -376,8 +599,12 @@ pub(crate) async fn apply_pending_migrations(
         let checksum = calculate_checksum(&migration_sql);
 
         // Parse migration into sections
-        let sections = parse_migration_sections(&migration.path, &migration_sql)
-            .with_context(|| format!("Failed to parse migration {}", migration.version))?;
+        let sections = parse_migration_sections_with_variables(
+            &migration.path,
+            &migration_sql,
+            &config.variables,
+        )
+        .with_context(|| format!("Failed to parse migration {}", migration.version))?;
 
         // Validate sections
         validate_sections(&sections).with_context(|| {
@@ -550,7 +777,7 @@ pub(crate) async fn apply_pending_migrations(
                 .iter()
                 .filter(|(_, s)| statuses.get(&s.name).is_some_and(|st| st.is_covered()))
                 .count();
-            applied_any = true;
+            applied += 1;
             println!(
                 "\nResuming migration {} - {} ({}/{} selected sections already complete)",
                 migration.version,
@@ -605,7 +832,7 @@ pub(crate) async fn apply_pending_migrations(
                 commit_pending!();
                 continue;
             }
-            applied_any = true;
+            applied += 1;
             println!(
                 "\nApplying migration {} - {}",
                 migration.version, migration.description
@@ -655,7 +882,8 @@ pub(crate) async fn apply_pending_migrations(
             reporter,
             ExecutionMode::Production,
             false,
-        );
+        )
+        .with_audit(audit.cloned());
 
         // Execute each to-run section (any already-covered ones would skip
         // inside — defense-in-depth; the classifier already excluded them).
@@ -669,6 +897,24 @@ pub(crate) async fn apply_pending_migrations(
                         migration.version, section.name
                     )
                 })?;
+            hooks
+                .run(
+                    pool,
+                    HookPoint::AfterEachSection,
+                    HookContext {
+                        version: Some(migration.version),
+                        description: Some(&migration.description),
+                        section: Some(&section.name),
+                    },
+                    executor.reporter(),
+                )
+                .await
+                .with_context(|| {
+                    format!(
+                        "Migration {} stopped after section '{}' (the section is applied)",
+                        migration.version, section.name
+                    )
+                })?;
         }
 
         let duration = start.elapsed();
@@ -690,8 +936,16 @@ pub(crate) async fn apply_pending_migrations(
     // beyond the last migration. This is what makes a pure re-tag (a
     // re-anchor with no accompanying DDL) land on the next bare apply of a
     // fully-up-to-date target — the loop keys off the derived cursor, not off
-    // pending migrations.
-    runtime.cross_re_anchors_through(None).await?;
-
-    Ok(applied_any)
+    // pending migrations. A limited apply sweeps only through its limit.
+    let ceiling = match (limit.to, stopped_before) {
+        (Some(to), _) => Some(to),
+        (None, Some(_)) => processed_through,
+        (None, None) => None,
+    };
+    runtime.cross_re_anchors_through(ceiling).await?;
+
+    Ok(ApplyOutcome {
+        applied_any: applied > 0,
+        stopped_before,
+    })
 }
@@ -4,12 +4,12 @@
 //! schema files to detect drift - changes made outside of migrations.
 //!
 //! Use this in CI/CD pipelines to ensure production matches the expected schema.
+//! Either side can come from a catalog snapshot instead.
 
-use crate::catalog::Catalog;
+use crate::commands::catalog::{CatalogSource, load_database_side, load_schema_side};
 use crate::commands::diff_output::{DiffContext, DiffFormat, has_differences, output_diff};
-use crate::config::{Config, ObjectFilter};
+use crate::config::{Config, ShadowDatabase, TargetUrl};
 use crate::diff::plan;
-use crate::schema_ops::apply_current_schema_to_shadow;
 use anyhow::Result;
 use std::path::Path;
 
@@ -41,28 +41,28 @@ pub async fn cmd_migrate_diff(
     config: &Config,
     root_dir: &Path,
     args: MigrateDiffArgs,
-    target: &crate::config::TargetUrl,
-    shadow: &crate::config::ShadowDatabase,
+    target: &CatalogSource<TargetUrl>,
+    shadow: &CatalogSource<ShadowDatabase>,
 ) -> Result<()> {
-    eprintln!("Checking target database for drift...\n");
+    let target_label = target.label("target database");
+    let schema_label = shadow.label("schema files");
+    eprintln!("Checking {} for drift...\n", target_label);
 
     // Load schema into shadow database
-    eprintln!("Loading schema files...");
-    let schema_catalog = apply_current_schema_to_shadow(config, root_dir, shadow).await?;
+    eprintln!("Loading {}...", schema_label);
+    let schema_catalog = load_schema_side(config, root_dir, shadow).await?;
 
     // Load target database catalog
-    eprintln!("Loading target database...");
-    let target_pool =
-        crate::db::connection::connect_to_database(target.as_str(), "target database").await?;
-    let filter = ObjectFilter::from_config(config);
-    let target_catalog = Catalog::load_managed(&target_pool, &filter).await?;
+    eprintln!("Loading {}...", target_label);
+    let target_catalog =
+        load_database_side(config, target, TargetUrl::as_str, "target database").await?;
 
     // Compute differences (target -> schema, so SQL shows how to fix target)
     eprintln!("Computing differences...\n");
     let ordered_steps = plan(&target_catalog, &schema_catalog)?;
 
     // Output results
-    let context = DiffContext::new("target database", "schema files");
+    let context = DiffContext::new(&target_label, &schema_label);
     output_diff(
         &ordered_steps,
         &args.format,
@@ -74,11 +74,27 @@ pub async fn cmd_migrate_diff(
 
     // Exit with code 1 if drift detected
     if has_differences(&ordered_steps) {
-        eprintln!("\nDrift detected! Target database differs from schema files.");
+        eprintln!(
+            "\nDrift detected! {} differs from {}.",
+            capitalize(&target_label),
+            schema_label
+        );
         std::process::exit(1);
     } else {
-        eprintln!("No drift detected. Target database matches schema files.");
+        eprintln!(
+            "No drift detected. {} matches {}.",
+            capitalize(&target_label),
+            schema_label
+        );
     }
 
     Ok(())
 }
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,440 @@
+//! `migrate export` — write the migration history in another tool's layout,
+//! for consumers that deploy with Flyway, golang-migrate, Sqitch or psql
+//! instead of pgmt.
+//!
+//! The history is the latest baseline followed by every migration after it,
+//! the same thing `migrate provision` lays down. Each section keeps its
+//! timeouts and assertions; what a transactional section gets from pgmt (its
+//! own transaction) becomes explicit `BEGIN`/`COMMIT` wherever the tool
+//! doesn't provide it, and non-transactional sections go wherever the tool
+//! runs SQL outside a transaction. Retries and module attribution have no
+//! equivalent and are dropped with a note; batched sections can't be exported.
+
+use crate::config::Config;
+use crate::migration::section_parser::{MigrationSection, TransactionMode};
+use crate::migration::{
+    discover_migrations, down_migration_path, find_latest_baseline,
+    parse_migration_sections_with_variables, validate_sections,
+};
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+
+/// Layout for `migrate export`
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// `V<version>__<description>.sql` scripts, with a `.sql.conf` turning
+    /// off Flyway's transaction where a section needs that
+    Flyway,
+    /// `<version>_<description>.up.sql` files, one per transaction
+    GolangMigrate,
+    /// A `sqitch.plan` with `deploy/` and `revert/` scripts
+    Sqitch,
+    /// Numbered scripts plus a `deploy.sql` that runs them in order
+    Psql,
+}
+
+impl ExportFormat {
+    fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Flyway => "Flyway",
+            ExportFormat::GolangMigrate => "golang-migrate",
+            ExportFormat::Sqitch => "Sqitch",
+            ExportFormat::Psql => "psql",
+        }
+    }
+}
+
+/// A baseline or migration to export, with its sections parsed.
+struct ExportUnit {
+    version: u64,
+    description: String,
+    source: PathBuf,
+    sections: Vec<MigrationSection>,
+    down: Option<String>,
+}
+
+pub async fn cmd_migrate_export(
+    config: &Config,
+    root_dir: &Path,
+    format: ExportFormat,
+    out: &Path,
+) -> Result<()> {
+    if out.exists()
+        && std::fs::read_dir(out)
+            .with_context(|| format!("Failed to read {}", out.display()))?
+            .next()
+            .is_some()
+    {
+        bail!(
+            "{} is not empty; export into an empty directory so no stale script is left \
+             behind",
+            out.display()
+        );
+    }
+
+    let units = export_units(config, root_dir)?;
+    if units.is_empty() {
+        println!("No baseline or migrations to export");
+        return Ok(());
+    }
+
+    let mut notes = Vec::new();
+    if config.modules.is_enabled() {
+        notes.push(
+            "module attribution is not exported: every module's sections are included".to_string(),
+        );
+    }
+    let files = match format {
+        ExportFormat::Flyway => flyway_files(&units, &mut notes),
+        ExportFormat::GolangMigrate => golang_migrate_files(&units, &mut notes)?,
+        ExportFormat::Sqitch => sqitch_files(&units, root_dir, &mut notes),
+        ExportFormat::Psql => psql_files(&units, &mut notes),
+    };
+
+    for (name, content) in &files {
+        let path = out.join(name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    println!(
+        "Exported {} migration(s) to {} for {} ({} file(s))",
+        units.len(),
+        out.display(),
+        format.name(),
+        files.len()
+    );
+    for note in &notes {
+        println!("  Note: {}", note);
+    }
+    Ok(())
+}
+
+/// The latest baseline, then the migrations after it.
+fn export_units(config: &Config, root_dir: &Path) -> Result<Vec<ExportUnit>> {
+    let baselines_dir = root_dir.join(&config.directories.baselines);
+    let migrations_dir = root_dir.join(&config.directories.migrations);
+    let baseline = find_latest_baseline(&baselines_dir)?;
+    let after = baseline.as_ref().map(|b| b.version);
+
+    let mut units = Vec::new();
+    if let Some(baseline) = baseline {
+        units.push(load_unit(
+            config,
+            baseline.version,
+            "baseline",
+            &baseline.path,
+            false,
+        )?);
+    }
+    for migration in discover_migrations(&migrations_dir)? {
+        if after.is_some_and(|bv| migration.version <= bv) {
+            continue;
+        }
+        units.push(load_unit(
+            config,
+            migration.version,
+            &migration.description,
+            &migration.path,
+            true,
+        )?);
+    }
+    Ok(units)
+}
+
+fn load_unit(
+    config: &Config,
+    version: u64,
+    description: &str,
+    path: &Path,
+    is_migration: bool,
+) -> Result<ExportUnit> {
+    let sql = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let sections = parse_migration_sections_with_variables(path, &sql, &config.variables)?;
+    validate_sections(&sections)
+        .with_context(|| format!("Invalid section configuration in {}", path.display()))?;
+    if let Some(batched) = sections.iter().find(|s| s.mode == TransactionMode::Batched) {
+        bail!(
+            "section '{}' in {} is batched, which other tools can't express (it re-runs until \
+             a batch affects no rows); rewrite it as a plain section to export",
+            batched.name,
+            path.display()
+        );
+    }
+    // A migration's remap sections are satisfied wherever its history
+    // replays in order — the source already created their objects — so
+    // they never run, as in shadow replay.
+    let sections = sections
+        .into_iter()
+        .filter(|s| !(is_migration && s.remaps.is_some()))
+        .collect();
+
+    let down_path = down_migration_path(path);
+    let down = if is_migration && down_path.exists() {
+        Some(
+            std::fs::read_to_string(&down_path)
+                .with_context(|| format!("Failed to read {}", down_path.display()))?,
+        )
+    } else {
+        None
+    };
+
+    Ok(ExportUnit {
+        version,
+        description: description.to_string(),
+        source: path.to_path_buf(),
+        sections,
+        down,
+    })
+}
+
+/// Whether `section` must run outside a transaction.
+fn outside_transaction(section: &MigrationSection) -> bool {
+    matches!(
+        section.mode,
+        TransactionMode::NonTransactional | TransactionMode::Autocommit
+    )
+}
+
+fn header(unit: &ExportUnit) -> String {
+    format!(
+        "-- Exported by pgmt from {}\n",
+        unit.source
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default()
+    )
+}
+
+/// Render one section the way pgmt runs it. `own_transaction` wraps a
+/// transactional section in `BEGIN`/`COMMIT`; without it the caller's
+/// transaction holds it (`SET LOCAL` still scopes to that).
+fn render_section(
+    unit: &ExportUnit,
+    section: &MigrationSection,
+    own_transaction: bool,
+    notes: &mut Vec<String>,
+) -> String {
+    let mut out = format!("\n-- section: {}\n", section.name);
+    if let Some(retry) = &section.retry_config
+        && retry.attempts > 1
+    {
+        notes.push(format!(
+            "{} section '{}': retries are not exported; it runs once",
+            unit.version, section.name
+        ));
+    }
+
+    let transactional = !outside_transaction(section);
+    let set = if transactional { "SET LOCAL" } else { "SET" };
+    if transactional && own_transaction {
+        out.push_str("BEGIN;\n");
+    }
+    out.push_str(&format!(
+        "{} statement_timeout = '{}';\n",
+        set,
+        section.timeout.as_millis()
+    ));
+    match section.lock_timeout {
+        Some(lock_timeout) => out.push_str(&format!(
+            "{} lock_timeout = '{}';\n",
+            set,
+            lock_timeout.as_millis()
+        )),
+        None => out.push_str(&format!("{} lock_timeout TO DEFAULT;\n", set)),
+    }
+    for assertion in &section.assert_before {
+        out.push_str(&render_assertion("assert_before", &assertion.query));
+    }
+    out.push_str(section.sql.trim_end());
+    out.push('\n');
+    for assertion in &section.assert_after {
+        out.push_str(&render_assertion("assert_after", &assertion.query));
+    }
+    if transactional {
+        if own_transaction {
+            out.push_str("COMMIT;\n");
+        }
+    } else {
+        out.push_str("RESET statement_timeout;\nRESET lock_timeout;\n");
+    }
+    out
+}
+
+/// A `-- pgmt:assert_*` query as a block that raises when it isn't true.
+fn render_assertion(kind: &str, query: &str) -> String {
+    let message = query.replace('\'', "''").replace('%', "%%");
+    format!(
+        "DO $pgmt$ BEGIN\n  IF NOT ({}) THEN\n    RAISE EXCEPTION '{} failed: {}';\n  END IF;\nEND $pgmt$;\n",
+        query.trim().trim_end_matches(';'),
+        kind,
+        message.trim().trim_end_matches(';')
+    )
+}
+
+/// One script per migration. Flyway runs a script in one transaction unless
+/// its `.sql.conf` says otherwise; then each transactional section gets its
+/// own, as under pgmt.
+fn flyway_files(units: &[ExportUnit], notes: &mut Vec<String>) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    for unit in units {
+        let name = format!("V{}__{}.sql", unit.version, unit.description);
+        let split = unit.sections.iter().any(outside_transaction);
+        let mut script = header(unit);
+        for section in &unit.sections {
+            script.push_str(&render_section(unit, section, split, notes));
+        }
+        if split {
+            files.push((
+                format!("{}.conf", name),
+                "executeInTransaction=false\n".to_string(),
+            ));
+        }
+        files.push((name, script));
+    }
+    files
+}
+
+/// golang-migrate runs each file as a single statement batch, which Postgres
+/// treats as one transaction. Consecutive transactional sections share a
+/// file; each section that must run outside a transaction gets a file of its
+/// own holding only its SQL. File versions are `<version><part:02>`, so
+/// ordering holds across split and unsplit migrations.
+fn golang_migrate_files(
+    units: &[ExportUnit],
+    notes: &mut Vec<String>,
+) -> Result<Vec<(String, String)>> {
+    let mut files = Vec::new();
+    for unit in units {
+        let mut parts: Vec<String> = Vec::new();
+        let mut open = false;
+        for section in &unit.sections {
+            if outside_transaction(section) {
+                let mut part = header(unit);
+                part.push_str(&format!("\n-- section: {}\n", section.name));
+                part.push_str(section.sql.trim_end());
+                part.push('\n');
+                if !section.assert_before.is_empty() || !section.assert_after.is_empty() {
+                    notes.push(format!(
+                        "{} section '{}': assertions are not exported for a section outside a \
+                         transaction",
+                        unit.version, section.name
+                    ));
+                }
+                parts.push(part);
+                open = false;
+            } else {
+                if !open {
+                    parts.push(header(unit));
+                    open = true;
+                }
+                let part = parts.last_mut().expect("a part was just opened");
+                part.push_str(&render_section(unit, section, true, notes));
+            }
+        }
+        if parts.len() > 100 {
+            bail!(
+                "migration {} splits into {} files; golang-migrate export supports at most 100",
+                unit.version,
+                parts.len()
+            );
+        }
+        for (i, part) in parts.iter().enumerate() {
+            files.push((
+                format!("{}{:02}_{}.up.sql", unit.version, i, unit.description),
+                part.clone(),
+            ));
+        }
+        if let Some(down) = &unit.down {
+            if parts.len() == 1 {
+                files.push((
+                    format!("{}00_{}.down.sql", unit.version, unit.description),
+                    down.clone(),
+                ));
+            } else {
+                notes.push(format!(
+                    "{}: its down migration is not exported, as the migration spans {} files",
+                    unit.version,
+                    parts.len()
+                ));
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// One change per migration. Sqitch runs deploy scripts through psql, so a
+/// script carries its own transactions and sections outside one run as is.
+/// Plan timestamps come from the versions, so re-exporting gives the same
+/// change IDs.
+fn sqitch_files(
+    units: &[ExportUnit],
+    root_dir: &Path,
+    notes: &mut Vec<String>,
+) -> Vec<(String, String)> {
+    let project: String = root_dir
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "pgmt".to_string())
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    let mut plan = format!("%syntax-version=1.0.0\n%project={}\n\n", project);
+    let mut files = vec![(
+        "sqitch.conf".to_string(),
+        "[core]\n\tengine = pg\n".to_string(),
+    )];
+    for unit in units {
+        let change = format!("v{}_{}", unit.version, unit.description);
+        let planned = i64::try_from(unit.version)
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .unwrap_or_default();
+        plan.push_str(&format!(
+            "{} {} pgmt <pgmt@localhost> # {}\n",
+            change,
+            planned.format("%Y-%m-%dT%H:%M:%SZ"),
+            unit.description.replace('_', " ")
+        ));
+
+        let mut deploy = header(unit);
+        for section in &unit.sections {
+            deploy.push_str(&render_section(unit, section, true, notes));
+        }
+        files.push((format!("deploy/{}.sql", change), deploy));
+        if let Some(down) = &unit.down {
+            files.push((
+                format!("revert/{}.sql", change),
+                format!("{}\nBEGIN;\n{}\nCOMMIT;\n", header(unit), down.trim_end()),
+            ));
+        }
+    }
+    files.push(("sqitch.plan".to_string(), plan));
+    files
+}
+
+/// One script per migration, each runnable with `psql -f`, and `deploy.sql`
+/// to run them all in order.
+fn psql_files(units: &[ExportUnit], notes: &mut Vec<String>) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    let mut deploy = String::from("\\set ON_ERROR_STOP on\n");
+    for unit in units {
+        let name = format!("{}_{}.sql", unit.version, unit.description);
+        let mut script = header(unit);
+        script.push_str("\\set ON_ERROR_STOP on\n");
+        for section in &unit.sections {
+            script.push_str(&render_section(unit, section, true, notes));
+        }
+        deploy.push_str(&format!("\\ir {}\n", name));
+        files.push((name, script));
+    }
+    files.push(("deploy.sql".to_string(), deploy));
+    files
+}
@@ -0,0 +1,323 @@
+//! `migrate apply --targets` — apply to many databases from the `targets:`
+//! inventory in one run.
+//!
+//! Each target is applied by a child `pgmt migrate apply` process with the
+//! target's URL in `PGMT_TARGET_URL`, so every target gets the ordinary apply
+//! — its own connection, its own `MigrationLock`, its own output — and one
+//! target's failure can't leave another half-applied. Canaries go first, one
+//! at a time, and any canary failure stops the run; the rest run with
+//! bounded concurrency. Under `--on-failure stop` no new target starts once
+//! one has failed; targets already running finish.
+
+use crate::config::FleetTarget;
+use anyhow::{Context, Result, bail};
+use futures_util::StreamExt;
+use serde_json::json;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// What `migrate apply --targets` does once a target fails
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum OnFailure {
+    /// Start no further targets; the ones already running finish
+    #[default]
+    Stop,
+    /// Keep applying to the remaining targets
+    Continue,
+}
+
+/// Report format for `migrate apply --targets`
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum FleetFormat {
+    /// Each target's output as it finishes, then a summary
+    #[default]
+    Human,
+    /// One report with every target's outcome and output, at the end
+    Json,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FleetOptions {
+    pub concurrency: usize,
+    pub on_failure: OnFailure,
+    pub format: FleetFormat,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    Applied,
+    Failed,
+    Skipped(String),
+}
+
+struct TargetResult {
+    outcome: Outcome,
+    duration: Duration,
+    stdout: String,
+    stderr: String,
+}
+
+impl TargetResult {
+    fn skipped(reason: String) -> Self {
+        TargetResult {
+            outcome: Outcome::Skipped(reason),
+            duration: Duration::ZERO,
+            stdout: String::new(),
+            stderr: String::new(),
+        }
+    }
+
+    /// The child's top-level error (its `Error:` line), for the summary.
+    fn error_line(&self) -> Option<&str> {
+        self.stderr
+            .lines()
+            .find_map(|line| line.strip_prefix("Error: "))
+            .or_else(|| self.stderr.lines().map(str::trim).find(|l| !l.is_empty()))
+    }
+}
+
+/// Apply to every target in `targets` (canaries first, as resolved).
+///
+/// `apply_args` is the child command line after the executable — global
+/// flags, `migrate apply` and the apply flags to forward — without `--env`,
+/// which is `env` unless the target names its own.
+pub async fn cmd_migrate_apply_fleet(
+    targets: &[FleetTarget],
+    apply_args: &[String],
+    env: Option<&str>,
+    options: FleetOptions,
+) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the pgmt executable")?;
+    let human = options.format == FleetFormat::Human;
+    if human {
+        let names: Vec<String> = targets
+            .iter()
+            .map(|t| {
+                if t.canary {
+                    format!("{} (canary)", t.name)
+                } else {
+                    t.name.clone()
+                }
+            })
+            .collect();
+        println!(
+            "Applying migrations to {} target(s): {}",
+            targets.len(),
+            names.join(", ")
+        );
+        println!(
+            "Concurrency {}; on failure: {}\n",
+            options.concurrency,
+            match options.on_failure {
+                OnFailure::Stop => "stop",
+                OnFailure::Continue => "continue",
+            }
+        );
+    }
+
+    let run = |target| run_target(&exe, apply_args, env, target, human);
+
+    let mut results: Vec<Option<TargetResult>> = targets.iter().map(|_| None).collect();
+    let canaries = targets.iter().take_while(|t| t.canary).count();
+
+    // Canaries, one at a time: the first failure ends the run.
+    let mut canary_failed = None;
+    for (i, target) in targets[..canaries].iter().enumerate() {
+        let result = match &canary_failed {
+            Some(name) => TargetResult::skipped(format!("canary {} failed", name)),
+            None => run(target).await,
+        };
+        if result.outcome == Outcome::Failed {
+            canary_failed = Some(target.name.clone());
+        }
+        results[i] = Some(result);
+    }
+
+    // The rest, `concurrency` at a time. Each checks `stopped` as it starts,
+    // so under `stop` nothing new begins after a failure.
+    let stopped = AtomicBool::new(false);
+    let rest = futures_util::stream::iter(targets.iter().enumerate().skip(canaries))
+        .map(|(i, target)| {
+            let canary_failed = &canary_failed;
+            let stopped = &stopped;
+            let run = &run;
+            async move {
+                if let Some(name) = canary_failed {
+                    return (i, TargetResult::skipped(format!("canary {} failed", name)));
+                }
+                if stopped.load(Ordering::SeqCst) {
+                    return (
+                        i,
+                        TargetResult::skipped("an earlier target failed".to_string()),
+                    );
+                }
+                let result = run(target).await;
+                if result.outcome == Outcome::Failed && options.on_failure == OnFailure::Stop {
+                    stopped.store(true, Ordering::SeqCst);
+                }
+                (i, result)
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+    for (i, result) in rest {
+        results[i] = Some(result);
+    }
+
+    let results: Vec<(&FleetTarget, TargetResult)> = targets
+        .iter()
+        .zip(
+            results
+                .into_iter()
+                .map(|r| r.expect("every target has a result")),
+        )
+        .collect();
+    let count =
+        |pred: fn(&Outcome) -> bool| results.iter().filter(|(_, r)| pred(&r.outcome)).count();
+    let applied = count(|o| *o == Outcome::Applied);
+    let failed = count(|o| *o == Outcome::Failed);
+    let skipped = count(|o| matches!(o, Outcome::Skipped(_)));
+
+    match options.format {
+        FleetFormat::Human => print_summary(&results, applied, failed, skipped),
+        FleetFormat::Json => {
+            let entries: Vec<serde_json::Value> = results
+                .iter()
+                .map(|(target, result)| {
+                    let (status, reason) = match &result.outcome {
+                        Outcome::Applied => ("applied", None),
+                        Outcome::Failed => ("failed", None),
+                        Outcome::Skipped(reason) => ("skipped", Some(reason)),
+                    };
+                    json!({
+                        "name": target.name,
+                        "groups": target.groups,
+                        "canary": target.canary,
+                        "status": status,
+                        "skip_reason": reason,
+                        "duration_ms": result.duration.as_millis() as u64,
+                        "error": (result.outcome == Outcome::Failed)
+                            .then(|| result.error_line())
+                            .flatten(),
+                        "stdout": result.stdout,
+                        "stderr": result.stderr,
+                    })
+                })
+                .collect();
+            let output = json!({
+                "applied": applied,
+                "failed": failed,
+                "skipped": skipped,
+                "targets": entries,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    if failed > 0 || skipped > 0 {
+        bail!(
+            "Fleet apply incomplete: {} of {} target(s) failed, {} skipped",
+            failed,
+            targets.len(),
+            skipped
+        );
+    }
+    Ok(())
+}
+
+/// Apply to one target in a child `pgmt migrate apply`, printing its output
+/// when it finishes (human format).
+async fn run_target(
+    exe: &Path,
+    apply_args: &[String],
+    env: Option<&str>,
+    target: &FleetTarget,
+    human: bool,
+) -> TargetResult {
+    let mut command = tokio::process::Command::new(exe);
+    command.args(apply_args);
+    if let Some(env) = target.env.as_deref().or(env) {
+        command.args(["--env", env]);
+    }
+    command
+        .env("PGMT_TARGET_URL", target.url.as_str())
+        .stdin(Stdio::null());
+
+    let started = Instant::now();
+    let output = command.output().await;
+    let duration = started.elapsed();
+    let result = match output {
+        Ok(output) => TargetResult {
+            outcome: if output.status.success() {
+                Outcome::Applied
+            } else {
+                Outcome::Failed
+            },
+            duration,
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        },
+        Err(e) => TargetResult {
+            outcome: Outcome::Failed,
+            duration,
+            stdout: String::new(),
+            stderr: format!("Failed to start pgmt: {}", e),
+        },
+    };
+    if human {
+        print_target(target, &result);
+    }
+    result
+}
+
+/// One target's output as a block, so concurrent targets don't interleave.
+fn print_target(target: &FleetTarget, result: &TargetResult) {
+    let status = match result.outcome {
+        Outcome::Applied => "✅ applied",
+        _ => "❌ failed",
+    };
+    println!(
+        "── {}: {} ({:.1}s)",
+        target.name,
+        status,
+        result.duration.as_secs_f64()
+    );
+    for line in result.stdout.lines().chain(result.stderr.lines()) {
+        println!("   {}", line);
+    }
+    println!();
+}
+
+fn print_summary(
+    results: &[(&FleetTarget, TargetResult)],
+    applied: usize,
+    failed: usize,
+    skipped: usize,
+) {
+    println!(
+        "Fleet summary: {} applied, {} failed, {} skipped",
+        applied, failed, skipped
+    );
+    let width = results.iter().map(|(t, _)| t.name.len()).max().unwrap_or(0);
+    for (target, result) in results {
+        match &result.outcome {
+            Outcome::Applied => println!(
+                "  ✅ {:width$}  {:.1}s",
+                target.name,
+                result.duration.as_secs_f64()
+            ),
+            Outcome::Failed => println!(
+                "  ❌ {:width$}  {:.1}s  {}",
+                target.name,
+                result.duration.as_secs_f64(),
+                result.error_line().unwrap_or("failed")
+            ),
+            Outcome::Skipped(reason) => {
+                println!("  ⏭️  {:width$}  skipped: {}", target.name, reason)
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! `migrate history` — the audit log as a timeline.
+//!
+//! Lists every recorded section attempt (retries and failures included) and
+//! every `migrate resolve` action, oldest first. Read-only like `migrate
+//! status`: no lock, and a target without the history table (auditing off, or
+//! never applied with it on) just says so.
+
+use crate::commands::migrate::status::relation_exists;
+use crate::commands::migrate::wait::parse_version;
+use crate::config::Config;
+use crate::db::connection::connect_to_database;
+use crate::migration_tracking::{HistoryEntry, TrackingStore, version_from_db};
+use anyhow::Result;
+use console::style;
+
+pub async fn cmd_migrate_history(
+    config: &Config,
+    target: &crate::config::TargetUrl,
+    version: Option<&str>,
+    show_sql: bool,
+) -> Result<()> {
+    let version = version.map(parse_version).transpose()?;
+    let pool = connect_to_database(target.as_str(), "target database").await?;
+    let store = TrackingStore::new(&pool, &config.migration.tracking_table)?;
+
+    let entries = if relation_exists(&pool, store.history_table()).await? {
+        Some(store.history(version).await?)
+    } else {
+        None
+    };
+    pool.close().await;
+
+    let Some(entries) = entries else {
+        println!(
+            "No history recorded on the target database. Enable it with `migration.audit: true` \
+             in pgmt.yaml; later applies are recorded."
+        );
+        return Ok(());
+    };
+    if entries.is_empty() {
+        match version {
+            Some(v) => println!("No history recorded for version {}", v),
+            None => println!("No history recorded yet"),
+        }
+        return Ok(());
+    }
+
+    let mut current = None;
+    for entry in &entries {
+        let key = (entry.migration_version, entry.is_baseline);
+        if current != Some(key) {
+            let kind = if entry.is_baseline {
+                "Baseline"
+            } else {
+                "Migration"
+            };
+            println!(
+                "\n{} {}",
+                kind,
+                style(version_from_db(entry.migration_version)).bold()
+            );
+            current = Some(key);
+        }
+        print_entry(entry, show_sql);
+    }
+    Ok(())
+}
+
+fn print_entry(entry: &HistoryEntry, show_sql: bool) {
+    let status = match entry.status.as_str() {
+        "completed" => style(entry.status.as_str()).green(),
+        "failed" => style(entry.status.as_str()).red(),
+        _ => style(entry.status.as_str()).yellow(),
+    };
+    let what = match (entry.event.as_str(), entry.attempt) {
+        ("attempt", Some(n)) => format!("attempt {}", n),
+        ("attempt", None) => "attempt".to_string(),
+        ("rollback", _) => "rollback".to_string(),
+        (event, _) => format!("resolve --{}", event.replace('_', "-")),
+    };
+    let duration = match (entry.duration_ms, entry.rows_affected) {
+        _ if entry.event != "attempt" => String::new(),
+        (Some(ms), Some(rows)) => format!(" in {}ms, {} row(s)", ms, rows),
+        (Some(ms), None) => format!(" in {}ms", ms),
+        _ => String::new(),
+    };
+    println!(
+        "  {}  {} {} {}{}",
+        entry.started_at, entry.section_name, what, status, duration
+    );
+
+    let mut by = format!("by {}", entry.applied_by);
+    if let Some(host) = &entry.client_hostname {
+        by.push_str(&format!(" on {}", host));
+    }
+    if let Some(version) = &entry.pgmt_version {
+        by.push_str(&format!(", pgmt {}", version));
+    }
+    if let Some(deploy_id) = &entry.deploy_id {
+        by.push_str(&format!(", deploy {}", deploy_id));
+    }
+    println!("      {}", by);
+    if let Some(settings) = &entry.settings {
+        println!("      settings: {}", settings);
+    }
+    if let Some(error) = &entry.error {
+        println!("      error:");
+        for line in error.lines() {
+            println!("        {}", line);
+        }
+    }
+    if show_sql && let Some(sql) = &entry.sql {
+        for line in sql.lines() {
+            println!("      | {}", line);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! Lifecycle hooks around `migrate apply`.
+//!
+//! Each hook point can have a SQL file in the hooks directory
+//! (`before_migrate.sql`, `after_each_section.sql`, `after_migrate.sql`) and a
+//! shell command under `migration.hooks` in pgmt.yaml. When both exist the SQL
+//! file runs first, in its own transaction on the target, then the command
+//! (via `sh -c`, with the caller's stdout/stderr).
+//!
+//! Hooks are told what they run for. Commands get `PGMT_HOOK`,
+//! `PGMT_MIGRATION_VERSION`, `PGMT_MIGRATION_DESCRIPTION` and
+//! `PGMT_SECTION_NAME` environment variables; SQL files get the same values as
+//! transaction-local settings (`current_setting('pgmt.section_name')`). Values
+//! that don't apply to a hook point are empty.
+//!
+//! A failing hook fails the apply. `after_each_section` runs once the section
+//! is committed and recorded, so a failure there stops the run with that
+//! section applied, and the next apply resumes after it without re-running the
+//! hook for it.
+
+use crate::config::Config;
+use crate::db::error_context::SqlErrorContext;
+use crate::progress::SectionReporter;
+use anyhow::{Context, Result, anyhow, bail};
+use sqlx::{Executor, PgPool};
+use std::path::Path;
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookPoint {
+    /// Once per apply, before any migration runs
+    BeforeMigrate,
+    /// After every section that ran
+    AfterEachSection,
+    /// Once per apply, after versioned and repeatable migrations
+    AfterMigrate,
+}
+
+impl HookPoint {
+    pub fn name(self) -> &'static str {
+        match self {
+            HookPoint::BeforeMigrate => "before_migrate",
+            HookPoint::AfterEachSection => "after_each_section",
+            HookPoint::AfterMigrate => "after_migrate",
+        }
+    }
+}
+
+/// The migration and section a hook runs for, if any.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HookContext<'a> {
+    pub version: Option<u64>,
+    pub description: Option<&'a str>,
+    pub section: Option<&'a str>,
+}
+
+impl HookContext<'_> {
+    /// Environment variable names and values; the SQL setting for `PGMT_X` is
+    /// `pgmt.x`.
+    fn vars(&self, point: HookPoint) -> [(&'static str, String); 4] {
+        [
+            ("PGMT_HOOK", point.name().to_string()),
+            (
+                "PGMT_MIGRATION_VERSION",
+                self.version.map(|v| v.to_string()).unwrap_or_default(),
+            ),
+            (
+                "PGMT_MIGRATION_DESCRIPTION",
+                self.description.unwrap_or_default().to_string(),
+            ),
+            (
+                "PGMT_SECTION_NAME",
+                self.section.unwrap_or_default().to_string(),
+            ),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Hook {
+    /// Path relative to the project root, and the substituted SQL.
+    file: Option<(String, String)>,
+    command: Option<String>,
+}
+
+/// The project's hooks, loaded (and `${var}`-substituted) up front so a
+/// broken hook file fails the apply before anything runs.
+#[derive(Debug, Clone, Default)]
+pub struct MigrationHooks {
+    before_migrate: Hook,
+    after_each_section: Hook,
+    after_migrate: Hook,
+}
+
+impl MigrationHooks {
+    pub fn load(config: &Config, root_dir: &Path) -> Result<Self> {
+        let load = |point: HookPoint, command: &Option<String>| -> Result<Hook> {
+            let relative =
+                Path::new(&config.directories.hooks).join(format!("{}.sql", point.name()));
+            let path = root_dir.join(&relative);
+            let file = if path.is_file() {
+                let content = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read hook file {}", path.display()))?;
+                let sql = config.variables.substitute(&content).with_context(|| {
+                    format!("Failed to substitute variables in {}", relative.display())
+                })?;
+                Some((relative.display().to_string(), sql))
+            } else {
+                None
+            };
+            Ok(Hook {
+                file,
+                command: command.clone(),
+            })
+        };
+
+        let commands = &config.migration.hooks;
+        Ok(Self {
+            before_migrate: load(HookPoint::BeforeMigrate, &commands.before_migrate)?,
+            after_each_section: load(HookPoint::AfterEachSection, &commands.after_each_section)?,
+            after_migrate: load(HookPoint::AfterMigrate, &commands.after_migrate)?,
+        })
+    }
+
+    /// Keep only the SQL files. A rehearsal runs these against its branch,
+    /// but a command (a notification, a deploy step) would act on the outside
+    /// world as if the real apply were happening.
+    pub fn without_commands(mut self) -> Self {
+        for hook in [
+            &mut self.before_migrate,
+            &mut self.after_each_section,
+            &mut self.after_migrate,
+        ] {
+            hook.command = None;
+        }
+        self
+    }
+
+    fn hook(&self, point: HookPoint) -> &Hook {
+        match point {
+            HookPoint::BeforeMigrate => &self.before_migrate,
+            HookPoint::AfterEachSection => &self.after_each_section,
+            HookPoint::AfterMigrate => &self.after_migrate,
+        }
+    }
+
+    /// Run the hook file and command for `point`, reporting the outcome on
+    /// `reporter`. A no-op when neither is configured.
+    pub async fn run(
+        &self,
+        pool: &PgPool,
+        point: HookPoint,
+        context: HookContext<'_>,
+        reporter: &SectionReporter,
+    ) -> Result<()> {
+        let hook = self.hook(point);
+        if hook.file.is_none() && hook.command.is_none() {
+            return Ok(());
+        }
+
+        let vars = context.vars(point);
+        let start = Instant::now();
+        let mut result = Ok(());
+        if let Some((path, sql)) = &hook.file {
+            result = run_sql(pool, path, sql, &vars).await;
+        }
+        if let (Ok(()), Some(command)) = (&result, &hook.command) {
+            result = run_command(command, &vars).await;
+        }
+
+        match &result {
+            Ok(()) => reporter.complete_hook(point.name(), start.elapsed()),
+            Err(e) => reporter.fail_hook(point.name(), e),
+        }
+        result.with_context(|| format!("{} hook failed", point.name()))
+    }
+}
+
+async fn run_sql(pool: &PgPool, path: &str, sql: &str, vars: &[(&str, String)]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    for (name, value) in vars {
+        let setting = format!("pgmt.{}", name["PGMT_".len()..].to_ascii_lowercase());
+        sqlx::query("SELECT set_config($1, $2, true)")
+            .bind(setting)
+            .bind(value)
+            .execute(&mut *tx)
+            .await?;
+    }
+    if let Err(e) = tx.execute(sqlx::AssertSqlSafe(sql.to_string())).await {
+        tx.rollback().await?;
+        let ctx = SqlErrorContext::from_sqlx_error(&e, sql);
+        return Err(anyhow!("{}", ctx.format(path, sql)));
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn run_command(command: &str, vars: &[(&str, String)]) -> Result<()> {
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(vars.iter().map(|(name, value)| (name, value)))
+        .status()
+        .await
+        .with_context(|| format!("Failed to start hook command `{}`", command))?;
+    if !status.success() {
+        bail!("hook command `{}` {}", command, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_context_vars() {
+        let context = HookContext {
+            version: Some(1000),
+            description: Some("users"),
+            section: Some("backfill"),
+        };
+        let vars = context.vars(HookPoint::AfterEachSection);
+        assert_eq!(
+            vars.map(|(name, value)| format!("{name}={value}")),
+            [
+                "PGMT_HOOK=after_each_section",
+                "PGMT_MIGRATION_VERSION=1000",
+                "PGMT_MIGRATION_DESCRIPTION=users",
+                "PGMT_SECTION_NAME=backfill",
+            ]
+        );
+
+        let vars = HookContext::default().vars(HookPoint::BeforeMigrate);
+        assert_eq!(vars[0].1, "before_migrate");
+        assert!(vars[1..].iter().all(|(_, value)| value.is_empty()));
+    }
+}
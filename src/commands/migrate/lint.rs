@@ -0,0 +1,160 @@
+use crate::config::Config;
+use crate::migration::lint::{LintFinding, Linter, RULES, Severity};
+use crate::migration::{discover_migrations, parse_migration_sections};
+use anyhow::{Context, Result, anyhow};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+/// Output format for `migrate lint`
+#[derive(Debug, Clone, PartialEq, Default, clap::ValueEnum)]
+pub enum LintFormat {
+    /// One line per finding, for terminals
+    #[default]
+    Human,
+    /// Findings plus a summary, for scripts
+    Json,
+    /// SARIF 2.1.0, for code-scanning UIs in CI
+    Sarif,
+}
+
+/// Statically check migration files for dangerous patterns.
+///
+/// With no `files`, lints every migration in the migrations directory;
+/// otherwise only the given files (hand-written migrations can be checked
+/// before they are committed). Never connects to a database. Fails when any
+/// finding has `error` severity.
+pub async fn cmd_migrate_lint(
+    config: &Config,
+    root_dir: &Path,
+    files: &[PathBuf],
+    format: &LintFormat,
+) -> Result<()> {
+    let linter = Linter::new(&config.migration.lint)?;
+
+    let paths: Vec<PathBuf> = if files.is_empty() {
+        let migrations_dir = root_dir.join(&config.directories.migrations);
+        discover_migrations(&migrations_dir)?
+            .into_iter()
+            .map(|m| m.path)
+            .collect()
+    } else {
+        files.to_vec()
+    };
+
+    let mut findings = Vec::new();
+    for path in &paths {
+        let sql = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read migration file: {}", path.display()))?;
+        let sections = parse_migration_sections(path, &sql)
+            .with_context(|| format!("Failed to parse migration {}", path.display()))?;
+        let display_path = path.strip_prefix(root_dir).unwrap_or(path);
+        findings.extend(linter.lint_sections(display_path, &sql, &sections)?);
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+
+    match format {
+        LintFormat::Human => print_human(&findings, paths.len(), errors, warnings),
+        LintFormat::Json => {
+            let output = json!({
+                "files_checked": paths.len(),
+                "errors": errors,
+                "warnings": warnings,
+                "findings": findings,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        LintFormat::Sarif => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&sarif_report(&findings))?
+            );
+        }
+    }
+
+    if errors > 0 {
+        return Err(anyhow!(
+            "Migration lint failed: {} error(s), {} warning(s)",
+            errors,
+            warnings
+        ));
+    }
+    Ok(())
+}
+
+fn print_human(findings: &[LintFinding], files: usize, errors: usize, warnings: usize) {
+    for finding in findings {
+        println!(
+            "{}:{}: {} [{}] {}",
+            finding.path.display(),
+            finding.line,
+            finding.severity.as_str(),
+            finding.rule,
+            finding.message
+        );
+    }
+    if findings.is_empty() {
+        println!("✅ No lint findings in {} migration file(s)", files);
+    } else {
+        println!(
+            "\n{} error(s), {} warning(s) in {} migration file(s). Suppress a reviewed \
+             finding with `-- pgmt:allow <rule-id>` in the section header.",
+            errors, warnings, files
+        );
+    }
+}
+
+/// Build a SARIF 2.1.0 log: one run, every known rule as driver metadata,
+/// one result per finding with a file/line location.
+fn sarif_report(findings: &[LintFinding]) -> serde_json::Value {
+    let rules: Vec<serde_json::Value> = RULES
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "shortDescription": { "text": rule.summary },
+                "defaultConfiguration": { "level": rule.default_severity.as_str() },
+            })
+        })
+        .collect();
+
+    let results: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|f| {
+            json!({
+                "ruleId": f.rule,
+                "level": f.severity.as_str(),
+                "message": { "text": f.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": f.path.to_string_lossy().replace('\\', "/"),
+                        },
+                        "region": { "startLine": f.line },
+                    },
+                }],
+                "properties": { "section": f.section },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "pgmt",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://pgmt.dev",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
@@ -1,18 +1,38 @@
 pub mod apply;
 pub mod common;
 pub mod diff;
+pub mod export;
+pub mod fleet;
+pub mod history;
+pub mod hooks;
+pub mod lint;
 pub mod new;
 pub mod provision;
+pub mod rebase;
+pub mod rehearse;
+pub mod repeatable;
 pub mod resolve;
+pub mod rollback;
 pub mod section_executor;
 pub mod status;
+pub mod tenants;
 pub mod update;
+pub mod wait;
 
 // Re-export all command functions
-pub use apply::cmd_migrate_apply;
+pub use apply::{ApplyLimit, cmd_migrate_apply};
 pub use diff::{MigrateDiffArgs, cmd_migrate_diff};
+pub use export::{ExportFormat, cmd_migrate_export};
+pub use fleet::{FleetFormat, FleetOptions, OnFailure, cmd_migrate_apply_fleet};
+pub use history::cmd_migrate_history;
+pub use lint::{LintFormat, cmd_migrate_lint};
 pub use new::cmd_migrate_new;
 pub use provision::cmd_migrate_provision;
+pub use rebase::cmd_migrate_rebase;
+pub use rehearse::cmd_migrate_rehearse;
 pub use resolve::{ResolveVerb, cmd_migrate_resolve};
-pub use status::{cmd_migrate_status, cmd_migrate_validate};
+pub use rollback::cmd_migrate_rollback;
+pub use status::{StatusFormat, cmd_migrate_status, cmd_migrate_validate};
+pub use tenants::{cmd_migrate_apply_tenants, cmd_migrate_status_tenants, reject_tenant_mode};
 pub use update::{cmd_migrate_update_specific, cmd_migrate_update_with_options};
+pub use wait::cmd_migrate_wait;
@@ -1,10 +1,10 @@
 use crate::baseline::operations::BaselineCreationRequest;
 use crate::catalog::Catalog;
-use crate::config::Config;
-use crate::migrate::{MigrationGenerationInput, generate_migration};
+use crate::config::{Config, PlaceholderRestorer};
+use crate::migrate::{MigrationGenerationInput, generate_down_migration, generate_migration};
 use crate::migration::{
-    BaselineConfig, get_migration_starting_state, get_migration_starting_state_with_attribution,
-    validate_baseline_against_catalog,
+    BaselineConfig, down_migration_path, get_migration_starting_state,
+    get_migration_starting_state_with_attribution, validate_baseline_against_catalog,
 };
 use crate::modules::{
     HistoricalAttribution, evaluate_module_generation, render_generated_migration,
@@ -21,8 +21,17 @@ pub async fn cmd_migrate_new(
     root_dir: &Path,
     description: Option<&str>,
     create_baseline: bool,
+    down: bool,
     shadow: &crate::config::ShadowDatabase,
 ) -> Result<()> {
+    if down && config.modules.is_enabled() {
+        anyhow::bail!(
+            "--down is not supported on module projects: a module deploy applies a subset of \
+             each migration's sections, so a whole-file down migration cannot be trusted to \
+             reverse it"
+        );
+    }
+
     let description = prompt_required_string_with_validation(
         description,
         "Enter migration description",
@@ -91,6 +100,10 @@ pub async fn cmd_migrate_new(
         .await?;
         (catalog, HistoricalAttribution::default())
     };
+    // The replayed migrations wrote the seed rows so far; diff against them.
+    let seeded = crate::seed_data::seeded_tables(config, root_dir)?;
+    let mut old_catalog = old_catalog.with_data(&starting_pool, &seeded).await?;
+    crate::tenants::name_seed_rows_for_planning(config, &mut old_catalog);
     crate::db::branch::drop_branch(starting_pool).await?;
 
     debug!("Applying current schema to shadow database");
@@ -105,6 +118,8 @@ pub async fn cmd_migrate_new(
         config.migration.column_order,
     )?;
 
+    let placeholders = crate::schema_ops::placeholder_restorer(config, root_dir)?;
+
     debug!("Generating migration steps");
     let migration_result = generate_migration(MigrationGenerationInput {
         old_catalog: old_catalog.clone(),
@@ -163,6 +178,7 @@ pub async fn cmd_migrate_new(
             &file_mapping,
             &historical,
         )?;
+        placeholders.restore_file(&result.path)?;
         println!("Created baseline: {}", result.path.display());
 
         if baseline_config.validate_consistency {
@@ -197,8 +213,18 @@ pub async fn cmd_migrate_new(
     match migration_sql {
         Some(sql) => {
             let migration_path = migrations_dir.join(&migration_result.migration_filename);
-            std::fs::write(&migration_path, &sql)?;
+            std::fs::write(&migration_path, placeholders.restore(&sql))?;
             println!("Created migration: {}", migration_path.display());
+
+            if down {
+                write_down_migration(
+                    &old_catalog,
+                    &new_catalog,
+                    &migration_path,
+                    &migration_result.migration_filename,
+                    &placeholders,
+                )?;
+            }
         }
         None => {
             // Pure base-sourced re-tag (e.g. modularizing an existing
@@ -211,3 +237,32 @@ pub async fn cmd_migrate_new(
     println!("Migration generation complete!");
     Ok(())
 }
+
+/// Write the paired down migration (`migrations/down/<filename>`) from the
+/// reverse diff, and call out any data loss it cannot undo.
+pub(crate) fn write_down_migration(
+    old_catalog: &Catalog,
+    new_catalog: &Catalog,
+    migration_path: &Path,
+    migration_filename: &str,
+    placeholders: &PlaceholderRestorer,
+) -> Result<()> {
+    let down = generate_down_migration(old_catalog, new_catalog, migration_filename)?;
+    let down_path = down_migration_path(migration_path);
+    if let Some(parent) = down_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&down_path, placeholders.restore(&down.down_sql))?;
+    println!("Created down migration: {}", down_path.display());
+
+    if !down.irreversible.is_empty() {
+        println!(
+            "⚠️  The down migration cannot restore data discarded by {} statement(s):",
+            down.irreversible.len()
+        );
+        for statement in &down.irreversible {
+            println!("   {}", statement);
+        }
+    }
+    Ok(())
+}
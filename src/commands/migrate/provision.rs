@@ -1,10 +1,11 @@
-use crate::commands::migrate::apply::apply_pending_migrations;
+use crate::commands::migrate::apply::{ApplyLimit, apply_pending_migrations};
+use crate::commands::migrate::hooks::MigrationHooks;
 use crate::config::Config;
 use crate::migration::baseline::apply_baseline_to_target;
 use crate::migration::{discover_migrations, find_latest_baseline};
 use crate::migration_tracking::{
-    MigrationLock, calculate_checksum, ensure_section_tracking_table, ensure_tracking_table_exists,
-    register_baseline_start,
+    AuditLog, MigrationLock, calculate_checksum, ensure_section_tracking_table,
+    ensure_tracking_table_exists, register_baseline_start,
 };
 use crate::modules::{ModuleRuntime, ModuleSelection, parse_section_files};
 use anyhow::{Context, Result};
@@ -57,6 +58,10 @@ async fn provision_inner(
     // Ensure the tracking tables exist so we can read the target's state.
     ensure_tracking_table_exists(pool, &config.migration.tracking_table).await?;
     ensure_section_tracking_table(pool, &config.migration.tracking_table).await?;
+    let audit = AuditLog::from_config(config, None);
+    if let Some(audit) = &audit {
+        audit.ensure_table(pool).await?;
+    }
 
     let store =
         crate::migration_tracking::TrackingStore::new(pool, &config.migration.tracking_table)?;
@@ -214,6 +219,7 @@ async fn provision_inner(
                 baseline.version,
                 &baseline_sql,
                 source,
+                audit.as_ref(),
                 |section| {
                     is_adopted(section)
                         && !crate::modules::remap_source_held(section, runtime.established())
@@ -260,8 +266,18 @@ async fn provision_inner(
         if let Some(named) = selection.named() {
             runtime.record_adopted(named).await?;
         }
-        let applied_any =
-            apply_pending_migrations(pool, config, &migrations, &selection, &mut runtime).await?;
+        let applied_any = apply_pending_migrations(
+            pool,
+            config,
+            &migrations,
+            &selection,
+            &mut runtime,
+            &MigrationHooks::default(),
+            &ApplyLimit::default(),
+            audit.as_ref(),
+        )
+        .await?
+        .applied_any;
         if !applied_any {
             println!("Nothing to apply — up to date.");
         }
@@ -310,6 +326,7 @@ async fn provision_inner(
                 baseline.version,
                 &baseline_sql,
                 source,
+                audit.as_ref(),
                 |section| selection.selects(section.module.as_deref()),
             )
             .await?;
@@ -323,8 +340,17 @@ async fn provision_inner(
                 .record_provisioned(&selection.named().cloned().unwrap_or_default())
                 .await?;
 
-            apply_pending_migrations(pool, config, &post_baseline, &selection, &mut runtime)
-                .await?;
+            apply_pending_migrations(
+                pool,
+                config,
+                &post_baseline,
+                &selection,
+                &mut runtime,
+                &MigrationHooks::default(),
+                &ApplyLimit::default(),
+                audit.as_ref(),
+            )
+            .await?;
             println!("✅ Provisioned from baseline {}.", baseline.version);
         }
         None => {
@@ -347,7 +373,17 @@ async fn provision_inner(
             runtime
                 .record_provisioned(&selection.named().cloned().unwrap_or_default())
                 .await?;
-            apply_pending_migrations(pool, config, &migrations, &selection, &mut runtime).await?;
+            apply_pending_migrations(
+                pool,
+                config,
+                &migrations,
+                &selection,
+                &mut runtime,
+                &MigrationHooks::default(),
+                &ApplyLimit::default(),
+                audit.as_ref(),
+            )
+            .await?;
             println!("✅ Provisioned from migrations.");
         }
     }
@@ -392,6 +428,7 @@ async fn register_and_apply_baseline(
     version: u64,
     baseline_sql: &str,
     source: &str,
+    audit: Option<&AuditLog>,
     select_section: impl Fn(&crate::migration::section_parser::MigrationSection) -> bool + Copy,
 ) -> Result<()> {
     let new_checksum = calculate_checksum(baseline_sql);
@@ -400,8 +437,11 @@ async fn register_and_apply_baseline(
     // an already-applied baseline section is pinned, but an unapplied one may
     // be fixed in the repo and re-run. Validate the current file's sections
     // against the registered rows and sync any unapplied ones.
-    let full_sections =
-        crate::migration::parse_migration_sections(Path::new(source), baseline_sql)?;
+    let full_sections = crate::migration::parse_migration_sections_with_variables(
+        Path::new(source),
+        baseline_sql,
+        &config.variables,
+    )?;
     crate::migration::validate_sections(&full_sections)
         .with_context(|| format!("Invalid section configuration in baseline ({})", source))?;
     let recorded = crate::migration_tracking::section_tracking::fetch_section_rows(
@@ -480,6 +520,7 @@ async fn register_and_apply_baseline(
         version,
         &selected,
         source,
+        audit,
     )
     .await
 }
@@ -0,0 +1,410 @@
+//! `migrate rebase` — move this branch's migrations after the main line's.
+//!
+//! Two branches that each ran `migrate new` generated their migrations from
+//! the same starting state. Once the main line is merged in, the branch's
+//! migrations may repeat steps main already took, miss objects main added, or
+//! sort between main's versions. Git tells them apart: the branch's own are
+//! the files main doesn't have, and one is stale when main has a migration the
+//! commit that added it didn't.
+//!
+//! Rebase renumbers the branch's migrations after main's newest, keeping their
+//! order, and regenerates the last one from the schema files through the same
+//! replay `migrate update` uses. Earlier branch migrations are only renumbered:
+//! the schema files describe the end state, not theirs. Sections written by
+//! hand (an explicit `-- pgmt:section` header) are kept as written; the
+//! regenerated steps only cover what they don't, and a section that no longer
+//! applies after main's migrations is kept with a warning. The `schema` and
+//! `seed_data` sections `migrate new` writes itself are regenerated instead.
+
+use crate::catalog::Catalog;
+use crate::commands::migrate::new::write_down_migration;
+use crate::commands::migrate::section_executor::{ExecutionMode, SectionExecutor};
+use crate::config::Config;
+use crate::config::filter::ObjectFilter;
+use crate::migrate::{MigrationGenerationInput, generate_migration, is_generated_section};
+use crate::migration::parsing::parse_migration_filename;
+use crate::migration::section_parser::MigrationSection;
+use crate::migration::{
+    BaselineConfig, ParsedMigration, discover_migrations, down_migration_path,
+    generate_baseline_filename, get_migration_update_starting_state,
+    parse_migration_sections_with_variables,
+};
+use crate::progress::SectionReporter;
+use anyhow::{Context, Result, anyhow, bail};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub async fn cmd_migrate_rebase(
+    config: &Config,
+    root_dir: &Path,
+    onto: &str,
+    dry_run: bool,
+    shadow: &crate::config::ShadowDatabase,
+) -> Result<()> {
+    if config.modules.is_enabled() {
+        bail!(
+            "migrate rebase is not supported on module projects: their migrations are \
+             sectioned per module, and a regenerated migration must keep each section's \
+             attribution"
+        );
+    }
+
+    let migrations_dir = root_dir.join(&config.directories.migrations);
+    let baselines_dir = root_dir.join(&config.directories.baselines);
+    let plan = plan_rebase(config, root_dir, onto)?;
+
+    let Some(plan) = plan else {
+        return Ok(());
+    };
+
+    println!(
+        "{} migration(s) on this branch were generated without {}'s migration(s) {}:",
+        plan.moves.len(),
+        onto,
+        plan.mainline_new
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    for (from, to) in &plan.moves {
+        println!("  {} -> {}", file_name(&from.path), file_name(&to.path));
+    }
+    if dry_run {
+        println!("Dry run: no files changed");
+        return Ok(());
+    }
+
+    // Renumber every branch migration before regenerating the last, so its
+    // starting-state replay sees main's migrations and the renumbered ones in
+    // their new order.
+    let mut last_has_down = false;
+    for (from, to) in &plan.moves {
+        std::fs::rename(&from.path, &to.path)
+            .with_context(|| format!("Failed to rename {}", from.path.display()))?;
+        let down = down_migration_path(&from.path);
+        last_has_down = down.exists();
+        if last_has_down {
+            std::fs::rename(&down, down_migration_path(&to.path))
+                .with_context(|| format!("Failed to rename {}", down.display()))?;
+        }
+    }
+
+    let (original, last) = plan.moves.last().expect("plan has moves");
+    for (_, migration) in &plan.moves[..plan.moves.len() - 1] {
+        println!("Renumbered: {} (kept as written)", migration.path.display());
+    }
+
+    let baseline_config = BaselineConfig {
+        validate_consistency: config.migration.validate_baseline_consistency,
+        verbose: true,
+    };
+    let roles_file = root_dir.join(&config.directories.roles);
+
+    // The hand-written sections run on top of the starting state first; what
+    // the schema files still need beyond them becomes the regenerated steps.
+    let original_sql = std::fs::read_to_string(&last.path)?;
+    let handwritten: Vec<MigrationSection> =
+        parse_migration_sections_with_variables(&last.path, &original_sql, &config.variables)?
+            .into_iter()
+            .filter(|s| !s.raw_header.is_empty() && !is_generated_section(&s.name))
+            .collect();
+
+    let starting_pool = shadow.connect_fresh().await?;
+    let seeded = crate::seed_data::seeded_tables(config, root_dir)?;
+    let mut old_catalog = get_migration_update_starting_state(
+        &starting_pool,
+        &baselines_dir,
+        &migrations_dir,
+        last.version,
+        &roles_file,
+        &baseline_config,
+        config,
+        None,
+    )
+    .await
+    .with_context(|| {
+        format!(
+            "Replaying the migrations before {} failed. An earlier migration on this branch \
+             may repeat a step from {}: edit it, then run 'pgmt migrate update {}{}'",
+            last.path.display(),
+            onto,
+            config.migration.filename_prefix,
+            last.version
+        )
+    })?
+    .with_data(&starting_pool, &seeded)
+    .await?;
+    crate::tenants::name_seed_rows_for_planning(config, &mut old_catalog);
+    let mut conflicts = Vec::new();
+    let covered_catalog = if handwritten.is_empty() {
+        old_catalog.clone()
+    } else {
+        let mut executor = SectionExecutor::new(
+            starting_pool.clone(),
+            config.migration.tracking_table.clone(),
+            SectionReporter::new(handwritten.len(), false),
+            ExecutionMode::Validation,
+            false,
+        );
+        for section in &handwritten {
+            if let Err(e) = executor.execute_section(0, section).await {
+                conflicts.push((section.name.clone(), format!("{:#}", e)));
+            }
+        }
+        let mut covered = Catalog::load_managed(&starting_pool, &ObjectFilter::from_config(config))
+            .await?
+            .with_data(&starting_pool, &seeded)
+            .await?;
+        crate::tenants::name_seed_rows_for_planning(config, &mut covered);
+        covered
+    };
+    crate::db::branch::drop_branch(starting_pool).await?;
+
+    let (new_catalog, _) =
+        crate::schema_ops::apply_current_schema_to_shadow_with_mapping(config, root_dir, shadow)
+            .await?;
+    crate::validation::apply_column_order_validation(
+        &old_catalog,
+        &new_catalog,
+        config.migration.column_order,
+    )?;
+
+    let placeholders = crate::schema_ops::placeholder_restorer(config, root_dir)?;
+    let generated = generate_migration(MigrationGenerationInput {
+        old_catalog: covered_catalog,
+        new_catalog: new_catalog.clone(),
+        description: last.description.clone(),
+        version: last.version,
+        filename_prefix: config.migration.filename_prefix.clone(),
+    })?;
+    let generated_sql = placeholders.restore(&generated.migration_sql);
+
+    let migration_sql = if handwritten.is_empty() {
+        generated_sql
+    } else {
+        render_with_handwritten(
+            &handwritten,
+            generated.has_changes.then_some(&*generated_sql),
+        )
+    };
+    std::fs::write(&last.path, &migration_sql)?;
+    println!(
+        "Regenerated: {} (was {})",
+        last.path.display(),
+        file_name(&original.path)
+    );
+    if last_has_down {
+        write_down_migration(
+            &old_catalog,
+            &new_catalog,
+            &last.path,
+            file_name(&last.path),
+            &placeholders,
+        )?;
+    }
+
+    for (name, error) in &conflicts {
+        println!(
+            "⚠️  Hand-written section '{}' no longer applies after {}'s migrations; it was \
+             kept as written — review it:",
+            name, onto
+        );
+        for line in error.lines() {
+            println!("   {}", line);
+        }
+    }
+
+    println!("Rebase complete!");
+    Ok(())
+}
+
+/// The branch's migrations and the versions they move to.
+struct RebasePlan {
+    /// Main's versions the branch's migrations were generated without.
+    mainline_new: BTreeSet<u64>,
+    /// Each branch migration, oldest first, and where it moves to.
+    moves: Vec<(ParsedMigration, ParsedMigration)>,
+}
+
+/// Work out what to renumber, or `None` (after saying why) when the branch's
+/// migrations already follow main's.
+fn plan_rebase(config: &Config, root_dir: &Path, onto: &str) -> Result<Option<RebasePlan>> {
+    let migrations_dir = root_dir.join(&config.directories.migrations);
+    let dir = config.directories.migrations.trim_end_matches('/');
+
+    let on_onto = migration_versions_at(root_dir, onto, dir)?;
+    let local = discover_migrations(&migrations_dir)?;
+
+    let local_versions: BTreeSet<u64> = local.iter().map(|m| m.version).collect();
+    if let Some(missing) = on_onto.difference(&local_versions).next() {
+        bail!(
+            "Migration {}{} from {} is not in the working tree: merge {} into this branch first",
+            config.migration.filename_prefix,
+            missing,
+            onto,
+            onto
+        );
+    }
+
+    let branch: Vec<ParsedMigration> = local
+        .into_iter()
+        .filter(|m| !on_onto.contains(&m.version))
+        .collect();
+    if branch.is_empty() {
+        println!(
+            "No migrations on this branch beyond {} - nothing to rebase",
+            onto
+        );
+        return Ok(None);
+    }
+
+    // A migration was generated against the migrations in the commit that
+    // added it (HEAD's, while it is uncommitted). After merging main, the
+    // merge base no longer says what the branch started from; these do.
+    let mut mainline_new = BTreeSet::new();
+    for migration in &branch {
+        let filename = file_name(&migration.path);
+        let added_in = git(
+            root_dir,
+            &[
+                "log",
+                "--diff-filter=A",
+                "--format=%H",
+                "--",
+                &format!("{}/{}", dir, filename),
+            ],
+        )?;
+        let base = added_in.lines().last().unwrap_or("HEAD");
+        let seen = migration_versions_at(root_dir, base, dir)?;
+        mainline_new.extend(on_onto.difference(&seen));
+    }
+    if mainline_new.is_empty() {
+        println!(
+            "This branch's migrations were generated after {}'s latest - nothing to rebase",
+            onto
+        );
+        return Ok(None);
+    }
+
+    // A paired baseline was rendered from the branch's end state without
+    // main's changes; regenerating it is `migrate baseline`'s job.
+    for migration in &branch {
+        let baseline = root_dir
+            .join(&config.directories.baselines)
+            .join(generate_baseline_filename(migration.version));
+        if baseline.exists() {
+            bail!(
+                "Migration {}{} has a paired baseline ({}), which rebase cannot regenerate. \
+                 Delete it, rebase, then recreate it with 'pgmt migrate baseline'",
+                config.migration.filename_prefix,
+                migration.version,
+                baseline.display()
+            );
+        }
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| anyhow!("System time is before Unix epoch: {}", e))?
+        .as_secs();
+    let newest = on_onto.last().copied().unwrap_or(0);
+    let first = now.max(newest + 1);
+    let moves = branch
+        .into_iter()
+        .zip(first..)
+        .map(|(from, version)| {
+            let filename = format!(
+                "{}{}_{}.sql",
+                config.migration.filename_prefix, version, from.description
+            );
+            let to = ParsedMigration {
+                path: migrations_dir.join(filename),
+                version,
+                description: from.description.clone(),
+            };
+            (from, to)
+        })
+        .collect();
+
+    Ok(Some(RebasePlan {
+        mainline_new,
+        moves,
+    }))
+}
+
+/// The versions of the migration files in `dir` at git revision `rev`.
+fn migration_versions_at(root_dir: &Path, rev: &str, dir: &str) -> Result<BTreeSet<u64>> {
+    let listing = git(
+        root_dir,
+        &["ls-tree", "--name-only", rev, "--", &format!("{}/", dir)],
+    )?;
+    Ok(listing
+        .lines()
+        .filter_map(|path| path.rsplit('/').next())
+        .filter_map(parse_migration_filename)
+        .map(|(version, _)| version)
+        .collect())
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+}
+
+/// Run git in the project root and return its trimmed stdout.
+fn git(root_dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root_dir)
+        .args(args)
+        .output()
+        .context("Failed to run git: migrate rebase needs git to find the main line")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The rebased file: the hand-written sections exactly as written, then the
+/// regenerated steps (if any) in a section of their own. That is the order the
+/// steps were computed in — the diff is taken against the starting state with
+/// the hand-written sections already run — so a backfill still runs before
+/// a drop it reads from.
+fn render_with_handwritten(handwritten: &[MigrationSection], generated: Option<&str>) -> String {
+    let mut parts = Vec::new();
+    for section in handwritten {
+        let body = section.raw_sql.as_deref().unwrap_or(&section.sql);
+        parts.push(format!(
+            "{}\n{}",
+            section.raw_header,
+            body.trim_end_matches('\n')
+        ));
+    }
+    // Seed data changes come back already split into their own sections.
+    if let Some(generated) = generated.filter(|sql| sql.starts_with("-- pgmt:section")) {
+        parts.push(generated.trim_end().to_string());
+    } else if let Some(generated) = generated {
+        let mut name = "rebased".to_string();
+        let mut n = 1;
+        while handwritten.iter().any(|s| s.name == name) {
+            n += 1;
+            name = format!("rebased_{}", n);
+        }
+        parts.push(format!(
+            "-- pgmt:section name=\"{}\"\n{}",
+            name,
+            generated.trim_end()
+        ));
+    }
+    let mut sql = parts.join("\n\n");
+    sql.push('\n');
+    sql
+}
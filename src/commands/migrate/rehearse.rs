@@ -0,0 +1,193 @@
+//! `migrate apply --rehearse` — run the pending migrations against a copy of
+//! the target's schema before running them for real.
+//!
+//! The copy is a schema-only branch: an empty database on the target's own
+//! server when the target role has CREATEDB there, otherwise a fresh shadow
+//! database (Docker unless configured). It is seeded with the target's managed
+//! schema and a copy of its tracking tables — the target's ledger, none of its
+//! data — and then the real apply path runs on it: the same guards, section
+//! executor, repeatable migrations and hook files. Finally the result is diffed
+//! against the schema files.
+//!
+//! The target is only read: no advisory lock is taken and nothing is written.
+//! Hook commands are skipped, since they act outside the database. Section
+//! timings come from empty tables, so they show lock-heavy DDL and broken SQL,
+//! not how long a backfill will take.
+
+use crate::catalog::Catalog;
+use crate::commands::diff_output::{DiffContext, DiffFormat, has_differences, output_diff};
+use crate::commands::migrate::apply::{ApplyLimit, apply_with_module_guard};
+use crate::commands::migrate::hooks::MigrationHooks;
+use crate::commands::migrate::repeatable::discover_repeatable_migrations;
+use crate::config::{Config, ObjectFilter, ShadowDatabase, TargetUrl};
+use crate::diff::operations::SqlRenderer;
+use crate::diff::plan;
+use crate::migration::{ParsedMigration, discover_migrations};
+use crate::migration_tracking::{
+    TrackingRows, TrackingStore, ensure_repeatable_tracking_table, ensure_section_tracking_table,
+    ensure_tracking_table_exists,
+};
+use crate::modules::ModuleSelection;
+use crate::schema_loader::SchemaFile;
+use crate::schema_ops::{apply_current_schema_to_shadow, apply_roles_file};
+use anyhow::{Context, Result, bail};
+use console::style;
+use sqlx::PgPool;
+use std::path::Path;
+
+pub async fn cmd_migrate_rehearse(
+    config: &Config,
+    root_dir: &Path,
+    target: &TargetUrl,
+    shadow: &ShadowDatabase,
+    selection: ModuleSelection,
+    limit: ApplyLimit,
+) -> Result<()> {
+    println!("Rehearsing migrations against a copy of the target schema");
+
+    let migrations = discover_migrations(&root_dir.join(&config.directories.migrations))?;
+    let repeatables = discover_repeatable_migrations(config, root_dir)?;
+    let hooks = MigrationHooks::load(config, root_dir)?.without_commands();
+
+    let target_pool =
+        crate::db::connection::connect_to_database(target.as_str(), "target database").await?;
+    let target_catalog =
+        Catalog::load_managed(&target_pool, &ObjectFilter::from_config(config)).await?;
+    let ledger = TrackingStore::new(&target_pool, &config.migration.tracking_table)?
+        .export_rows()
+        .await?;
+    target_pool.close().await;
+
+    let branch = create_rehearsal_branch(config, root_dir, target, shadow).await?;
+    let result = rehearse_on_branch(
+        config,
+        root_dir,
+        &branch,
+        &target_catalog,
+        &ledger,
+        &migrations,
+        &repeatables,
+        &selection,
+        &hooks,
+        &limit,
+    )
+    .await;
+    crate::db::branch::drop_branch(branch).await?;
+    let rehearsed_catalog = result.context("Rehearsal failed; the target was not changed")?;
+
+    println!("\nComparing the rehearsed schema with the schema files...");
+    let schema_catalog = apply_current_schema_to_shadow(config, root_dir, shadow).await?;
+    let steps = plan(&rehearsed_catalog, &schema_catalog)?;
+    if has_differences(&steps) {
+        output_diff(
+            &steps,
+            &DiffFormat::Detailed,
+            &DiffContext::new("rehearsed target", "schema files"),
+            &rehearsed_catalog,
+            &schema_catalog,
+            None,
+        )?;
+        bail!(
+            "the migrations applied cleanly, but the rehearsed schema differs from the schema \
+             files (see above); the target was not changed"
+        );
+    }
+    println!(
+        "{} Rehearsed schema matches the schema files; the target was not changed",
+        style("✓").green()
+    );
+    Ok(())
+}
+
+/// An empty branch on the target's server, or a clean shadow database when
+/// the target role can't create databases there.
+async fn create_rehearsal_branch(
+    config: &Config,
+    root_dir: &Path,
+    target: &TargetUrl,
+    shadow: &ShadowDatabase,
+) -> Result<PgPool> {
+    match crate::db::branch::empty_branch_url(target.as_str()).await {
+        Ok(url) => {
+            println!("Created a rehearsal branch on the target's server");
+            crate::db::connection::connect_with_retry(&url).await
+        }
+        Err(e) => {
+            println!(
+                "Could not branch on the target's server ({}); rehearsing on the shadow \
+                 database instead",
+                e
+            );
+            let pool = shadow.connect_fresh().await?;
+            crate::db::cleaner::clean_shadow_db(&pool, &config.objects).await?;
+            // Roles are server-wide: a same-server branch shares the target's,
+            // a shadow only has the ones the roles file creates.
+            apply_roles_file(
+                &pool,
+                &root_dir.join(&config.directories.roles),
+                &config.variables,
+            )
+            .await?;
+            Ok(pool)
+        }
+    }
+}
+
+/// Seed the branch with the target's schema and ledger, run the apply on it,
+/// and return the branch's managed catalog.
+#[allow(clippy::too_many_arguments)]
+async fn rehearse_on_branch(
+    config: &Config,
+    root_dir: &Path,
+    pool: &PgPool,
+    target_catalog: &Catalog,
+    ledger: &TrackingRows,
+    migrations: &[ParsedMigration],
+    repeatables: &[SchemaFile],
+    selection: &ModuleSelection,
+    hooks: &MigrationHooks,
+    limit: &ApplyLimit,
+) -> Result<Catalog> {
+    for step in plan(&Catalog::empty(), target_catalog)? {
+        for rendered in step.to_sql() {
+            sqlx::raw_sql(sqlx::AssertSqlSafe(rendered.sql.clone()))
+                .execute(pool)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to copy the target schema to the rehearsal branch:\n{}",
+                        rendered.sql
+                    )
+                })?;
+        }
+    }
+
+    // A target from before section tracking gets its sections table (and the
+    // synthetic rows for its applied migrations) from the apply itself, as the
+    // real apply would; otherwise copy the target's tables as they are.
+    let tracking_table = &config.migration.tracking_table;
+    ensure_tracking_table_exists(pool, tracking_table).await?;
+    ensure_repeatable_tracking_table(pool, tracking_table).await?;
+    let store = TrackingStore::new(pool, tracking_table)?;
+    if ledger.sections.is_some() {
+        ensure_section_tracking_table(pool, tracking_table).await?;
+    } else if ledger.modules.is_some() {
+        store.ensure_subscription_tables().await?;
+    }
+    store.import_rows(ledger).await?;
+
+    apply_with_module_guard(
+        config,
+        root_dir,
+        pool,
+        migrations,
+        repeatables,
+        selection,
+        hooks,
+        limit,
+        None,
+    )
+    .await?;
+
+    Catalog::load_managed(pool, &ObjectFilter::from_config(config)).await
+}
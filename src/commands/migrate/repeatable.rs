@@ -0,0 +1,130 @@
+//! Repeatable migrations: files in the `repeatable/` directory that
+//! `migrate apply` re-executes, after every versioned migration has settled,
+//! whenever their content differs from what last ran on the target.
+//!
+//! They suit objects that are replaced wholesale (`CREATE OR REPLACE
+//! FUNCTION`/`VIEW`, grants): the file is the definition, and editing it is
+//! the change. Files are ordered by `-- require:` exactly like schema files,
+//! and each one runs in its own transaction together with its tracking row,
+//! so a failed file is retried on the next apply.
+
+use crate::config::{Config, Variables};
+use crate::db::error_context::SqlErrorContext;
+use crate::migration_tracking::{TrackingStore, calculate_checksum};
+use crate::schema_loader::{SchemaFile, SchemaLoader, SchemaLoaderConfig};
+use anyhow::{Context, Result, anyhow};
+use sqlx::{Executor, PgPool};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+
+/// The repeatable migrations in the project, in `-- require:` order.
+pub fn discover_repeatable_migrations(config: &Config, root_dir: &Path) -> Result<Vec<SchemaFile>> {
+    let repeatable_dir = root_dir.join(&config.directories.repeatable);
+    SchemaLoader::new(SchemaLoaderConfig::new(repeatable_dir))
+        .load_ordered_schema_files()
+        .context("Failed to load repeatable migrations")
+}
+
+/// The repeatable files whose current checksum differs from the recorded one
+/// (including files that never ran), in execution order.
+pub fn changed_repeatable_migrations<'a>(
+    files: &'a [SchemaFile],
+    recorded: &BTreeMap<String, String>,
+) -> Vec<(&'a SchemaFile, String)> {
+    files
+        .iter()
+        .map(|file| (file, calculate_checksum(&file.content)))
+        .filter(|(file, checksum)| recorded.get(&file.relative_path) != Some(checksum))
+        .collect()
+}
+
+/// Re-run every changed repeatable migration on the target. The repeatable
+/// tracking table must already exist. Returns whether anything ran.
+///
+/// `${var}` placeholders are substituted into what runs; the checksum stays
+/// that of the file as written, so changing a variable does not re-run it.
+pub(crate) async fn apply_repeatable_migrations(
+    pool: &PgPool,
+    store: &TrackingStore,
+    files: &[SchemaFile],
+    variables: &Variables,
+) -> Result<bool> {
+    let recorded = store.repeatable_checksums().await?;
+    let changed = changed_repeatable_migrations(files, &recorded);
+    if changed.is_empty() {
+        return Ok(false);
+    }
+
+    println!("\nApplying repeatable migrations");
+    for (file, checksum) in changed {
+        let sql = variables
+            .substitute(&file.content)
+            .with_context(|| format!("Failed to substitute variables in {}", file.relative_path))?;
+        let start = Instant::now();
+        let mut tx = pool.begin().await?;
+        if let Err(e) = tx.execute(sqlx::AssertSqlSafe(sql.clone())).await {
+            tx.rollback().await?;
+            let ctx = SqlErrorContext::from_sqlx_error(&e, &sql);
+            return Err(anyhow!("{}", ctx.format(&file.relative_path, &sql)))
+                .with_context(|| format!("Repeatable migration '{}' failed", file.relative_path));
+        }
+        let duration = start.elapsed();
+        store
+            .record_repeatable_applied(
+                &mut *tx,
+                &file.relative_path,
+                &checksum,
+                duration.as_millis() as i64,
+            )
+            .await?;
+        tx.commit().await?;
+
+        let action = if recorded.contains_key(&file.relative_path) {
+            "re-ran"
+        } else {
+            "ran"
+        };
+        println!(
+            "  ✓ {} ({}, {}ms)",
+            file.relative_path,
+            action,
+            duration.as_millis()
+        );
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> SchemaFile {
+        SchemaFile {
+            relative_path: path.to_string(),
+            content: content.to_string(),
+            dependencies: vec![],
+        }
+    }
+
+    #[test]
+    fn test_changed_repeatable_migrations() {
+        let files = vec![
+            file("a.sql", "CREATE OR REPLACE VIEW a AS SELECT 1;"),
+            file("b.sql", "CREATE OR REPLACE VIEW b AS SELECT 2;"),
+            file("c.sql", "CREATE OR REPLACE VIEW c AS SELECT 3;"),
+        ];
+        let recorded = BTreeMap::from([
+            ("a.sql".to_string(), calculate_checksum(&files[0].content)),
+            ("b.sql".to_string(), "stale".to_string()),
+        ]);
+
+        let changed: Vec<&str> = changed_repeatable_migrations(&files, &recorded)
+            .into_iter()
+            .map(|(f, _)| f.relative_path.as_str())
+            .collect();
+        // Unchanged a.sql is skipped; edited b.sql and new c.sql run, in order.
+        assert_eq!(changed, vec!["b.sql", "c.sql"]);
+    }
+}
@@ -23,8 +23,8 @@ use crate::migration::section_parser::MigrationSection;
 use crate::migration::{discover_baselines, discover_migrations, parse_migration_sections};
 use crate::migration_tracking::section_tracking::{SectionStatus, format_sections_table_name};
 use crate::migration_tracking::{
-    MigrationLock, calculate_checksum, ensure_section_tracking_table, ensure_tracking_table_exists,
-    update_stored_file_checksum, version_to_db,
+    AuditLog, MigrationLock, calculate_checksum, ensure_section_tracking_table,
+    ensure_tracking_table_exists, update_stored_file_checksum, version_to_db,
 };
 use anyhow::{Context, Result, anyhow, bail};
 use sqlx::PgPool;
@@ -169,6 +169,19 @@ async fn mark_completed(
         .await?;
     }
 
+    if let Some(audit) = AuditLog::from_config(config, None) {
+        audit
+            .record_resolution(
+                pool,
+                version,
+                is_baseline,
+                section,
+                "mark_completed",
+                "completed",
+            )
+            .await?;
+    }
+
     println!(
         "Marked {} section {}/{} completed:",
         kind(is_baseline),
@@ -276,7 +289,8 @@ async fn restamp(
         version
     );
 
-    for (name, _status, stored_checksum, stored_order) in targets {
+    let audit = AuditLog::from_config(config, None);
+    for (name, status, stored_checksum, stored_order) in targets {
         // The section's index in the CURRENT file is its new section_order —
         // restamping a consciously reordered section syncs the stored order too,
         // otherwise the immutability reorder bail would keep blocking it forever.
@@ -311,6 +325,12 @@ async fn restamp(
         .execute(pool)
         .await?;
 
+        if let Some(audit) = &audit {
+            audit
+                .record_resolution(pool, version, is_baseline, name, "restamp", status)
+                .await?;
+        }
+
         println!(
             "  {}: {} -> {}",
             name,
@@ -0,0 +1,188 @@
+//! `migrate rollback` — run paired down migrations against the target.
+//!
+//! Down files are written by `migrate new --down` into `migrations/down/`
+//! under the same filename as their migration. Rollback walks the applied
+//! migrations newest-first, runs each down file section by section through
+//! the same executor as `apply` (assertions, retries, timeouts and the audit
+//! log, but no section tracking rows), and only then forgets the version in
+//! the tracking tables — so a failed rollback leaves the migration recorded as
+//! applied and the operator decides what to do with the partially reverted
+//! schema.
+//!
+//! Runs under the same advisory lock `apply`/`provision` take.
+
+use crate::commands::migrate::section_executor::{ExecutionMode, SectionExecutor};
+use crate::config::Config;
+use crate::migration::parsing::find_migration_by_version;
+use crate::migration::{
+    ParsedMigration, discover_migrations, down_migration_path,
+    parse_migration_sections_with_variables, validate_sections,
+};
+use crate::migration_tracking::{
+    AuditLog, MigrationLock, TrackingStore, ensure_section_tracking_table,
+    ensure_tracking_table_exists, version_from_db,
+};
+use crate::progress::SectionReporter;
+use anyhow::{Context, Result, anyhow, bail};
+use sqlx::PgPool;
+use std::path::Path;
+use std::time::Instant;
+
+pub async fn cmd_migrate_rollback(
+    config: &Config,
+    root_dir: &Path,
+    target: &crate::config::TargetUrl,
+    to: Option<&str>,
+) -> Result<()> {
+    if config.modules.is_enabled() {
+        bail!(
+            "migrate rollback is not supported on module projects: a module deploy applies a \
+             subset of each migration's sections, so a whole-file down migration cannot be \
+             trusted to reverse it"
+        );
+    }
+
+    let migrations_dir = root_dir.join(&config.directories.migrations);
+    let migrations = discover_migrations(&migrations_dir)?;
+    let keep_through = match to {
+        Some(version_str) => Some(
+            find_migration_by_version(&migrations_dir, version_str)?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Migration '{}' not found. Use 'pgmt migrate status' to see available \
+                         migrations.",
+                        version_str
+                    )
+                })?
+                .version,
+        ),
+        None => None,
+    };
+
+    let pool =
+        crate::db::connection::connect_to_database(target.as_str(), "target database").await?;
+    let lock = MigrationLock::acquire(target.as_str(), &config.migration.tracking_table).await?;
+
+    let result = rollback_inner(config, &pool, &migrations, keep_through).await;
+    lock.release().await?;
+    result
+}
+
+async fn rollback_inner(
+    config: &Config,
+    pool: &PgPool,
+    migrations: &[ParsedMigration],
+    keep_through: Option<u64>,
+) -> Result<()> {
+    ensure_tracking_table_exists(pool, &config.migration.tracking_table).await?;
+    ensure_section_tracking_table(pool, &config.migration.tracking_table).await?;
+    let store = TrackingStore::new(pool, &config.migration.tracking_table)?;
+
+    let listing = store.migration_listing().await?;
+    let baseline_watermark = listing
+        .iter()
+        .filter(|(_, _, _, _, is_baseline, _)| *is_baseline)
+        .map(|(version, ..)| version_from_db(*version))
+        .max();
+    let mut applied: Vec<(u64, i64)> = listing
+        .iter()
+        .filter(|(_, _, _, _, is_baseline, _)| !*is_baseline)
+        .map(|(version, _, _, incomplete, _, _)| (version_from_db(*version), *incomplete))
+        .collect();
+    applied.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+
+    let to_roll_back: Vec<(u64, i64)> = match keep_through {
+        Some(keep) => applied.into_iter().filter(|(v, _)| *v > keep).collect(),
+        None => applied.into_iter().take(1).collect(),
+    };
+    if to_roll_back.is_empty() {
+        println!("Nothing to roll back");
+        return Ok(());
+    }
+
+    // Validate the whole plan before touching the schema: every version must
+    // be fully applied, above the baseline, and have a down file on disk.
+    let mut plan = Vec::new();
+    for (version, incomplete) in &to_roll_back {
+        if let Some(watermark) = baseline_watermark
+            && *version <= watermark
+        {
+            bail!(
+                "cannot roll back migration {version}: it is covered by baseline {watermark}, \
+                 which this target was provisioned from"
+            );
+        }
+        if *incomplete > 0 {
+            bail!(
+                "migration {version} is only partially applied ({incomplete} section(s) not \
+                 completed). Finish it with `pgmt migrate apply` (or repair it with `pgmt migrate \
+                 resolve`) before rolling back."
+            );
+        }
+        let migration = migrations
+            .iter()
+            .find(|m| m.version == *version)
+            .ok_or_else(|| {
+                anyhow!("migration {version} is applied on the target but its file is missing")
+            })?;
+        let down_path = down_migration_path(&migration.path);
+        if !down_path.exists() {
+            bail!(
+                "migration {version} has no down migration ({} not found). Write one by hand, \
+                 or generate future migrations with `pgmt migrate new --down`.",
+                down_path.display()
+            );
+        }
+        let sql = std::fs::read_to_string(&down_path)
+            .with_context(|| format!("Failed to read down migration {}", down_path.display()))?;
+        let sections = parse_migration_sections_with_variables(&down_path, &sql, &config.variables)
+            .with_context(|| format!("Failed to parse down migration {}", down_path.display()))?;
+        validate_sections(&sections)
+            .with_context(|| format!("Invalid down migration {}", down_path.display()))?;
+        plan.push((migration, sections));
+    }
+
+    let audit = AuditLog::from_config(config, None);
+    if let Some(audit) = &audit {
+        audit.ensure_table(pool).await?;
+    }
+
+    for (migration, sections) in &plan {
+        println!(
+            "\nRolling back migration {} - {}",
+            migration.version, migration.description
+        );
+        let start = Instant::now();
+        let mut executor = SectionExecutor::new(
+            pool.clone(),
+            config.migration.tracking_table.clone(),
+            SectionReporter::new(sections.len(), false),
+            ExecutionMode::Rollback,
+            false,
+        )
+        .with_audit(audit.clone());
+        for section in sections {
+            executor
+                .execute_section(migration.version, section)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Rollback of migration {} failed at section '{}'; the migration is \
+                         still recorded as applied and earlier down sections are not undone",
+                        migration.version, section.name
+                    )
+                })?;
+        }
+
+        store.remove_migration(migration.version).await?;
+        executor
+            .reporter()
+            .migration_summary(start.elapsed(), sections.len());
+    }
+
+    println!(
+        "\n✅ Rolled back {} migration(s); run `pgmt migrate apply` to re-apply",
+        plan.len()
+    );
+    Ok(())
+}
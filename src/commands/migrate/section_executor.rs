@@ -1,13 +1,15 @@
 use crate::config::types::TrackingTable;
 use crate::db::error_context::SqlErrorContext;
 use crate::migration::section_parser::{
-    BackoffStrategy, LockTimeoutAction, MigrationSection, RetryConfig, TransactionMode,
+    BackoffStrategy, LockTimeoutAction, MigrationSection, RetryConfig, SectionAssertion,
+    TransactionMode,
 };
+use crate::migration_tracking::AuditLog;
 use crate::migration_tracking::section_tracking::*;
 use crate::progress::SectionReporter;
 use anyhow::Result;
-use sqlx::PgPool;
 use sqlx::postgres::PgDatabaseError;
+use sqlx::{PgConnection, PgPool};
 use std::time::{Duration, Instant};
 
 /// Format a SQL execution error with rich PostgreSQL context (DETAIL, HINT, etc.)
@@ -16,6 +18,53 @@ fn format_section_error(error: sqlx::Error, sql: &str, section_name: &str) -> an
     anyhow::anyhow!("{}", ctx.format(section_name, sql))
 }
 
+/// Evaluate a section's `assert_before` (or `assert_after`) queries on `conn`,
+/// failing on the first one that doesn't return true. `outcome` finishes the
+/// error message with what that means for the section.
+async fn check_assertions(
+    conn: &mut PgConnection,
+    section: &MigrationSection,
+    before: bool,
+    outcome: &str,
+) -> Result<()> {
+    let (kind, assertions) = if before {
+        ("assert_before", &section.assert_before)
+    } else {
+        ("assert_after", &section.assert_after)
+    };
+    for SectionAssertion { query, line } in assertions {
+        let holds: Option<bool> = sqlx::query_scalar(sqlx::AssertSqlSafe(query.clone()))
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::ColumnDecode { .. } | sqlx::Error::RowNotFound => anyhow::anyhow!(
+                    "Section '{}': {} at line {} must return a single boolean: {}",
+                    section.name,
+                    kind,
+                    line,
+                    query
+                ),
+                e => format_section_error(e, query, &format!("{} {kind}", section.name)),
+            })?;
+        if holds != Some(true) {
+            anyhow::bail!(
+                "Section '{}': {} at line {} returned {}: {}\n{}",
+                section.name,
+                kind,
+                line,
+                holds.map_or("NULL".to_string(), |h| h.to_string()),
+                query,
+                outcome
+            );
+        }
+    }
+    Ok(())
+}
+
+/// What an `assert_after` failure means for a section that commits as it goes.
+const NON_TRANSACTIONAL_AFTER_FAILED: &str =
+    "The section's SQL has already run and is not rolled back; re-running re-executes it.";
+
 /// Read-only probe for INVALID indexes, run only after a non-transactional
 /// section has exhausted its retries.
 ///
@@ -77,6 +126,10 @@ pub enum ExecutionMode {
     Production,
     /// Validation mode: no retries or timeouts, just execute SQL
     Validation,
+    /// Rollback mode: production retries, timeouts and assertions for down
+    /// sections, without section tracking rows — down sections have none of
+    /// their own, and the version's rows are removed once they all succeed
+    Rollback,
 }
 
 pub struct SectionExecutor {
@@ -88,6 +141,8 @@ pub struct SectionExecutor {
     /// migration (false) — part of the section tracking key, since one version
     /// can host both.
     is_baseline: bool,
+    /// Records each attempt in the history table when auditing is on.
+    audit: Option<AuditLog>,
 }
 
 impl SectionExecutor {
@@ -104,9 +159,96 @@ impl SectionExecutor {
             reporter,
             mode,
             is_baseline,
+            audit: None,
         }
     }
 
+    /// Record every section attempt in the audit log.
+    pub fn with_audit(mut self, audit: Option<AuditLog>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// The reporter sections are reported on, for output that belongs between
+    /// them (e.g. hooks).
+    pub fn reporter(&self) -> &SectionReporter {
+        &self.reporter
+    }
+
+    /// Whether section progress goes to the sections table.
+    fn tracked(&self) -> bool {
+        !matches!(self.mode, ExecutionMode::Rollback)
+    }
+
+    /// Record `error` as the section's failure and return it.
+    async fn fail(
+        &self,
+        migration_version: u64,
+        section: &MigrationSection,
+        error: anyhow::Error,
+    ) -> Result<()> {
+        self.record_failed(migration_version, section, &format!("{:#}", error))
+            .await?;
+        Err(error)
+    }
+
+    async fn record_start(&self, migration_version: u64, section: &MigrationSection) -> Result<()> {
+        if !self.tracked() {
+            return Ok(());
+        }
+        record_section_start(
+            &self.pool,
+            &self.tracking_table,
+            migration_version,
+            self.is_baseline,
+            &section.name,
+        )
+        .await
+    }
+
+    async fn record_failed(
+        &self,
+        migration_version: u64,
+        section: &MigrationSection,
+        error: &str,
+    ) -> Result<()> {
+        if !self.tracked() {
+            return Ok(());
+        }
+        record_section_failed(
+            &self.pool,
+            &self.tracking_table,
+            migration_version,
+            self.is_baseline,
+            &section.name,
+            error,
+        )
+        .await
+    }
+
+    async fn record_complete<'e>(
+        &self,
+        executor: impl sqlx::PgExecutor<'e>,
+        migration_version: u64,
+        section: &MigrationSection,
+        rows_affected: i64,
+        duration: Duration,
+    ) -> Result<()> {
+        if !self.tracked() {
+            return Ok(());
+        }
+        record_section_complete(
+            executor,
+            &self.tracking_table,
+            migration_version,
+            self.is_baseline,
+            &section.name,
+            Some(rows_affected),
+            duration.as_millis() as i64,
+        )
+        .await
+    }
+
     /// Execute a single section
     pub async fn execute_section(
         &mut self,
@@ -121,20 +263,36 @@ impl SectionExecutor {
         // Check if section is already covered (for resume): completed, or
         // satisfied — a source-covered remap section whose objects are already
         // present; re-executing it would double-create.
-        if get_section_status(
-            &self.pool,
-            &self.tracking_table,
-            migration_version,
-            self.is_baseline,
-            &section.name,
-        )
-        .await?
-        .is_some_and(|st| st.is_covered())
+        if self.tracked()
+            && get_section_status(
+                &self.pool,
+                &self.tracking_table,
+                migration_version,
+                self.is_baseline,
+                &section.name,
+            )
+            .await?
+            .is_some_and(|st| st.is_covered())
         {
             self.reporter.skip_section(&section.name);
             return Ok(());
         }
 
+        let attempt = match &self.audit {
+            Some(audit) => Some(
+                audit
+                    .start_attempt(
+                        &self.pool,
+                        migration_version,
+                        self.is_baseline,
+                        section,
+                        !self.tracked(),
+                    )
+                    .await?,
+            ),
+            None => None,
+        };
+
         // Execute based on mode
         let result = match section.mode {
             TransactionMode::Transactional => {
@@ -147,6 +305,7 @@ impl SectionExecutor {
             TransactionMode::Autocommit => {
                 self.execute_autocommit(migration_version, section).await
             }
+            TransactionMode::Batched => self.execute_batched(migration_version, section).await,
         };
 
         match &result {
@@ -155,6 +314,13 @@ impl SectionExecutor {
                 self.reporter.fail_section(&section.name, e);
             }
         }
+        if let (Some(audit), Some(id)) = (&self.audit, attempt) {
+            // A section error outranks failing to record it.
+            let recorded = audit.finish_attempt(&self.pool, id, &result).await;
+            if result.is_ok() {
+                recorded?;
+            }
+        }
 
         result
     }
@@ -169,14 +335,7 @@ impl SectionExecutor {
             .start_section(&section.name, section.description.as_deref());
 
         let start = Instant::now();
-        record_section_start(
-            &self.pool,
-            &self.tracking_table,
-            migration_version,
-            self.is_baseline,
-            &section.name,
-        )
-        .await?;
+        self.record_start(migration_version, section).await?;
 
         // Begin transaction
         let mut tx = self.pool.begin().await?;
@@ -200,25 +359,31 @@ impl SectionExecutor {
             .await?;
         }
 
+        // Preconditions see the same transaction the body runs in
+        if let Err(e) = check_assertions(&mut tx, section, true, "The section was not run.").await {
+            tx.rollback().await?;
+            return self.fail(migration_version, section, e).await;
+        }
+
         // Execute SQL - use raw execute to support multiple statements
         use sqlx::Executor;
         let result = match tx.execute(sqlx::AssertSqlSafe(section.sql.clone())).await {
             Ok(result) => result,
             Err(e) => {
                 tx.rollback().await?;
-                record_section_failed(
-                    &self.pool,
-                    &self.tracking_table,
-                    migration_version,
-                    self.is_baseline,
-                    &section.name,
-                    &e.to_string(),
-                )
-                .await?;
+                self.record_failed(migration_version, section, &format!("{:#}", e))
+                    .await?;
                 return Err(format_section_error(e, &section.sql, &section.name));
             }
         };
 
+        if let Err(e) =
+            check_assertions(&mut tx, section, false, "The section was rolled back.").await
+        {
+            tx.rollback().await?;
+            return self.fail(migration_version, section, e).await;
+        }
+
         let duration = start.elapsed();
         let rows = result.rows_affected() as i64;
 
@@ -227,16 +392,8 @@ impl SectionExecutor {
         // a crash can never leave the DDL applied with the row stuck at
         // `running`. The recorded duration excludes the commit itself, which is
         // an acceptable trade for the closed crash window.
-        record_section_complete(
-            &mut *tx,
-            &self.tracking_table,
-            migration_version,
-            self.is_baseline,
-            &section.name,
-            Some(rows),
-            duration.as_millis() as i64,
-        )
-        .await?;
+        self.record_complete(&mut *tx, migration_version, section, rows, duration)
+            .await?;
 
         // Commit transaction (atomically persists both the DDL and the record)
         tx.commit().await?;
@@ -263,14 +420,14 @@ impl SectionExecutor {
             .unwrap_or(&default_retry_config);
         let start = Instant::now();
 
-        record_section_start(
-            &self.pool,
-            &self.tracking_table,
-            migration_version,
-            self.is_baseline,
-            &section.name,
-        )
-        .await?;
+        self.record_start(migration_version, section).await?;
+
+        let mut conn = self.pool.acquire().await?;
+        if let Err(e) = check_assertions(&mut conn, section, true, "The section was not run.").await
+        {
+            return self.fail(migration_version, section, e).await;
+        }
+        drop(conn);
 
         // Retry loop
         for attempt in 1..=retry_config.attempts {
@@ -303,19 +460,19 @@ impl SectionExecutor {
                 .await
             {
                 Ok(result) => {
+                    let mut conn = self.pool.acquire().await?;
+                    if let Err(e) =
+                        check_assertions(&mut conn, section, false, NON_TRANSACTIONAL_AFTER_FAILED)
+                            .await
+                    {
+                        return self.fail(migration_version, section, e).await;
+                    }
+
                     let duration = start.elapsed();
                     let rows = result.rows_affected() as i64;
 
-                    record_section_complete(
-                        &self.pool,
-                        &self.tracking_table,
-                        migration_version,
-                        self.is_baseline,
-                        &section.name,
-                        Some(rows),
-                        duration.as_millis() as i64,
-                    )
-                    .await?;
+                    self.record_complete(&self.pool, migration_version, section, rows, duration)
+                        .await?;
 
                     self.reporter.complete_section_with_retry(
                         &section.name,
@@ -349,18 +506,11 @@ impl SectionExecutor {
                         let guidance = detect_invalid_index_guidance(&self.pool).await;
 
                         let recorded_error = match &guidance {
-                            Some(note) => format!("{}\n\n{}", e, note),
-                            None => e.to_string(),
+                            Some(note) => format!("{:#}\n\n{}", e, note),
+                            None => format!("{:#}", e),
                         };
-                        record_section_failed(
-                            &self.pool,
-                            &self.tracking_table,
-                            migration_version,
-                            self.is_baseline,
-                            &section.name,
-                            &recorded_error,
-                        )
-                        .await?;
+                        self.record_failed(migration_version, section, &recorded_error)
+                            .await?;
 
                         let formatted = format_section_error(e, &section.sql, &section.name);
                         return Err(match guidance {
@@ -385,14 +535,7 @@ impl SectionExecutor {
             .start_section(&section.name, section.description.as_deref());
 
         let start = Instant::now();
-        record_section_start(
-            &self.pool,
-            &self.tracking_table,
-            migration_version,
-            self.is_baseline,
-            &section.name,
-        )
-        .await?;
+        self.record_start(migration_version, section).await?;
 
         // Set timeouts
         let timeout_ms = section.timeout.as_millis();
@@ -413,24 +556,31 @@ impl SectionExecutor {
             .await?;
         }
 
+        let mut conn = self.pool.acquire().await?;
+        if let Err(e) = check_assertions(&mut conn, section, true, "The section was not run.").await
+        {
+            return self.fail(migration_version, section, e).await;
+        }
+        drop(conn);
+
         let result = sqlx::query(sqlx::AssertSqlSafe(section.sql.clone()))
             .execute(&self.pool)
             .await
             .map_err(|e| format_section_error(e, &section.sql, &section.name))?;
 
+        let mut conn = self.pool.acquire().await?;
+        if let Err(e) =
+            check_assertions(&mut conn, section, false, NON_TRANSACTIONAL_AFTER_FAILED).await
+        {
+            return self.fail(migration_version, section, e).await;
+        }
+        drop(conn);
+
         let duration = start.elapsed();
         let rows = result.rows_affected() as i64;
 
-        record_section_complete(
-            &self.pool,
-            &self.tracking_table,
-            migration_version,
-            self.is_baseline,
-            &section.name,
-            Some(rows),
-            duration.as_millis() as i64,
-        )
-        .await?;
+        self.record_complete(&self.pool, migration_version, section, rows, duration)
+            .await?;
 
         self.reporter
             .complete_section(&section.name, duration, Some(rows as usize));
@@ -438,6 +588,122 @@ impl SectionExecutor {
         Ok(())
     }
 
+    /// Execute section in batches until one affects no rows. Each batch is
+    /// its own transaction and adds its row count to the section's
+    /// `rows_affected` before committing, so an interrupted run resumes with
+    /// the total intact. The SQL must only touch rows that still need the
+    /// change (e.g. `WHERE new_col IS NULL ... LIMIT 1000`), which is what
+    /// makes re-running it after an interruption safe.
+    async fn execute_batched(
+        &mut self,
+        migration_version: u64,
+        section: &MigrationSection,
+    ) -> Result<()> {
+        self.reporter
+            .start_section(&section.name, section.description.as_deref());
+
+        let batch = section.batch.unwrap_or_default();
+        let start = Instant::now();
+        self.record_start(migration_version, section).await?;
+
+        let mut conn = self.pool.acquire().await?;
+        if let Err(e) = check_assertions(&mut conn, section, true, "The section was not run.").await
+        {
+            return self.fail(migration_version, section, e).await;
+        }
+        drop(conn);
+
+        let mut number = 0;
+        let mut total = 0;
+        loop {
+            number += 1;
+            if batch.max_batches.is_some_and(|max| number > max) {
+                let error = anyhow::anyhow!(
+                    "Section '{}' still affected rows after max_batches={} batches; the \
+                     batches so far are committed and recorded, re-run to continue",
+                    section.name,
+                    number - 1
+                );
+                self.record_failed(migration_version, section, &format!("{:#}", error))
+                    .await?;
+                return Err(error);
+            }
+
+            let mut tx = self.pool.begin().await?;
+
+            // Timeouts apply to each batch
+            let timeout_ms = section.timeout.as_millis();
+            sqlx::query(sqlx::AssertSqlSafe(format!(
+                "SET LOCAL statement_timeout = '{}'",
+                timeout_ms
+            )))
+            .execute(&mut *tx)
+            .await?;
+
+            if let Some(lock_timeout) = section.lock_timeout {
+                let lock_timeout_ms = lock_timeout.as_millis();
+                sqlx::query(sqlx::AssertSqlSafe(format!(
+                    "SET LOCAL lock_timeout = '{}'",
+                    lock_timeout_ms
+                )))
+                .execute(&mut *tx)
+                .await?;
+            }
+
+            use sqlx::Executor;
+            let rows = match tx.execute(sqlx::AssertSqlSafe(section.sql.clone())).await {
+                Ok(result) => result.rows_affected() as i64,
+                Err(e) => {
+                    tx.rollback().await?;
+                    self.record_failed(migration_version, section, &format!("{:#}", e))
+                        .await?;
+                    return Err(format_section_error(e, &section.sql, &section.name));
+                }
+            };
+
+            total = if self.tracked() {
+                record_batch_progress(
+                    &mut *tx,
+                    &self.tracking_table,
+                    migration_version,
+                    self.is_baseline,
+                    &section.name,
+                    rows,
+                )
+                .await?
+            } else {
+                total + rows
+            };
+
+            if rows == 0 {
+                if let Err(e) = check_assertions(
+                    &mut tx,
+                    section,
+                    false,
+                    "The committed batches stay applied; re-running continues the section.",
+                )
+                .await
+                {
+                    tx.rollback().await?;
+                    return self.fail(migration_version, section, e).await;
+                }
+
+                let duration = start.elapsed();
+                self.record_complete(&mut *tx, migration_version, section, total, duration)
+                    .await?;
+                tx.commit().await?;
+
+                self.reporter
+                    .complete_section(&section.name, duration, Some(total as usize));
+                return Ok(());
+            }
+
+            tx.commit().await?;
+            self.reporter.batch(number, rows as u64, total as u64);
+            tokio::time::sleep(batch.sleep).await;
+        }
+    }
+
     /// Execute section in validation mode (no retries, no timeouts, no tracking)
     async fn execute_validation(&mut self, section: &MigrationSection) -> Result<()> {
         use sqlx::Executor;
@@ -449,6 +715,31 @@ impl SectionExecutor {
                     .map_err(|e| format_section_error(e, &section.sql, &section.name))?;
                 tx.commit().await?;
             }
+            TransactionMode::Batched => {
+                // Same stopping rule as production, without the pauses
+                let max_batches = section.batch.and_then(|b| b.max_batches);
+                let mut number = 0;
+                loop {
+                    number += 1;
+                    if max_batches.is_some_and(|max| number > max) {
+                        anyhow::bail!(
+                            "Section '{}' still affected rows after max_batches={} batches",
+                            section.name,
+                            number - 1
+                        );
+                    }
+                    let mut tx = self.pool.begin().await?;
+                    let rows = tx
+                        .execute(sqlx::AssertSqlSafe(section.sql.clone()))
+                        .await
+                        .map_err(|e| format_section_error(e, &section.sql, &section.name))?
+                        .rows_affected();
+                    tx.commit().await?;
+                    if rows == 0 {
+                        break;
+                    }
+                }
+            }
             TransactionMode::NonTransactional | TransactionMode::Autocommit => {
                 self.pool
                     .execute(sqlx::AssertSqlSafe(section.sql.clone()))
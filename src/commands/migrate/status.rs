@@ -1,17 +1,79 @@
-use crate::config::Config;
+use crate::commands::migrate::apply::out_of_order_migrations;
+use crate::commands::migrate::repeatable::{
+    changed_repeatable_migrations, discover_repeatable_migrations,
+};
+use crate::commands::migrate::wait::parse_version;
+use crate::config::{Config, TargetUrl};
 use crate::migration::{
-    BaselineConfig, discover_migrations, find_latest_baseline, get_migration_starting_state,
+    BaselineConfig, ParsedMigration, discover_migrations, find_latest_baseline,
+    get_migration_starting_state, get_migration_state_in_target_order, parse_migration_sections,
+};
+use crate::migration_tracking::section_tracking::SectionStatus;
+use crate::migration_tracking::{
+    SectionDetail, TrackingStore, calculate_checksum, version_from_db,
 };
-use crate::migration_tracking::TrackingStore;
 use crate::modules::{UNMODULED_DISPLAY, display_module};
 use crate::validation::{ValidationConfig, validate_catalogs};
 use crate::validation_output::{BaselineInfo, ValidationOutputOptions, format_validation_output};
-use anyhow::{Result, anyhow};
-use std::collections::BTreeMap;
+use anyhow::{Context, Result, anyhow};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 
 use crate::db::connection::connect_to_database;
 
+/// Output format for `migrate status`
+#[derive(Debug, Clone, PartialEq, Default, clap::ValueEnum)]
+pub enum StatusFormat {
+    /// Listing plus module and repeatable summaries, for terminals
+    #[default]
+    Human,
+    /// Every migration with its section records, for deploy pipelines
+    Json,
+}
+
+/// Overall state of the reported database. Variants are ordered by severity
+/// and the report takes the worst one found; with `--exit-code` it becomes
+/// the process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TargetState {
+    /// Every migration file is applied and every repeatable is current
+    UpToDate,
+    /// Migrations (or repeatables) the next `migrate apply` would run
+    Pending,
+    /// A migration started but has failed or still-running sections
+    Failed,
+    /// An applied migration's file no longer matches what was recorded
+    ChecksumMismatch,
+}
+
+impl TargetState {
+    pub fn name(self) -> &'static str {
+        match self {
+            TargetState::UpToDate => "up_to_date",
+            TargetState::Pending => "pending",
+            TargetState::Failed => "failed",
+            TargetState::ChecksumMismatch => "checksum_mismatch",
+        }
+    }
+
+    /// 1 is left to errors from status itself (connection, unreadable files)
+    /// and 2 to usage errors from argument parsing, so a script can tell a
+    /// state from a failed invocation.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            TargetState::UpToDate => 0,
+            TargetState::Pending => 10,
+            TargetState::Failed => 11,
+            TargetState::ChecksumMismatch => 12,
+        }
+    }
+}
+
+/// One row of the migration listing: `(version, description, applied_at,
+/// incomplete section count, is_baseline, consumed_re_anchor)`.
+type ListingRow = (i64, String, String, i64, bool, bool);
+
 /// Report migration status for a target or dev database.
 ///
 /// Status is the triage tool for production incidents, so it is strictly
@@ -22,59 +84,362 @@ use crate::db::connection::connect_to_database;
 /// `database_url` are resolved by the caller through the connection args
 /// structs (`--target-url` flag > `PGMT_TARGET_URL` > yaml target > dev
 /// fallback), so this function just reports on whatever it is handed.
+///
+/// Returns the database's overall state; the caller decides whether it sets
+/// the exit code.
 pub async fn cmd_migrate_status(
     config: &Config,
-    _root_dir: &Path,
+    root_dir: &Path,
     database_label: &str,
     database_url: &str,
-) -> Result<()> {
-    println!("Migration status for {} database", database_label);
+    format: &StatusFormat,
+) -> Result<TargetState> {
+    if *format == StatusFormat::Human {
+        println!("Migration status for {} database", database_label);
+    }
 
     let pool = connect_to_database(database_url, &format!("{} database", database_label)).await?;
 
-    let result = report_status(config, &pool).await;
+    let result = report_status(config, root_dir, &pool, database_label, format).await;
     pool.close().await;
     result
 }
 
-async fn report_status(config: &Config, pool: &sqlx::PgPool) -> Result<()> {
+/// What status found on one database's tracking tables, before printing.
+pub(super) struct Assessment {
+    migrations: Vec<ParsedMigration>,
+    listing: Vec<ListingRow>,
+    sections: Vec<SectionDetail>,
+    sections_exist: bool,
+    out_of_order: BTreeSet<u64>,
+    mismatches: BTreeMap<u64, BTreeSet<String>>,
+    repeatables: Vec<(String, Option<&'static str>)>,
+    pub(super) state: TargetState,
+}
+
+impl Assessment {
+    pub(super) fn pending(&self) -> Vec<&ParsedMigration> {
+        pending_migrations(&self.migrations, &self.listing)
+    }
+
+    /// The newest migration recorded as fully applied.
+    pub(super) fn latest_applied(&self) -> Option<u64> {
+        self.listing
+            .iter()
+            .filter(|row| !row.4 && row.3 == 0)
+            .map(|row| version_from_db(row.0))
+            .max()
+    }
+
+    /// Every migration the target has a row for, finished or not.
+    fn applied_migrations(&self) -> BTreeSet<u64> {
+        self.listing
+            .iter()
+            .filter(|row| !row.4)
+            .map(|row| version_from_db(row.0))
+            .collect()
+    }
+}
+
+pub(super) async fn assess(
+    config: &Config,
+    root_dir: &Path,
+    pool: &sqlx::PgPool,
+) -> Result<Assessment> {
     let store = TrackingStore::new(pool, &config.migration.tracking_table)?;
+    let migrations = discover_migrations(&root_dir.join(&config.directories.migrations))?;
 
     // Read-only probe: never CREATE or evolve the tracking tables on the
-    // reported database. If pgmt has never run here, there is nothing to show.
-    if !relation_exists(pool, store.main_table()).await? {
-        println!("No migrations have been applied");
-        return Ok(());
-    }
-    let sections_exist = relation_exists(pool, store.sections_table()).await?;
+    // reported database. If pgmt has never run here, every file is pending.
+    let tracked = relation_exists(pool, store.main_table()).await?;
+    let sections_exist = tracked && relation_exists(pool, store.sections_table()).await?;
 
-    if sections_exist {
+    let listing = if sections_exist {
         // The version row is written when a migration STARTS (see
         // register_migration_start), so surface rows whose recorded sections
         // aren't all complete — they're in-progress or failed, not applied.
-        let applied_migrations = store.migration_listing().await?;
-        print_migration_listing(&applied_migrations);
-    } else {
+        store.migration_listing().await?
+    } else if tracked {
         // Legacy target: a main tracking table with no per-section table. We do
         // not evolve it here (read-only), so treat every recorded version as
         // fully applied.
-        let listing = store.migration_listing_legacy().await?;
-        print_migration_listing(&listing);
+        store.migration_listing_legacy().await?
+    } else {
+        Vec::new()
+    };
+    let sections = if sections_exist {
+        store.section_details().await?
+    } else {
+        Vec::new()
+    };
+    let pending = pending_migrations(&migrations, &listing);
+    let out_of_order: BTreeSet<u64> = out_of_order_migrations(
+        &migrations,
+        listing
+            .iter()
+            .filter(|row| row.4)
+            .map(|row| version_from_db(row.0))
+            .max(),
+        listing
+            .iter()
+            .filter(|row| !row.4)
+            .map(|row| version_from_db(row.0)),
+    )
+    .iter()
+    .map(|m| m.version)
+    .collect();
+    let mismatches = if tracked {
+        checksum_mismatches(&store, &migrations, &listing, &sections).await?
+    } else {
+        BTreeMap::new()
+    };
+    let repeatables = repeatable_status(config, root_dir, pool, &store).await?;
+
+    let mut state = TargetState::UpToDate;
+    if !pending.is_empty() || repeatables.iter().any(|(_, reason)| reason.is_some()) {
+        state = state.max(TargetState::Pending);
+    }
+    if listing.iter().any(|row| row.3 > 0 && !row.5) {
+        state = state.max(TargetState::Failed);
     }
+    if !mismatches.is_empty() {
+        state = state.max(TargetState::ChecksumMismatch);
+    }
+
+    Ok(Assessment {
+        migrations,
+        listing,
+        sections,
+        sections_exist,
+        out_of_order,
+        mismatches,
+        repeatables,
+        state,
+    })
+}
+
+async fn report_status(
+    config: &Config,
+    root_dir: &Path,
+    pool: &sqlx::PgPool,
+    database_label: &str,
+    format: &StatusFormat,
+) -> Result<TargetState> {
+    let store = TrackingStore::new(pool, &config.migration.tracking_table)?;
+    let Assessment {
+        migrations,
+        listing,
+        sections,
+        sections_exist,
+        out_of_order,
+        mismatches,
+        repeatables,
+        state,
+    } = assess(config, root_dir, pool).await?;
+    let pending = pending_migrations(&migrations, &listing);
+
+    match format {
+        StatusFormat::Human => {
+            print_migration_listing(&listing);
+            print_pending_migrations(&pending, &migrations, &out_of_order, config);
+            print_checksum_mismatches(&mismatches);
+
+            // Per-module rollup for module projects, derived from the stored
+            // section rows' module column. Needs the section table; a legacy
+            // target without it can't be rolled up by module.
+            if config.modules.is_enabled() && sections_exist {
+                print_module_rollup(config, &store).await?;
+            }
 
-    // Per-module rollup for module projects, derived from the stored section
-    // rows' module column. Needs the section table; a legacy target without it
-    // can't be rolled up by module.
-    if config.modules.is_enabled() && sections_exist {
-        print_module_rollup(config, &store).await?;
+            print_repeatable_status(&repeatables);
+        }
+        StatusFormat::Json => {
+            let modules = if config.modules.is_enabled() && sections_exist {
+                Some(if store.subscription_tables_exist().await? {
+                    store.load_subscription().await?.modules
+                } else {
+                    store.established_module_literals().await?
+                })
+            } else {
+                None
+            };
+            let output = json!({
+                "database": database_label,
+                "state": state.name(),
+                "baseline_watermark": listing.iter().filter(|row| row.4).map(|row| version_from_db(row.0)).max(),
+                "migrations": migrations_json(&listing, &pending, &out_of_order, &sections, &mismatches),
+                "modules": modules,
+                "repeatable": repeatables
+                    .iter()
+                    .map(|(path, reason)| json!({
+                        "path": path,
+                        "status": reason.unwrap_or("up_to_date"),
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
     }
 
-    Ok(())
+    Ok(state)
+}
+
+/// The migration files `migrate apply` has yet to start here: no migration
+/// row, and above the newest recorded baseline (which covers everything below
+/// it). Started-but-unfinished versions are already in the listing.
+fn pending_migrations<'a>(
+    migrations: &'a [ParsedMigration],
+    listing: &[ListingRow],
+) -> Vec<&'a ParsedMigration> {
+    let baseline = listing
+        .iter()
+        .filter(|row| row.4)
+        .map(|row| version_from_db(row.0))
+        .max();
+    let recorded: BTreeSet<u64> = listing
+        .iter()
+        .filter(|row| !row.4)
+        .map(|row| version_from_db(row.0))
+        .collect();
+    migrations
+        .iter()
+        .filter(|m| !baseline.is_some_and(|bv| m.version <= bv) && !recorded.contains(&m.version))
+        .collect()
+}
+
+/// Applied migrations whose file has changed since, which the next `migrate
+/// apply` would refuse: version -> the covered sections that changed or were
+/// removed. An empty set means the whole-file checksum differs on a migration
+/// recorded without per-section checksums. Unfinished sections may still be
+/// edited (apply re-syncs them), so only covered ones count.
+async fn checksum_mismatches(
+    store: &TrackingStore,
+    migrations: &[ParsedMigration],
+    listing: &[ListingRow],
+    sections: &[SectionDetail],
+) -> Result<BTreeMap<u64, BTreeSet<String>>> {
+    let recorded: BTreeSet<u64> = listing
+        .iter()
+        .filter(|row| !row.4 && !row.5)
+        .map(|row| version_from_db(row.0))
+        .collect();
+    let file_checksums = store.migration_checksums().await?;
+
+    let mut mismatches = BTreeMap::new();
+    for migration in migrations {
+        if !recorded.contains(&migration.version) {
+            continue;
+        }
+        let sql = std::fs::read_to_string(&migration.path).with_context(|| {
+            format!(
+                "Failed to read migration file: {}",
+                migration.path.display()
+            )
+        })?;
+        let rows: Vec<&SectionDetail> = sections
+            .iter()
+            .filter(|s| {
+                !s.is_baseline
+                    && version_from_db(s.migration_version) == migration.version
+                    && s.checksum.is_some()
+            })
+            .collect();
+
+        if rows.is_empty() {
+            if file_checksums
+                .get(&migration.version)
+                .is_some_and(|stored| *stored != calculate_checksum(&sql))
+            {
+                mismatches.insert(migration.version, BTreeSet::new());
+            }
+            continue;
+        }
+
+        let file_sections: BTreeMap<String, String> =
+            parse_migration_sections(&migration.path, &sql)
+                .with_context(|| format!("Failed to parse migration {}", migration.version))?
+                .into_iter()
+                .map(|section| {
+                    let checksum = calculate_checksum(&section.checksum_content());
+                    (section.name, checksum)
+                })
+                .collect();
+        let changed: BTreeSet<String> = rows
+            .into_iter()
+            .filter(|row| matches!(row.status.parse::<SectionStatus>(), Ok(s) if s.is_covered()))
+            .filter(|row| file_sections.get(&row.section_name) != row.checksum.as_ref())
+            .map(|row| row.section_name.clone())
+            .collect();
+        if !changed.is_empty() {
+            mismatches.insert(migration.version, changed);
+        }
+    }
+    Ok(mismatches)
+}
+
+/// Every repeatable migration file with the reason the next `migrate apply`
+/// would (re-)run it — `changed` or `never_run` — or `None` when the checksum
+/// recorded on this database is current. A database without the repeatable
+/// table has run none of them.
+async fn repeatable_status(
+    config: &Config,
+    root_dir: &Path,
+    pool: &sqlx::PgPool,
+    store: &TrackingStore,
+) -> Result<Vec<(String, Option<&'static str>)>> {
+    let files = discover_repeatable_migrations(config, root_dir)?;
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let recorded = if relation_exists(pool, store.repeatable_table()).await? {
+        store.repeatable_checksums().await?
+    } else {
+        BTreeMap::new()
+    };
+
+    let changed: BTreeSet<&str> = changed_repeatable_migrations(&files, &recorded)
+        .into_iter()
+        .map(|(file, _)| file.relative_path.as_str())
+        .collect();
+    Ok(files
+        .iter()
+        .map(|file| {
+            let reason = if !changed.contains(file.relative_path.as_str()) {
+                None
+            } else if recorded.contains_key(&file.relative_path) {
+                Some("changed")
+            } else {
+                Some("never_run")
+            };
+            (file.relative_path.clone(), reason)
+        })
+        .collect())
+}
+
+fn print_repeatable_status(repeatables: &[(String, Option<&'static str>)]) {
+    if repeatables.is_empty() {
+        return;
+    }
+    let pending: Vec<_> = repeatables
+        .iter()
+        .filter_map(|(path, reason)| reason.map(|r| (path, r)))
+        .collect();
+    if pending.is_empty() {
+        println!("Repeatable migrations: {} up to date", repeatables.len());
+        return;
+    }
+    println!(
+        "Repeatable migrations: {} pending (run `pgmt migrate apply`):",
+        pending.len()
+    );
+    for (path, reason) in pending {
+        println!("  {} ({})", path, reason.replace('_', " "));
+    }
 }
 
 /// Whether a relation exists, without touching it. `qualified` is a
 /// double-quoted `"schema"."name"` reference.
-async fn relation_exists(pool: &sqlx::PgPool, qualified: &str) -> Result<bool> {
+pub(crate) async fn relation_exists(pool: &sqlx::PgPool, qualified: &str) -> Result<bool> {
     let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
         .bind(qualified)
         .fetch_one(pool)
@@ -82,7 +447,7 @@ async fn relation_exists(pool: &sqlx::PgPool, qualified: &str) -> Result<bool> {
     Ok(exists)
 }
 
-fn print_migration_listing(applied_migrations: &[(i64, String, String, i64, bool, bool)]) {
+fn print_migration_listing(applied_migrations: &[ListingRow]) {
     if applied_migrations.is_empty() {
         println!("No migrations have been applied");
         return;
@@ -114,6 +479,124 @@ fn print_migration_listing(applied_migrations: &[(i64, String, String, i64, bool
     }
 }
 
+/// List the pending migration files, flagging those older than the newest
+/// applied migration. Nothing is printed for a project without migrations.
+fn print_pending_migrations(
+    pending: &[&ParsedMigration],
+    migrations: &[ParsedMigration],
+    out_of_order: &BTreeSet<u64>,
+    config: &Config,
+) {
+    if migrations.is_empty() {
+        return;
+    }
+    if pending.is_empty() {
+        println!("Pending migrations: none");
+        return;
+    }
+    println!("Pending migrations:");
+    for migration in pending {
+        if out_of_order.contains(&migration.version) {
+            println!(
+                "  {} - {} (OUT OF ORDER: older than the newest applied migration)",
+                migration.version, migration.description
+            );
+        } else {
+            println!("  {} - {}", migration.version, migration.description);
+        }
+    }
+    if !out_of_order.is_empty() && !config.migration.allow_out_of_order {
+        println!(
+            "  `pgmt migrate apply` refuses out-of-order migrations without --allow-out-of-order \
+             (or `migration.allow_out_of_order: true`)"
+        );
+    }
+}
+
+fn print_checksum_mismatches(mismatches: &BTreeMap<u64, BTreeSet<String>>) {
+    if mismatches.is_empty() {
+        return;
+    }
+    println!("Modified after being applied (the next `pgmt migrate apply` will refuse):");
+    for (version, sections) in mismatches {
+        if sections.is_empty() {
+            println!("  {} - file checksum differs", version);
+        } else {
+            let names: Vec<&str> = sections.iter().map(String::as_str).collect();
+            println!("  {} - section(s) {}", version, names.join(", "));
+        }
+    }
+}
+
+/// The JSON `migrations` array: recorded rows in listing order, then the
+/// pending files.
+fn migrations_json(
+    listing: &[ListingRow],
+    pending: &[&ParsedMigration],
+    out_of_order: &BTreeSet<u64>,
+    sections: &[SectionDetail],
+    mismatches: &BTreeMap<u64, BTreeSet<String>>,
+) -> Vec<serde_json::Value> {
+    let mut entries = Vec::new();
+    for (version, description, applied_at, incomplete, is_baseline, consumed_re_anchor) in listing {
+        let rows: Vec<&SectionDetail> = sections
+            .iter()
+            .filter(|s| s.migration_version == *version && s.is_baseline == *is_baseline)
+            .collect();
+        let status = if *consumed_re_anchor {
+            "consumed_re_anchor"
+        } else if *incomplete == 0 {
+            "applied"
+        } else if rows.iter().any(|s| s.status == "failed") {
+            "failed"
+        } else if rows.iter().any(|s| s.status == "running") {
+            "running"
+        } else {
+            "incomplete"
+        };
+        let version = version_from_db(*version);
+        let changed = (!*is_baseline).then(|| mismatches.get(&version)).flatten();
+        entries.push(json!({
+            "version": version,
+            "description": description,
+            "baseline": is_baseline,
+            "status": status,
+            "applied_at": applied_at,
+            "checksum_mismatch": changed.is_some(),
+            "out_of_order": false,
+            "sections": rows
+                .iter()
+                .map(|s| json!({
+                    "name": s.section_name,
+                    "status": s.status,
+                    "module": s.module,
+                    "mode": s.mode,
+                    "attempts": s.attempts,
+                    "started_at": s.started_at,
+                    "completed_at": s.completed_at,
+                    "duration_ms": s.duration_ms,
+                    "rows_affected": s.rows_affected,
+                    "last_error": s.last_error,
+                    "checksum_mismatch": changed.is_some_and(|c| c.contains(&s.section_name)),
+                }))
+                .collect::<Vec<_>>(),
+        }));
+    }
+    for migration in pending {
+        entries.push(json!({
+            "version": migration.version,
+            "description": migration.description,
+            "baseline": false,
+            "status": "pending",
+            "applied_at": null,
+            "checksum_mismatch": false,
+            "out_of_order": out_of_order.contains(&migration.version),
+            "sections": [],
+        }));
+    }
+    entries
+}
+
 /// One module's tally of recorded section rows on the reported database.
 #[derive(Default)]
 struct ModuleTally {
@@ -243,11 +726,24 @@ async fn print_module_rollup(config: &Config, store: &TrackingStore) -> Result<(
     Ok(())
 }
 
+/// Whether `--ignore-migrations` names `migration`, by version or file name.
+fn is_ignored(migration: &ParsedMigration, ignore: &[String]) -> bool {
+    let name = migration.path.file_name().and_then(|s| s.to_str());
+    ignore.iter().any(|entry| {
+        let entry = entry.trim();
+        Some(entry) == name || parse_version(entry).is_ok_and(|v| v == migration.version)
+    })
+}
+
+/// `target`, when one is configured, is only read: with out-of-order apply
+/// allowed, its pending migrations below the newest applied one are the ones
+/// checked for running late.
 pub async fn cmd_migrate_validate(
     config: &Config,
     root_dir: &Path,
     validation_options: &ValidationOutputOptions,
     shadow: &crate::config::ShadowDatabase,
+    target: Option<&TargetUrl>,
 ) -> Result<()> {
     if !validation_options.quiet {
         eprintln!("🔍 Validating migration consistency...");
@@ -310,8 +806,81 @@ pub async fn cmd_migrate_validate(
         &validation_config,
     )?;
 
-    // Step 4: Collect migration information for reporting
+    // Step 4: With out-of-order apply allowed, the target may run its
+    // pending migrations numbered below its newest applied one after the
+    // later ones. Replay history in the target's order — what it has
+    // applied, then everything pending in version order, as `apply` runs it
+    // — and check the files still describe the result: e.g. an ADD COLUMN
+    // run late lands in a different column position. Only the target knows
+    // that order.
     let all_migrations = discover_migrations(&migrations_dir)?;
+    let mut order_dependent = Vec::new();
+    if config.migration.allow_out_of_order && result.passed {
+        match target {
+            Some(target) => {
+                let pool = connect_to_database(target.as_str(), "target database").await?;
+                let assessment = assess(config, root_dir, &pool).await;
+                pool.close().await;
+                let assessment = assessment?;
+                let late: Vec<String> = all_migrations
+                    .iter()
+                    .filter(|m| assessment.out_of_order.contains(&m.version))
+                    .filter(|m| !is_ignored(m, &validation_options.ignore_migrations))
+                    .map(|m| format!("{} ({})", m.version, m.description))
+                    .collect();
+                if !late.is_empty() {
+                    if !validation_options.quiet {
+                        eprintln!(
+                            "🔍 Checking {} migration(s) applied out of order...",
+                            late.len()
+                        );
+                    }
+                    let quiet_config = BaselineConfig {
+                        validate_consistency: false,
+                        verbose: false,
+                    };
+                    let target_pool = shadow.connect_fresh().await?;
+                    let target_catalog = get_migration_state_in_target_order(
+                        &target_pool,
+                        &baselines_dir,
+                        &migrations_dir,
+                        &assessment.applied_migrations(),
+                        &roles_file,
+                        &quiet_config,
+                        config,
+                    )
+                    .await;
+                    crate::db::branch::drop_branch(target_pool).await?;
+                    let target_catalog = target_catalog.with_context(|| {
+                        format!(
+                            "Migration validation failed: the target's pending migrations don't \
+                             replay when {} run after the migrations numbered above them",
+                            late.join(", ")
+                        )
+                    })?;
+                    let target_result = validate_catalogs(
+                        &target_catalog,
+                        &desired_catalog,
+                        config,
+                        &ValidationConfig {
+                            show_differences: false,
+                            verbose: false,
+                        },
+                    )?;
+                    if !target_result.passed {
+                        order_dependent = late;
+                    }
+                }
+            }
+            None => eprintln!(
+                "⚠️  Warning: migration.allow_out_of_order is on but no target database was \
+                 given, so the out-of-order check was skipped. Pass --target-url to check the \
+                 order that target applies its migrations in."
+            ),
+        }
+    }
+
+    // Step 5: Collect migration information for reporting
     let migration_versions: Vec<u64> = all_migrations.iter().map(|m| m.version).collect();
 
     let baseline_info = if let Some(latest_baseline) = find_latest_baseline(&baselines_dir)? {
@@ -324,7 +893,7 @@ pub async fn cmd_migrate_validate(
         None
     };
 
-    // Step 5: Format and output results for CI/CD validation
+    // Step 6: Format and output results for CI/CD validation
     let output = format_validation_output(
         &result,
         validation_options,
@@ -336,7 +905,14 @@ pub async fn cmd_migrate_validate(
     println!("{}", output);
 
     // Return appropriate exit code for CI/CD
-    if result.passed {
+    if !order_dependent.is_empty() {
+        Err(anyhow!(
+            "Migration validation failed: with migration.allow_out_of_order, a target that \
+             applies {} after the migrations numbered above it won't match the schema files. \
+             Renumber them after the newest migration, or make them independent of later ones",
+            order_dependent.join(", ")
+        ))
+    } else if result.passed {
         if !validation_options.quiet {
             eprintln!("✅ Migration consistency validation passed");
         }
@@ -0,0 +1,351 @@
+//! `migrate apply` and `migrate status --tenants` in schema-per-tenant mode
+//! (see `crate::tenants`).
+//!
+//! Apply runs the ordinary apply once per tenant schema, in name order, with
+//! a per-tenant config: `${tenant}` set to the tenant in everything executed,
+//! and the tracking tables (and so the migration lock) inside the tenant's
+//! schema. A tenant with no history yet starts from the latest baseline when
+//! the repo has one, as `migrate provision` would. The first tenant that
+//! fails stops the run; tenants already migrated stay migrated, and rerunning
+//! picks up where it stopped.
+
+use crate::catalog::Catalog;
+use crate::commands::migrate::apply::{ApplyLimit, cmd_migrate_apply};
+use crate::commands::migrate::provision::cmd_migrate_provision;
+use crate::commands::migrate::status::{StatusFormat, TargetState, assess};
+use crate::config::filter::ObjectFilter;
+use crate::config::types::{Tenants, TrackingTable};
+use crate::config::{Config, ShadowDatabase, TargetUrl};
+use crate::db::connection::connect_to_database;
+use crate::migration_tracking::TrackingStore;
+use crate::modules::ModuleSelection;
+use crate::render::quote_ident;
+use crate::tenants::{list_tenant_schemas, schema_exists, tenant_config};
+use anyhow::{Context, Result, bail};
+use serde_json::json;
+use sqlx::PgPool;
+use std::path::Path;
+
+/// Refuse a command that has no tenant-mode equivalent.
+pub fn reject_tenant_mode(config: &Config, command: &str) -> Result<()> {
+    if config.tenants.is_some() {
+        bail!(
+            "`{}` is not supported in schema-per-tenant mode (`tenants:` in pgmt.yaml)",
+            command
+        );
+    }
+    Ok(())
+}
+
+/// Apply pending migrations to every tenant schema on the target, creating
+/// schemas `tenants.query` lists that don't exist yet.
+pub async fn cmd_migrate_apply_tenants(
+    config: &Config,
+    root_dir: &Path,
+    target: &TargetUrl,
+    selection: ModuleSelection,
+    limit: ApplyLimit,
+    deploy_id: Option<String>,
+) -> Result<()> {
+    let tenants = tenants_of(config)?;
+    let pool = connect_to_database(target.as_str(), "target database").await?;
+    let result = apply_each_tenant(
+        config, root_dir, target, &pool, tenants, selection, limit, deploy_id,
+    )
+    .await;
+    pool.close().await;
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn apply_each_tenant(
+    config: &Config,
+    root_dir: &Path,
+    target: &TargetUrl,
+    pool: &PgPool,
+    tenants: &Tenants,
+    selection: ModuleSelection,
+    limit: ApplyLimit,
+    deploy_id: Option<String>,
+) -> Result<()> {
+    let schemas = list_tenant_schemas(pool, tenants).await?;
+    if schemas.is_empty() {
+        println!(
+            "No tenant schemas match '{}' - nothing to apply",
+            tenants.schemas
+        );
+        return Ok(());
+    }
+    println!(
+        "Applying migrations to {} tenant schema(s) from template {}: {}",
+        schemas.len(),
+        tenants.template,
+        schemas.join(", ")
+    );
+
+    let has_baselines =
+        !crate::migration::discover_baselines(&root_dir.join(&config.directories.baselines))?
+            .is_empty();
+    for schema in &schemas {
+        println!("\n── {}", schema);
+        // The tracking tables live in the schema, so it must exist first.
+        sqlx::query(sqlx::AssertSqlSafe(format!(
+            "CREATE SCHEMA IF NOT EXISTS {}",
+            quote_ident(schema)
+        )))
+        .execute(pool)
+        .await
+        .with_context(|| format!("Failed to create tenant schema {}", schema))?;
+        let tenant = tenant_config(config, schema);
+        let applied =
+            if has_baselines && !has_history(pool, &tenant.migration.tracking_table).await? {
+                if limit.to.is_some() || limit.count.is_some() {
+                    bail!(
+                        "tenant schema {} has no history yet and starts from the latest baseline, \
+                     which --to/--count can't stop inside; rerun without them",
+                        schema
+                    );
+                }
+                cmd_migrate_provision(&tenant, root_dir, target, false, selection.clone()).await
+            } else {
+                cmd_migrate_apply(
+                    &tenant,
+                    root_dir,
+                    target,
+                    selection.clone(),
+                    limit,
+                    deploy_id.clone(),
+                )
+                .await
+            };
+        applied.with_context(|| {
+            format!(
+                "Tenant schema {} failed; the tenants after it were not started",
+                schema
+            )
+        })?;
+    }
+
+    println!("\n✅ Applied to {} tenant schema(s)", schemas.len());
+    Ok(())
+}
+
+/// Whether the tenant's tracking table exists and records anything.
+async fn has_history(pool: &PgPool, tracking_table: &TrackingTable) -> Result<bool> {
+    let store = TrackingStore::new(pool, tracking_table)?;
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+        .bind(store.main_table())
+        .fetch_one(pool)
+        .await?;
+    Ok(exists && !store.main_table_is_empty().await?)
+}
+
+/// One tenant schema's line in `migrate status --tenants`.
+struct TenantStatus {
+    schema: String,
+    state: TargetState,
+    latest_applied: Option<u64>,
+    pending: usize,
+    /// Objects that differ from the schema files; `None` when not checked
+    /// (the tenant isn't up to date, so differences are expected).
+    drift: Option<Vec<String>>,
+}
+
+/// Summarize every tenant schema on the reported database: each one's
+/// migration state, the laggards behind the newest migration, and the
+/// up-to-date tenants whose objects no longer match the schema files.
+///
+/// Drift is checked per tenant against a shadow built from the schema files
+/// with `${tenant}` set to the tenant, loaded with the template schema named
+/// after the tenant. Returns the worst tenant state; drift doesn't change it.
+pub async fn cmd_migrate_status_tenants(
+    config: &Config,
+    root_dir: &Path,
+    database_label: &str,
+    database_url: &str,
+    shadow: &ShadowDatabase,
+    format: &StatusFormat,
+) -> Result<TargetState> {
+    let pool = connect_to_database(database_url, &format!("{} database", database_label)).await?;
+    let result = report_tenants(config, root_dir, &pool, database_label, shadow, format).await;
+    pool.close().await;
+    result
+}
+
+async fn report_tenants(
+    config: &Config,
+    root_dir: &Path,
+    pool: &PgPool,
+    database_label: &str,
+    shadow: &ShadowDatabase,
+    format: &StatusFormat,
+) -> Result<TargetState> {
+    let tenants = tenants_of(config)?;
+    let mut statuses = Vec::new();
+    for schema in list_tenant_schemas(pool, tenants).await? {
+        // A listed tenant whose schema doesn't exist yet has no tracking
+        // tables, so everything is pending.
+        let assessment = assess(&tenant_config(config, &schema), root_dir, pool).await?;
+        statuses.push(TenantStatus {
+            pending: assessment.pending().len(),
+            latest_applied: assessment.latest_applied(),
+            state: assessment.state,
+            schema,
+            drift: None,
+        });
+    }
+
+    check_drift(config, root_dir, pool, shadow, tenants, &mut statuses).await?;
+
+    let state = statuses
+        .iter()
+        .map(|t| t.state)
+        .max()
+        .unwrap_or(TargetState::UpToDate);
+    match format {
+        StatusFormat::Human => print_tenants(database_label, tenants, &statuses),
+        StatusFormat::Json => {
+            let output = json!({
+                "database": database_label,
+                "template": tenants.template,
+                "state": state.name(),
+                "tenants": statuses
+                    .iter()
+                    .map(|t| json!({
+                        "schema": t.schema,
+                        "state": t.state.name(),
+                        "latest_applied": t.latest_applied,
+                        "pending": t.pending,
+                        "drift": t.drift,
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+    Ok(state)
+}
+
+/// Fill in `drift` for every up-to-date tenant, each on its own shadow branch.
+async fn check_drift(
+    config: &Config,
+    root_dir: &Path,
+    pool: &PgPool,
+    shadow: &ShadowDatabase,
+    tenants: &Tenants,
+    statuses: &mut [TenantStatus],
+) -> Result<()> {
+    for status in statuses
+        .iter_mut()
+        .filter(|t| t.state == TargetState::UpToDate)
+    {
+        let tenant = tenant_config(config, &status.schema);
+        let mut objects = tenant.objects.clone();
+        objects.include.schemas = vec![status.schema.clone()];
+        let filter = ObjectFilter::new(&objects, &tenant.migration.tracking_table);
+
+        let shadow_pool = shadow.connect_fresh().await?;
+        let expected =
+            expected_tenant_catalog(&tenant, root_dir, &shadow_pool, tenants, &status.schema).await;
+        crate::db::branch::drop_branch(shadow_pool).await?;
+        let expected = filter.filter_catalog(expected?);
+
+        let actual = Catalog::load_managed(pool, &filter).await?;
+        let mut drifted: Vec<String> = crate::diff::plan(&actual, &expected)?
+            .iter()
+            .map(|step| step.id().to_string())
+            .collect();
+        drifted.dedup();
+        status.drift = Some(drifted);
+    }
+    Ok(())
+}
+
+/// The objects the schema files give `schema`: built with the tenant's
+/// config, then loaded with the template (if the files create it under its
+/// literal name) renamed to the tenant.
+async fn expected_tenant_catalog(
+    tenant: &Config,
+    root_dir: &Path,
+    shadow_pool: &PgPool,
+    tenants: &Tenants,
+    schema: &str,
+) -> Result<Catalog> {
+    crate::schema_ops::build_desired_state(tenant, root_dir, shadow_pool).await?;
+    if schema_exists(shadow_pool, &tenants.template).await? {
+        Catalog::load_unfiltered_renamed(shadow_pool, &tenants.template, schema).await
+    } else {
+        Catalog::load_unfiltered(shadow_pool).await
+    }
+}
+
+fn print_tenants(database_label: &str, tenants: &Tenants, statuses: &[TenantStatus]) {
+    println!(
+        "Tenant status for {} database: {} tenant schema(s) matching '{}' (template {})",
+        database_label,
+        statuses.len(),
+        tenants.schemas,
+        tenants.template
+    );
+    if statuses.is_empty() {
+        return;
+    }
+    println!();
+    let width = statuses.iter().map(|t| t.schema.len()).max().unwrap_or(0);
+    for t in statuses {
+        let latest = t
+            .latest_applied
+            .map(|v| format!("V{}", v))
+            .unwrap_or_else(|| "none".to_string());
+        let drift = match &t.drift {
+            Some(objects) if objects.is_empty() => "no drift".to_string(),
+            Some(objects) => format!("drift: {} object(s)", objects.len()),
+            None => String::new(),
+        };
+        let line = format!(
+            "  {:width$}  {:17}  latest {:14}  {} pending  {}",
+            t.schema,
+            t.state.name(),
+            latest,
+            t.pending,
+            drift
+        );
+        println!("{}", line.trim_end());
+    }
+
+    let laggards: Vec<String> = statuses
+        .iter()
+        .filter(|t| t.state != TargetState::UpToDate)
+        .map(|t| format!("{} ({})", t.schema, t.state.name()))
+        .collect();
+    if !laggards.is_empty() {
+        println!("\nLaggards: {}", laggards.join(", "));
+    }
+    let drifted: Vec<&TenantStatus> = statuses
+        .iter()
+        .filter(|t| t.drift.as_ref().is_some_and(|d| !d.is_empty()))
+        .collect();
+    if !drifted.is_empty() {
+        println!("\nDrifted from the schema files:");
+        for t in drifted {
+            println!("  {}:", t.schema);
+            for object in t.drift.iter().flatten() {
+                println!("    {}", object);
+            }
+        }
+    }
+    if laggards.is_empty()
+        && statuses
+            .iter()
+            .all(|t| t.drift.as_ref().is_some_and(Vec::is_empty))
+    {
+        println!("\n✅ Every tenant schema is up to date and matches the schema files");
+    }
+}
+
+fn tenants_of(config: &Config) -> Result<&Tenants> {
+    config
+        .tenants
+        .as_ref()
+        .context("no `tenants:` in pgmt.yaml; schema-per-tenant mode is not configured")
+}
@@ -1,9 +1,10 @@
 use crate::baseline::operations::{BaselineCreationRequest, create_baseline};
 use crate::catalog::Catalog;
-use crate::config::Config;
+use crate::commands::migrate::new::write_down_migration;
+use crate::config::{Config, PlaceholderRestorer};
 use crate::migrate::{MigrationGenerationInput, generate_migration};
 use crate::migration::{
-    BaselineConfig, find_latest_migration, generate_baseline_filename,
+    BaselineConfig, down_migration_path, find_latest_migration, generate_baseline_filename,
     get_migration_update_starting_state, should_manage_baseline_for_migration,
     validate_baseline_against_catalog,
 };
@@ -11,7 +12,7 @@ use crate::modules::{
     HistoricalAttribution, evaluate_module_generation, render_generated_migration,
     section_baseline_if_moduled,
 };
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::debug;
@@ -58,7 +59,7 @@ pub async fn cmd_migrate_update_with_options(
     let starting_pool = shadow.connect_fresh().await?;
     let mut historical = HistoricalAttribution::default();
     let attribution = config.modules.is_enabled().then_some(&mut historical);
-    let old_catalog = get_migration_update_starting_state(
+    let mut old_catalog = get_migration_update_starting_state(
         &starting_pool,
         &baselines_dir,
         &migrations_dir,
@@ -68,7 +69,13 @@ pub async fn cmd_migrate_update_with_options(
         config,
         attribution,
     )
+    .await?
+    .with_data(
+        &starting_pool,
+        &crate::seed_data::seeded_tables(config, root_dir)?,
+    )
     .await?;
+    crate::tenants::name_seed_rows_for_planning(config, &mut old_catalog);
     crate::db::branch::drop_branch(starting_pool).await?;
 
     // Step 2: Reset shadow database and apply current schema
@@ -83,6 +90,7 @@ pub async fn cmd_migrate_update_with_options(
         &new_catalog,
         config.migration.column_order,
     )?;
+    let placeholders = crate::schema_ops::placeholder_restorer(config, root_dir)?;
 
     // Step 3: Generate migration using pure logic
     debug!("Generating updated migration steps");
@@ -128,6 +136,13 @@ pub async fn cmd_migrate_update_with_options(
             "Updated migration: {} (now empty)",
             latest_migration.path.display()
         );
+        update_down_migration(
+            &latest_migration.path,
+            &latest_migration.path,
+            &old_catalog,
+            &new_catalog,
+            &placeholders,
+        )?;
 
         return Ok(());
     }
@@ -153,6 +168,7 @@ pub async fn cmd_migrate_update_with_options(
             &file_mapping,
             &historical,
         )?;
+        placeholders.restore_file(&result.path)?;
         println!("Updated baseline: {}", result.path.display());
 
         // Step 6: Validate that the baseline matches the intended schema using pure logic
@@ -186,9 +202,17 @@ pub async fn cmd_migrate_update_with_options(
         &file_mapping,
         &historical,
     )?
+    .map(|sql| placeholders.restore(&sql))
     .unwrap_or_else(|| "-- No changes detected\n".to_string());
     std::fs::write(&latest_migration.path, &migration_sql)?;
     println!("Updated migration: {}", latest_migration.path.display());
+    update_down_migration(
+        &latest_migration.path,
+        &latest_migration.path,
+        &old_catalog,
+        &new_catalog,
+        &placeholders,
+    )?;
 
     println!("Migration update complete!");
     Ok(())
@@ -266,7 +290,7 @@ pub async fn cmd_migrate_update_specific(
     let starting_pool = shadow.connect_fresh().await?;
     let mut historical = HistoricalAttribution::default();
     let attribution = config.modules.is_enabled().then_some(&mut historical);
-    let old_catalog = get_migration_update_starting_state(
+    let mut old_catalog = get_migration_update_starting_state(
         &starting_pool,
         &baselines_dir,
         &migrations_dir,
@@ -276,7 +300,13 @@ pub async fn cmd_migrate_update_specific(
         config,
         attribution,
     )
+    .await?
+    .with_data(
+        &starting_pool,
+        &crate::seed_data::seeded_tables(config, root_dir)?,
+    )
     .await?;
+    crate::tenants::name_seed_rows_for_planning(config, &mut old_catalog);
     crate::db::branch::drop_branch(starting_pool).await?;
 
     // Apply current schema to shadow database
@@ -291,6 +321,7 @@ pub async fn cmd_migrate_update_specific(
         &new_catalog,
         config.migration.column_order,
     )?;
+    let placeholders = crate::schema_ops::placeholder_restorer(config, root_dir)?;
 
     // Determine version and description for the new migration
     let (new_version, new_description) = if is_latest {
@@ -351,6 +382,13 @@ pub async fn cmd_migrate_update_specific(
                 "Updated migration: {} (now empty)",
                 target_migration.path.display()
             );
+            update_down_migration(
+                &target_migration.path,
+                &target_migration.path,
+                &old_catalog,
+                &new_catalog,
+                &placeholders,
+            )?;
         } else {
             println!("No changes detected - conflicts resolved by other migrations");
             // For older migrations with no changes, still create/update the file with a comment
@@ -386,6 +424,13 @@ pub async fn cmd_migrate_update_specific(
                     target_migration.version, new_version
                 );
                 println!("Created: {}", new_path.display());
+                update_down_migration(
+                    &target_migration.path,
+                    &new_path,
+                    &old_catalog,
+                    &new_catalog,
+                    &placeholders,
+                )?;
             }
         }
         if !partition_diverged {
@@ -414,6 +459,7 @@ pub async fn cmd_migrate_update_specific(
             &file_mapping,
             &historical,
         )?;
+        placeholders.restore_file(&result.path)?;
         if is_latest {
             println!("Updated baseline: {}", result.path.display());
         } else {
@@ -452,7 +498,8 @@ pub async fn cmd_migrate_update_specific(
         &new_catalog,
         &file_mapping,
         &historical,
-    )?;
+    )?
+    .map(|sql| placeholders.restore(&sql));
 
     // Write the migration file
     if let Some(migration_sql) = &migration_sql {
@@ -483,6 +530,13 @@ pub async fn cmd_migrate_update_specific(
             // For latest migration, overwrite the existing file (current behavior)
             std::fs::write(&target_migration.path, migration_sql)?;
             println!("Updated migration: {}", target_migration.path.display());
+            update_down_migration(
+                &target_migration.path,
+                &target_migration.path,
+                &old_catalog,
+                &new_catalog,
+                &placeholders,
+            )?;
         } else {
             // For older migration, delete old file and create new one with a
             // fresh timestamp. The no-changes fall-through (a pure re-tag with
@@ -504,6 +558,13 @@ pub async fn cmd_migrate_update_specific(
                 target_migration.version, new_version
             );
             println!("Created: {}", new_path.display());
+            update_down_migration(
+                &target_migration.path,
+                &new_path,
+                &old_catalog,
+                &new_catalog,
+                &placeholders,
+            )?;
         }
     }
 
@@ -514,3 +575,30 @@ pub async fn cmd_migrate_update_specific(
     }
     Ok(())
 }
+
+/// Keep the down migration paired with the migration that was at `old_path`
+/// and is now at `new_path`: regenerated from the same catalogs as the
+/// migration, and moved along when the migration was renumbered. A migration
+/// without a down file keeps going without one.
+fn update_down_migration(
+    old_path: &Path,
+    new_path: &Path,
+    old_catalog: &Catalog,
+    new_catalog: &Catalog,
+    placeholders: &PlaceholderRestorer,
+) -> Result<()> {
+    let old_down = down_migration_path(old_path);
+    if !old_down.exists() {
+        return Ok(());
+    }
+    if old_path != new_path {
+        std::fs::remove_file(&old_down)
+            .with_context(|| format!("Failed to remove {}", old_down.display()))?;
+        println!("Deleted: {}", old_down.display());
+    }
+    let filename = new_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    write_down_migration(old_catalog, new_catalog, new_path, filename, placeholders)
+}
@@ -0,0 +1,172 @@
+//! `migrate wait` — block until a database reaches a schema version.
+//!
+//! Meant for application startup: a pod built against migration V waits for
+//! the deploy to get there before serving. Like `migrate status` it only reads
+//! the tracking tables — no advisory lock, nothing created — so any number of
+//! waiters can poll while `migrate apply` holds the lock.
+//!
+//! [`schema_version_reached`] and [`wait_for_schema_version`] are the same
+//! check for Rust services that want to assert it at boot.
+
+use crate::commands::migrate::status::relation_exists;
+use crate::config::types::TrackingTable;
+use crate::config::{Config, TargetUrl};
+use crate::db::connection::connect_to_database;
+use crate::migration::discover_migrations;
+use crate::migration_tracking::{TrackingStore, version_from_db};
+use anyhow::{Result, bail};
+use console::style;
+use sqlx::PgPool;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How often [`wait_for_schema_version`] re-reads the tracking tables.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Whether the database has reached schema `version`: a migration or baseline
+/// at or above it is recorded with every section completed or satisfied, no
+/// recorded migration or baseline up to it is still unfinished, and none of
+/// `migrations` (the migration file versions the caller knows of) up to it is
+/// still pending — an out-of-order or late-merged migration below `version`
+/// holds it back even though a newer one landed. A newer migration that is
+/// still running doesn't matter. A database pgmt has never run against has
+/// reached nothing.
+pub async fn schema_version_reached(
+    pool: &PgPool,
+    tracking_table: &TrackingTable,
+    version: u64,
+    migrations: &[u64],
+) -> Result<bool> {
+    let store = TrackingStore::new(pool, tracking_table)?;
+    if !relation_exists(pool, store.main_table()).await? {
+        return Ok(false);
+    }
+    let listing = if relation_exists(pool, store.sections_table()).await? {
+        store.migration_listing().await?
+    } else {
+        // Legacy target: every recorded version is fully applied.
+        store.migration_listing_legacy().await?
+    };
+
+    let mut reached = false;
+    let mut recorded = BTreeSet::new();
+    let mut baseline = None;
+    for (row_version, _, _, incomplete, is_baseline, _) in listing {
+        let row_version = version_from_db(row_version);
+        if is_baseline {
+            baseline = baseline.max(Some(row_version));
+        } else {
+            recorded.insert(row_version);
+        }
+        if incomplete > 0 {
+            if row_version <= version {
+                return Ok(false);
+            }
+        } else if row_version >= version {
+            reached = true;
+        }
+    }
+
+    // A recorded baseline covers every migration up to it.
+    let pending_below = migrations.iter().any(|&migration| {
+        migration <= version
+            && !recorded.contains(&migration)
+            && !baseline.is_some_and(|baseline| migration <= baseline)
+    });
+    Ok(reached && !pending_below)
+}
+
+/// Poll until [`schema_version_reached`], failing once `timeout` has passed.
+pub async fn wait_for_schema_version(
+    pool: &PgPool,
+    tracking_table: &TrackingTable,
+    version: u64,
+    migrations: &[u64],
+    timeout: Duration,
+) -> Result<()> {
+    let start = Instant::now();
+    loop {
+        if schema_version_reached(pool, tracking_table, version, migrations).await? {
+            return Ok(());
+        }
+        if start.elapsed() >= timeout {
+            bail!(
+                "Timed out after {}s waiting for schema version {}. See where the database is \
+                 with `pgmt migrate status`.",
+                timeout.as_secs(),
+                version
+            );
+        }
+        tokio::time::sleep(POLL_INTERVAL.min(timeout.saturating_sub(start.elapsed()))).await;
+    }
+}
+
+pub async fn cmd_migrate_wait(
+    config: &Config,
+    root_dir: &Path,
+    target: &TargetUrl,
+    until: &str,
+    timeout: Duration,
+) -> Result<()> {
+    let version = parse_version(until)?;
+    // Whatever migration files ship alongside; none is fine (see `parse_version`).
+    let migrations: Vec<u64> = discover_migrations(&root_dir.join(&config.directories.migrations))?
+        .iter()
+        .map(|m| m.version)
+        .collect();
+    let pool = connect_to_database(target.as_str(), "target database").await?;
+
+    println!(
+        "Waiting for the target database to reach schema version {} (timeout {}s)",
+        version,
+        timeout.as_secs()
+    );
+    let result = wait_for_schema_version(
+        &pool,
+        &config.migration.tracking_table,
+        version,
+        &migrations,
+        timeout,
+    )
+    .await;
+    pool.close().await;
+    result?;
+
+    println!("{} Schema version {} reached", style("✓").green(), version);
+    Ok(())
+}
+
+/// `V1734500000`, `1734500000` or a migration file stem like
+/// `V1734500000_add_users`. Unlike `migrate apply --to`, the version needn't
+/// exist in the local migrations directory: an application image may not ship
+/// one, and history outlives deleted files.
+pub(crate) fn parse_version(until: &str) -> Result<u64> {
+    let digits: String = until
+        .strip_prefix('V')
+        .unwrap_or(until)
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    match digits.parse() {
+        Ok(version) => Ok(version),
+        Err(_) => bail!(
+            "Invalid version '{}': expected a migration version like V1734500000",
+            until
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("V1000").unwrap(), 1000);
+        assert_eq!(parse_version("1000").unwrap(), 1000);
+        assert_eq!(parse_version("V1000_add_users").unwrap(), 1000);
+        assert!(parse_version("latest").is_err());
+        assert!(parse_version("V").is_err());
+    }
+}
@@ -1,23 +1,35 @@
 pub mod apply;
 pub mod baseline;
+pub mod catalog;
+pub mod compare;
 pub mod config;
 pub mod debug;
+pub mod dev;
 pub mod diff;
 pub mod diff_output;
 pub mod init;
 pub mod migrate;
+pub mod test;
 pub mod validate;
 
 // Re-export all command functions
-pub use apply::{cmd_apply, cmd_apply_watch};
+pub use apply::{cmd_apply, cmd_apply_undo, cmd_apply_watch};
 pub use baseline::{cmd_baseline_list, cmd_migrate_baseline};
+pub use catalog::cmd_catalog_snapshot;
+pub use compare::cmd_compare;
 pub use config::cmd_config;
 pub use debug::cmd_debug_dependencies;
+pub use dev::{cmd_dev_snapshots_list, cmd_dev_snapshots_prune, cmd_dev_snapshots_restore};
 pub use diff::cmd_diff;
 pub use init::cmd_init_with_args;
 pub use migrate::{
-    MigrateDiffArgs, ResolveVerb, cmd_migrate_apply, cmd_migrate_diff, cmd_migrate_new,
-    cmd_migrate_provision, cmd_migrate_resolve, cmd_migrate_status, cmd_migrate_update_specific,
-    cmd_migrate_update_with_options, cmd_migrate_validate,
+    ApplyLimit, ExportFormat, FleetFormat, FleetOptions, LintFormat, MigrateDiffArgs, OnFailure,
+    ResolveVerb, StatusFormat, cmd_migrate_apply, cmd_migrate_apply_fleet,
+    cmd_migrate_apply_tenants, cmd_migrate_diff, cmd_migrate_export, cmd_migrate_history,
+    cmd_migrate_lint, cmd_migrate_new, cmd_migrate_provision, cmd_migrate_rebase,
+    cmd_migrate_rehearse, cmd_migrate_resolve, cmd_migrate_rollback, cmd_migrate_status,
+    cmd_migrate_status_tenants, cmd_migrate_update_specific, cmd_migrate_update_with_options,
+    cmd_migrate_validate, cmd_migrate_wait, reject_tenant_mode,
 };
+pub use test::{TestFormat, cmd_test};
 pub use validate::cmd_validate;
@@ -0,0 +1,699 @@
+//! pgmt test - Run SQL tests against the schema files
+//!
+//! The schema (and seed data) is built on a fresh shadow branch, then each
+//! `tests/*.sql` file runs in its own transaction, which is always rolled
+//! back. A test passes when its file runs without error and reports no
+//! failing pgTAP assertion. A file that would end that transaction early is
+//! an error without running: what it did would stay for the tests after it.
+//!
+//! Every transaction gets the `pgmt_test` assertion functions
+//! ([`ASSERTION_FUNCTIONS`]) on its search path, and pgTAP as well when the
+//! shadow's server has the extension.
+
+use crate::config::{Config, ShadowDatabase};
+use crate::db::branch::drop_branch;
+use crate::db::error_context::SqlErrorContext;
+use crate::migration::lint::split_statements;
+use crate::schema_ops::build_desired_state;
+use anyhow::{Context, Result, anyhow};
+use futures_util::StreamExt;
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{PgPool, Row};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Output format for `pgmt test`
+#[derive(Debug, Clone, PartialEq, Default, clap::ValueEnum)]
+pub enum TestFormat {
+    /// One line per test, for terminals
+    #[default]
+    Human,
+    /// Results plus a summary, for scripts
+    Json,
+    /// JUnit XML, for CI test reports
+    Junit,
+}
+
+/// The assertion functions, created in each test's transaction. A failed
+/// assertion raises `assert_failure` (P0004), which ends the test; passed
+/// ones are counted in the `pgmt_test.assertions` setting.
+const ASSERTION_FUNCTIONS: &str = r#"
+CREATE SCHEMA pgmt_test;
+
+CREATE FUNCTION pgmt_test.pass() RETURNS void LANGUAGE plpgsql AS $$
+BEGIN
+    PERFORM set_config(
+        'pgmt_test.assertions',
+        (coalesce(nullif(current_setting('pgmt_test.assertions', true), ''), '0')::int + 1)::text,
+        true
+    );
+END $$;
+
+CREATE FUNCTION pgmt_test.fail(description text, detail text) RETURNS void LANGUAGE plpgsql AS $$
+BEGIN
+    RAISE EXCEPTION USING
+        ERRCODE = 'assert_failure',
+        MESSAGE = 'assertion failed' || coalesce(': ' || description, ''),
+        DETAIL = detail;
+END $$;
+
+CREATE FUNCTION pgmt_test.assert_true(condition boolean, description text DEFAULT NULL)
+RETURNS void LANGUAGE plpgsql AS $$
+BEGIN
+    IF condition IS NOT TRUE THEN
+        PERFORM pgmt_test.fail(description, format('expected true, got %s', quote_nullable(condition)));
+    END IF;
+    PERFORM pgmt_test.pass();
+END $$;
+
+CREATE FUNCTION pgmt_test.assert_false(condition boolean, description text DEFAULT NULL)
+RETURNS void LANGUAGE plpgsql AS $$
+BEGIN
+    IF condition IS NOT FALSE THEN
+        PERFORM pgmt_test.fail(description, format('expected false, got %s', quote_nullable(condition)));
+    END IF;
+    PERFORM pgmt_test.pass();
+END $$;
+
+CREATE FUNCTION pgmt_test.assert_equals(actual anycompatible, expected anycompatible, description text DEFAULT NULL)
+RETURNS void LANGUAGE plpgsql AS $$
+BEGIN
+    IF actual IS DISTINCT FROM expected THEN
+        PERFORM pgmt_test.fail(
+            description,
+            format('expected %s, got %s', quote_nullable(expected), quote_nullable(actual))
+        );
+    END IF;
+    PERFORM pgmt_test.pass();
+END $$;
+
+CREATE FUNCTION pgmt_test.assert_not_equals(actual anycompatible, unexpected anycompatible, description text DEFAULT NULL)
+RETURNS void LANGUAGE plpgsql AS $$
+BEGIN
+    IF actual IS NOT DISTINCT FROM unexpected THEN
+        PERFORM pgmt_test.fail(description, format('expected anything but %s', quote_nullable(unexpected)));
+    END IF;
+    PERFORM pgmt_test.pass();
+END $$;
+
+CREATE FUNCTION pgmt_test.assert_raises(statement text, expected_sqlstate text DEFAULT NULL, description text DEFAULT NULL)
+RETURNS void LANGUAGE plpgsql AS $$
+DECLARE
+    raised boolean := false;
+BEGIN
+    BEGIN
+        EXECUTE statement;
+    EXCEPTION WHEN OTHERS THEN
+        IF expected_sqlstate IS NOT NULL AND SQLSTATE <> expected_sqlstate THEN
+            PERFORM pgmt_test.fail(
+                description,
+                format('expected error %s, got %s: %s', expected_sqlstate, SQLSTATE, SQLERRM)
+            );
+        END IF;
+        raised := true;
+    END;
+    IF NOT raised THEN
+        PERFORM pgmt_test.fail(description, 'expected an error, but none was raised');
+    END IF;
+    PERFORM pgmt_test.pass();
+END $$;
+"#;
+
+/// Whether pgTAP can be used in the shadow
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pgtap {
+    /// The schema files create it
+    Installed,
+    /// The server has it: each test creates it in `pgmt_test`
+    Available,
+    Missing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    /// An assertion failed
+    Failed,
+    /// The file raised an error other than a failed assertion
+    Error,
+}
+
+/// Why a test didn't pass
+#[derive(Debug, Clone, Serialize)]
+pub struct TestFailure {
+    pub message: String,
+    /// Line in the test file, when PostgreSQL reports a position
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Detail, hint and context lines, and failing pgTAP output
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+/// The outcome of one test file
+#[derive(Debug, Clone, Serialize)]
+pub struct TestResult {
+    pub name: String,
+    pub file: String,
+    pub status: TestStatus,
+    pub assertions: usize,
+    pub duration_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<TestFailure>,
+}
+
+/// Run the SQL tests: `files`, or every `.sql` file in the tests directory.
+///
+/// Returns an error when any test fails, after reporting all of them.
+pub async fn cmd_test(
+    config: &Config,
+    root_dir: &Path,
+    files: &[PathBuf],
+    format: &TestFormat,
+    shadow: &ShadowDatabase,
+) -> Result<()> {
+    let tests_dir = root_dir.join(&config.directories.tests);
+    let paths = if files.is_empty() {
+        discover_tests(&tests_dir)?
+    } else {
+        files.to_vec()
+    };
+    if paths.is_empty() {
+        eprintln!("No test files in {}", tests_dir.display());
+        return report(&[], format, Duration::ZERO);
+    }
+
+    let started = Instant::now();
+    let pool = shadow.connect_fresh().await?;
+    let results = run_tests(config, root_dir, &pool, &paths).await;
+    drop_branch(pool).await?;
+    let results = results?;
+    report(&results, format, started.elapsed())?;
+
+    let failed = results
+        .iter()
+        .filter(|r| r.status != TestStatus::Passed)
+        .count();
+    if failed > 0 {
+        return Err(anyhow!("{} of {} test(s) failed", failed, results.len()));
+    }
+    Ok(())
+}
+
+/// The `.sql` files directly in `tests_dir`, in name order. A missing
+/// directory has none.
+fn discover_tests(tests_dir: &Path) -> Result<Vec<PathBuf>> {
+    if !tests_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(tests_dir)
+        .with_context(|| format!("Failed to read tests directory {}", tests_dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "sql"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+async fn run_tests(
+    config: &Config,
+    root_dir: &Path,
+    pool: &PgPool,
+    paths: &[PathBuf],
+) -> Result<Vec<TestResult>> {
+    eprintln!("Building the schema on a fresh shadow database...");
+    build_desired_state(config, root_dir, pool).await?;
+
+    let pgtap = detect_pgtap(pool).await?;
+    eprintln!("Running {} test file(s)...\n", paths.len());
+
+    let mut results = Vec::with_capacity(paths.len());
+    for path in paths {
+        results.push(run_test(config, root_dir, pool, path, pgtap).await?);
+    }
+    Ok(results)
+}
+
+async fn detect_pgtap(pool: &PgPool) -> Result<Pgtap> {
+    let (installed, available): (bool, bool) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'pgtap'),
+                EXISTS (SELECT 1 FROM pg_available_extensions WHERE name = 'pgtap')",
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(match (installed, available) {
+        (true, _) => Pgtap::Installed,
+        (false, true) => match can_create_pgtap(pool).await {
+            Ok(()) => Pgtap::Available,
+            // pgTAP isn't a trusted extension: a role without the privilege
+            // to create it still gets the pgmt_test assertions.
+            Err(e) => {
+                eprintln!(
+                    "Note: pgTAP is installed on the shadow's server but can't be created ({:#}); \
+                     running without it",
+                    e
+                );
+                Pgtap::Missing
+            }
+        },
+        (false, false) => Pgtap::Missing,
+    })
+}
+
+/// Create pgTAP the way each test does, in a transaction rolled back after.
+async fn can_create_pgtap(pool: &PgPool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::raw_sql("CREATE SCHEMA pgmt_test; CREATE EXTENSION pgtap SCHEMA pgmt_test")
+        .execute(&mut *tx)
+        .await?;
+    tx.rollback().await?;
+    Ok(())
+}
+
+/// Run one test file in a transaction that is rolled back afterwards.
+async fn run_test(
+    config: &Config,
+    root_dir: &Path,
+    pool: &PgPool,
+    path: &Path,
+    pgtap: Pgtap,
+) -> Result<TestResult> {
+    let file = path
+        .strip_prefix(root_dir)
+        .unwrap_or(path)
+        .display()
+        .to_string();
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| file.clone());
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read test file {}", path.display()))?;
+    let sql = config
+        .variables
+        .substitute(&content)
+        .with_context(|| format!("Failed to substitute variables in {}", file))?;
+
+    if let Some((line, statement)) = ends_transaction(&sql) {
+        return Ok(TestResult {
+            name,
+            file,
+            status: TestStatus::Error,
+            assertions: 0,
+            duration_ms: 0,
+            failure: Some(TestFailure {
+                message: format!("{} would end the transaction the test runs in", statement),
+                line: Some(line),
+                details: vec![
+                    "Hint: use SAVEPOINT and ROLLBACK TO SAVEPOINT inside a test; \
+                     a ROLLBACK is only allowed as the file's last statement"
+                        .to_string(),
+                ],
+            }),
+        });
+    }
+
+    let started = Instant::now();
+    let mut tx = pool.begin().await?;
+    sqlx::raw_sql(ASSERTION_FUNCTIONS)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to create the test assertion functions")?;
+    if pgtap == Pgtap::Available {
+        sqlx::raw_sql("CREATE EXTENSION pgtap SCHEMA pgmt_test")
+            .execute(&mut *tx)
+            .await
+            .context("Failed to create the pgtap extension")?;
+    }
+    sqlx::raw_sql(
+        "SELECT set_config('search_path', current_setting('search_path') || ', pgmt_test', true)",
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // pgTAP reports through the rows its functions return.
+    let mut tap = Vec::new();
+    let mut error = None;
+    {
+        let mut rows = sqlx::raw_sql(sqlx::AssertSqlSafe(sql.clone())).fetch(&mut *tx);
+        while let Some(row) = rows.next().await {
+            match row {
+                Ok(row) if row.len() == 1 => {
+                    if let Ok(Some(text)) = row.try_get::<Option<String>, _>(0) {
+                        tap.extend(text.lines().filter(|l| is_tap_line(l)).map(String::from));
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
+    }
+
+    let counted: usize = if error.is_none() {
+        sqlx::query_scalar::<_, Option<String>>(
+            "SELECT current_setting('pgmt_test.assertions', true)",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(0)
+    } else {
+        0
+    };
+    // A file ending in ROLLBACK (as pgTAP files do) leaves nothing to roll
+    // back; PostgreSQL only warns about that.
+    tx.rollback().await?;
+    let duration_ms = started.elapsed().as_millis();
+
+    let tap_summary = summarize_tap(&tap);
+    let (status, failure) = match error {
+        Some(e) => {
+            let ctx = SqlErrorContext::from_sqlx_error(&e, &sql);
+            let status = if ctx.code.as_deref() == Some("P0004") {
+                TestStatus::Failed
+            } else {
+                TestStatus::Error
+            };
+            let mut details: Vec<String> = [
+                ctx.detail.filter(|d| !d.is_empty()),
+                ctx.hint.map(|h| format!("Hint: {}", h)),
+                ctx.context.map(|c| format!("Context: {}", c)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            details.extend(tap_summary.failures);
+            (
+                status,
+                Some(TestFailure {
+                    message: ctx.message,
+                    line: ctx.line_number,
+                    details,
+                }),
+            )
+        }
+        None if !tap_summary.failures.is_empty() => (
+            TestStatus::Failed,
+            Some(TestFailure {
+                message: format!("{} pgTAP assertion(s) failed", tap_summary.failed),
+                line: None,
+                details: tap_summary.failures,
+            }),
+        ),
+        None => (TestStatus::Passed, None),
+    };
+
+    Ok(TestResult {
+        name,
+        file,
+        status,
+        assertions: counted + tap_summary.assertions,
+        duration_ms,
+        failure,
+    })
+}
+
+/// The line and keyword of the first statement in `sql` that ends the
+/// transaction: `COMMIT`, `END`, `PREPARE TRANSACTION`, or a `ROLLBACK` that
+/// isn't the last statement. `BEGIN` only warns inside a transaction, and
+/// pgTAP files conventionally end with `ROLLBACK`, so those two are let be.
+fn ends_transaction(sql: &str) -> Option<(usize, String)> {
+    let statements = split_statements(sql);
+    let mut in_atomic_body = false;
+    for (i, statement) in statements.iter().enumerate() {
+        let tokens = statement.tokens();
+        // The `END` closing a `BEGIN ATOMIC` function body isn't a statement.
+        if in_atomic_body {
+            in_atomic_body = tokens != ["END"];
+            continue;
+        }
+        if tokens.windows(2).any(|pair| pair == ["BEGIN", "ATOMIC"]) {
+            in_atomic_body = true;
+            continue;
+        }
+        let last = i + 1 == statements.len();
+        let ends = match tokens.as_slice() {
+            ["COMMIT" | "END", ..] | ["PREPARE", "TRANSACTION", ..] => true,
+            ["ROLLBACK" | "ABORT", rest @ ..] => {
+                let to_savepoint = rest.iter().take(2).any(|t| *t == "TO");
+                let prepared = rest.first() == Some(&"PREPARED");
+                prepared || (!to_savepoint && !last)
+            }
+            _ => false,
+        };
+        if ends {
+            return Some((statement.line, tokens[0].to_string()));
+        }
+    }
+    None
+}
+
+fn is_tap_line(line: &str) -> bool {
+    let rest = line.strip_prefix("not ").unwrap_or(line);
+    rest.strip_prefix("ok ")
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        || line.starts_with("# ")
+}
+
+struct TapSummary {
+    assertions: usize,
+    failed: usize,
+    /// Failing assertions and the diagnostics that follow them, plus pgTAP's
+    /// own complaints (a plan that wasn't met)
+    failures: Vec<String>,
+}
+
+/// Tally pgTAP output lines: `ok N` / `not ok N` per assertion (a `# TODO`
+/// failure is expected), `# ...` diagnostics.
+fn summarize_tap(lines: &[String]) -> TapSummary {
+    let mut summary = TapSummary {
+        assertions: 0,
+        failed: 0,
+        failures: Vec::new(),
+    };
+    let mut in_failure = false;
+    for line in lines {
+        if let Some(diagnostic) = line.strip_prefix("# ") {
+            if in_failure {
+                summary.failures.push(line.clone());
+            } else if diagnostic.starts_with("Looks like you planned") {
+                summary.failed += 1;
+                summary.failures.push(line.clone());
+            }
+            continue;
+        }
+        summary.assertions += 1;
+        in_failure = line.starts_with("not ok") && !line.contains("# TODO");
+        if in_failure {
+            summary.failed += 1;
+            summary.failures.push(line.clone());
+        }
+    }
+    summary
+}
+
+fn report(results: &[TestResult], format: &TestFormat, elapsed: Duration) -> Result<()> {
+    let passed = results
+        .iter()
+        .filter(|r| r.status == TestStatus::Passed)
+        .count();
+    let failed = results.len() - passed;
+    match format {
+        TestFormat::Human => print_human(results, passed, failed, elapsed),
+        TestFormat::Json => {
+            let output = json!({
+                "passed": passed,
+                "failed": failed,
+                "duration_ms": elapsed.as_millis(),
+                "tests": results,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        TestFormat::Junit => print!("{}", junit_report(results, elapsed)),
+    }
+    Ok(())
+}
+
+fn print_human(results: &[TestResult], passed: usize, failed: usize, elapsed: Duration) {
+    for result in results {
+        match &result.failure {
+            None => println!(
+                "✅ {} ({} assertion(s), {} ms)",
+                result.file, result.assertions, result.duration_ms
+            ),
+            Some(failure) => {
+                let location = match failure.line {
+                    Some(line) => format!("{}:{}", result.file, line),
+                    None => result.file.clone(),
+                };
+                println!("❌ {} ({} ms)", location, result.duration_ms);
+                println!("   {}", failure.message);
+                for detail in &failure.details {
+                    println!("   {}", detail);
+                }
+            }
+        }
+    }
+    if !results.is_empty() {
+        println!(
+            "\n{} passed, {} failed in {:.2}s",
+            passed,
+            failed,
+            elapsed.as_secs_f64()
+        );
+    }
+}
+
+/// A JUnit XML report: one suite, one test case per file.
+fn junit_report(results: &[TestResult], elapsed: Duration) -> String {
+    let failures = results
+        .iter()
+        .filter(|r| r.status == TestStatus::Failed)
+        .count();
+    let errors = results
+        .iter()
+        .filter(|r| r.status == TestStatus::Error)
+        .count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"pgmt\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+        results.len(),
+        failures,
+        errors,
+        elapsed.as_secs_f64()
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"pgmt\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+        results.len(),
+        failures,
+        errors,
+        elapsed.as_secs_f64()
+    ));
+    for result in results {
+        let attributes = format!(
+            "name=\"{}\" classname=\"pgmt\" file=\"{}\" assertions=\"{}\" time=\"{:.3}\"",
+            xml_escape(&result.name),
+            xml_escape(&result.file),
+            result.assertions,
+            result.duration_ms as f64 / 1000.0
+        );
+        match &result.failure {
+            None => xml.push_str(&format!("    <testcase {}/>\n", attributes)),
+            Some(failure) => {
+                let element = match result.status {
+                    TestStatus::Error => "error",
+                    _ => "failure",
+                };
+                let mut body = failure.message.clone();
+                if let Some(line) = failure.line {
+                    body.push_str(&format!("\nat {}:{}", result.file, line));
+                }
+                for detail in &failure.details {
+                    body.push('\n');
+                    body.push_str(detail);
+                }
+                xml.push_str(&format!("    <testcase {}>\n", attributes));
+                xml.push_str(&format!(
+                    "      <{} message=\"{}\">{}</{}>\n",
+                    element,
+                    xml_escape(&failure.message),
+                    xml_escape(&body),
+                    element
+                ));
+                xml.push_str("    </testcase>\n");
+            }
+        }
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_summarize_tap() {
+        let summary = summarize_tap(&lines(
+            "ok 1 - users exist\n\
+             not ok 2 - owner sees own rows\n\
+             # Failed test 2: \"owner sees own rows\"\n\
+             #         have: 2\n\
+             #         want: 3\n\
+             not ok 3 - later # TODO not built yet\n\
+             # Looks like you failed 1 test of 3",
+        ));
+        assert_eq!(summary.assertions, 3);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(
+            summary.failures,
+            lines(
+                "not ok 2 - owner sees own rows\n\
+                 # Failed test 2: \"owner sees own rows\"\n\
+                 #         have: 2\n\
+                 #         want: 3"
+            )
+        );
+
+        let unmet = summarize_tap(&lines("ok 1\n# Looks like you planned 2 tests but ran 1"));
+        assert_eq!(unmet.failed, 1);
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let results = vec![
+            TestResult {
+                name: "users".to_string(),
+                file: "tests/users.sql".to_string(),
+                status: TestStatus::Passed,
+                assertions: 2,
+                duration_ms: 12,
+                failure: None,
+            },
+            TestResult {
+                name: "rls".to_string(),
+                file: "tests/rls.sql".to_string(),
+                status: TestStatus::Failed,
+                assertions: 0,
+                duration_ms: 5,
+                failure: Some(TestFailure {
+                    message: "assertion failed: a < b".to_string(),
+                    line: None,
+                    details: vec!["expected '3', got '2'".to_string()],
+                }),
+            },
+        ];
+        let xml = junit_report(&results, Duration::from_millis(40));
+        assert!(xml.contains("<testsuites name=\"pgmt\" tests=\"2\" failures=\"1\" errors=\"0\""));
+        assert!(xml.contains(
+            "<testcase name=\"users\" classname=\"pgmt\" file=\"tests/users.sql\" \
+             assertions=\"2\" time=\"0.012\"/>"
+        ));
+        assert!(xml.contains(
+            "<failure message=\"assertion failed: a &lt; b\">assertion failed: a &lt; b\n\
+             expected &apos;3&apos;, got &apos;2&apos;</failure>"
+        ));
+    }
+}
@@ -1,34 +1,28 @@
-use crate::catalog::Catalog;
-use crate::config::Config;
-use crate::validation::{ValidationConfig, validate_database_against_schema_files};
+use crate::commands::catalog::{CatalogSource, load_database_side, load_schema_side};
+use crate::config::{Config, DevUrl, ShadowDatabase};
+use crate::validation::{ValidationConfig, validate_catalogs};
 use anyhow::{Result, anyhow};
 use std::path::Path;
 
 pub async fn cmd_validate(
     config: &Config,
     root_dir: &Path,
-    dev: &crate::config::DevUrl,
-    shadow: &crate::config::ShadowDatabase,
+    dev: &CatalogSource<DevUrl>,
+    shadow: &CatalogSource<ShadowDatabase>,
 ) -> Result<()> {
     println!("🔍 Validating schema consistency...");
 
-    let dev_pool =
-        crate::db::connection::connect_to_database(dev.as_str(), "development database").await?;
+    println!("📊 Loading {}...", dev.label("current database schema"));
+    let db_catalog =
+        load_database_side(config, dev, DevUrl::as_str, "development database").await?;
 
-    println!("📊 Loading current database schema...");
-    let filter = crate::config::filter::ObjectFilter::from_config(config);
-    let db_catalog = Catalog::load_managed(&dev_pool, &filter).await?;
-
-    let validation_config = ValidationConfig::default();
-
-    let result = validate_database_against_schema_files(
+    let expected_catalog = load_schema_side(config, root_dir, shadow).await?;
+    let result = validate_catalogs(
         &db_catalog,
+        &expected_catalog,
         config,
-        root_dir,
-        &validation_config,
-        shadow,
-    )
-    .await?;
+        &ValidationConfig::default(),
+    )?;
 
     if result.passed {
         println!("✅ {}", result.message);
@@ -1,14 +1,16 @@
 use crate::config::{merge::Merge, types::*};
-use anyhow::Result;
+use anyhow::{Result, bail};
 
 pub struct ConfigBuilder {
     config_input: ConfigInput,
+    variables: Variables,
 }
 
 impl ConfigBuilder {
     pub fn new() -> Self {
         Self {
             config_input: ConfigInput::default(),
+            variables: Variables::default(),
         }
     }
 
@@ -17,6 +19,13 @@ impl ConfigBuilder {
         self
     }
 
+    /// Placeholder values resolved at the command boundary (they depend on
+    /// `--var`/`--env` and the environment, like database connections).
+    pub fn with_variables(mut self, variables: Variables) -> Self {
+        self.variables = variables;
+        self
+    }
+
     /// Resolve project configuration against built-in defaults.
     ///
     /// Database connections are NOT resolved here — they're typed values
@@ -24,6 +33,26 @@ impl ConfigBuilder {
     /// resolved Config carries no connection strings.
     pub fn resolve(self) -> Result<Config> {
         let defaults = Config::default();
+        let tenants = self.resolve_tenants()?;
+        let variables = match &tenants {
+            Some(tenants) => {
+                if self
+                    .variables
+                    .iter()
+                    .any(|(name, _)| name == crate::tenants::TENANT_VARIABLE)
+                {
+                    bail!(
+                        "the variable '{}' is set by `tenants:` (the tenant's schema); \
+                         rename yours",
+                        crate::tenants::TENANT_VARIABLE
+                    );
+                }
+                self.variables
+                    .clone()
+                    .with_value(crate::tenants::TENANT_VARIABLE, &tenants.template)
+            }
+            None => self.variables.clone(),
+        };
 
         let config = Config {
             directories: self.resolve_directories(&defaults.directories),
@@ -32,6 +61,9 @@ impl ConfigBuilder {
             migration: self.resolve_migration(&defaults.migration),
             schema: self.resolve_schema(&defaults.schema),
             docker: self.resolve_docker(&defaults.docker),
+            dev: self.resolve_dev(&defaults.dev),
+            tenants,
+            variables,
         };
 
         if config.modules.is_enabled() {
@@ -162,6 +194,22 @@ impl ConfigBuilder {
                 .and_then(|d| d.roles_file.as_ref())
                 .cloned()
                 .unwrap_or_else(|| defaults.roles.clone()),
+            repeatable: dir_input
+                .and_then(|d| d.repeatable_dir.as_ref())
+                .cloned()
+                .unwrap_or_else(|| defaults.repeatable.clone()),
+            hooks: dir_input
+                .and_then(|d| d.hooks_dir.as_ref())
+                .cloned()
+                .unwrap_or_else(|| defaults.hooks.clone()),
+            data: dir_input
+                .and_then(|d| d.data_dir.as_ref())
+                .cloned()
+                .unwrap_or_else(|| defaults.data.clone()),
+            tests: dir_input
+                .and_then(|d| d.tests_dir.as_ref())
+                .cloned()
+                .unwrap_or_else(|| defaults.tests.clone()),
         }
     }
 
@@ -207,9 +255,62 @@ impl ConfigBuilder {
                 .and_then(|m| m.filename_prefix.as_ref())
                 .cloned()
                 .unwrap_or_else(|| defaults.filename_prefix.clone()),
+            lint: Lint {
+                rules: mig_input
+                    .and_then(|m| m.lint.as_ref())
+                    .and_then(|l| l.rules.clone())
+                    .unwrap_or_else(|| defaults.lint.rules.clone()),
+            },
+            hooks: mig_input
+                .and_then(|m| m.hooks.as_ref())
+                .map(|h| Hooks {
+                    before_migrate: h.before_migrate.clone(),
+                    after_each_section: h.after_each_section.clone(),
+                    after_migrate: h.after_migrate.clone(),
+                })
+                .unwrap_or_else(|| defaults.hooks.clone()),
+            audit: mig_input.and_then(|m| m.audit).unwrap_or(defaults.audit),
+            allow_out_of_order: mig_input
+                .and_then(|m| m.allow_out_of_order)
+                .unwrap_or(defaults.allow_out_of_order),
         }
     }
 
+    /// Resolve and validate `tenants:`. Names are plain lowercase
+    /// identifiers, so `${tenant}` can be substituted quoted or bare.
+    fn resolve_tenants(&self) -> Result<Option<Tenants>> {
+        let Some(input) = &self.config_input.tenants else {
+            return Ok(None);
+        };
+        let (Some(template), Some(schemas)) = (&input.template, &input.schemas) else {
+            bail!(
+                "`tenants:` needs both `template` (the schema name schema files use) and `schemas` (a glob matching the tenant schemas)"
+            );
+        };
+        if !crate::tenants::is_plain_schema_name(template) {
+            bail!(
+                "tenants.template '{}' must be a lowercase identifier (letters, digits, underscores)",
+                template
+            );
+        }
+        glob::Pattern::new(schemas)
+            .map_err(|e| anyhow::anyhow!("invalid tenants.schemas glob '{}': {}", schemas, e))?;
+        if self
+            .config_input
+            .modules
+            .as_ref()
+            .is_some_and(|m| !m.is_empty())
+        {
+            bail!("`tenants:` and `modules:` can't be combined");
+        }
+        Ok(Some(Tenants {
+            template: template.clone(),
+            schemas: schemas.clone(),
+            query: input.query.clone(),
+            generating: false,
+        }))
+    }
+
     fn resolve_schema(&self, defaults: &Schema) -> Schema {
         let schema_input = self.config_input.schema.as_ref();
 
@@ -238,6 +339,16 @@ impl ConfigBuilder {
                 .unwrap_or(defaults.check_system_identifier),
         }
     }
+
+    fn resolve_dev(&self, defaults: &Dev) -> Dev {
+        let dev_input = self.config_input.dev.as_ref();
+
+        Dev {
+            snapshot_retention: dev_input
+                .and_then(|d| d.snapshot_retention)
+                .unwrap_or(defaults.snapshot_retention),
+        }
+    }
 }
 
 impl Default for ConfigBuilder {
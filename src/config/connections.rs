@@ -13,7 +13,8 @@ use anyhow::{Result, anyhow};
 use clap::Args;
 
 use super::types::{
-    ConfigInput, DatabasesInput, ShadowDatabase, ShadowDockerConfig, ShadowResetMode,
+    ConfigInput, DatabasesInput, FleetTargetInput, ShadowDatabase, ShadowDockerConfig,
+    ShadowResetMode,
 };
 
 fn env_var(name: &str) -> Option<String> {
@@ -96,6 +97,11 @@ impl TargetUrlArgs {
         })
     }
 
+    /// The target, for commands that only use one when it is configured
+    pub fn resolve_optional(&self, file: &ConfigInput) -> Option<TargetUrl> {
+        self.lookup(file).map(TargetUrl)
+    }
+
     /// The effective value without requiring it (used by `pgmt config` display)
     pub fn lookup(&self, file: &ConfigInput) -> Option<String> {
         self.target_url
@@ -105,6 +111,98 @@ impl TargetUrlArgs {
     }
 }
 
+/// A target from the `targets:` inventory, picked by `--targets`.
+#[derive(Debug, Clone)]
+pub struct FleetTarget {
+    pub name: String,
+    pub url: TargetUrl,
+    pub groups: Vec<String>,
+    pub canary: bool,
+    pub env: Option<String>,
+}
+
+/// Resolve a `--targets` selector against the `targets:` inventory.
+///
+/// The selector is comma-separated: `all`, `group:<tag>`, or a target name.
+/// Targets come back canaries first, each group in inventory (name) order,
+/// without duplicates. Naming a target or group that doesn't exist is an
+/// error rather than a smaller fleet.
+pub fn resolve_fleet_targets(file: &ConfigInput, selector: &str) -> Result<Vec<FleetTarget>> {
+    let inventory = file.targets.clone().unwrap_or_default();
+    if inventory.is_empty() {
+        return Err(anyhow!(
+            "--targets needs a `targets:` inventory in pgmt.yaml, e.g.\n\n\
+             targets:\n  eu-1:\n    url_env: EU_1_DATABASE_URL\n    groups: [eu]"
+        ));
+    }
+
+    let mut picked: Vec<&String> = Vec::new();
+    for item in selector.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+        let matches: Vec<&String> = if item == "all" {
+            inventory.keys().collect()
+        } else if let Some(group) = item.strip_prefix("group:") {
+            let members: Vec<&String> = inventory
+                .iter()
+                .filter(|(_, t)| t.groups.iter().flatten().any(|g| g == group))
+                .map(|(name, _)| name)
+                .collect();
+            if members.is_empty() {
+                return Err(anyhow!("no target in `targets:` has group '{}'", group));
+            }
+            members
+        } else if let Some((name, _)) = inventory.get_key_value(item) {
+            vec![name]
+        } else {
+            return Err(anyhow!(
+                "unknown target '{}'; `targets:` has: {}",
+                item,
+                inventory.keys().cloned().collect::<Vec<_>>().join(", ")
+            ));
+        };
+        for name in matches {
+            if !picked.contains(&name) {
+                picked.push(name);
+            }
+        }
+    }
+    if picked.is_empty() {
+        return Err(anyhow!("--targets selected no targets"));
+    }
+
+    let mut targets = picked
+        .into_iter()
+        .map(|name| fleet_target(name, &inventory[name]))
+        .collect::<Result<Vec<_>>>()?;
+    targets.sort_by_key(|t| !t.canary);
+    Ok(targets)
+}
+
+fn fleet_target(name: &str, input: &FleetTargetInput) -> Result<FleetTarget> {
+    let url = match (&input.url, &input.url_env) {
+        (Some(url), None) => url.clone(),
+        (None, Some(var)) => env_var(var).ok_or_else(|| {
+            anyhow!(
+                "target '{}' reads its URL from {}, which is not set",
+                name,
+                var
+            )
+        })?,
+        _ => {
+            return Err(anyhow!(
+                "target '{}' needs exactly one of `url` and `url_env`",
+                name
+            ));
+        }
+    };
+    Ok(FleetTarget {
+        name: name.to_string(),
+        url: TargetUrl(url),
+        groups: input.groups.clone().unwrap_or_default(),
+        canary: input.canary.unwrap_or(false),
+        env: input.env.clone(),
+    })
+}
+
 #[derive(Debug, Clone, Default, Args)]
 pub struct ShadowUrlArgs {
     #[arg(
@@ -239,6 +337,59 @@ mod tests {
         assert!(err.contains("--target-url"));
     }
 
+    fn fleet_file() -> ConfigInput {
+        let target = |url: &str, groups: &[&str], canary: bool| FleetTargetInput {
+            url: Some(url.into()),
+            groups: Some(groups.iter().map(|g| g.to_string()).collect()),
+            canary: Some(canary),
+            ..Default::default()
+        };
+        ConfigInput {
+            targets: Some(
+                [
+                    ("eu-1", target("postgres://eu-1/db", &["eu"], false)),
+                    ("eu-canary", target("postgres://eu-c/db", &["eu"], true)),
+                    ("us-1", target("postgres://us-1/db", &["us"], false)),
+                ]
+                .into_iter()
+                .map(|(name, t)| (name.to_string(), t))
+                .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn names(targets: &[FleetTarget]) -> Vec<&str> {
+        targets.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn fleet_group_selection_puts_canaries_first() {
+        let targets = resolve_fleet_targets(&fleet_file(), "group:eu").unwrap();
+        assert_eq!(names(&targets), ["eu-canary", "eu-1"]);
+        assert_eq!(targets[1].url.as_str(), "postgres://eu-1/db");
+
+        let targets = resolve_fleet_targets(&fleet_file(), "us-1,group:eu,eu-1").unwrap();
+        assert_eq!(names(&targets), ["eu-canary", "us-1", "eu-1"]);
+        assert_eq!(
+            names(&resolve_fleet_targets(&fleet_file(), "all").unwrap()),
+            ["eu-canary", "eu-1", "us-1"]
+        );
+    }
+
+    #[test]
+    fn fleet_unknown_names_are_errors() {
+        let err = resolve_fleet_targets(&fleet_file(), "group:apac")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("group 'apac'"));
+        let err = resolve_fleet_targets(&fleet_file(), "eu-2")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("eu-1, eu-canary, us-1"));
+        assert!(resolve_fleet_targets(&ConfigInput::default(), "all").is_err());
+    }
+
     #[test]
     fn shadow_defaults_to_auto() {
         let shadow = ShadowUrlArgs::default()
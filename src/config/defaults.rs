@@ -1,6 +1,6 @@
 use crate::config::types::{
-    ColumnOrderMode, Directories, Docker, Migration, ObjectExclude, Schema, ShadowDockerConfig,
-    TrackingTable,
+    ColumnOrderMode, Dev, Directories, Docker, Hooks, Lint, Migration, ObjectExclude, Schema,
+    ShadowDockerConfig, TrackingTable,
 };
 use std::collections::HashMap;
 
@@ -16,6 +16,10 @@ impl Default for Directories {
             migrations: "migrations".to_string(),
             baselines: "schema_baselines".to_string(),
             roles: "roles.sql".to_string(),
+            repeatable: "repeatable".to_string(),
+            hooks: "hooks".to_string(),
+            data: "data".to_string(),
+            tests: "tests".to_string(),
         }
     }
 }
@@ -41,6 +45,10 @@ impl Default for Migration {
             tracking_table: TrackingTable::default(),
             column_order: ColumnOrderMode::Strict,
             filename_prefix: String::new(),
+            lint: Lint::default(),
+            hooks: Hooks::default(),
+            audit: false,
+            allow_out_of_order: false,
         }
     }
 }
@@ -73,6 +81,14 @@ impl Default for Docker {
     }
 }
 
+impl Default for Dev {
+    fn default() -> Self {
+        Self {
+            snapshot_retention: 5,
+        }
+    }
+}
+
 impl Default for ShadowDockerConfig {
     fn default() -> Self {
         Self {
@@ -1,6 +1,6 @@
 use crate::catalog;
 use crate::catalog::id::DbObjectId;
-use crate::config::types::{ObjectExclude, ObjectInclude, Objects, TrackingTable};
+use crate::config::types::{ObjectExclude, ObjectInclude, Objects, Tenants, TrackingTable};
 use glob::Pattern;
 
 /// Object filter for determining which database objects pgmt should manage.
@@ -10,6 +10,8 @@ pub struct ObjectFilter {
     include: ObjectInclude,
     exclude: ObjectExclude,
     tracking_table: TrackingTable,
+    /// Tenant schemas (schema-per-tenant mode) other than the template.
+    tenants: Option<Tenants>,
 }
 
 impl ObjectFilter {
@@ -19,6 +21,7 @@ impl ObjectFilter {
             include: config.include.clone(),
             exclude: config.exclude.clone(),
             tracking_table: tracking_table.clone(),
+            tenants: None,
         }
     }
 
@@ -26,11 +29,23 @@ impl ObjectFilter {
     /// Every command must build its filter here; `new` is for callers that
     /// don't have a full Config yet (init while composing one, unit tests).
     pub fn from_config(config: &crate::config::types::Config) -> Self {
-        Self::new(&config.objects, &config.migration.tracking_table)
+        Self {
+            tenants: config.tenants.clone(),
+            ..Self::new(&config.objects, &config.migration.tracking_table)
+        }
     }
 
     /// Check if a schema should be included
     pub fn should_include_schema(&self, schema_name: &str) -> bool {
+        // Tenant schemas are migrated one by one from the template, never
+        // managed as themselves.
+        if let Some(tenants) = &self.tenants
+            && schema_name != tenants.template
+            && self.matches_patterns(std::slice::from_ref(&tenants.schemas), schema_name)
+        {
+            return false;
+        }
+
         // Check exclude patterns first
         if self.matches_patterns(&self.exclude.schemas, schema_name) {
             return false;
@@ -82,6 +97,9 @@ impl ObjectFilter {
         let internal_tables = [
             self.tracking_table.name.as_str(), // pgmt_migrations
             &format!("{}_sections", self.tracking_table.name), // pgmt_migrations_sections
+            &format!("{}_modules", self.tracking_table.name), // pgmt_migrations_modules
+            &format!("{}_repeatable", self.tracking_table.name), // pgmt_migrations_repeatable
+            &format!("{}_history", self.tracking_table.name), // pgmt_migrations_history
         ];
 
         internal_tables.contains(&table_name)
@@ -259,6 +277,9 @@ mod tests {
         assert!(filter.is_pgmt_internal_table("public", "pgmt_migrations"));
         // Sections table
         assert!(filter.is_pgmt_internal_table("public", "pgmt_migrations_sections"));
+        assert!(filter.is_pgmt_internal_table("public", "pgmt_migrations_modules"));
+        assert!(filter.is_pgmt_internal_table("public", "pgmt_migrations_repeatable"));
+        assert!(filter.is_pgmt_internal_table("public", "pgmt_migrations_history"));
         // Not internal - wrong schema
         assert!(!filter.is_pgmt_internal_table("other", "pgmt_migrations"));
         // Not internal - different table
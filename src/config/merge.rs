@@ -39,6 +39,20 @@ impl Merge<ConfigInput> for ConfigInput {
             migration: self.migration.merge(other.migration),
             schema: self.schema.merge(other.schema),
             docker: self.docker.merge(other.docker),
+            dev: self.dev.merge(other.dev),
+            // Overlay values win per variable; environments are replaced
+            // whole, like `modules:`.
+            variables: match (self.variables, other.variables) {
+                (Some(mut a), Some(b)) => {
+                    a.extend(b);
+                    Some(a)
+                }
+                (a, b) => b.or(a),
+            },
+            environments: self.environments.merge(other.environments),
+            // Replaced whole: an overlay's inventory is the fleet.
+            targets: self.targets.merge(other.targets),
+            tenants: self.tenants.merge(other.tenants),
         }
     }
 }
@@ -103,6 +117,10 @@ impl DirectoriesInput {
             migrations_dir: other.migrations_dir.or(self.migrations_dir),
             baselines_dir: other.baselines_dir.or(self.baselines_dir),
             roles_file: other.roles_file.or(self.roles_file),
+            repeatable_dir: other.repeatable_dir.or(self.repeatable_dir),
+            hooks_dir: other.hooks_dir.or(self.hooks_dir),
+            data_dir: other.data_dir.or(self.data_dir),
+            tests_dir: other.tests_dir.or(self.tests_dir),
         }
     }
 }
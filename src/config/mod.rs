@@ -4,14 +4,18 @@ pub mod defaults;
 pub mod filter;
 pub mod merge;
 pub mod types;
+pub mod variables;
 
 #[cfg(test)]
 mod tests;
 
 pub use builder::ConfigBuilder;
-pub use connections::{DevUrl, DevUrlArgs, ShadowUrlArgs, TargetUrl, TargetUrlArgs};
+pub use connections::{
+    DevUrl, DevUrlArgs, FleetTarget, ShadowUrlArgs, TargetUrl, TargetUrlArgs, resolve_fleet_targets,
+};
 pub use filter::ObjectFilter;
 pub use types::*;
+pub use variables::{PlaceholderRestorer, VariableArgs};
 
 use anyhow::Result;
 use std::path::Path;
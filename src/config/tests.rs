@@ -22,11 +22,20 @@ fn test_config_input_merge() {
             migrations_dir: None,
             baselines_dir: None,
             roles_file: None,
+            repeatable_dir: None,
+            hooks_dir: None,
+            data_dir: None,
+            tests_dir: None,
         }),
         objects: None,
         migration: None,
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     let cli_config = ConfigInput {
@@ -42,6 +51,10 @@ fn test_config_input_merge() {
             migrations_dir: Some("migrations_override".to_string()),
             baselines_dir: Some("baselines".to_string()),
             roles_file: Some("roles.sql".to_string()),
+            repeatable_dir: None,
+            hooks_dir: None,
+            data_dir: None,
+            tests_dir: None,
         }),
         objects: Some(ObjectsInput {
             include: None,
@@ -53,6 +66,11 @@ fn test_config_input_merge() {
         migration: None,
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     let merged = file_config.merge(cli_config);
@@ -121,6 +139,11 @@ fn test_config_builder_resolve() {
         migration: None, // Use defaults
         schema: None,    // Use defaults
         docker: None,    // Use defaults
+        dev: None,       // Use defaults
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     // Database connections resolve separately from project config
@@ -317,6 +340,11 @@ fn test_config_builder_shadow_docker_version() {
         migration: None,
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     // Verify version was set correctly
@@ -359,6 +387,11 @@ fn test_config_builder_shadow_docker_explicit_image() {
         migration: None,
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     // Verify explicit image takes precedence
@@ -410,6 +443,11 @@ fn test_config_builder_shadow_docker_platform() {
         migration: None,
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     match ShadowUrlArgs::default().resolve(&config_input).unwrap() {
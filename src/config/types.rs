@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
+pub use super::variables::Variables;
+
 /// Raw configuration input - all fields Optional for merging
 #[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct ConfigInput {
@@ -18,6 +20,21 @@ pub struct ConfigInput {
     pub schema: Option<SchemaInput>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub docker: Option<DockerInput>,
+    /// Development database settings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dev: Option<DevInput>,
+    /// `${name}` placeholder values shared by every environment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<BTreeMap<String, String>>,
+    /// Per-environment overrides, selected with `--env` / `PGMT_ENV`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environments: Option<BTreeMap<String, EnvironmentInput>>,
+    /// Named deployment targets for `migrate apply --targets`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub targets: Option<BTreeMap<String, FleetTargetInput>>,
+    /// Schema-per-tenant mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenants: Option<TenantsInput>,
 }
 
 /// Resolved configuration with all defaults applied.
@@ -34,6 +51,69 @@ pub struct Config {
     pub migration: Migration,
     pub schema: Schema,
     pub docker: Docker,
+    pub dev: Dev,
+    /// Resolved `${name}` placeholder values (see `config::variables`).
+    pub variables: Variables,
+    /// Schema-per-tenant mode; `None` when `tenants:` is absent.
+    pub tenants: Option<Tenants>,
+}
+
+/// `tenants:` in `pgmt.yaml`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct TenantsInput {
+    /// The schema name the schema files are written against (required).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Glob matching the tenant schemas (required), e.g. `tenant_*`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schemas: Option<String>,
+    /// Query listing the tenant schemas, instead of every existing schema
+    /// matching `schemas`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+}
+
+/// Resolved schema-per-tenant mode (see `crate::tenants`). Schemas matching
+/// `schemas` other than `template` are tenants: outside the managed universe
+/// on every database, and each migrated separately by `migrate apply`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tenants {
+    pub template: String,
+    pub schemas: String,
+    pub query: Option<String>,
+    /// Set for the commands that write migration files: the catalogs they
+    /// plan from name the template schema `${tenant}`.
+    pub generating: bool,
+}
+
+/// One `environments.<name>` block in `pgmt.yaml`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct EnvironmentInput {
+    /// Placeholder values overriding the top-level `variables:`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables: Option<BTreeMap<String, String>>,
+}
+
+/// One `targets.<name>` entry in `pgmt.yaml`: a database in the fleet that
+/// `migrate apply --targets` deploys to.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct FleetTargetInput {
+    /// Connection URL. Prefer `url_env` for anything with a password.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Environment variable holding the connection URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url_env: Option<String>,
+    /// Tags selected with `--targets group:<tag>`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<String>>,
+    /// Deployed first, one at a time; a failure stops the whole run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canary: Option<bool>,
+    /// Environment (`environments.<name>`) whose variables apply to this
+    /// target, instead of `--env`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
 }
 
 /// One module declaration as written in `pgmt.yaml`.
@@ -240,6 +320,14 @@ pub struct DirectoriesInput {
     pub baselines_dir: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roles_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeatable_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hooks_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tests_dir: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -248,6 +336,17 @@ pub struct Directories {
     pub migrations: String,
     pub baselines: String,
     pub roles: String,
+    /// Repeatable migrations: re-run by `migrate apply` whenever their
+    /// checksum changes.
+    pub repeatable: String,
+    /// SQL lifecycle hooks (`before_migrate.sql`, `after_each_section.sql`,
+    /// `after_migrate.sql`) run by `migrate apply`.
+    pub hooks: String,
+    /// Seed data files (`<table>.csv` / `<table>.sql`), loaded into the
+    /// shadow after the schema files.
+    pub data: String,
+    /// SQL test files run by `pgmt test`.
+    pub tests: String,
 }
 
 // Object filtering configuration
@@ -313,6 +412,34 @@ pub struct MigrationInput {
     pub column_order: Option<ColumnOrderMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub filename_prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lint: Option<LintInput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<HooksInput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_out_of_order: Option<bool>,
+}
+
+/// `migration.lint` — per-rule severity overrides for `pgmt migrate lint`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct LintInput {
+    /// Rule ID → level. Rules not listed keep their built-in severity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules: Option<BTreeMap<String, LintLevel>>,
+}
+
+/// `migration.hooks` — shell commands `migrate apply` runs at each lifecycle
+/// point, after the matching SQL hook file (if any).
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct HooksInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_migrate: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_each_section: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_migrate: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
@@ -331,6 +458,40 @@ pub struct Migration {
     pub tracking_table: TrackingTable,
     pub column_order: ColumnOrderMode,
     pub filename_prefix: String,
+    pub lint: Lint,
+    pub hooks: Hooks,
+    /// Record every section attempt, with its SQL, in the history table
+    pub audit: bool,
+    /// Apply pending migrations older than the newest applied one instead of
+    /// refusing
+    pub allow_out_of_order: bool,
+}
+
+/// Resolved lint configuration. Rule IDs are validated by the linter, not at
+/// config load, so a typo surfaces as a `migrate lint` error naming the rule.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lint {
+    pub rules: BTreeMap<String, LintLevel>,
+}
+
+/// Resolved `migration.hooks` commands; `None` means no command for that point.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hooks {
+    pub before_migrate: Option<String>,
+    pub after_each_section: Option<String>,
+    pub after_migrate: Option<String>,
+}
+
+/// Configured level for a lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    /// Report and fail the lint run
+    Error,
+    /// Report without failing
+    Warning,
+    /// Don't check this rule at all
+    Off,
 }
 
 #[derive(Debug, Clone)]
@@ -367,6 +528,20 @@ pub struct Docker {
     pub check_system_identifier: bool,
 }
 
+// Development database configuration
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct DevInput {
+    /// How many dev database snapshots to keep (see `crate::db::snapshot`);
+    /// 0 turns snapshots before destructive applies off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_retention: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Dev {
+    pub snapshot_retention: usize,
+}
+
 // Schema configuration
 #[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct SchemaInput {
@@ -0,0 +1,534 @@
+//! `${name}` placeholders in schema, migration, baseline, repeatable and roles
+//! files.
+//!
+//! Values are resolved once per invocation. Precedence, highest first:
+//! `--var name=value` > `PGMT_VAR_<NAME>` env var >
+//! `environments.<env>.variables` in pgmt.yaml (the environment picked by
+//! `--env` / `PGMT_ENV`) > top-level `variables:` in pgmt.yaml.
+//!
+//! Substitution is opt-in: a project that defines no variables at all has its
+//! files run as written, so existing SQL containing `${...}` keeps working.
+//! Once any variable is defined, names are case-insensitive (`${APP_ROLE}`,
+//! `${app_role}` and `PGMT_VAR_APP_ROLE` are the same variable), a
+//! placeholder with no value is an error rather than being left in place, and
+//! `$${name}` is the escape for a literal `${name}`. Any other `${` (not
+//! followed by a name and `}`) is plain SQL and is left alone.
+//!
+//! Substitution only changes what is *executed*: checksums are computed over
+//! the files as written, so one migration file is valid in every environment.
+//! For the same reason generated SQL is written with the placeholders put back
+//! (see [`PlaceholderRestorer`]) rather than the current environment's values.
+
+use anyhow::{Result, anyhow, bail};
+use clap::Args;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::types::ConfigInput;
+
+const ENV_VAR_PREFIX: &str = "PGMT_VAR_";
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct VariableArgs {
+    #[arg(
+        long = "env",
+        global = true,
+        value_name = "NAME",
+        help = "Environment whose `environments.<NAME>.variables` apply [env: PGMT_ENV]"
+    )]
+    pub env: Option<String>,
+
+    #[arg(
+        long = "var",
+        global = true,
+        value_name = "NAME=VALUE",
+        help = "Set a ${NAME} placeholder value (repeatable) [env: PGMT_VAR_<NAME>]"
+    )]
+    pub vars: Vec<String>,
+}
+
+impl VariableArgs {
+    pub fn resolve(&self, file: &ConfigInput) -> Result<Variables> {
+        let env_vars = std::env::vars().filter_map(|(key, value)| {
+            key.strip_prefix(ENV_VAR_PREFIX)
+                .map(|name| (name.to_string(), value))
+        });
+        let environment = self
+            .env
+            .clone()
+            .or_else(|| std::env::var("PGMT_ENV").ok().filter(|v| !v.is_empty()));
+        self.resolve_with(file, environment.as_deref(), env_vars)
+    }
+
+    fn resolve_with(
+        &self,
+        file: &ConfigInput,
+        environment: Option<&str>,
+        env_vars: impl Iterator<Item = (String, String)>,
+    ) -> Result<Variables> {
+        let mut values = BTreeMap::new();
+        let mut set = |name: &str, value: String| -> Result<()> {
+            validate_name(name)?;
+            values.insert(name.to_ascii_lowercase(), value);
+            Ok(())
+        };
+
+        for (name, value) in file.variables.iter().flatten() {
+            set(name, value.clone())?;
+        }
+        if let Some(environment) = environment {
+            let declared = file
+                .environments
+                .as_ref()
+                .and_then(|envs| envs.get(environment))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "environment '{}' is not declared under `environments:` in pgmt.yaml",
+                        environment
+                    )
+                })?;
+            for (name, value) in declared.variables.iter().flatten() {
+                set(name, value.clone())?;
+            }
+        }
+        for (name, value) in env_vars {
+            set(&name, value)?;
+        }
+        for assignment in &self.vars {
+            let (name, value) = assignment
+                .split_once('=')
+                .ok_or_else(|| anyhow!("invalid --var '{}': expected NAME=VALUE", assignment))?;
+            set(name, value.to_string())?;
+        }
+
+        Ok(Variables::new(values))
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        bail!(
+            "invalid variable name '{}': use letters, digits and underscores, \
+             not starting with a digit",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Resolved placeholder values (names lowercased).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Variables {
+    values: BTreeMap<String, String>,
+}
+
+impl Variables {
+    pub fn new<K: AsRef<str>, V: Into<String>>(values: impl IntoIterator<Item = (K, V)>) -> Self {
+        Self {
+            values: values
+                .into_iter()
+                .map(|(k, v)| (k.as_ref().to_ascii_lowercase(), v.into()))
+                .collect(),
+        }
+    }
+
+    /// Set `name` to `value`, replacing any value it had.
+    pub fn with_value(mut self, name: &str, value: &str) -> Self {
+        self.values
+            .insert(name.to_ascii_lowercase(), value.to_string());
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Whether any variable is defined, i.e. whether placeholders are
+    /// substituted at all.
+    pub fn is_enabled(&self) -> bool {
+        !self.values.is_empty()
+    }
+
+    /// Replace every `${name}` placeholder in `text`. Values may not contain
+    /// line breaks, so the result has exactly the lines of `text`. Without any
+    /// variables defined the placeholders are left as written.
+    pub fn substitute(&self, text: &str) -> Result<String> {
+        if self.is_enabled() {
+            self.substitute_placeholders(text)
+        } else {
+            Ok(text.to_string())
+        }
+    }
+
+    fn substitute_placeholders(&self, text: &str) -> Result<String> {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(pos) = rest.find("${") {
+            let escaped = rest[..pos].ends_with('$');
+            let after = &rest[pos + 2..];
+            let Some(name) = placeholder_name(after) else {
+                out.push_str(&rest[..pos + 2]);
+                rest = after;
+                continue;
+            };
+            let end = pos + 2 + name.len() + 1;
+            if escaped {
+                // `$${name}` → literal `${name}`: drop the escaping `$`.
+                out.push_str(&rest[..pos - 1]);
+                out.push_str(&rest[pos..end]);
+            } else {
+                let value = self.values.get(&name.to_ascii_lowercase()).ok_or_else(|| {
+                    let offset = text.len() - rest.len() + pos;
+                    let line = text[..offset].matches('\n').count() + 1;
+                    anyhow!(
+                        "undefined variable '${{{name}}}' at line {line}. Set it in pgmt.yaml \
+                         (`variables:` or `environments.<env>.variables`), with {ENV_VAR_PREFIX}{} \
+                         or with --var {name}=...",
+                        name.to_ascii_uppercase()
+                    )
+                })?;
+                if value.contains(['\n', '\r']) {
+                    bail!("variable '{}' must be a single line", name);
+                }
+                out.push_str(&rest[..pos]);
+                out.push_str(value);
+            }
+            rest = &rest[end..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// A [`PlaceholderRestorer`] for SQL generated from a shadow built out of
+    /// `sources` (the project files as written). A variable's value is only
+    /// mapped back when it cannot have come from anywhere else: the variable
+    /// is referenced in `sources`, no other referenced variable has the same
+    /// value, and the value never appears in `sources` as a whole word
+    /// outside a placeholder. Variables that fail this are reported in
+    /// [`PlaceholderRestorer::skipped`] and their values stay hardcoded.
+    pub fn placeholder_restorer<'a>(
+        &self,
+        sources: impl IntoIterator<Item = &'a str>,
+    ) -> PlaceholderRestorer {
+        if !self.is_enabled() {
+            return PlaceholderRestorer::default();
+        }
+
+        let mut used = BTreeSet::new();
+        let mut literal = String::new();
+        for source in sources {
+            let mut rest = source;
+            while let Some(pos) = rest.find("${") {
+                let after = &rest[pos + 2..];
+                match placeholder_name(after) {
+                    Some(name) if !rest[..pos].ends_with('$') => {
+                        used.insert(name.to_ascii_lowercase());
+                        literal.push_str(&rest[..pos]);
+                        literal.push(' ');
+                        rest = &after[name.len() + 1..];
+                    }
+                    _ => {
+                        literal.push_str(&rest[..pos + 2]);
+                        rest = after;
+                    }
+                }
+            }
+            literal.push_str(rest);
+            literal.push('\n');
+        }
+
+        let mut restorer = PlaceholderRestorer {
+            enabled: true,
+            ..Default::default()
+        };
+        for name in &used {
+            let Some(value) = self.values.get(name) else {
+                continue;
+            };
+            let shared = used
+                .iter()
+                .any(|other| other != name && self.values.get(other) == Some(value));
+            if value.is_empty() || shared || find_word(&literal, value).is_some() {
+                restorer.skipped.push(name.clone());
+            } else {
+                restorer.values.push((value.clone(), name.clone()));
+            }
+        }
+        // Longest first, so a value that extends another one wins.
+        restorer
+            .values
+            .sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        restorer
+    }
+}
+
+/// Puts `${name}` placeholders back into SQL that pgmt generated from a
+/// substituted shadow (`migrate new`, `migrate update`, baselines), so the
+/// written file is valid in every environment rather than hardcoding the
+/// values of the environment it was generated in. Substituting the restored
+/// SQL gives back exactly the generated SQL: placeholder-shaped text that was
+/// already in it is escaped as `$${name}`.
+#[derive(Debug, Clone, Default)]
+pub struct PlaceholderRestorer {
+    enabled: bool,
+    /// (value, variable name), longest value first.
+    values: Vec<(String, String)>,
+    skipped: Vec<String>,
+    /// Placeholders the generated SQL itself carries, left unescaped.
+    live: Vec<String>,
+}
+
+impl PlaceholderRestorer {
+    /// Leave `${name}` in generated SQL as a placeholder rather than escaping
+    /// it: pgmt put it there (schema-per-tenant mode names the template
+    /// schema `${tenant}` in the catalogs it plans from).
+    pub fn keeping(mut self, name: &str) -> Self {
+        self.live.push(name.to_ascii_lowercase());
+        self
+    }
+
+    /// Variables referenced in the project whose values are ambiguous, and so
+    /// are left hardcoded in generated SQL.
+    pub fn skipped(&self) -> &[String] {
+        &self.skipped
+    }
+
+    pub fn restore(&self, sql: &str) -> String {
+        if !self.enabled {
+            return sql.to_string();
+        }
+        let mut out = String::with_capacity(sql.len());
+        let mut i = 0;
+        while i < sql.len() {
+            let rest = &sql[i..];
+            if let Some(name) = rest.strip_prefix("${").and_then(placeholder_name) {
+                let end = 2 + name.len() + 1;
+                if !self.live.contains(&name.to_ascii_lowercase()) {
+                    out.push('$');
+                }
+                out.push_str(&rest[..end]);
+                i += end;
+                continue;
+            }
+            let at_word_start = !sql[..i].chars().next_back().is_some_and(is_word_char);
+            let restored = at_word_start
+                .then(|| {
+                    self.values.iter().find(|(value, _)| {
+                        rest.starts_with(value.as_str())
+                            && !rest[value.len()..].chars().next().is_some_and(is_word_char)
+                    })
+                })
+                .flatten();
+            if let Some((value, name)) = restored {
+                out.push_str("${");
+                out.push_str(name);
+                out.push('}');
+                i += value.len();
+            } else {
+                let c = rest.chars().next().expect("i is below sql.len()");
+                out.push(c);
+                i += c.len_utf8();
+            }
+        }
+        out
+    }
+
+    /// [`Self::restore`] a file pgmt just wrote, in place.
+    pub fn restore_file(&self, path: &Path) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let sql = std::fs::read_to_string(path)?;
+        let restored = self.restore(&sql);
+        if restored != sql {
+            std::fs::write(path, restored)?;
+        }
+        Ok(())
+    }
+}
+
+/// The variable name of a placeholder, given the text right after its `${`.
+fn placeholder_name(after: &str) -> Option<&str> {
+    let name_len = after
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(after.len());
+    let name = &after[..name_len];
+    (after[name_len..].starts_with('}') && validate_name(name).is_ok()).then_some(name)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Byte offset of the first occurrence of `word` in `text` that is not part
+/// of a longer word.
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    text.match_indices(word).map(|(pos, _)| pos).find(|&pos| {
+        !text[..pos].chars().next_back().is_some_and(is_word_char)
+            && !text[pos + word.len()..]
+                .chars()
+                .next()
+                .is_some_and(is_word_char)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::EnvironmentInput;
+
+    fn file_config() -> ConfigInput {
+        ConfigInput {
+            variables: Some(BTreeMap::from([
+                ("app_role".to_string(), "app".to_string()),
+                ("tablespace".to_string(), "pg_default".to_string()),
+            ])),
+            environments: Some(BTreeMap::from([(
+                "prod".to_string(),
+                EnvironmentInput {
+                    variables: Some(BTreeMap::from([(
+                        "app_role".to_string(),
+                        "app_rw".to_string(),
+                    )])),
+                },
+            )])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_precedence() {
+        let file = file_config();
+        let args = VariableArgs::default();
+
+        let vars = args.resolve_with(&file, None, std::iter::empty()).unwrap();
+        assert_eq!(vars.substitute("${app_role}").unwrap(), "app");
+
+        let vars = args
+            .resolve_with(&file, Some("prod"), std::iter::empty())
+            .unwrap();
+        assert_eq!(vars.substitute("${app_role}").unwrap(), "app_rw");
+
+        let env = vec![("TABLESPACE".to_string(), "fast_ssd".to_string())];
+        let args = VariableArgs {
+            env: None,
+            vars: vec!["APP_ROLE=cli_role".to_string()],
+        };
+        let vars = args
+            .resolve_with(&file, Some("prod"), env.into_iter())
+            .unwrap();
+        assert_eq!(
+            vars.substitute("${app_role} ${tablespace}").unwrap(),
+            "cli_role fast_ssd"
+        );
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let file = file_config();
+        let err = VariableArgs::default()
+            .resolve_with(&file, Some("staging"), std::iter::empty())
+            .unwrap_err();
+        assert!(err.to_string().contains("'staging' is not declared"));
+
+        let args = VariableArgs {
+            env: None,
+            vars: vec!["app_role".to_string()],
+        };
+        let err = args
+            .resolve_with(&file, None, std::iter::empty())
+            .unwrap_err();
+        assert!(err.to_string().contains("expected NAME=VALUE"));
+    }
+
+    #[test]
+    fn test_substitute() {
+        let vars = Variables::new([("app_role", "app_rw")]);
+        assert_eq!(
+            vars.substitute("GRANT SELECT ON t TO ${app_role};\nGRANT ${APP_ROLE} TO x;")
+                .unwrap(),
+            "GRANT SELECT ON t TO app_rw;\nGRANT app_rw TO x;"
+        );
+        // Escaped placeholders and non-placeholder `${` are left as SQL.
+        assert_eq!(
+            vars.substitute("SELECT '$${app_role}', '${ not a var', $${\"a\": 1}$$")
+                .unwrap(),
+            "SELECT '${app_role}', '${ not a var', $${\"a\": 1}$$"
+        );
+
+        let err = vars
+            .substitute("SELECT 1;\nSELECT ${missing};")
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("'${missing}' at line 2"), "{message}");
+        assert!(message.contains("PGMT_VAR_MISSING"), "{message}");
+
+        // Multi-line values would shift section line numbers.
+        let vars = Variables::new([("role", "a\nb")]);
+        assert!(vars.substitute("GRANT x TO ${role};").is_err());
+    }
+
+    /// Without any variables defined, `${...}` is plain SQL.
+    #[test]
+    fn test_substitute_is_opt_in() {
+        let vars = Variables::default();
+        assert_eq!(
+            vars.substitute("SELECT '${not_a_var}';").unwrap(),
+            "SELECT '${not_a_var}';"
+        );
+    }
+
+    #[test]
+    fn test_placeholder_restorer() {
+        let vars = Variables::new([
+            ("app_role", "app"),
+            ("owner", "app"),
+            ("reader", "app_ro"),
+            ("max_rows", "1000"),
+            ("unused", "users"),
+        ]);
+        let schema = "CREATE TABLE users (id INT, app_ro_note TEXT, limit_ INT DEFAULT 1000);\n\
+                      GRANT SELECT ON users TO ${reader};\n\
+                      SELECT '$${literal}';";
+        let restorer = vars.placeholder_restorer([schema]);
+        // `max_rows` is never referenced; `app_role`/`owner` are not referenced
+        // either, so only `reader` is a candidate.
+        assert!(restorer.skipped().is_empty());
+
+        let generated = "GRANT SELECT ON public.users TO app_ro;\n\
+                         COMMENT ON COLUMN users.app_ro_note IS '${literal}';";
+        let restored = restorer.restore(generated);
+        assert_eq!(
+            restored,
+            "GRANT SELECT ON public.users TO ${reader};\n\
+             COMMENT ON COLUMN users.app_ro_note IS '$${literal}';"
+        );
+        assert_eq!(vars.substitute(&restored).unwrap(), generated);
+
+        // Shared values and values written literally elsewhere are ambiguous.
+        let schema = "GRANT SELECT ON t TO ${app_role};\nGRANT ALL ON t TO ${owner};\n\
+                      CREATE TABLE t (n INT CHECK (n < ${max_rows}), m INT DEFAULT 1000);";
+        let restorer = vars.placeholder_restorer([schema]);
+        assert_eq!(restorer.skipped(), ["app_role", "max_rows", "owner"]);
+        assert_eq!(
+            restorer.restore("GRANT SELECT ON t TO app;"),
+            "GRANT SELECT ON t TO app;"
+        );
+
+        // A placeholder pgmt generated itself stays live.
+        let restorer = vars.placeholder_restorer([schema]).keeping("tenant");
+        assert_eq!(
+            restorer.restore(r#"CREATE TABLE "${tenant}".t (s TEXT DEFAULT '${x}');"#),
+            r#"CREATE TABLE "${tenant}".t (s TEXT DEFAULT '$${x}');"#
+        );
+
+        // Nothing to restore when substitution is off.
+        let restorer = Variables::default().placeholder_restorer([schema]);
+        assert_eq!(restorer.restore("SELECT '${x}';"), "SELECT '${x}';");
+    }
+}
@@ -3,12 +3,18 @@ use std::time::Duration;
 // File watch timing constants
 pub const WATCH_DEBOUNCE_DURATION: Duration = Duration::from_millis(500);
 pub const WATCH_POLL_TIMEOUT: Duration = Duration::from_millis(100);
+/// Watch mode redoes only the schema files a save affects while they are at
+/// most this percentage of all schema files; past it, a full rebuild costs
+/// about as much.
+pub const WATCH_INCREMENTAL_MAX_PERCENT: usize = 25;
 
 // Lock file management
 pub const LOCK_FILE_STALE_TIMEOUT: Duration = Duration::from_secs(600);
 
 // Migration file naming conventions
 pub const BASELINE_FILENAME_PREFIX: &str = "baseline_";
+/// Subdirectory of the migrations directory holding paired down migrations.
+pub const DOWN_MIGRATIONS_SUBDIR: &str = "down";
 
 // Configuration file name
 pub const CONFIG_FILENAME: &str = "pgmt.yaml";
@@ -110,6 +110,25 @@ pub async fn branch_url(url: &str) -> Result<String> {
     rewrite_database(url, &source_db, &branch)
 }
 
+/// Create an empty branch (a copy of `template0`) on the server `url` points
+/// at and return its connection string. `migrate apply --rehearse` seeds it
+/// with a target's schema so pending migrations can run next to the target
+/// without touching it. Requires CREATEDB on that server.
+pub async fn empty_branch_url(url: &str) -> Result<String> {
+    let options =
+        PgConnectOptions::from_str(url).map_err(|e| anyhow!("Invalid database URL: {}", e))?;
+    let database = options
+        .get_database()
+        .ok_or_else(|| anyhow!("Database URL has no database name"))?
+        .to_string();
+
+    let admin = admin_pool(options.clone().database(admin_db_name(&database))).await?;
+    let result = create_branch(&admin, "template0").await;
+    admin.close().await;
+
+    rewrite_database(url, &database, &result?)
+}
+
 /// Drop the ephemeral branch a pool is connected to, reclaiming it as soon as
 /// a phase finishes instead of holding every branch until process exit.
 ///
@@ -4,4 +4,5 @@ pub mod connection;
 pub mod error_context;
 pub mod schema_executor;
 pub mod schema_processor;
+pub mod snapshot;
 pub mod sql_executor;
@@ -13,7 +13,7 @@ use crate::catalog::file_dependencies::{
     FileDependencyAugmentation, FileToObjectMapping, create_dependency_augmentation,
 };
 use crate::catalog::identity::{self, CatalogIdentity};
-use crate::config::types::Objects;
+use crate::config::types::{Objects, Variables};
 use crate::db::cleaner;
 use crate::db::schema_executor::SchemaFileExecutor;
 use crate::schema_loader::SchemaFile;
@@ -29,6 +29,8 @@ pub struct SchemaProcessorConfig {
     pub clean_before_apply: bool,
     /// Object filter config (controls which schemas the cleaner drops)
     pub objects: Objects,
+    /// `${var}` placeholder values substituted into each file before it runs
+    pub variables: Variables,
 }
 
 impl Default for SchemaProcessorConfig {
@@ -37,6 +39,7 @@ impl Default for SchemaProcessorConfig {
             verbose: false,
             clean_before_apply: true,
             objects: Objects::default(),
+            variables: Variables::default(),
         }
     }
 }
@@ -93,9 +96,51 @@ impl SchemaProcessor {
         }
 
         // Step 2: Load and order schema files
+        let schema_files = self.load_schema_files(schema_dir)?;
+
+        // Steps 3-4: Apply them, attributing each created object to its file
+        let file_mapping = self.apply_schema_files(&schema_files).await?;
+
+        // Step 5: Load full catalog once at the end for diff operations
+        info!("Loading full catalog for diff operations");
+        // Physical-world load: every consumer (apply, watch, debug) scopes
+        // this catalog with the objects filter before diffing or reporting.
+        let final_catalog = Catalog::load_unfiltered(&self.pool)
+            .await
+            .context("Failed to load final catalog")?;
+
+        // Step 6: Create file-based dependency augmentation
+        info!("Creating file-based dependency augmentation");
+        let augmentation = create_dependency_augmentation(&file_mapping, &schema_files)
+            .context("Failed to create dependency augmentation from file mappings")?;
+
+        // Step 7: Extract file-to-file dependencies before schema_files goes out of scope
+        let file_dependencies: BTreeMap<String, Vec<String>> = schema_files
+            .iter()
+            .filter(|f| !f.dependencies.is_empty())
+            .map(|f| (f.relative_path.clone(), f.dependencies.clone()))
+            .collect();
+
+        // Step 8: Return catalog and augmentation separately
+        info!(
+            "✅ Schema processing complete: {} files processed",
+            schema_files.len()
+        );
+
+        Ok(ProcessedSchema {
+            catalog: final_catalog,
+            augmentation,
+            file_mapping,
+            file_dependencies,
+        })
+    }
+
+    /// Load and order the schema files in `schema_dir`, with `${var}`
+    /// placeholders substituted.
+    pub fn load_schema_files(&self, schema_dir: &Path) -> Result<Vec<SchemaFile>> {
         info!("📁 Loading schema files from: {}", schema_dir.display());
         let loader = SchemaLoader::new(SchemaLoaderConfig::new(schema_dir.to_path_buf()));
-        let schema_files = loader.load_ordered_schema_files().with_context(|| {
+        let mut schema_files = loader.load_ordered_schema_files().with_context(|| {
             format!(
                 "Failed to load and order schema files from directory: {}\n\n\
                     Common causes:\n\
@@ -111,11 +156,27 @@ impl SchemaProcessor {
             "📁 Loaded {} schema files with dependency information",
             schema_files.len()
         );
+        for file in &mut schema_files {
+            file.content = self
+                .config
+                .variables
+                .substitute(&file.content)
+                .with_context(|| {
+                    format!("Failed to substitute variables in {}", file.relative_path)
+                })?;
+        }
+        Ok(schema_files)
+    }
 
-        // Step 3: Create executor for applying files
+    /// Apply `schema_files` in order and map each object they create to the
+    /// file that created it. Objects that already existed belong to no file.
+    pub async fn apply_schema_files(
+        &self,
+        schema_files: &[SchemaFile],
+    ) -> Result<FileToObjectMapping> {
         let executor = SchemaFileExecutor::new(self.pool.clone(), self.config.verbose);
 
-        // Step 4: Apply the files, recording an OID boundary after each.
+        // Apply the files, recording an OID boundary after each.
         //
         // Attribution is derived from those boundaries and ONE snapshot at the
         // end: a file's objects are the ones whose OID falls in the half-open
@@ -177,37 +238,6 @@ impl SchemaProcessor {
             file_mapping.object_files.len()
         );
 
-        // Step 5: Load full catalog once at the end for diff operations
-        info!("Loading full catalog for diff operations");
-        // Physical-world load: every consumer (apply, watch, debug) scopes
-        // this catalog with the objects filter before diffing or reporting.
-        let final_catalog = Catalog::load_unfiltered(&self.pool)
-            .await
-            .context("Failed to load final catalog")?;
-
-        // Step 6: Create file-based dependency augmentation
-        info!("Creating file-based dependency augmentation");
-        let augmentation = create_dependency_augmentation(&file_mapping, &schema_files)
-            .context("Failed to create dependency augmentation from file mappings")?;
-
-        // Step 7: Extract file-to-file dependencies before schema_files goes out of scope
-        let file_dependencies: BTreeMap<String, Vec<String>> = schema_files
-            .iter()
-            .filter(|f| !f.dependencies.is_empty())
-            .map(|f| (f.relative_path.clone(), f.dependencies.clone()))
-            .collect();
-
-        // Step 8: Return catalog and augmentation separately
-        info!(
-            "✅ Schema processing complete: {} files processed",
-            schema_files.len()
-        );
-
-        Ok(ProcessedSchema {
-            catalog: final_catalog,
-            augmentation,
-            file_mapping,
-            file_dependencies,
-        })
+        Ok(file_mapping)
     }
 }
@@ -0,0 +1,394 @@
+//! Dev database snapshots.
+//!
+//! Before a destructive `pgmt apply`, the dev database is copied with
+//! `CREATE DATABASE ... TEMPLATE dev` (the same mechanism as shadow branches,
+//! see `db::branch`), so the data it held can be brought back with
+//! `pgmt apply --undo` or `pgmt dev snapshots restore`. Snapshots are plain
+//! databases on the dev server named `pgmt_snap_<timestamp>_<id>`; the dev
+//! database they belong to, when they were taken and why are kept in the
+//! database comment, so listing needs nothing but the server.
+//!
+//! Copying requires that nothing is connected to the dev database, pgmt
+//! included: callers close their own pool first.
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{Executor, PgPool};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::info;
+
+use super::branch::admin_db_name;
+use crate::render::{escape_string, quote_ident};
+
+const SNAPSHOT_PREFIX: &str = "pgmt_snap_";
+
+/// A snapshot of a dev database
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// The snapshot database's name
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// What the snapshot was taken before
+    pub reason: String,
+    pub size_bytes: i64,
+}
+
+/// What the snapshot database's comment records
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotComment {
+    pgmt_snapshot_of: String,
+    created_at: DateTime<Utc>,
+    reason: String,
+}
+
+/// The dev server's maintenance database, and the dev database's name.
+struct DevServer {
+    admin: PgPool,
+    database: String,
+}
+
+impl DevServer {
+    async fn connect(dev_url: &str) -> Result<Self> {
+        let options = PgConnectOptions::from_str(dev_url)
+            .map_err(|e| anyhow!("Invalid dev database URL: {}", e))?;
+        let database = options
+            .get_database()
+            .ok_or_else(|| anyhow!("Dev database URL has no database name"))?
+            .to_string();
+        let admin = PgPoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(Duration::from_secs(10))
+            .connect_with(options.database(admin_db_name(&database)))
+            .await
+            .context("Failed to open maintenance connection to the dev database server")?;
+        Ok(Self { admin, database })
+    }
+
+    async fn execute(&self, sql: String) -> Result<()> {
+        self.admin.execute(sqlx::AssertSqlSafe(sql)).await?;
+        Ok(())
+    }
+
+    async fn close(self) {
+        self.admin.close().await;
+    }
+}
+
+/// Copy the dev database to a new snapshot. Fails if anything is connected
+/// to the dev database.
+async fn create_on(server: &DevServer, reason: &str) -> Result<Snapshot> {
+    let created_at = Utc::now();
+    let name = format!(
+        "{}{}_{}",
+        SNAPSHOT_PREFIX,
+        created_at.format("%Y%m%d%H%M%S"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    );
+    let started = std::time::Instant::now();
+    server
+        .execute(format!(
+            "CREATE DATABASE {} TEMPLATE {}",
+            quote_ident(&name),
+            quote_ident(&server.database)
+        ))
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "Failed to snapshot dev database {}: {} \
+                 (close other connections to it, or set dev.snapshot_retention: 0 \
+                 to apply without snapshots)",
+                server.database,
+                e
+            )
+        })?;
+
+    let comment = serde_json::to_string(&SnapshotComment {
+        pgmt_snapshot_of: server.database.clone(),
+        created_at,
+        reason: reason.to_string(),
+    })?;
+    server
+        .execute(format!(
+            "COMMENT ON DATABASE {} IS {}",
+            quote_ident(&name),
+            escape_string(&comment)
+        ))
+        .await?;
+    info!(
+        "Snapshotted {} to {} in {:?}",
+        server.database,
+        name,
+        started.elapsed()
+    );
+
+    let size_bytes = sqlx::query_scalar("SELECT pg_database_size($1)")
+        .bind(&name)
+        .fetch_one(&server.admin)
+        .await?;
+    Ok(Snapshot {
+        name,
+        created_at,
+        reason: reason.to_string(),
+        size_bytes,
+    })
+}
+
+/// The dev database's snapshots, newest first.
+pub async fn list_snapshots(dev_url: &str) -> Result<Vec<Snapshot>> {
+    let server = DevServer::connect(dev_url).await?;
+    let result = list_on(&server).await;
+    server.close().await;
+    result
+}
+
+async fn list_on(server: &DevServer) -> Result<Vec<Snapshot>> {
+    let rows: Vec<(String, Option<String>, i64)> = sqlx::query_as(
+        "SELECT datname::text, shobj_description(oid, 'pg_database'), pg_database_size(oid) \
+         FROM pg_database WHERE starts_with(datname, $1)",
+    )
+    .bind(SNAPSHOT_PREFIX)
+    .fetch_all(&server.admin)
+    .await
+    .context("Failed to list dev database snapshots")?;
+
+    let mut snapshots: Vec<Snapshot> = rows
+        .into_iter()
+        .filter_map(|(name, comment, size_bytes)| {
+            let comment: SnapshotComment = serde_json::from_str(comment.as_deref()?).ok()?;
+            (comment.pgmt_snapshot_of == server.database).then_some(Snapshot {
+                name,
+                created_at: comment.created_at,
+                reason: comment.reason,
+                size_bytes,
+            })
+        })
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    Ok(snapshots)
+}
+
+/// Replace the dev database with a copy of `name`, keeping the snapshot; or,
+/// with `consume`, with the snapshot itself. Connections to the dev database
+/// are terminated. The replacement gets the dev database's owner, grants,
+/// `ALTER DATABASE`/`ALTER ROLE ... IN DATABASE` settings and comment.
+pub async fn restore_snapshot(dev_url: &str, name: &str, consume: bool) -> Result<()> {
+    let server = DevServer::connect(dev_url).await?;
+    let result = restore_on(&server, name, consume).await;
+    server.close().await;
+    result
+}
+
+async fn restore_on(server: &DevServer, name: &str, consume: bool) -> Result<()> {
+    if !list_on(server).await?.iter().any(|s| s.name == name) {
+        return Err(anyhow!(
+            "No snapshot '{}' of dev database {} (see pgmt dev snapshots list)",
+            name,
+            server.database
+        ));
+    }
+
+    let properties = DatabaseProperties::load(server).await?;
+    // Copy first, so a failure leaves the dev database as it was.
+    let replacement = if consume {
+        name.to_string()
+    } else {
+        let copy = format!("pgmt_restore_{}", uuid::Uuid::new_v4().simple());
+        server
+            .execute(format!(
+                "CREATE DATABASE {} TEMPLATE {}",
+                quote_ident(&copy),
+                quote_ident(name)
+            ))
+            .await
+            .with_context(|| format!("Failed to copy snapshot {}", name))?;
+        copy
+    };
+
+    server
+        .execute(format!(
+            "DROP DATABASE {} WITH (FORCE)",
+            quote_ident(&server.database)
+        ))
+        .await
+        .with_context(|| format!("Failed to drop dev database {}", server.database))?;
+    server
+        .execute(format!(
+            "ALTER DATABASE {} RENAME TO {}",
+            quote_ident(&replacement),
+            quote_ident(&server.database)
+        ))
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to rename {} to {}; the dev database's data is in {}",
+                replacement, server.database, replacement
+            )
+        })?;
+    properties.apply(server).await.with_context(|| {
+        format!(
+            "Restored {}, but failed to carry over its owner, grants and settings",
+            server.database
+        )
+    })
+}
+
+/// What a database has besides its contents: a copy made from a template
+/// gets none of it.
+struct DatabaseProperties {
+    owner: String,
+    /// Explicit grants as (grantee, privilege, grantable), the grantee `None`
+    /// for PUBLIC; `None` when the database has the default privileges.
+    grants: Option<Vec<(Option<String>, String, bool)>>,
+    /// (role, name, value) per setting, the role `None` for database-wide.
+    settings: Vec<(Option<String>, String, String)>,
+    comment: Option<String>,
+}
+
+impl DatabaseProperties {
+    async fn load(server: &DevServer) -> Result<Self> {
+        let (owner, default_grants, comment): (String, bool, Option<String>) = sqlx::query_as(
+            "SELECT pg_get_userbyid(datdba)::text, datacl IS NULL, \
+             shobj_description(oid, 'pg_database') \
+             FROM pg_database WHERE datname = $1",
+        )
+        .bind(&server.database)
+        .fetch_one(&server.admin)
+        .await
+        .context("Failed to read the dev database's owner")?;
+        let grants = if default_grants {
+            None
+        } else {
+            Some(
+                sqlx::query_as(
+                    "SELECT CASE WHEN a.grantee = 0 THEN NULL \
+                     ELSE pg_get_userbyid(a.grantee)::text END, \
+                     a.privilege_type, a.is_grantable \
+                     FROM pg_database d, aclexplode(d.datacl) a WHERE d.datname = $1",
+                )
+                .bind(&server.database)
+                .fetch_all(&server.admin)
+                .await
+                .context("Failed to read the dev database's grants")?,
+            )
+        };
+        let settings: Vec<(Option<String>, String)> = sqlx::query_as(
+            "SELECT CASE WHEN s.setrole = 0 THEN NULL \
+             ELSE pg_get_userbyid(s.setrole)::text END, unnest(s.setconfig) \
+             FROM pg_db_role_setting s JOIN pg_database d ON d.oid = s.setdatabase \
+             WHERE d.datname = $1",
+        )
+        .bind(&server.database)
+        .fetch_all(&server.admin)
+        .await
+        .context("Failed to read the dev database's settings")?;
+        Ok(Self {
+            owner,
+            grants,
+            settings: settings
+                .into_iter()
+                .filter_map(|(role, setting)| {
+                    let (name, value) = setting.split_once('=')?;
+                    Some((role, name.to_string(), value.to_string()))
+                })
+                .collect(),
+            comment: comment.filter(|c| serde_json::from_str::<SnapshotComment>(c).is_err()),
+        })
+    }
+
+    async fn apply(&self, server: &DevServer) -> Result<()> {
+        let database = quote_ident(&server.database);
+        server
+            .execute(format!(
+                "ALTER DATABASE {} OWNER TO {}",
+                database,
+                quote_ident(&self.owner)
+            ))
+            .await?;
+        if let Some(grants) = &self.grants {
+            server
+                .execute(format!("REVOKE ALL ON DATABASE {} FROM PUBLIC", database))
+                .await?;
+            for (grantee, privilege, grantable) in grants {
+                server
+                    .execute(format!(
+                        "GRANT {} ON DATABASE {} TO {}{}",
+                        privilege,
+                        database,
+                        grantee.as_deref().map_or("PUBLIC".to_string(), quote_ident),
+                        if *grantable { " WITH GRANT OPTION" } else { "" }
+                    ))
+                    .await?;
+            }
+        }
+        // The stored value is in the form `set_config` parses, so it goes
+        // through the session rather than being re-quoted as a literal.
+        for (role, name, value) in &self.settings {
+            let mut tx = server.admin.begin().await?;
+            sqlx::query("SELECT set_config($1, $2, true)")
+                .bind(name)
+                .bind(value)
+                .execute(&mut *tx)
+                .await?;
+            let target = match role {
+                Some(role) => format!("ROLE {} IN DATABASE {}", quote_ident(role), database),
+                None => format!("DATABASE {}", database),
+            };
+            (&mut *tx)
+                .execute(sqlx::AssertSqlSafe(format!(
+                    "ALTER {} SET {} FROM CURRENT",
+                    target,
+                    quote_ident(name)
+                )))
+                .await?;
+            tx.commit().await?;
+        }
+        server
+            .execute(format!(
+                "COMMENT ON DATABASE {} IS {}",
+                database,
+                self.comment
+                    .as_deref()
+                    .map_or("NULL".to_string(), escape_string)
+            ))
+            .await
+    }
+}
+
+/// Drop all but the `keep` newest snapshots, returning the names dropped.
+pub async fn prune_snapshots(dev_url: &str, keep: usize) -> Result<Vec<String>> {
+    let server = DevServer::connect(dev_url).await?;
+    let result = prune_on(&server, keep).await;
+    server.close().await;
+    result
+}
+
+async fn prune_on(server: &DevServer, keep: usize) -> Result<Vec<String>> {
+    let mut dropped = Vec::new();
+    for snapshot in list_on(server).await?.into_iter().skip(keep) {
+        server
+            .execute(format!(
+                "DROP DATABASE IF EXISTS {} WITH (FORCE)",
+                quote_ident(&snapshot.name)
+            ))
+            .await
+            .with_context(|| format!("Failed to drop snapshot {}", snapshot.name))?;
+        dropped.push(snapshot.name);
+    }
+    Ok(dropped)
+}
+
+/// Snapshot the dev database, then drop the oldest snapshots past `retain`.
+pub async fn snapshot_and_prune(dev_url: &str, reason: &str, retain: usize) -> Result<Snapshot> {
+    let server = DevServer::connect(dev_url).await?;
+    let result = async {
+        let snapshot = create_on(&server, reason).await?;
+        prune_on(&server, retain).await?;
+        Ok(snapshot)
+    }
+    .await;
+    server.close().await;
+    result
+}
@@ -0,0 +1,219 @@
+//! Seed data diffing: rows of the tables in the data directory, matched by
+//! primary key.
+//!
+//! A table only has data on the new side when it has a seed file, and only on
+//! the old side when the caller loaded it there (`Catalog::with_data`). A
+//! table the old catalog holds without data is one whose rows are unknown, so
+//! it is left alone; a table the old catalog lacks altogether starts empty.
+//! Removing a seed file stops managing the table's rows, it does not delete
+//! them.
+
+use crate::catalog::Catalog;
+use crate::catalog::data::TableData;
+use crate::diff::operations::{DataOperation, MigrationStep, RowUpdate};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::warn;
+
+/// Diff the seed rows of every table with data in `new`.
+pub fn diff_data(old: &Catalog, new: &Catalog) -> Vec<MigrationStep> {
+    let mut steps = Vec::new();
+    for new_data in &new.data {
+        match old.find_table_data(&new_data.schema, &new_data.table) {
+            Some(old_data) => steps.extend(diff(old_data, new_data)),
+            None if old.find_table(&new_data.schema, &new_data.table).is_none() => {
+                steps.extend(diff(&empty_like(new_data), new_data))
+            }
+            None => {}
+        }
+    }
+    steps
+}
+
+fn empty_like(data: &TableData) -> TableData {
+    TableData {
+        rows: Vec::new(),
+        ..data.clone()
+    }
+}
+
+/// Diff one table's rows: deletes, inserts, then updates.
+pub fn diff(old: &TableData, new: &TableData) -> Vec<MigrationStep> {
+    if old.key_columns != new.key_columns && !old.rows.is_empty() {
+        warn!(
+            "The primary key of {}.{} changed ({} -> {}); its seed rows are not compared",
+            new.schema,
+            new.table,
+            old.key_columns.join(", "),
+            new.key_columns.join(", ")
+        );
+        return vec![];
+    }
+
+    let old_rows: BTreeMap<Vec<Option<String>>, &Vec<Option<String>>> =
+        old.rows.iter().map(|row| (old.key_of(row), row)).collect();
+    let new_keys: BTreeSet<Vec<Option<String>>> =
+        new.rows.iter().map(|row| new.key_of(row)).collect();
+
+    let deleted: Vec<Vec<Option<String>>> = old
+        .rows
+        .iter()
+        .map(|row| old.key_of(row))
+        .filter(|key| !new_keys.contains(key))
+        .collect();
+
+    let mut inserted = Vec::new();
+    let mut updated = Vec::new();
+    for row in &new.rows {
+        let key = new.key_of(row);
+        let Some(old_row) = old_rows.get(&key) else {
+            inserted.push(row.clone());
+            continue;
+        };
+        // A column the old side lacks (just added, say) always gets written.
+        let set: Vec<(String, Option<String>)> = new
+            .columns
+            .iter()
+            .zip(row)
+            .skip(new.key_columns.len())
+            .filter(|(column, value)| {
+                old.columns
+                    .iter()
+                    .position(|c| c == *column)
+                    .is_none_or(|i| &old_row[i] != *value)
+            })
+            .map(|(column, value)| (column.clone(), value.clone()))
+            .collect();
+        if !set.is_empty() {
+            updated.push(RowUpdate { key, set });
+        }
+    }
+
+    let mut steps = Vec::new();
+    if !deleted.is_empty() {
+        steps.push(MigrationStep::Data(DataOperation::Delete {
+            schema: new.schema.clone(),
+            table: new.table.clone(),
+            key_columns: new.key_columns.clone(),
+            keys: deleted,
+        }));
+    }
+    if !inserted.is_empty() {
+        steps.push(MigrationStep::Data(DataOperation::Insert {
+            schema: new.schema.clone(),
+            table: new.table.clone(),
+            columns: new.columns.clone(),
+            rows: inserted,
+        }));
+    }
+    if !updated.is_empty() {
+        steps.push(MigrationStep::Data(DataOperation::Update {
+            schema: new.schema.clone(),
+            table: new.table.clone(),
+            key_columns: new.key_columns.clone(),
+            rows: updated,
+        }));
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    fn plans(columns: &[&str], rows: &[&[&str]]) -> TableData {
+        TableData {
+            schema: "public".to_string(),
+            table: "plans".to_string(),
+            key_columns: vec!["id".to_string()],
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|v| text(v)).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_rows_matched_by_key() {
+        let old = plans(
+            &["id", "name"],
+            &[&["1", "Free"], &["2", "Pro"], &["3", "Legacy"]],
+        );
+        let new = plans(
+            &["id", "name"],
+            &[&["1", "Free"], &["2", "Professional"], &["4", "Team"]],
+        );
+        let steps = diff(&old, &new);
+        assert_eq!(steps.len(), 3);
+        assert!(matches!(
+            &steps[0],
+            MigrationStep::Data(DataOperation::Delete { keys, .. }) if keys == &vec![vec![text("3")]]
+        ));
+        assert!(matches!(
+            &steps[1],
+            MigrationStep::Data(DataOperation::Insert { rows, .. })
+                if rows == &vec![vec![text("4"), text("Team")]]
+        ));
+        match &steps[2] {
+            MigrationStep::Data(DataOperation::Update { rows, .. }) => {
+                assert_eq!(
+                    rows,
+                    &vec![RowUpdate {
+                        key: vec![text("2")],
+                        set: vec![("name".to_string(), text("Professional"))],
+                    }]
+                );
+            }
+            other => panic!("expected an update, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unchanged_rows_produce_nothing() {
+        let data = plans(&["id", "name"], &[&["1", "Free"]]);
+        assert!(diff(&data, &data).is_empty());
+    }
+
+    #[test]
+    fn test_column_missing_on_old_side_is_written() {
+        let old = plans(&["id", "name"], &[&["1", "Free"]]);
+        let new = plans(&["id", "name", "price"], &[&["1", "Free", "0"]]);
+        match &diff(&old, &new)[..] {
+            [MigrationStep::Data(DataOperation::Update { rows, .. })] => {
+                assert_eq!(rows[0].set, vec![("price".to_string(), text("0"))]);
+            }
+            other => panic!("expected one update, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_table_with_unknown_rows_is_left_alone() {
+        let data = plans(&["id", "name"], &[&["1", "Free"]]);
+        let mut new = Catalog::empty();
+        new.data.push(data);
+
+        // The old side has no table at all: every row is new.
+        let steps = diff_data(&Catalog::empty(), &new);
+        assert_eq!(steps.len(), 1);
+        assert!(matches!(
+            &steps[0],
+            MigrationStep::Data(DataOperation::Insert { .. })
+        ));
+
+        // The old side has the table but its rows weren't loaded.
+        let mut old = Catalog::empty();
+        old.tables.push(crate::catalog::table::Table::new(
+            "public".to_string(),
+            "plans".to_string(),
+            vec![],
+            None,
+            None,
+            vec![],
+        ));
+        assert!(diff_data(&old, &new).is_empty());
+    }
+}
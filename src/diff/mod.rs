@@ -6,6 +6,7 @@ pub mod columns;
 pub mod comments;
 pub mod constraints;
 pub mod custom_types;
+pub mod data;
 pub mod domains;
 pub mod extensions;
 pub mod functions;
@@ -180,6 +181,8 @@ pub fn diff_all(old: &Catalog, new: &Catalog) -> Vec<MigrationStep> {
     // diff_grants).
     out.extend(comments::diff_comments(old, new));
 
+    out.extend(data::diff_data(old, new));
+
     info!("Diff complete");
     out
 }
@@ -120,7 +120,9 @@ pub fn namespace_slots(id: &DbObjectId) -> Vec<NamespaceSlot> {
         // (schema, name, encoding); they do not collide with any other object
         // kind, so same-name conflicts are covered by the exact-identity rule.
         | DbObjectId::Collation { .. }
-        | DbObjectId::Column { .. } => vec![],
+        | DbObjectId::Column { .. }
+        // Seed rows are not a named object at all.
+        | DbObjectId::TableData { .. } => vec![],
     }
 }
 
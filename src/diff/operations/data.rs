@@ -0,0 +1,64 @@
+use super::OperationKind;
+
+/// A row whose managed columns differ: its key, and the new values of the
+/// columns that changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowUpdate {
+    pub key: Vec<Option<String>>,
+    pub set: Vec<(String, Option<String>)>,
+}
+
+/// Seed data changes to one table. Values are text, written as untyped
+/// literals.
+///
+/// Deletes sort as drops and inserts as creates, so for one table the usual
+/// drop → create → alter order runs them delete, insert, update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataOperation {
+    Insert {
+        schema: String,
+        table: String,
+        columns: Vec<String>,
+        rows: Vec<Vec<Option<String>>>,
+    },
+    Update {
+        schema: String,
+        table: String,
+        key_columns: Vec<String>,
+        rows: Vec<RowUpdate>,
+    },
+    Delete {
+        schema: String,
+        table: String,
+        key_columns: Vec<String>,
+        keys: Vec<Vec<Option<String>>>,
+    },
+}
+
+impl DataOperation {
+    pub fn operation_kind(&self) -> OperationKind {
+        match self {
+            Self::Insert { .. } => OperationKind::Create,
+            Self::Delete { .. } => OperationKind::Drop,
+            Self::Update { .. } => OperationKind::Alter,
+        }
+    }
+
+    /// The table the rows are in
+    pub fn table(&self) -> (&str, &str) {
+        match self {
+            Self::Insert { schema, table, .. }
+            | Self::Update { schema, table, .. }
+            | Self::Delete { schema, table, .. } => (schema, table),
+        }
+    }
+
+    /// How many rows the operation touches
+    pub fn row_count(&self) -> usize {
+        match self {
+            Self::Insert { rows, .. } => rows.len(),
+            Self::Update { rows, .. } => rows.len(),
+            Self::Delete { keys, .. } => keys.len(),
+        }
+    }
+}
@@ -27,6 +27,7 @@ pub use cast::*;
 pub use collation::*;
 pub use comments::*;
 pub use constraint::*;
+pub use data::*;
 pub use domain::*;
 pub use extension::*;
 pub use function::*;
@@ -46,6 +47,7 @@ pub mod cast;
 pub mod collation;
 pub mod comments;
 pub mod constraint;
+pub mod data;
 pub mod domain;
 pub mod extension;
 pub mod function;
@@ -84,6 +86,8 @@ pub enum MigrationStep {
     /// type) because a comment renders and routes purely from its `AttrTarget` —
     /// the object kind is carried in the target, exactly like a grant.
     Comment(CommentOperation),
+    /// Seed rows of a table in the data directory
+    Data(DataOperation),
 }
 
 impl MigrationStep {
@@ -114,6 +118,7 @@ impl MigrationStep {
             Self::Extension(op) => op.operation_kind(),
             Self::Grant(op) => op.operation_kind(),
             Self::Comment(op) => op.operation_kind(),
+            Self::Data(op) => op.operation_kind(),
         }
     }
 
@@ -145,6 +150,15 @@ impl MigrationStep {
 
     /// Returns a human-readable one-line summary like "Create view public.user_rankings"
     pub fn summary(&self) -> String {
+        if let MigrationStep::Data(op) = self {
+            let (schema, table) = op.table();
+            let verb = match op {
+                DataOperation::Insert { .. } => "Insert",
+                DataOperation::Update { .. } => "Update",
+                DataOperation::Delete { .. } => "Delete",
+            };
+            return format!("{} {} row(s) in {}.{}", verb, op.row_count(), schema, table);
+        }
         let verb = match self.operation_kind() {
             OperationKind::Create => "Create",
             OperationKind::Drop => "Drop",
@@ -153,6 +167,11 @@ impl MigrationStep {
         format!("{} {}", verb, self.id())
     }
 
+    /// Returns true if this step changes seed data rather than the schema
+    pub fn is_data(&self) -> bool {
+        matches!(self, MigrationStep::Data(_))
+    }
+
     /// Returns true if this step is a grant operation
     pub fn is_grant(&self) -> bool {
         matches!(self, MigrationStep::Grant(_))
@@ -167,6 +186,14 @@ impl MigrationStep {
             MigrationStep::Grant(GrantOperation::Revoke { grant }) => grant.depends_on.clone(),
             MigrationStep::Grant(GrantOperation::GrantColumns(cg)) => cg.depends_on.clone(),
             MigrationStep::Grant(GrantOperation::RevokeColumns(cg)) => cg.depends_on.clone(),
+            // Rows go in once their table is in shape
+            MigrationStep::Data(op) => {
+                let (schema, table) = op.table();
+                vec![DbObjectId::Table {
+                    schema: schema.to_string(),
+                    name: table.to_string(),
+                }]
+            }
             // Other operations use catalog.forward_deps exclusively
             _ => vec![],
         }
@@ -18,6 +18,7 @@
 //! step shares its object's id but does not PROVIDE the object).
 
 use crate::catalog::Catalog;
+use crate::catalog::data::{TableName, order_by_references, references_between};
 use crate::catalog::id::DbObjectId;
 use crate::catalog::utils::is_system_schema;
 use crate::diff::operations::{MigrationStep, OperationKind};
@@ -338,9 +339,93 @@ fn collect_edges(
         }
     }
 
+    edges.extend(seed_data_edges(steps, old_catalog, new_catalog));
+
     (edges, missing_deps)
 }
 
+/// Order seed data steps along the foreign keys between their tables: a
+/// referenced table's inserts come before the referencing table's inserts and
+/// updates, and the referencing table's deletes before the referenced
+/// table's. (Each data step already follows its own table's DDL through its
+/// step-level dependency, and one table's steps run delete → insert → update
+/// by the drop/create/alter rules.)
+///
+/// The tables are ranked along their foreign keys first and an edge is only
+/// added from a lower rank to a higher one, so a reference cycle between
+/// seeded tables cannot make the graph cyclic; its rows are left to
+/// deferrable constraints.
+fn seed_data_edges(
+    steps: &[MigrationStep],
+    old_catalog: &Catalog,
+    new_catalog: &Catalog,
+) -> Vec<(usize, usize)> {
+    let data_steps: Vec<(usize, TableName, OperationKind)> = steps
+        .iter()
+        .enumerate()
+        .filter_map(|(i, step)| match step {
+            MigrationStep::Data(op) => {
+                let (schema, table) = op.table();
+                Some((
+                    i,
+                    (schema.to_string(), table.to_string()),
+                    op.operation_kind(),
+                ))
+            }
+            _ => None,
+        })
+        .collect();
+    let tables: BTreeSet<TableName> = data_steps
+        .iter()
+        .map(|(_, table, _)| table.clone())
+        .collect();
+    if tables.len() < 2 {
+        return vec![];
+    }
+
+    // Deletes run against the old foreign keys, so both sides count.
+    let references = references_between(
+        &tables,
+        old_catalog
+            .constraints
+            .iter()
+            .chain(&new_catalog.constraints),
+    );
+    if references.is_empty() {
+        return vec![];
+    }
+    let rank: BTreeMap<TableName, usize> = order_by_references(&tables, &references)
+        .into_iter()
+        .enumerate()
+        .map(|(position, table)| (table, position))
+        .collect();
+
+    let mut edges = Vec::new();
+    for (referenced, referencing) in &references {
+        if rank[referenced] >= rank[referencing] {
+            continue;
+        }
+        for (parent_i, parent_table, parent_kind) in &data_steps {
+            if parent_table != referenced {
+                continue;
+            }
+            for (child_i, child_table, child_kind) in &data_steps {
+                if child_table != referencing {
+                    continue;
+                }
+                match (parent_kind, child_kind) {
+                    (OperationKind::Create, OperationKind::Create | OperationKind::Alter) => {
+                        edges.push((*parent_i, *child_i))
+                    }
+                    (OperationKind::Drop, OperationKind::Drop) => edges.push((*child_i, *parent_i)),
+                    _ => {}
+                }
+            }
+        }
+    }
+    edges
+}
+
 // ---------------------------------------------------------------------------
 // Annotated step graph (PlannedStep) + module-affinity traversal
 // ---------------------------------------------------------------------------
@@ -946,4 +1031,65 @@ mod tests {
             "old overload must be dropped before the new overload is created"
         );
     }
+
+    /// Seed data follows foreign keys between seeded tables: the referenced
+    /// table's insert precedes the referencing table's insert, and the
+    /// referencing table's delete precedes the referenced table's. Fed child
+    /// first: insert orders (0), insert plans (1), delete orders (2), delete
+    /// plans (3).
+    #[test]
+    fn test_collect_edges_seed_data_follows_foreign_keys() {
+        use crate::catalog::constraint::{Constraint, ConstraintType};
+        use crate::diff::operations::DataOperation;
+        let insert = |table: &str| {
+            MigrationStep::Data(DataOperation::Insert {
+                schema: "public".to_string(),
+                table: table.to_string(),
+                columns: vec!["id".to_string()],
+                rows: vec![vec![Some("1".to_string())]],
+            })
+        };
+        let delete = |table: &str| {
+            MigrationStep::Data(DataOperation::Delete {
+                schema: "public".to_string(),
+                table: table.to_string(),
+                key_columns: vec!["id".to_string()],
+                keys: vec![vec![Some("2".to_string())]],
+            })
+        };
+        let steps = vec![
+            insert("orders"),
+            insert("plans"),
+            delete("orders"),
+            delete("plans"),
+        ];
+        let mut catalog = Catalog::empty();
+        catalog.constraints.push(Constraint {
+            schema: "public".to_string(),
+            table_name: "orders".to_string(),
+            name: "orders_plan_fk".to_string(),
+            constraint_type: ConstraintType::ForeignKey {
+                columns: vec!["plan_id".to_string()],
+                referenced_schema: "public".to_string(),
+                referenced_table: "plans".to_string(),
+                referenced_columns: vec!["id".to_string()],
+                on_delete: None,
+                on_update: None,
+                deferrable: false,
+                initially_deferred: false,
+            },
+            comment: None,
+            depends_on: vec![],
+        });
+        let (edges, _) = collect_edges(&steps, &Catalog::empty(), &catalog);
+        assert!(
+            edges.contains(&(1, 0)),
+            "referenced rows must be inserted first; edges={edges:?}"
+        );
+        assert!(
+            edges.contains(&(2, 3)),
+            "referencing rows must be deleted first; edges={edges:?}"
+        );
+        assert!(!edges.contains(&(0, 1)) && !edges.contains(&(3, 2)));
+    }
 }
@@ -16,5 +16,7 @@ pub mod render;
 pub mod schema_generator;
 pub mod schema_loader;
 pub mod schema_ops;
+pub mod seed_data;
+pub mod tenants;
 pub mod validation;
 pub mod validation_output;
@@ -16,6 +16,8 @@ mod render;
 mod schema_generator;
 mod schema_loader;
 mod schema_ops;
+mod seed_data;
+mod tenants;
 mod validation;
 mod validation_output;
 
@@ -24,6 +26,7 @@ use crate::commands::diff_output::DiffFormat;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use dotenvy::dotenv;
+use std::process::ExitCode;
 use tracing::info;
 use tracing_subscriber::{EnvFilter, fmt};
 
@@ -45,6 +48,9 @@ struct Cli {
     #[arg(long, global = true)]
     debug: bool,
 
+    #[command(flatten)]
+    variables: config::VariableArgs,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -71,6 +77,11 @@ struct ApplyArgs {
     #[arg(long)]
     watch: bool,
 
+    /// Restore the dev database from the snapshot taken before the last
+    /// destructive apply, keeping its owner, grants, settings and comment
+    #[arg(long, conflicts_with_all = ["mode", "watch"])]
+    undo: bool,
+
     #[command(flatten)]
     dev: config::DevUrlArgs,
 
@@ -94,6 +105,49 @@ pub struct DiffArgs {
 
     #[command(flatten)]
     pub shadow: config::ShadowUrlArgs,
+
+    #[command(flatten)]
+    pub snapshots: commands::catalog::SnapshotArgs,
+
+    /// Compare with the schema files at this git revision instead of the
+    /// dev database
+    #[arg(long, value_name = "REV", conflicts_with_all = ["dev_url", "from_snapshot"])]
+    pub from_ref: Option<String>,
+
+    /// With --from-ref, the git revision to compare with instead of the
+    /// working tree's schema files (or --to-snapshot)
+    #[arg(
+        long,
+        value_name = "REV",
+        requires = "from_ref",
+        conflicts_with = "to_snapshot"
+    )]
+    pub to_ref: Option<String>,
+}
+
+/// Arguments for pgmt compare (one database vs another)
+#[derive(Parser, Debug)]
+pub struct CompareArgs {
+    /// Database to compare from, e.g. production
+    #[arg(long, value_name = "URL")]
+    pub from_url: String,
+
+    /// Database to compare with it, e.g. staging
+    #[arg(long, value_name = "URL")]
+    pub to_url: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "detailed")]
+    pub format: DiffFormat,
+
+    /// Save SQL output to file
+    #[arg(long)]
+    pub output_sql: Option<String>,
+
+    /// Write the SQL that brings the --to-url database in line with the
+    /// --from-url database to this file
+    #[arg(long, value_name = "FILE")]
+    pub sync_sql: Option<std::path::PathBuf>,
 }
 
 /// Arguments for pgmt validate (dev vs schema files)
@@ -104,6 +158,23 @@ pub struct ValidateArgs {
 
     #[command(flatten)]
     pub shadow: config::ShadowUrlArgs,
+
+    #[command(flatten)]
+    pub snapshots: commands::catalog::SnapshotArgs,
+}
+
+/// Arguments for pgmt test
+#[derive(Parser, Debug)]
+pub struct TestArgs {
+    /// Test files to run. Default: every .sql file in the tests directory
+    pub files: Vec<std::path::PathBuf>,
+
+    /// Output format: human (default), json, junit
+    #[arg(long, value_enum, default_value = "human")]
+    pub format: commands::TestFormat,
+
+    #[command(flatten)]
+    pub shadow: config::ShadowUrlArgs,
 }
 
 /// Arguments for pgmt migrate diff (schema vs target)
@@ -122,6 +193,9 @@ pub struct MigrateDiffArgs {
 
     #[command(flatten)]
     pub shadow: config::ShadowUrlArgs,
+
+    #[command(flatten)]
+    pub snapshots: commands::catalog::SnapshotArgs,
 }
 
 #[derive(Subcommand)]
@@ -141,15 +215,33 @@ enum Commands {
     /// Compare schema files with dev database (preview what apply would do)
     Diff(DiffArgs),
 
+    /// Compare two databases, independent of the schema files
+    Compare(CompareArgs),
+
     /// Validate schema consistency
     Validate(ValidateArgs),
 
+    /// Run the SQL tests against the schema files on a fresh shadow database
+    Test(TestArgs),
+
     /// Manage configuration
     Config {
         #[command(subcommand)]
         command: Option<commands::config::ConfigCommands>,
     },
 
+    /// Catalog snapshots for diffing without a database
+    Catalog {
+        #[command(subcommand)]
+        command: commands::catalog::CatalogCommands,
+    },
+
+    /// Development database snapshots
+    Dev {
+        #[command(subcommand)]
+        command: commands::dev::DevCommands,
+    },
+
     /// Debug commands for troubleshooting
     Debug {
         #[command(subcommand)]
@@ -171,6 +263,10 @@ enum MigrateCommands {
         #[arg(long)]
         create_baseline: bool,
 
+        /// Also write a paired down migration (migrations/down/) for `migrate rollback`
+        #[arg(long)]
+        down: bool,
+
         #[command(flatten)]
         shadow: config::ShadowUrlArgs,
     },
@@ -192,6 +288,20 @@ enum MigrateCommands {
         shadow: config::ShadowUrlArgs,
     },
 
+    /// Renumber and regenerate this branch's migrations after the main line's
+    Rebase {
+        /// Git ref of the main line this branch has merged
+        #[arg(long, default_value = "main")]
+        onto: String,
+
+        /// Show the renumbering without changing any files
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(flatten)]
+        shadow: config::ShadowUrlArgs,
+    },
+
     /// Apply explicit migrations
     Apply {
         #[command(flatten)]
@@ -201,6 +311,67 @@ enum MigrateCommands {
         /// unmoduled base. Falls back to PGMT_MODULES.
         #[arg(long, value_delimiter = ',')]
         modules: Vec<String>,
+
+        /// Stop after this migration (e.g., V1734567890): nothing above it
+        /// is applied
+        #[arg(long, conflicts_with = "count")]
+        to: Option<String>,
+
+        /// Stop after applying this many pending migrations
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        count: Option<u64>,
+
+        /// Apply pending migrations older than the newest applied one
+        /// instead of refusing (`migration.allow_out_of_order`)
+        #[arg(long)]
+        allow_out_of_order: bool,
+
+        /// Apply pending migrations to a schema-only copy of the target
+        /// instead, then diff the result against the schema files. The
+        /// target is only read.
+        #[arg(long)]
+        rehearse: bool,
+
+        // Used only by --rehearse: holds the copy when the target role can't
+        // create databases, and builds the schema files' state for the diff.
+        #[command(flatten)]
+        shadow: config::ShadowUrlArgs,
+
+        /// Identifier recorded with each section in the audit history
+        /// (`migration.audit`). Falls back to PGMT_DEPLOY_ID.
+        #[arg(long)]
+        deploy_id: Option<String>,
+
+        /// Apply to targets from the `targets:` inventory in pgmt.yaml instead
+        /// of one target URL: `all`, `group:<tag>` or target names,
+        /// comma-separated
+        #[arg(long, value_name = "SELECTOR", conflicts_with_all = ["target_url", "rehearse"])]
+        targets: Option<String>,
+
+        /// How many targets --targets applies at once (canaries always run
+        /// alone, first)
+        #[arg(long, default_value_t = 4, requires = "targets",
+              value_parser = clap::value_parser!(u64).range(1..))]
+        concurrency: u64,
+
+        /// With --targets: stop starting targets after a failure, or continue
+        #[arg(long, value_enum, default_value = "stop", requires = "targets")]
+        on_failure: commands::OnFailure,
+
+        /// With --targets: human (default) or a json report
+        #[arg(long, value_enum, default_value = "human", requires = "targets")]
+        format: commands::FleetFormat,
+    },
+
+    /// Roll back applied migrations using their down migrations
+    Rollback {
+        #[command(flatten)]
+        target: config::TargetUrlArgs,
+
+        /// Roll back every migration applied after this version (e.g.,
+        /// V1734567890), which stays applied. Default: only the latest migration.
+        #[arg(long)]
+        to: Option<String>,
     },
 
     /// Provision a fresh database from a baseline + migrations
@@ -228,6 +399,54 @@ enum MigrateCommands {
 
         #[command(flatten)]
         dev: config::DevUrlArgs,
+
+        /// Output format: human (default), json
+        #[arg(long, value_enum, default_value = "human")]
+        format: commands::StatusFormat,
+
+        /// Exit 10 when migrations are pending, 11 when one failed or is still
+        /// running, 12 on a checksum mismatch (0 when up to date)
+        #[arg(long)]
+        exit_code: bool,
+
+        /// Summarize every tenant schema (schema-per-tenant mode): each one's
+        /// state, the laggards, and up-to-date tenants that drifted from the
+        /// schema files
+        #[arg(long)]
+        tenants: bool,
+
+        // Used only by --tenants: builds the schema files' state to check
+        // tenants for drift.
+        #[command(flatten)]
+        shadow: config::ShadowUrlArgs,
+    },
+
+    /// Show the audit history of section attempts and resolutions
+    History {
+        /// Only this migration version, e.g. V1734500000
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Include the SQL each attempt ran
+        #[arg(long)]
+        sql: bool,
+
+        #[command(flatten)]
+        target: config::TargetUrlArgs,
+    },
+
+    /// Wait until the target database reaches a schema version (for app startup)
+    Wait {
+        /// Migration version to wait for, e.g. V1734500000
+        #[arg(long)]
+        until: String,
+
+        /// Give up after this long, e.g. 30s, 5m
+        #[arg(long, default_value = "5m", value_parser = migration::section_parser::parse_duration)]
+        timeout: std::time::Duration,
+
+        #[command(flatten)]
+        target: config::TargetUrlArgs,
     },
 
     /// Validate migration consistency (for CI)
@@ -235,6 +454,11 @@ enum MigrateCommands {
         #[command(flatten)]
         shadow: config::ShadowUrlArgs,
 
+        /// With out-of-order apply allowed, the target whose pending
+        /// migrations are checked for running late (only read)
+        #[command(flatten)]
+        target: config::TargetUrlArgs,
+
         /// Output format: human (default), json
         #[arg(long, default_value = "human")]
         format: String,
@@ -250,6 +474,34 @@ enum MigrateCommands {
         /// Ignore specific migrations during validation
         #[arg(long, value_delimiter = ',')]
         ignore_migrations: Vec<String>,
+
+        /// Also check that the target's migrations numbered below its newest
+        /// applied one still match the schema files when run last
+        /// (`migration.allow_out_of_order`)
+        #[arg(long)]
+        allow_out_of_order: bool,
+    },
+
+    /// Check migration files for risky patterns (no database needed)
+    Lint {
+        /// Migration files to lint. Default: every migration in the migrations directory
+        files: Vec<std::path::PathBuf>,
+
+        /// Output format: human (default), json, sarif
+        #[arg(long, value_enum, default_value = "human")]
+        format: commands::LintFormat,
+    },
+
+    /// Write the migration history for Flyway, golang-migrate, Sqitch or psql
+    /// (no database needed)
+    Export {
+        /// Layout to write
+        #[arg(long, value_enum)]
+        format: commands::ExportFormat,
+
+        /// Directory to write into; must be empty or not exist
+        #[arg(long)]
+        out: std::path::PathBuf,
     },
 
     /// Create a baseline and optionally consolidate old migrations
@@ -342,7 +594,7 @@ pub enum DebugOutputFormat {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     dotenv().ok();
     let cli = Cli::parse();
     initialize_logging(&cli);
@@ -350,7 +602,7 @@ async fn main() -> Result<()> {
         result = run_main(cli) => result,
         _ = wait_for_shutdown_signal() => {
             info!("Received shutdown signal, cleaning up...");
-            Ok(())
+            Ok(ExitCode::SUCCESS)
         }
     };
 
@@ -411,19 +663,46 @@ fn initialize_logging(cli: &Cli) {
     fmt().with_env_filter(filter).with_target(false).init();
 }
 
-async fn run_main(cli: Cli) -> Result<()> {
-    match &cli.command {
-        Commands::Init(args) => commands::cmd_init_with_args(args).await,
+/// Runs the command. Commands report failure through `Err`; the few whose
+/// exit code carries a result (`migrate status --exit-code`) return it, so
+/// cleanup in `main` still runs.
+async fn run_main(cli: Cli) -> Result<ExitCode> {
+    let result = match &cli.command {
+        Commands::Init(args) if args.adopt_from.is_none() => {
+            commands::cmd_init_with_args(args).await
+        }
         _ => {
             let (file_config, root_dir) = config::load_config(&cli.config_file)?;
+            let variables = cli.variables.resolve(&file_config)?;
 
             match &cli.command {
-                Commands::Init(_) => unreachable!(),
+                Commands::Init(args) => {
+                    let config = config::ConfigBuilder::new()
+                        .with_file(file_config.clone())
+                        .with_variables(variables.clone())
+                        .resolve()?;
+                    let target = args.target.resolve(&file_config)?;
+                    let shadow = config::ShadowUrlArgs {
+                        shadow_url: args.shadow_url.clone(),
+                    }
+                    .resolve(&file_config)?;
+                    let tool = args.adopt_from.expect("plain init is handled above");
+
+                    info!("Adopting from another migration tool");
+                    commands::init::cmd_init_adopt(&config, &root_dir, &target, tool, &shadow).await
+                }
                 Commands::Apply(args) => {
                     let config = config::ConfigBuilder::new()
                         .with_file(file_config.clone())
+                        .with_variables(variables.clone())
                         .resolve()?;
                     let dev = args.dev.resolve(&file_config)?;
+                    if args.undo {
+                        info!("Restoring the dev database from its latest snapshot");
+                        return commands::cmd_apply_undo(&root_dir, &dev)
+                            .await
+                            .map(|()| ExitCode::SUCCESS);
+                    }
                     let shadow = args.shadow.resolve(&file_config)?;
 
                     use commands::apply::ApplyOutcome;
@@ -466,9 +745,14 @@ async fn run_main(cli: Cli) -> Result<()> {
                     MigrateCommands::Diff(args) => {
                         let config = config::ConfigBuilder::new()
                             .with_file(file_config.clone())
+                            .with_variables(variables.clone())
                             .resolve()?;
-                        let target = args.target.resolve(&file_config)?;
-                        let shadow = args.shadow.resolve(&file_config)?;
+                        let target = args
+                            .snapshots
+                            .database_side(|| args.target.resolve(&file_config))?;
+                        let shadow = args
+                            .snapshots
+                            .schema_side(|| args.shadow.resolve(&file_config))?;
 
                         let diff_args = commands::MigrateDiffArgs {
                             format: args.format.clone(),
@@ -482,11 +766,15 @@ async fn run_main(cli: Cli) -> Result<()> {
                     MigrateCommands::New {
                         description,
                         create_baseline,
+                        down,
                         shadow,
                     } => {
-                        let config = config::ConfigBuilder::new()
-                            .with_file(file_config.clone())
-                            .resolve()?;
+                        let config = tenants::for_generation(
+                            config::ConfigBuilder::new()
+                                .with_file(file_config.clone())
+                                .with_variables(variables.clone())
+                                .resolve()?,
+                        );
                         let shadow = shadow.resolve(&file_config)?;
 
                         info!("Generating migration from diff");
@@ -495,6 +783,7 @@ async fn run_main(cli: Cli) -> Result<()> {
                             &root_dir,
                             description.as_deref(),
                             *create_baseline,
+                            *down,
                             &shadow,
                         )
                         .await
@@ -505,9 +794,12 @@ async fn run_main(cli: Cli) -> Result<()> {
                         dry_run,
                         shadow,
                     } => {
-                        let config = config::ConfigBuilder::new()
-                            .with_file(file_config.clone())
-                            .resolve()?;
+                        let config = tenants::for_generation(
+                            config::ConfigBuilder::new()
+                                .with_file(file_config.clone())
+                                .with_variables(variables.clone())
+                                .resolve()?,
+                        );
                         let shadow = shadow.resolve(&file_config)?;
 
                         if let Some(version) = migration_version {
@@ -524,15 +816,152 @@ async fn run_main(cli: Cli) -> Result<()> {
                             .await
                         }
                     }
-                    MigrateCommands::Apply { target, modules } => {
+                    MigrateCommands::Rebase {
+                        onto,
+                        dry_run,
+                        shadow,
+                    } => {
+                        let config = tenants::for_generation(
+                            config::ConfigBuilder::new()
+                                .with_file(file_config.clone())
+                                .with_variables(variables.clone())
+                                .resolve()?,
+                        );
+                        let shadow = shadow.resolve(&file_config)?;
+
+                        info!("Rebasing migrations onto {}", onto);
+                        commands::cmd_migrate_rebase(&config, &root_dir, onto, *dry_run, &shadow)
+                            .await
+                    }
+                    MigrateCommands::Apply {
+                        modules,
+                        to,
+                        count,
+                        allow_out_of_order,
+                        deploy_id,
+                        targets: Some(selector),
+                        concurrency,
+                        on_failure,
+                        format,
+                        ..
+                    } => {
                         let config = config::ConfigBuilder::new()
                             .with_file(file_config.clone())
+                            .with_variables(variables.clone())
+                            .resolve()?;
+                        let targets = config::resolve_fleet_targets(&file_config, selector)?;
+                        // Checked here so a bad flag fails once, not per target.
+                        modules::ModuleSelection::resolve(modules, &config)?;
+                        commands::ApplyLimit::resolve(
+                            &root_dir.join(&config.directories.migrations),
+                            to.as_deref(),
+                            count.map(|n| n as usize),
+                        )?;
+                        let mut apply_args =
+                            vec!["--config-file".to_string(), cli.config_file.clone()];
+                        for var in &cli.variables.vars {
+                            apply_args.extend(["--var".to_string(), var.clone()]);
+                        }
+                        for (set, flag) in [
+                            (cli.verbose, "--verbose"),
+                            (cli.quiet, "--quiet"),
+                            (cli.debug, "--debug"),
+                        ] {
+                            if set {
+                                apply_args.push(flag.to_string());
+                            }
+                        }
+                        apply_args.extend(["migrate".to_string(), "apply".to_string()]);
+                        if !modules.is_empty() {
+                            apply_args.extend(["--modules".to_string(), modules.join(",")]);
+                        }
+                        if let Some(to) = to {
+                            apply_args.extend(["--to".to_string(), to.clone()]);
+                        }
+                        if let Some(count) = count {
+                            apply_args.extend(["--count".to_string(), count.to_string()]);
+                        }
+                        if *allow_out_of_order {
+                            apply_args.push("--allow-out-of-order".to_string());
+                        }
+                        if let Some(deploy_id) = deploy_id {
+                            apply_args.extend(["--deploy-id".to_string(), deploy_id.clone()]);
+                        }
+
+                        info!("Applying migrations to {} targets", targets.len());
+                        commands::cmd_migrate_apply_fleet(
+                            &targets,
+                            &apply_args,
+                            cli.variables.env.as_deref(),
+                            commands::FleetOptions {
+                                concurrency: *concurrency as usize,
+                                on_failure: *on_failure,
+                                format: *format,
+                            },
+                        )
+                        .await
+                    }
+                    MigrateCommands::Apply {
+                        target,
+                        modules,
+                        to,
+                        count,
+                        allow_out_of_order,
+                        rehearse,
+                        shadow,
+                        deploy_id,
+                        ..
+                    } => {
+                        let mut config = config::ConfigBuilder::new()
+                            .with_file(file_config.clone())
+                            .with_variables(variables.clone())
                             .resolve()?;
+                        config.migration.allow_out_of_order |= *allow_out_of_order;
                         let target = target.resolve(&file_config)?;
                         let selection = modules::ModuleSelection::resolve(modules, &config)?;
+                        let limit = commands::ApplyLimit::resolve(
+                            &root_dir.join(&config.directories.migrations),
+                            to.as_deref(),
+                            count.map(|n| n as usize),
+                        )?;
+
+                        if *rehearse {
+                            commands::reject_tenant_mode(&config, "migrate apply --rehearse")?;
+                            let shadow = shadow.resolve(&file_config)?;
+                            info!("Rehearsing explicit migrations");
+                            commands::cmd_migrate_rehearse(
+                                &config, &root_dir, &target, &shadow, selection, limit,
+                            )
+                            .await
+                        } else {
+                            info!("Applying explicit migrations");
+                            let deploy_id = deploy_id
+                                .clone()
+                                .or_else(|| std::env::var("PGMT_DEPLOY_ID").ok());
+                            if config.tenants.is_some() {
+                                commands::cmd_migrate_apply_tenants(
+                                    &config, &root_dir, &target, selection, limit, deploy_id,
+                                )
+                                .await
+                            } else {
+                                commands::cmd_migrate_apply(
+                                    &config, &root_dir, &target, selection, limit, deploy_id,
+                                )
+                                .await
+                            }
+                        }
+                    }
+                    MigrateCommands::Rollback { target, to } => {
+                        let config = config::ConfigBuilder::new()
+                            .with_file(file_config.clone())
+                            .with_variables(variables.clone())
+                            .resolve()?;
+                        commands::reject_tenant_mode(&config, "migrate rollback")?;
+                        let target = target.resolve(&file_config)?;
 
-                        info!("Applying explicit migrations");
-                        commands::cmd_migrate_apply(&config, &root_dir, &target, selection).await
+                        info!("Rolling back migrations");
+                        commands::cmd_migrate_rollback(&config, &root_dir, &target, to.as_deref())
+                            .await
                     }
                     MigrateCommands::Provision {
                         target,
@@ -541,7 +970,14 @@ async fn run_main(cli: Cli) -> Result<()> {
                     } => {
                         let config = config::ConfigBuilder::new()
                             .with_file(file_config.clone())
+                            .with_variables(variables.clone())
                             .resolve()?;
+                        if config.tenants.is_some() {
+                            anyhow::bail!(
+                                "`migrate provision` is not supported in schema-per-tenant mode; \
+                                 `migrate apply` starts each new tenant from the latest baseline"
+                            );
+                        }
                         let target = target.resolve(&file_config)?;
                         let selection = modules::ModuleSelection::resolve(modules, &config)?;
 
@@ -551,9 +987,17 @@ async fn run_main(cli: Cli) -> Result<()> {
                         )
                         .await
                     }
-                    MigrateCommands::Status { target, dev } => {
+                    MigrateCommands::Status {
+                        target,
+                        dev,
+                        format,
+                        exit_code,
+                        tenants,
+                        shadow,
+                    } => {
                         let config = config::ConfigBuilder::new()
                             .with_file(file_config.clone())
+                            .with_variables(variables.clone())
                             .resolve()?;
 
                         // Precedence: explicit --target-url flag > PGMT_TARGET_URL
@@ -570,19 +1014,78 @@ async fn run_main(cli: Cli) -> Result<()> {
                         };
 
                         info!("Checking migration status");
-                        commands::cmd_migrate_status(&config, &root_dir, label, &url).await
+                        let state = if *tenants {
+                            let shadow = shadow.resolve(&file_config)?;
+                            commands::cmd_migrate_status_tenants(
+                                &config, &root_dir, label, &url, &shadow, format,
+                            )
+                            .await?
+                        } else {
+                            if config.tenants.is_some() {
+                                anyhow::bail!(
+                                    "`tenants:` is configured: migrations are tracked per tenant \
+                                     schema, so use `pgmt migrate status --tenants`"
+                                );
+                            }
+                            commands::cmd_migrate_status(&config, &root_dir, label, &url, format)
+                                .await?
+                        };
+                        if *exit_code {
+                            return Ok(ExitCode::from(state.exit_code()));
+                        }
+                        Ok(())
+                    }
+                    MigrateCommands::History {
+                        version,
+                        sql,
+                        target,
+                    } => {
+                        let config = config::ConfigBuilder::new()
+                            .with_file(file_config.clone())
+                            .with_variables(variables.clone())
+                            .resolve()?;
+                        commands::reject_tenant_mode(&config, "migrate history")?;
+                        let target = target.resolve(&file_config)?;
+
+                        info!("Showing migration history");
+                        commands::cmd_migrate_history(&config, &target, version.as_deref(), *sql)
+                            .await
+                    }
+                    MigrateCommands::Wait {
+                        until,
+                        timeout,
+                        target,
+                    } => {
+                        let config = config::ConfigBuilder::new()
+                            .with_file(file_config.clone())
+                            .with_variables(variables.clone())
+                            .resolve()?;
+                        commands::reject_tenant_mode(&config, "migrate wait")?;
+                        let target = target.resolve(&file_config)?;
+
+                        info!("Waiting for schema version");
+                        commands::cmd_migrate_wait(&config, &root_dir, &target, until, *timeout)
+                            .await
                     }
                     MigrateCommands::Validate {
                         shadow,
+                        target,
                         format,
                         quiet,
                         verbose,
                         ignore_migrations,
+                        allow_out_of_order,
                     } => {
-                        let config = config::ConfigBuilder::new()
+                        let mut config = config::ConfigBuilder::new()
                             .with_file(file_config.clone())
+                            .with_variables(variables.clone())
                             .resolve()?;
+                        config.migration.allow_out_of_order |= *allow_out_of_order;
                         let shadow = shadow.resolve(&file_config)?;
+                        let target = target.resolve_optional(&file_config);
+                        if target.is_some() {
+                            commands::reject_tenant_mode(&config, "migrate validate --target-url")?;
+                        }
 
                         info!("Validating migration consistency");
 
@@ -598,13 +1101,35 @@ async fn run_main(cli: Cli) -> Result<()> {
                             &root_dir,
                             &validation_options,
                             &shadow,
+                            target.as_ref(),
                         )
                         .await
                     }
-                    MigrateCommands::Baseline(args) => {
+                    MigrateCommands::Lint { files, format } => {
+                        let config = config::ConfigBuilder::new()
+                            .with_file(file_config.clone())
+                            .with_variables(variables.clone())
+                            .resolve()?;
+
+                        commands::cmd_migrate_lint(&config, &root_dir, files, format).await
+                    }
+                    MigrateCommands::Export { format, out } => {
                         let config = config::ConfigBuilder::new()
                             .with_file(file_config.clone())
+                            .with_variables(variables.clone())
                             .resolve()?;
+                        commands::reject_tenant_mode(&config, "migrate export")?;
+
+                        info!("Exporting migrations");
+                        commands::cmd_migrate_export(&config, &root_dir, *format, out).await
+                    }
+                    MigrateCommands::Baseline(args) => {
+                        let config = tenants::for_generation(
+                            config::ConfigBuilder::new()
+                                .with_file(file_config.clone())
+                                .with_variables(variables.clone())
+                                .resolve()?,
+                        );
 
                         match &args.command {
                             Some(MigrateBaselineSubcommands::List) => {
@@ -629,7 +1154,9 @@ async fn run_main(cli: Cli) -> Result<()> {
                     MigrateCommands::Resolve(args) => {
                         let config = config::ConfigBuilder::new()
                             .with_file(file_config.clone())
+                            .with_variables(variables.clone())
                             .resolve()?;
+                        commands::reject_tenant_mode(&config, "migrate resolve")?;
                         let target = args.target.resolve(&file_config)?;
 
                         // clap guarantees exactly one verb is set.
@@ -655,28 +1182,81 @@ async fn run_main(cli: Cli) -> Result<()> {
                 Commands::Diff(args) => {
                     let config = config::ConfigBuilder::new()
                         .with_file(file_config.clone())
+                        .with_variables(variables.clone())
                         .resolve()?;
-                    let dev = args.dev.resolve(&file_config)?;
-                    let shadow = args.shadow.resolve(&file_config)?;
-
                     let diff_args = commands::diff::DiffArgs {
                         format: args.format.clone(),
                         output_sql: args.output_sql.clone(),
                     };
 
+                    if let Some(from_ref) = &args.from_ref {
+                        let shadow = args.shadow.resolve(&file_config)?;
+                        info!("Comparing schema files across git revisions");
+                        return commands::diff::cmd_diff_refs(
+                            &config,
+                            &root_dir,
+                            diff_args,
+                            from_ref,
+                            args.to_ref.as_deref(),
+                            args.snapshots.to_snapshot.as_deref(),
+                            &shadow,
+                        )
+                        .await
+                        .map(|()| ExitCode::SUCCESS);
+                    }
+
+                    let dev = args
+                        .snapshots
+                        .database_side(|| args.dev.resolve(&file_config))?;
+                    let shadow = args
+                        .snapshots
+                        .schema_side(|| args.shadow.resolve(&file_config))?;
+
                     info!("Comparing schema files with dev database");
                     commands::cmd_diff(&config, &root_dir, diff_args, &dev, &shadow).await
                 }
+                Commands::Compare(args) => {
+                    let config = config::ConfigBuilder::new()
+                        .with_file(file_config.clone())
+                        .with_variables(variables.clone())
+                        .resolve()?;
+
+                    let compare_args = commands::compare::CompareArgs {
+                        from_url: args.from_url.clone(),
+                        to_url: args.to_url.clone(),
+                        format: args.format.clone(),
+                        output_sql: args.output_sql.clone(),
+                        sync_sql: args.sync_sql.clone(),
+                    };
+
+                    info!("Comparing two databases");
+                    commands::cmd_compare(&config, compare_args).await
+                }
                 Commands::Validate(args) => {
                     let config = config::ConfigBuilder::new()
                         .with_file(file_config.clone())
+                        .with_variables(variables.clone())
                         .resolve()?;
-                    let dev = args.dev.resolve(&file_config)?;
-                    let shadow = args.shadow.resolve(&file_config)?;
+                    let dev = args
+                        .snapshots
+                        .database_side(|| args.dev.resolve(&file_config))?;
+                    let shadow = args
+                        .snapshots
+                        .schema_side(|| args.shadow.resolve(&file_config))?;
 
                     info!("Validating schema consistency");
                     commands::cmd_validate(&config, &root_dir, &dev, &shadow).await
                 }
+                Commands::Test(args) => {
+                    let config = config::ConfigBuilder::new()
+                        .with_file(file_config.clone())
+                        .with_variables(variables.clone())
+                        .resolve()?;
+                    let shadow = args.shadow.resolve(&file_config)?;
+
+                    info!("Running SQL tests");
+                    commands::cmd_test(&config, &root_dir, &args.files, &args.format, &shadow).await
+                }
                 Commands::Config { command } => {
                     match &command {
                         Some(_) => {
@@ -684,6 +1264,7 @@ async fn run_main(cli: Cli) -> Result<()> {
                             let (file_config, _) = config::load_config(&cli.config_file)?;
                             let config = config::ConfigBuilder::new()
                                 .with_file(file_config.clone())
+                                .with_variables(variables.clone())
                                 .resolve()?;
 
                             info!("Managing configuration");
@@ -693,11 +1274,66 @@ async fn run_main(cli: Cli) -> Result<()> {
                             // Just show help for config command
                             let config = config::ConfigBuilder::new()
                                 .with_file(file_config.clone())
+                                .with_variables(variables.clone())
                                 .resolve()?;
                             commands::cmd_config(&config, &file_config, None).await
                         }
                     }
                 }
+                Commands::Dev { command } => match command {
+                    commands::dev::DevCommands::Snapshots { command } => {
+                        use commands::dev::SnapshotCommands;
+                        match command {
+                            SnapshotCommands::List { dev } => {
+                                let dev = dev.resolve(&file_config)?;
+                                commands::cmd_dev_snapshots_list(&dev).await
+                            }
+                            SnapshotCommands::Restore { name, dev } => {
+                                let dev = dev.resolve(&file_config)?;
+                                info!("Restoring the dev database from {}", name);
+                                commands::cmd_dev_snapshots_restore(&dev, name).await
+                            }
+                            SnapshotCommands::Prune { keep, dev } => {
+                                let config = config::ConfigBuilder::new()
+                                    .with_file(file_config.clone())
+                                    .with_variables(variables.clone())
+                                    .resolve()?;
+                                let dev = dev.resolve(&file_config)?;
+                                commands::cmd_dev_snapshots_prune(&config, &dev, *keep).await
+                            }
+                        }
+                    }
+                },
+                Commands::Catalog { command } => match command {
+                    commands::catalog::CatalogCommands::Snapshot {
+                        out,
+                        source,
+                        dev,
+                        target,
+                        shadow,
+                    } => {
+                        let config = config::ConfigBuilder::new()
+                            .with_file(file_config.clone())
+                            .with_variables(variables.clone())
+                            .resolve()?;
+                        let of = match source {
+                            commands::catalog::SnapshotSource::Schema => {
+                                commands::catalog::SnapshotOf::SchemaFiles(
+                                    shadow.resolve(&file_config)?,
+                                )
+                            }
+                            commands::catalog::SnapshotSource::Dev => {
+                                commands::catalog::SnapshotOf::Dev(dev.resolve(&file_config)?)
+                            }
+                            commands::catalog::SnapshotSource::Target => {
+                                commands::catalog::SnapshotOf::Target(target.resolve(&file_config)?)
+                            }
+                        };
+
+                        info!("Writing catalog snapshot");
+                        commands::cmd_catalog_snapshot(&config, &root_dir, &of, out).await
+                    }
+                },
                 Commands::Debug { command } => match command {
                     DebugCommands::Dependencies {
                         format,
@@ -706,6 +1342,7 @@ async fn run_main(cli: Cli) -> Result<()> {
                     } => {
                         let config = config::ConfigBuilder::new()
                             .with_file(file_config.clone())
+                            .with_variables(variables.clone())
                             .resolve()?;
                         let shadow = shadow.resolve(&file_config)?;
 
@@ -726,5 +1363,6 @@ async fn run_main(cli: Cli) -> Result<()> {
                 },
             }
         }
-    }
+    };
+    result.map(|()| ExitCode::SUCCESS)
 }
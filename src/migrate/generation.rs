@@ -1,6 +1,7 @@
 use crate::catalog::Catalog;
 use crate::diff::operations::{MigrationStep, SqlRenderer};
 use crate::diff::plan;
+use crate::render::Safety;
 use anyhow::Result;
 
 /// Input for migration generation - all pure data, no side effects
@@ -49,18 +50,121 @@ pub fn generate_migration(input: MigrationGenerationInput) -> Result<MigrationGe
     })
 }
 
-/// Pure function to render migration steps into SQL
+/// The section schema changes are rendered in when seed data steps follow.
+pub const SCHEMA_SECTION: &str = "schema";
+
+/// The section seed data steps are rendered in, after the schema changes.
+pub const SEED_DATA_SECTION: &str = "seed_data";
+
+/// Whether a section named `name` is one `migrate new` renders itself. The
+/// names are reserved: `migrate rebase` regenerates these sections rather than
+/// keeping them as hand-written.
+pub fn is_generated_section(name: &str) -> bool {
+    name == SCHEMA_SECTION || name == SEED_DATA_SECTION
+}
+
+/// Pure function to render migration steps into SQL. Seed data steps, if any,
+/// go in their own section after the schema changes: nothing in the schema
+/// depends on them, so they can always run last.
 fn render_migration_steps(steps: &[MigrationStep]) -> Result<String> {
-    let mut sql_parts = Vec::new();
+    let (data, schema): (Vec<&MigrationStep>, Vec<&MigrationStep>) =
+        steps.iter().partition(|step| step.is_data());
+    if data.is_empty() {
+        return Ok(render_steps(&schema));
+    }
+
+    let mut sections = Vec::new();
+    if !schema.is_empty() {
+        sections.push(format!(
+            "-- pgmt:section name=\"{}\"\n{}",
+            SCHEMA_SECTION,
+            render_steps(&schema)
+        ));
+    }
+    sections.push(format!(
+        "-- pgmt:section name=\"{}\"\n{}",
+        SEED_DATA_SECTION,
+        render_steps(&data)
+    ));
+    Ok(sections.join("\n\n"))
+}
+
+fn render_steps(steps: &[&MigrationStep]) -> String {
+    steps
+        .iter()
+        .flat_map(|step| step.to_sql())
+        .map(|rendered| rendered.sql)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
 
-    for step in steps {
-        let rendered_sqls = step.to_sql();
-        for rendered in rendered_sqls {
-            sql_parts.push(rendered.sql);
+/// Result of down-migration generation - the reverse of a migration
+#[derive(Debug, Clone)]
+pub struct DownMigrationResult {
+    pub down_sql: String,
+    /// Forward statements whose data loss the down migration cannot undo
+    /// (e.g. the up migration drops a column: the down migration re-adds it
+    /// empty).
+    pub irreversible: Vec<String>,
+}
+
+/// Pure function to generate the down migration paired with a migration from
+/// `old_catalog` to `new_catalog`: the reverse plan (`new` → `old`), rendered
+/// with explicit warnings wherever running it, or having run the up migration,
+/// loses data that cannot be brought back.
+pub fn generate_down_migration(
+    old_catalog: &Catalog,
+    new_catalog: &Catalog,
+    migration_filename: &str,
+) -> Result<DownMigrationResult> {
+    let forward_steps = plan(old_catalog, new_catalog)?;
+    let reverse_steps = plan(new_catalog, old_catalog)?;
+
+    let irreversible: Vec<String> = forward_steps
+        .iter()
+        .flat_map(|step| step.to_sql())
+        .filter(|rendered| rendered.safety == Safety::Destructive)
+        .map(|rendered| first_line(&rendered.sql).to_string())
+        .collect();
+
+    let mut header = vec![
+        format!("-- Down migration for {}", migration_filename),
+        "-- Generated from the reverse schema diff; review it before relying on it.".to_string(),
+        "-- Run with `pgmt migrate rollback`.".to_string(),
+    ];
+    if !irreversible.is_empty() {
+        header.push("--".to_string());
+        header.push(
+            "-- IRREVERSIBLE: the up migration discards data this file cannot restore:".to_string(),
+        );
+        header.extend(irreversible.iter().map(|stmt| format!("--   {}", stmt)));
+    }
+
+    let mut sql_parts = vec![header.join("\n")];
+    for step in &reverse_steps {
+        for rendered in step.to_sql() {
+            if rendered.safety == Safety::Destructive {
+                sql_parts.push(format!(
+                    "-- IRREVERSIBLE: discards data written since the up migration ran\n{}",
+                    rendered.sql
+                ));
+            } else {
+                sql_parts.push(rendered.sql);
+            }
         }
     }
+    if reverse_steps.is_empty() {
+        sql_parts.push("-- No changes detected".to_string());
+    }
 
-    Ok(sql_parts.join("\n\n"))
+    Ok(DownMigrationResult {
+        down_sql: format!("{}\n", sql_parts.join("\n\n")),
+        irreversible,
+    })
+}
+
+fn first_line(sql: &str) -> &str {
+    sql.lines().next().unwrap_or_default().trim()
 }
 
 /// Pure function to sanitize migration description for filename
@@ -231,6 +335,78 @@ mod tests {
         assert!(result.has_changes);
     }
 
+    #[test]
+    fn test_generate_down_migration_reverses_plan() {
+        let old_catalog = Catalog::empty();
+        let mut new_catalog = Catalog::empty();
+        new_catalog.schemas.push(Schema {
+            name: "reporting".to_string(),
+            comment: None,
+        });
+
+        let result =
+            generate_down_migration(&old_catalog, &new_catalog, "V123_add_schema.sql").unwrap();
+
+        assert!(result.irreversible.is_empty());
+        assert!(
+            result
+                .down_sql
+                .contains("Down migration for V123_add_schema.sql")
+        );
+        assert!(result.down_sql.contains("DROP SCHEMA"));
+        assert!(!result.down_sql.contains("CREATE SCHEMA"));
+    }
+
+    #[test]
+    fn test_generate_down_migration_flags_irreversible_drops() {
+        let mut old_catalog = Catalog::empty();
+        let mut table = Table::new(
+            "public".to_string(),
+            "events".to_string(),
+            vec![Column {
+                name: "id".to_string(),
+                data_type: "integer".to_string(),
+                default: None,
+                not_null: true,
+                generated: None,
+                identity: None,
+                collation: None,
+                comment: None,
+                depends_on: vec![],
+            }],
+            None,
+            None,
+            vec![DbObjectId::Schema {
+                name: "public".to_string(),
+            }],
+        );
+        table.update_all_dependencies();
+        old_catalog.tables.push(table);
+        let new_catalog = Catalog::empty();
+
+        // Up drops the table: the down migration can recreate it, but not its rows.
+        let result =
+            generate_down_migration(&old_catalog, &new_catalog, "V124_drop_events.sql").unwrap();
+        assert_eq!(result.irreversible.len(), 1);
+        assert!(result.irreversible[0].contains("DROP TABLE"));
+        assert!(
+            result
+                .down_sql
+                .contains("-- IRREVERSIBLE: the up migration")
+        );
+        assert!(result.down_sql.contains("CREATE TABLE"));
+
+        // Up creates the table: rolling back drops whatever was written since.
+        let result =
+            generate_down_migration(&new_catalog, &old_catalog, "V125_add_events.sql").unwrap();
+        assert!(result.irreversible.is_empty());
+        assert!(
+            result
+                .down_sql
+                .contains("-- IRREVERSIBLE: discards data written since the up migration ran")
+        );
+    }
+
     #[test]
     fn test_generate_migration_with_v_prefix() {
         let old_catalog = Catalog::empty();
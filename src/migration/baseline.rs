@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use sqlx::PgPool;
+use std::collections::BTreeSet;
 use std::path::Path;
 use tracing::info;
 
@@ -7,14 +8,14 @@ use crate::catalog::Catalog;
 use crate::catalog::identity::{CatalogIdentity, find_new_objects};
 use crate::commands::migrate::section_executor::{ExecutionMode, SectionExecutor};
 use crate::config::Config;
-use crate::config::filter::ObjectFilter;
 use crate::config::types::TrackingTable;
 use crate::db::cleaner;
 use crate::migration::section_parser::MigrationSection;
 use crate::migration::{
     ParsedMigration, discover_migrations, find_baseline_for_version, find_latest_baseline,
-    parse_migration_sections, validate_sections,
+    parse_migration_sections_with_variables, validate_sections,
 };
+use crate::migration_tracking::AuditLog;
 use crate::modules::HistoricalAttribution;
 use crate::progress::SectionReporter;
 use crate::validation::validate_baseline_consistency;
@@ -38,10 +39,10 @@ impl Default for BaselineConfig {
 /// Shadow catalogs feed baseline rendering and validation: scope them to the
 /// managed universe. Shadow branches legitimately contain image-provided
 /// substrate (excluded schemas, their extensions), which must never appear in
-/// baselines or count as a validation difference.
+/// baselines or count as a validation difference. (When generating in
+/// schema-per-tenant mode they also name the template `${tenant}`.)
 async fn load_managed_catalog(shadow_pool: &PgPool, config: &Config) -> Result<Catalog> {
-    let filter = ObjectFilter::from_config(config);
-    Catalog::load_managed(shadow_pool, &filter).await
+    crate::tenants::load_for_planning(config, shadow_pool).await
 }
 
 /// Parse baseline SQL into its sections.
@@ -49,9 +50,14 @@ async fn load_managed_catalog(shadow_pool: &PgPool, config: &Config) -> Result<C
 /// Baselines use the same `-- pgmt:section` header syntax as migrations; a
 /// header-less baseline parses as one "default" transactional section, which
 /// preserves the historical all-or-nothing apply for existing baselines.
-fn parse_baseline_sections(baseline_sql: &str, source: &str) -> Result<Vec<MigrationSection>> {
-    let sections = parse_migration_sections(Path::new(source), baseline_sql)
-        .with_context(|| format!("Failed to parse baseline sections ({})", source))?;
+fn parse_baseline_sections(
+    baseline_sql: &str,
+    source: &str,
+    config: &Config,
+) -> Result<Vec<MigrationSection>> {
+    let sections =
+        parse_migration_sections_with_variables(Path::new(source), baseline_sql, &config.variables)
+            .with_context(|| format!("Failed to parse baseline sections ({})", source))?;
     validate_sections(&sections)
         .with_context(|| format!("Invalid section configuration in baseline ({})", source))?;
     Ok(sections)
@@ -122,7 +128,7 @@ async fn prepare_shadow_for_replay(
 ) -> Result<()> {
     cleaner::clean_shadow_db(shadow_pool, &config.objects).await?;
     // Apply roles before any history (handles non-existent files gracefully)
-    crate::schema_ops::apply_roles_file(shadow_pool, roles_file).await?;
+    crate::schema_ops::apply_roles_file(shadow_pool, roles_file, &config.variables).await?;
     Ok(())
 }
 
@@ -141,7 +147,7 @@ async fn apply_baseline_file_sections(
         .and_then(|name| name.to_str())
         .unwrap_or("unknown_baseline.sql");
 
-    let sections = parse_baseline_sections(&baseline_sql, source)?;
+    let sections = parse_baseline_sections(&baseline_sql, source, config)?;
     execute_validation_sections(shadow_pool, &sections, config, attribution)
         .await
         .with_context(|| format!("Failed to apply baseline SQL: {}", baseline_path.display()))
@@ -205,7 +211,7 @@ pub async fn replay_history_for_checkpoint(
 
     replay_migrations(shadow_pool, &migrations_to_replay, config, false, collector).await?;
 
-    let catalog = Catalog::load_unfiltered(shadow_pool).await?;
+    let catalog = crate::tenants::load_unfiltered_for_planning(config, shadow_pool).await?;
     Ok((base, catalog, attribution))
 }
 
@@ -235,6 +241,7 @@ pub async fn apply_baseline_to_target(
     version: u64,
     sections: &[(i32, MigrationSection)],
     source: &str,
+    audit: Option<&AuditLog>,
 ) -> Result<()> {
     let reporter = SectionReporter::new(sections.len(), false);
     let mut executor = SectionExecutor::new(
@@ -243,7 +250,8 @@ pub async fn apply_baseline_to_target(
         reporter,
         ExecutionMode::Production,
         true,
-    );
+    )
+    .with_audit(audit.cloned());
     for (_, section) in sections {
         executor
             .execute_section(version, section)
@@ -288,7 +296,11 @@ async fn replay_migrations(
             )
         })?;
 
-        let sections = parse_migration_sections(&migration.path, &migration_sql)?;
+        let sections = parse_migration_sections_with_variables(
+            &migration.path,
+            &migration_sql,
+            &config.variables,
+        )?;
         validate_sections(&sections)?;
 
         // Full-replay pin: shadow replay builds history with
@@ -384,6 +396,47 @@ pub async fn get_migration_starting_state(
     .await
 }
 
+/// Like [`get_migration_starting_state`], but replayed in the order a target
+/// reaches under `migration.allow_out_of_order`: the migrations it has
+/// `applied` first, then the rest in version order, as `migrate apply` runs
+/// them. An error means some migration fails in that order.
+#[allow(clippy::too_many_arguments)]
+pub async fn get_migration_state_in_target_order(
+    shadow_pool: &PgPool,
+    baselines_dir: &Path,
+    migrations_dir: &Path,
+    applied: &BTreeSet<u64>,
+    roles_file: &Path,
+    baseline_config: &BaselineConfig,
+    config: &Config,
+) -> Result<Catalog> {
+    let migrations = prepare_replay(
+        shadow_pool,
+        baselines_dir,
+        migrations_dir,
+        roles_file,
+        baseline_config,
+        config,
+        None,
+    )
+    .await?;
+    let (applied, pending): (Vec<_>, Vec<_>) = migrations
+        .into_iter()
+        .partition(|m| applied.contains(&m.version));
+
+    for migrations in [applied, pending] {
+        replay_migrations(
+            shadow_pool,
+            &migrations,
+            config,
+            baseline_config.verbose,
+            None,
+        )
+        .await?;
+    }
+    load_managed_catalog(shadow_pool, config).await
+}
+
 /// Like [`get_migration_starting_state`], but also collects object→module
 /// attribution from the replayed history's section tags (per-section identity
 /// snapshots). Used by module-aware generation to attribute DROP steps —
@@ -421,6 +474,38 @@ async fn get_migration_starting_state_inner(
     config: &Config,
     mut attribution: Option<&mut HistoricalAttribution>,
 ) -> Result<Catalog> {
+    let migrations_to_replay = prepare_replay(
+        shadow_pool,
+        baselines_dir,
+        migrations_dir,
+        roles_file,
+        baseline_config,
+        config,
+        attribution.as_deref_mut(),
+    )
+    .await?;
+    replay_migrations(
+        shadow_pool,
+        &migrations_to_replay,
+        config,
+        baseline_config.verbose,
+        attribution,
+    )
+    .await?;
+    load_managed_catalog(shadow_pool, config).await
+}
+
+/// Lay down the latest baseline (or prepare an empty shadow) and return the
+/// migrations left to replay on top of it.
+async fn prepare_replay(
+    shadow_pool: &PgPool,
+    baselines_dir: &Path,
+    migrations_dir: &Path,
+    roles_file: &Path,
+    baseline_config: &BaselineConfig,
+    config: &Config,
+    attribution: Option<&mut HistoricalAttribution>,
+) -> Result<Vec<ParsedMigration>> {
     let all_migrations = discover_migrations(migrations_dir)?;
 
     let migrations_to_replay = if let Some(baseline) = find_latest_baseline(baselines_dir)? {
@@ -432,7 +517,7 @@ async fn get_migration_starting_state_inner(
             &baseline.path,
             roles_file,
             config,
-            attribution.as_deref_mut(),
+            attribution,
         )
         .await?;
         warn_pre_baseline_migrations(&all_migrations, baseline.version);
@@ -464,16 +549,7 @@ async fn get_migration_starting_state_inner(
         }
         all_migrations
     };
-
-    replay_migrations(
-        shadow_pool,
-        &migrations_to_replay,
-        config,
-        baseline_config.verbose,
-        attribution,
-    )
-    .await?;
-    load_managed_catalog(shadow_pool, config).await
+    Ok(migrations_to_replay)
 }
 
 /// Get the starting catalog state for updating a specific migration version:
@@ -0,0 +1,832 @@
+//! Static checks for migration files (`pgmt migrate lint`).
+//!
+//! The linter looks for statements that are legal but dangerous to run against
+//! a live production database: table rewrites, long `ACCESS EXCLUSIVE` locks,
+//! irreversible data loss. It runs on hand-written migrations as much as on
+//! generated ones, and never connects to a database.
+//!
+//! Like the rest of pgmt it does NOT parse SQL. Statements are split with a
+//! quote/comment-aware scanner and matched on keywords, so the checks are
+//! heuristics: a false positive is silenced per section with
+//! `-- pgmt:allow <rule-id>`, and a rule can be downgraded or turned off
+//! project-wide under `migration.lint.rules` in pgmt.yaml.
+
+use crate::config::types::{Lint, LintLevel};
+use crate::migration::section_parser::MigrationSection;
+use crate::migration::section_validator::uses_concurrently_in_transaction;
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Severity of a reported finding. `error` findings fail the lint run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// A lint rule: stable ID (used in `-- pgmt:allow` and pgmt.yaml), built-in
+/// severity, and a one-line description for `--format sarif` rule metadata.
+#[derive(Debug, Clone, Copy)]
+pub struct LintRule {
+    pub id: &'static str,
+    pub default_severity: Severity,
+    pub summary: &'static str,
+}
+
+pub const VOLATILE_DEFAULT: &str = "volatile-default";
+pub const INDEX_NOT_CONCURRENT: &str = "index-not-concurrent";
+pub const RENAME_COLUMN: &str = "rename-column";
+pub const DROP_WITHOUT_ALLOW: &str = "drop-without-allow";
+pub const MISSING_LOCK_TIMEOUT: &str = "missing-lock-timeout";
+pub const CONCURRENTLY_IN_TRANSACTION: &str = "concurrently-in-transaction";
+
+/// Every rule the linter knows, in reporting order.
+pub const RULES: &[LintRule] = &[
+    LintRule {
+        id: VOLATILE_DEFAULT,
+        default_severity: Severity::Warning,
+        summary: "Adding a column with a volatile default rewrites the whole table under an \
+                  ACCESS EXCLUSIVE lock",
+    },
+    LintRule {
+        id: INDEX_NOT_CONCURRENT,
+        default_severity: Severity::Warning,
+        summary: "CREATE INDEX without CONCURRENTLY blocks writes to an existing table for the \
+                  whole build",
+    },
+    LintRule {
+        id: RENAME_COLUMN,
+        default_severity: Severity::Warning,
+        summary: "Renaming a column breaks every client still using the old name",
+    },
+    LintRule {
+        id: DROP_WITHOUT_ALLOW,
+        default_severity: Severity::Error,
+        summary: "Dropping a table, column, schema or sequence loses data irreversibly; \
+                  confirm it with an explicit allow annotation",
+    },
+    LintRule {
+        id: MISSING_LOCK_TIMEOUT,
+        default_severity: Severity::Warning,
+        summary: "A section taking strong locks without lock_timeout can queue behind a long \
+                  transaction and block all traffic to the table",
+    },
+    LintRule {
+        id: CONCURRENTLY_IN_TRANSACTION,
+        default_severity: Severity::Error,
+        summary: "CONCURRENTLY operations cannot run inside a transactional section",
+    },
+];
+
+/// Look up a rule by ID.
+pub fn find_rule(id: &str) -> Option<&'static LintRule> {
+    RULES.iter().find(|r| r.id == id)
+}
+
+/// One problem found in a migration file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintFinding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub path: PathBuf,
+    pub section: String,
+    /// 1-based line in the file where the offending statement starts
+    pub line: usize,
+    pub message: String,
+}
+
+/// The configured rule set: which rules run, and at what severity.
+pub struct Linter {
+    levels: BTreeMap<&'static str, Severity>,
+}
+
+impl Linter {
+    /// Build the rule set from built-in defaults overlaid with
+    /// `migration.lint.rules`. Unknown rule IDs are rejected so a typo can't
+    /// silently leave a rule at its default.
+    pub fn new(config: &Lint) -> Result<Self> {
+        for id in config.rules.keys() {
+            if find_rule(id).is_none() {
+                return Err(anyhow!(
+                    "unknown lint rule '{}' in migration.lint.rules (known rules: {})",
+                    id,
+                    known_rule_ids()
+                ));
+            }
+        }
+
+        let levels = RULES
+            .iter()
+            .filter_map(|rule| match config.rules.get(rule.id) {
+                Some(LintLevel::Off) => None,
+                Some(LintLevel::Error) => Some((rule.id, Severity::Error)),
+                Some(LintLevel::Warning) => Some((rule.id, Severity::Warning)),
+                None => Some((rule.id, rule.default_severity)),
+            })
+            .collect();
+
+        Ok(Self { levels })
+    }
+
+    /// Lint one migration file's parsed sections. `sql` is the full file text,
+    /// used to map findings back to file line numbers.
+    pub fn lint_sections(
+        &self,
+        path: &Path,
+        sql: &str,
+        sections: &[MigrationSection],
+    ) -> Result<Vec<LintFinding>> {
+        let file_lines: Vec<&str> = sql.lines().collect();
+        // Tables created anywhere earlier in this file are new: locking or
+        // rewriting them can't block production traffic.
+        let mut created_tables: HashSet<String> = HashSet::new();
+        let mut findings = Vec::new();
+
+        for section in sections {
+            for id in &section.allow {
+                if find_rule(id).is_none() {
+                    return Err(anyhow!(
+                        "{}: section '{}' (line {}) allows unknown lint rule '{}' (known rules: {})",
+                        path.display(),
+                        section.name,
+                        section.start_line,
+                        id,
+                        known_rule_ids()
+                    ));
+                }
+            }
+
+            let body_line = body_start_line(&file_lines, section);
+            let statements = split_statements(&section.sql);
+            let mut report = |rule: &'static str, line: usize, message: String| {
+                if section.allow.iter().any(|a| a == rule) {
+                    return;
+                }
+                if let Some(severity) = self.levels.get(rule) {
+                    findings.push(LintFinding {
+                        rule,
+                        severity: *severity,
+                        path: path.to_path_buf(),
+                        section: section.name.clone(),
+                        line: body_line + line - 1,
+                        message,
+                    });
+                }
+            };
+
+            let mut strong_lock_line = None;
+            let mut sets_lock_timeout = false;
+
+            for stmt in &statements {
+                let tokens = stmt.tokens();
+                let existing =
+                    |table: Option<String>| table.is_some_and(|t| !created_tables.contains(&t));
+
+                if is_set_lock_timeout(&tokens) {
+                    sets_lock_timeout = true;
+                }
+
+                if let Some(table) = check_volatile_default(&tokens)
+                    && existing(Some(table.clone()))
+                {
+                    report(
+                        VOLATILE_DEFAULT,
+                        stmt.line,
+                        format!(
+                            "adding a column with a volatile default to existing table {} \
+                             rewrites the table under an ACCESS EXCLUSIVE lock; add the column \
+                             without a default, backfill in batches, then set the default",
+                            table
+                        ),
+                    );
+                }
+
+                if let Some(index) = create_index(&tokens)
+                    && !index.concurrently
+                    && existing(index.table.clone())
+                {
+                    report(
+                        INDEX_NOT_CONCURRENT,
+                        stmt.line,
+                        format!(
+                            "CREATE INDEX on existing table {} without CONCURRENTLY blocks \
+                             writes for the whole build; use CREATE INDEX CONCURRENTLY in a \
+                             non-transactional section",
+                            index.table.as_deref().unwrap_or("?")
+                        ),
+                    );
+                }
+
+                if let Some(table) = renamed_column_table(&tokens)
+                    && existing(Some(table.clone()))
+                {
+                    report(
+                        RENAME_COLUMN,
+                        stmt.line,
+                        format!(
+                            "renaming a column of {} breaks clients still using the old name; \
+                             add the new column, migrate readers, then drop the old one",
+                            table
+                        ),
+                    );
+                }
+
+                if let Some(what) = data_loss_drop(&tokens) {
+                    report(
+                        DROP_WITHOUT_ALLOW,
+                        stmt.line,
+                        format!(
+                            "{} loses data irreversibly; if intended, add \
+                             `-- pgmt:allow {}` to this section",
+                            what, DROP_WITHOUT_ALLOW
+                        ),
+                    );
+                }
+
+                if strong_lock_line.is_none()
+                    && let Some(table) = strong_lock_target(&tokens)
+                    && table.as_ref().is_none_or(|t| !created_tables.contains(t))
+                {
+                    strong_lock_line = Some(stmt.line);
+                }
+
+                if let Some(table) = created_table(&tokens) {
+                    created_tables.insert(table);
+                }
+            }
+
+            if let Some(line) = strong_lock_line
+                && section.lock_timeout.is_none()
+                && !sets_lock_timeout
+            {
+                report(
+                    MISSING_LOCK_TIMEOUT,
+                    line,
+                    format!(
+                        "section '{}' takes strong locks on existing objects without a \
+                         lock_timeout; add `-- pgmt:  lock_timeout=\"5s\"` (with retries) so it \
+                         can't queue behind a long transaction and block traffic",
+                        section.name
+                    ),
+                );
+            }
+
+            if uses_concurrently_in_transaction(section) {
+                let line = statements
+                    .iter()
+                    .find(|s| s.tokens().contains(&"CONCURRENTLY"))
+                    .map(|s| s.line)
+                    .unwrap_or(1);
+                report(
+                    CONCURRENTLY_IN_TRANSACTION,
+                    line,
+                    format!(
+                        "section '{}' uses CONCURRENTLY but mode is '{}'; use \
+                         mode=\"non-transactional\"",
+                        section.name,
+                        section.mode.as_str()
+                    ),
+                );
+            }
+        }
+
+        Ok(findings)
+    }
+}
+
+fn known_rule_ids() -> String {
+    RULES.iter().map(|r| r.id).collect::<Vec<_>>().join(", ")
+}
+
+/// 1-based file line where a section's body starts. Section bodies are
+/// trimmed by the parser, so find the first body line after the header; the
+/// header-less `default` section's body is the whole file.
+fn body_start_line(file_lines: &[&str], section: &MigrationSection) -> usize {
+    if section.raw_header.is_empty() {
+        return 1;
+    }
+    let Some(first) = section.sql.lines().next() else {
+        return section.start_line;
+    };
+    file_lines
+        .iter()
+        .enumerate()
+        .skip(section.start_line)
+        .find(|(_, l)| l.trim_end() == first.trim_end())
+        .map(|(i, _)| i + 1)
+        .unwrap_or(section.start_line)
+}
+
+/// A statement with comments removed and literal/dollar-quoted contents
+/// blanked, upper-cased with punctuation spaced out for keyword matching.
+pub(crate) struct Statement {
+    normalized: String,
+    /// 1-based line within the section body where the statement starts
+    pub(crate) line: usize,
+}
+
+impl Statement {
+    pub(crate) fn tokens(&self) -> Vec<&str> {
+        self.normalized.split_whitespace().collect()
+    }
+}
+
+/// Split SQL into statements on top-level semicolons, skipping comments and
+/// the contents of string literals and dollar-quoted bodies (so a `DROP TABLE`
+/// inside a function body is not reported as a drop).
+pub(crate) fn split_statements(sql: &str) -> Vec<Statement> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut line = 1;
+    let mut start_line = None;
+    let mut i = 0;
+
+    let mut flush = |current: &mut String, start_line: &mut Option<usize>| {
+        if let Some(l) = start_line.take()
+            && !current.trim().is_empty()
+        {
+            statements.push(Statement {
+                normalized: current.to_uppercase(),
+                line: l,
+            });
+        }
+        current.clear();
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c == '\n' {
+            line += 1;
+            current.push(' ');
+            i += 1;
+        } else if c == '-' && next == Some('-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+            current.push(' ');
+        } else if c == '\'' || c == '"' {
+            start_line.get_or_insert(line);
+            // Identifiers keep their text (quotes dropped); literals are blanked.
+            let quote = c;
+            i += 1;
+            if quote == '\'' {
+                current.push_str(" '' ");
+            }
+            while i < chars.len() {
+                if chars[i] == quote {
+                    if chars.get(i + 1) == Some(&quote) {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                if quote == '"' {
+                    current.push(chars[i]);
+                }
+                i += 1;
+            }
+            i += 1;
+        } else if c == '$'
+            && let Some(tag_len) = dollar_tag_len(&chars[i..])
+        {
+            start_line.get_or_insert(line);
+            let tag: Vec<char> = chars[i..i + tag_len].to_vec();
+            i += tag_len;
+            while i < chars.len() && !chars[i..].starts_with(&tag) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += tag_len;
+            current.push_str(" $$ ");
+        } else if c == ';' {
+            flush(&mut current, &mut start_line);
+            i += 1;
+        } else {
+            if !c.is_whitespace() {
+                start_line.get_or_insert(line);
+            }
+            if matches!(c, '(' | ')' | ',') {
+                current.push(' ');
+                current.push(c);
+                current.push(' ');
+            } else {
+                current.push(c);
+            }
+            i += 1;
+        }
+    }
+    flush(&mut current, &mut start_line);
+
+    statements
+}
+
+/// Length of a dollar-quote opening tag (`$$` or `$tag$`) at the start of
+/// `chars`, or `None` if this `$` is not one (e.g. a `$1` parameter).
+fn dollar_tag_len(chars: &[char]) -> Option<usize> {
+    let end = chars[1..]
+        .iter()
+        .position(|c| !(c.is_alphanumeric() || *c == '_'))?;
+    if chars[1 + end] != '$' {
+        return None;
+    }
+    if end > 0 && chars[1].is_ascii_digit() {
+        return None;
+    }
+    Some(end + 2)
+}
+
+/// Normalize an object name token for same-file comparisons and messages:
+/// last dot-separated component (schema-qualification is dropped, a
+/// heuristic), folded to lower case the way PostgreSQL folds unquoted names.
+fn object_name(token: &str) -> String {
+    token.rsplit('.').next().unwrap_or(token).to_lowercase()
+}
+
+/// Skip leading keyword tokens (e.g. `IF EXISTS`, `ONLY`) and return the index
+/// of the next token.
+fn skip_keywords(tokens: &[&str], mut i: usize, keywords: &[&[&str]]) -> usize {
+    'outer: loop {
+        for kw in keywords {
+            if tokens.len() >= i + kw.len() && tokens[i..i + kw.len()] == **kw {
+                i += kw.len();
+                continue 'outer;
+            }
+        }
+        return i;
+    }
+}
+
+/// Target of `CREATE [UNLOGGED|TEMP] TABLE [IF NOT EXISTS] name`.
+fn created_table(tokens: &[&str]) -> Option<String> {
+    if tokens.first() != Some(&"CREATE") {
+        return None;
+    }
+    let i = skip_keywords(
+        tokens,
+        1,
+        &[
+            &["UNLOGGED"],
+            &["TEMP"],
+            &["TEMPORARY"],
+            &["GLOBAL"],
+            &["LOCAL"],
+        ],
+    );
+    if tokens.get(i) != Some(&"TABLE") {
+        return None;
+    }
+    let i = skip_keywords(tokens, i + 1, &[&["IF", "NOT", "EXISTS"]]);
+    tokens.get(i).map(|t| object_name(t))
+}
+
+/// Target of `ALTER TABLE [IF EXISTS] [ONLY] name ...`.
+fn altered_table(tokens: &[&str]) -> Option<String> {
+    if tokens.len() < 3 || tokens[0] != "ALTER" || tokens[1] != "TABLE" {
+        return None;
+    }
+    let i = skip_keywords(tokens, 2, &[&["IF", "EXISTS"], &["ONLY"]]);
+    tokens.get(i).map(|t| object_name(t))
+}
+
+struct CreateIndex {
+    concurrently: bool,
+    table: Option<String>,
+}
+
+/// `CREATE [UNIQUE] INDEX [CONCURRENTLY] [IF NOT EXISTS] [name] ON [ONLY] table`.
+fn create_index(tokens: &[&str]) -> Option<CreateIndex> {
+    if tokens.first() != Some(&"CREATE") {
+        return None;
+    }
+    let i = skip_keywords(tokens, 1, &[&["UNIQUE"]]);
+    if tokens.get(i) != Some(&"INDEX") {
+        return None;
+    }
+    let on = tokens.iter().position(|t| *t == "ON")?;
+    let table_at = skip_keywords(tokens, on + 1, &[&["ONLY"]]);
+    Some(CreateIndex {
+        concurrently: tokens[i..on].contains(&"CONCURRENTLY"),
+        table: tokens.get(table_at).map(|t| object_name(t)),
+    })
+}
+
+/// Functions whose value differs per row, forcing a table rewrite when used
+/// as the default of an added column.
+const VOLATILE_FUNCTIONS: &[&str] = &[
+    "RANDOM",
+    "CLOCK_TIMESTAMP",
+    "TIMEOFDAY",
+    "GEN_RANDOM_UUID",
+    "UUID_GENERATE_V1",
+    "UUID_GENERATE_V1MC",
+    "UUID_GENERATE_V4",
+    "UUIDV7",
+    "NEXTVAL",
+    "TXID_CURRENT",
+];
+
+/// `ALTER TABLE t ADD [COLUMN] ... DEFAULT <volatile>(...)`, or an added
+/// serial/identity column (filled from a sequence). Returns the table.
+fn check_volatile_default(tokens: &[&str]) -> Option<String> {
+    let table = altered_table(tokens)?;
+    let add = tokens.iter().position(|t| *t == "ADD")?;
+    let rest = &tokens[add + 1..];
+    if rest.first().is_some_and(|t| {
+        matches!(
+            *t,
+            "CONSTRAINT" | "PRIMARY" | "UNIQUE" | "CHECK" | "FOREIGN" | "EXCLUDE"
+        )
+    }) {
+        return None;
+    }
+
+    let serial = rest
+        .iter()
+        .any(|t| matches!(*t, "SERIAL" | "BIGSERIAL" | "SMALLSERIAL" | "IDENTITY"));
+    let volatile_default = rest.iter().position(|t| *t == "DEFAULT").is_some_and(|d| {
+        rest[d + 1..].windows(2).any(|w| {
+            w[1] == "(" && VOLATILE_FUNCTIONS.contains(&w[0].rsplit('.').next().unwrap_or(w[0]))
+        })
+    });
+
+    (serial || volatile_default).then_some(table)
+}
+
+/// `ALTER TABLE t RENAME [COLUMN] a TO b` (not `RENAME TO` / `RENAME CONSTRAINT`).
+fn renamed_column_table(tokens: &[&str]) -> Option<String> {
+    let table = altered_table(tokens)?;
+    let rename = tokens.iter().position(|t| *t == "RENAME")?;
+    match tokens.get(rename + 1) {
+        Some(&"TO") | Some(&"CONSTRAINT") | None => None,
+        Some(_) => Some(table),
+    }
+}
+
+/// A drop that loses data: `DROP TABLE|SCHEMA|SEQUENCE` or
+/// `ALTER TABLE ... DROP [COLUMN] c`. Returns a description of what is lost.
+fn data_loss_drop(tokens: &[&str]) -> Option<String> {
+    if tokens.first() == Some(&"DROP") {
+        let kind = match tokens.get(1) {
+            Some(&"TABLE") => "table",
+            Some(&"SCHEMA") => "schema",
+            Some(&"SEQUENCE") => "sequence",
+            _ => return None,
+        };
+        let i = skip_keywords(tokens, 2, &[&["IF", "EXISTS"]]);
+        let names: Vec<String> = tokens[i..]
+            .iter()
+            .take_while(|t| !matches!(**t, "CASCADE" | "RESTRICT"))
+            .filter(|t| **t != ",")
+            .map(|t| object_name(t))
+            .collect();
+        return Some(format!("DROP {} {}", kind.to_uppercase(), names.join(", ")));
+    }
+
+    let table = altered_table(tokens)?;
+    let drop = tokens.iter().position(|t| *t == "DROP")?;
+    let column = match tokens.get(drop + 1).copied()? {
+        "CONSTRAINT" | "DEFAULT" | "NOT" | "EXPRESSION" | "IDENTITY" => return None,
+        "COLUMN" => {
+            let i = skip_keywords(tokens, drop + 2, &[&["IF", "EXISTS"]]);
+            tokens.get(i).copied().unwrap_or("?")
+        }
+        column => column,
+    };
+    Some(format!("DROP COLUMN {}.{}", table, object_name(column)))
+}
+
+/// Whether a statement takes a lock that blocks reads or writes on an object
+/// for its whole duration. Returns the table it locks when known (so tables
+/// created in the same file can be exempted), `Some(None)` when the target
+/// isn't a single known table.
+fn strong_lock_target(tokens: &[&str]) -> Option<Option<String>> {
+    if let Some(table) = altered_table(tokens) {
+        return Some(Some(table));
+    }
+    if let Some(index) = create_index(tokens) {
+        return (!index.concurrently).then_some(index.table);
+    }
+    match tokens.first().copied() {
+        Some("DROP") if tokens.get(1) == Some(&"TABLE") => Some(None),
+        Some("TRUNCATE") | Some("LOCK") | Some("CLUSTER") => Some(None),
+        Some("VACUUM") if tokens.contains(&"FULL") => Some(None),
+        Some("REINDEX") if !tokens.contains(&"CONCURRENTLY") => Some(None),
+        Some("REFRESH") if !tokens.contains(&"CONCURRENTLY") => Some(None),
+        Some("CREATE") if tokens.get(1) == Some(&"TRIGGER") => {
+            let on = tokens.iter().position(|t| *t == "ON")?;
+            Some(tokens.get(on + 1).map(|t| object_name(t)))
+        }
+        _ => None,
+    }
+}
+
+/// `SET [LOCAL|SESSION] lock_timeout ...`
+fn is_set_lock_timeout(tokens: &[&str]) -> bool {
+    if tokens.first() != Some(&"SET") {
+        return false;
+    }
+    let i = skip_keywords(tokens, 1, &[&["LOCAL"], &["SESSION"]]);
+    tokens.get(i) == Some(&"LOCK_TIMEOUT")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migration::section_parser::parse_migration_sections;
+
+    fn lint(sql: &str) -> Vec<LintFinding> {
+        lint_with(&Lint::default(), sql)
+    }
+
+    fn lint_with(config: &Lint, sql: &str) -> Vec<LintFinding> {
+        let path = Path::new("V1_test.sql");
+        let sections = parse_migration_sections(path, sql).unwrap();
+        Linter::new(config)
+            .unwrap()
+            .lint_sections(path, sql, &sections)
+            .unwrap()
+    }
+
+    fn rules(findings: &[LintFinding]) -> Vec<&'static str> {
+        findings.iter().map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn test_split_statements_skips_comments_and_quoted_bodies() {
+        let sql = "-- DROP TABLE a;\nCREATE FUNCTION f() RETURNS void AS $body$ DROP TABLE b; $body$ LANGUAGE sql;\n\
+                   /* DROP TABLE c; */ SELECT 'DROP TABLE d;';\nALTER TABLE \"Users\" ADD x int;";
+        let statements = split_statements(sql);
+        assert_eq!(statements.len(), 3);
+        assert!(!statements.iter().any(|s| s.normalized.contains("DROP")));
+        assert_eq!(statements[0].line, 2);
+        assert_eq!(statements[1].line, 3);
+        assert_eq!(statements[2].line, 4);
+        assert_eq!(statements[2].tokens()[2], "USERS");
+    }
+
+    #[test]
+    fn test_volatile_default_on_existing_table() {
+        let findings = lint(
+            "-- pgmt:section name=\"s\" lock_timeout=\"2s\"\n\
+             ALTER TABLE users ADD COLUMN token uuid DEFAULT gen_random_uuid();",
+        );
+        assert_eq!(rules(&findings), vec![VOLATILE_DEFAULT]);
+        assert_eq!(findings[0].line, 2);
+
+        // A constant default is a catalog-only change since PG11.
+        let findings = lint(
+            "-- pgmt:section name=\"s\" lock_timeout=\"2s\"\n\
+             ALTER TABLE users ADD COLUMN status text DEFAULT 'active';",
+        );
+        assert!(findings.is_empty(), "{findings:?}");
+    }
+
+    #[test]
+    fn test_new_tables_are_exempt() {
+        let findings = lint(
+            "CREATE TABLE events (id int);\n\
+             CREATE INDEX idx_events_id ON events (id);\n\
+             ALTER TABLE events ADD COLUMN id2 bigserial;",
+        );
+        assert!(findings.is_empty(), "{findings:?}");
+    }
+
+    #[test]
+    fn test_index_without_concurrently() {
+        let findings = lint("CREATE INDEX idx_users_email ON public.users (email);");
+        assert_eq!(
+            rules(&findings),
+            vec![INDEX_NOT_CONCURRENT, MISSING_LOCK_TIMEOUT]
+        );
+
+        let findings = lint(
+            "-- pgmt:section name=\"idx\" mode=\"non-transactional\"\n\
+             CREATE INDEX CONCURRENTLY idx_users_email ON users (email);",
+        );
+        assert!(findings.is_empty(), "{findings:?}");
+    }
+
+    #[test]
+    fn test_rename_column() {
+        let findings =
+            lint("SET lock_timeout = '2s';\nALTER TABLE users RENAME COLUMN name TO full_name;");
+        assert_eq!(rules(&findings), vec![RENAME_COLUMN]);
+        assert_eq!(findings[0].line, 2);
+
+        // Renaming the table or a constraint is not a column rename.
+        let findings = lint(
+            "SET lock_timeout = '2s';\nALTER TABLE users RENAME CONSTRAINT a TO b;\nALTER TABLE users RENAME TO people;",
+        );
+        assert!(findings.is_empty(), "{findings:?}");
+    }
+
+    #[test]
+    fn test_drop_requires_allow() {
+        let sql = "-- pgmt:section name=\"cleanup\" lock_timeout=\"2s\"\n\
+                   DROP TABLE IF EXISTS legacy_events, old_audit CASCADE;\n\
+                   ALTER TABLE users DROP COLUMN IF EXISTS nickname;\n\
+                   ALTER TABLE users DROP CONSTRAINT users_name_check;\n\
+                   DROP VIEW user_summary;";
+        let findings = lint(sql);
+        assert_eq!(
+            rules(&findings),
+            vec![DROP_WITHOUT_ALLOW, DROP_WITHOUT_ALLOW]
+        );
+        assert!(findings[0].message.contains("legacy_events, old_audit"));
+        assert!(findings[1].message.contains("users.nickname"));
+
+        let allowed = sql.replace(
+            "lock_timeout=\"2s\"\n",
+            "lock_timeout=\"2s\"\n-- pgmt:allow drop-without-allow\n",
+        );
+        assert!(lint(&allowed).is_empty());
+    }
+
+    #[test]
+    fn test_missing_lock_timeout_satisfied_by_attribute_or_set() {
+        let findings = lint("ALTER TABLE users ALTER COLUMN email SET NOT NULL;");
+        assert_eq!(rules(&findings), vec![MISSING_LOCK_TIMEOUT]);
+
+        let findings = lint(
+            "-- pgmt:section name=\"s\"\n-- pgmt:  lock_timeout=\"2s\"\n\
+             ALTER TABLE users ALTER COLUMN email SET NOT NULL;",
+        );
+        assert!(findings.is_empty(), "{findings:?}");
+
+        let findings = lint(
+            "SET LOCAL lock_timeout = '2s';\nALTER TABLE users ALTER COLUMN email SET NOT NULL;",
+        );
+        assert!(findings.is_empty(), "{findings:?}");
+    }
+
+    #[test]
+    fn test_concurrently_in_transactional_section() {
+        let findings = lint(
+            "-- pgmt:section name=\"idx\"\n\
+             CREATE INDEX CONCURRENTLY idx_users_email ON users (email);",
+        );
+        assert_eq!(rules(&findings), vec![CONCURRENTLY_IN_TRANSACTION]);
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_config_overrides_severity_and_disables_rules() {
+        let config = Lint {
+            rules: BTreeMap::from([
+                (MISSING_LOCK_TIMEOUT.to_string(), LintLevel::Off),
+                (RENAME_COLUMN.to_string(), LintLevel::Error),
+            ]),
+        };
+        let findings = lint_with(&config, "ALTER TABLE users RENAME name TO full_name;");
+        assert_eq!(rules(&findings), vec![RENAME_COLUMN]);
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_unknown_rule_ids_are_rejected() {
+        let config = Lint {
+            rules: BTreeMap::from([("no-such-rule".to_string(), LintLevel::Off)]),
+        };
+        let err = Linter::new(&config).err().unwrap().to_string();
+        assert!(err.contains("no-such-rule"), "{err}");
+
+        let sql = "-- pgmt:section name=\"s\"\n-- pgmt:allow drop-everything\nSELECT 1;";
+        let path = Path::new("V1_test.sql");
+        let sections = parse_migration_sections(path, sql).unwrap();
+        let err = Linter::new(&Lint::default())
+            .unwrap()
+            .lint_sections(path, sql, &sections)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("drop-everything"), "{err}");
+    }
+}
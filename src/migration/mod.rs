@@ -1,19 +1,22 @@
 pub mod baseline;
+pub mod lint;
 pub mod parsing;
 pub mod section_parser;
 pub mod section_validator;
 
 pub use parsing::{
-    ParsedMigration, discover_baselines, discover_migrations, find_baseline_for_version,
-    find_latest_baseline, find_latest_migration, generate_baseline_filename,
+    ParsedMigration, discover_baselines, discover_migrations, down_migration_path,
+    find_baseline_for_version, find_latest_baseline, find_latest_migration,
+    generate_baseline_filename,
 };
 
 pub use baseline::{
     BaselineConfig, get_migration_starting_state, get_migration_starting_state_with_attribution,
-    get_migration_update_starting_state, replay_history_for_checkpoint,
-    should_manage_baseline_for_migration, validate_baseline_against_catalog,
+    get_migration_state_in_target_order, get_migration_update_starting_state,
+    replay_history_for_checkpoint, should_manage_baseline_for_migration,
+    validate_baseline_against_catalog,
 };
 
-pub use section_parser::parse_migration_sections;
+pub use section_parser::{parse_migration_sections, parse_migration_sections_with_variables};
 
 pub use section_validator::validate_sections;
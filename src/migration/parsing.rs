@@ -1,4 +1,4 @@
-use crate::constants::BASELINE_FILENAME_PREFIX;
+use crate::constants::{BASELINE_FILENAME_PREFIX, DOWN_MIGRATIONS_SUBDIR};
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 
@@ -65,6 +65,15 @@ pub fn generate_baseline_filename(version: u64) -> String {
     format!("{}{}.sql", BASELINE_FILENAME_PREFIX, version)
 }
 
+/// The down migration paired with a migration file: same filename, in the
+/// `down/` subdirectory of the migrations directory. Living in a subdirectory
+/// keeps down files out of `discover_migrations`.
+pub fn down_migration_path(migration_path: &Path) -> PathBuf {
+    let migrations_dir = migration_path.parent().unwrap_or(Path::new(""));
+    let filename = migration_path.file_name().unwrap_or_default();
+    migrations_dir.join(DOWN_MIGRATIONS_SUBDIR).join(filename)
+}
+
 /// Find all migration files in a directory and return them sorted by version
 pub fn discover_migrations(migrations_dir: &Path) -> Result<Vec<ParsedMigration>> {
     let mut migrations = Vec::new();
@@ -290,6 +299,15 @@ mod tests {
         .unwrap();
         std::fs::write(temp_dir.join("invalid_file.sql"), "-- Invalid").unwrap();
         std::fs::write(temp_dir.join("readme.txt"), "-- Not SQL").unwrap();
+        // Paired down migrations are not forward migrations
+        std::fs::create_dir_all(temp_dir.join("down")).unwrap();
+        std::fs::write(
+            temp_dir
+                .join("down")
+                .join("V3000000000_third_migration.sql"),
+            "-- Down",
+        )
+        .unwrap();
 
         let migrations = discover_migrations(&temp_dir).unwrap();
 
@@ -305,6 +323,14 @@ mod tests {
         let _ = std::fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_down_migration_path() {
+        assert_eq!(
+            down_migration_path(Path::new("migrations/V123_add_users.sql")),
+            PathBuf::from("migrations/down/V123_add_users.sql")
+        );
+    }
+
     #[test]
     fn test_discover_baselines() {
         let temp_dir = env::temp_dir().join("pgmt_test_discover_baselines");
@@ -1,3 +1,4 @@
+use crate::config::Variables;
 use anyhow::{Context, Result, anyhow};
 use std::path::Path;
 use std::time::Duration;
@@ -23,9 +24,25 @@ pub struct MigrationSection {
     /// Retry configuration (optional)
     pub retry_config: Option<RetryConfig>,
 
+    /// Batch settings; `Some` exactly when `mode` is `Batched`
+    pub batch: Option<BatchConfig>,
+
+    /// `-- pgmt:assert_before` queries: all must return true before the body
+    /// runs
+    pub assert_before: Vec<SectionAssertion>,
+
+    /// `-- pgmt:assert_after` queries: all must return true once the body has
+    /// run
+    pub assert_after: Vec<SectionAssertion>,
+
     /// The SQL to execute for this section
     pub sql: String,
 
+    /// The body exactly as written, when `${var}` substitution changed it
+    /// (see [`parse_migration_sections_with_variables`]). Checksums hash this,
+    /// so the same file checksums identically in every environment.
+    pub raw_sql: Option<String>,
+
     /// The raw header line(s) of this section (the `-- pgmt:section` line plus
     /// any `-- pgmt:` attribute continuation lines), exactly as written.
     /// Empty for the implicit header-less `default` section. It participates
@@ -44,6 +61,11 @@ pub struct MigrationSection {
     /// value, not a list. Establishment derivation reads it.
     pub remaps: Option<String>,
 
+    /// Lint rule IDs suppressed for this section via `-- pgmt:allow <rule-id>`
+    /// header lines (see `migration::lint`). Execution ignores them; like any
+    /// header line they are part of `raw_header` and so of the checksum.
+    pub allow: Vec<String>,
+
     /// Line number where this section starts (for error reporting)
     pub start_line: usize,
 }
@@ -54,10 +76,11 @@ impl MigrationSection {
     /// attribution, so it is part of the immutable unit. The header-less
     /// `default` section hashes its body alone.
     pub fn checksum_content(&self) -> String {
+        let body = self.raw_sql.as_deref().unwrap_or(&self.sql);
         if self.raw_header.is_empty() {
-            self.sql.clone()
+            body.to_string()
         } else {
-            format!("{}\n{}", self.raw_header, self.sql)
+            format!("{}\n{}", self.raw_header, body)
         }
     }
 }
@@ -73,6 +96,10 @@ pub enum TransactionMode {
 
     /// Execute each statement individually with auto-commit
     Autocommit,
+
+    /// Re-execute the section, one transaction per batch, until a batch
+    /// affects no rows (for backfills)
+    Batched,
 }
 
 impl TransactionMode {
@@ -82,6 +109,7 @@ impl TransactionMode {
             Self::Transactional => "transactional",
             Self::NonTransactional => "non-transactional",
             Self::Autocommit => "autocommit",
+            Self::Batched => "batched",
         }
     }
 }
@@ -102,6 +130,26 @@ pub struct RetryConfig {
     pub on_lock_timeout: LockTimeoutAction,
 }
 
+/// A boolean query from a `-- pgmt:assert_before` / `-- pgmt:assert_after`
+/// header line (`${var}` placeholders substituted).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionAssertion {
+    pub query: String,
+
+    /// Line number of the header line (for error reporting)
+    pub line: usize,
+}
+
+/// Settings for a `mode="batched"` section
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchConfig {
+    /// Pause between batches
+    pub sleep: Duration,
+
+    /// Give up (recording progress) after this many batches in one run
+    pub max_batches: Option<u64>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BackoffStrategy {
     /// Use constant delay between retries
@@ -132,18 +180,105 @@ impl Default for RetryConfig {
 }
 
 /// Parse a migration file into sections
-pub fn parse_migration_sections(_file_path: &Path, sql: &str) -> Result<Vec<MigrationSection>> {
+pub fn parse_migration_sections(file_path: &Path, sql: &str) -> Result<Vec<MigrationSection>> {
+    parse_sections(file_path, sql, sql)
+}
+
+/// Parse a migration file into sections, with `${var}` placeholders in the
+/// SQL substituted first. The whole file is substituted up front, so an
+/// undefined placeholder anywhere fails the parse; lines are then classified
+/// on the file as written — `-- pgmt:` header lines are pgmt directives, not
+/// SQL — and each section keeps its unsubstituted body in `raw_sql` for
+/// checksumming.
+pub fn parse_migration_sections_with_variables(
+    file_path: &Path,
+    sql: &str,
+    variables: &Variables,
+) -> Result<Vec<MigrationSection>> {
+    let substituted = variables
+        .substitute(sql)
+        .with_context(|| format!("Failed to substitute variables in {}", file_path.display()))?;
+    parse_sections(file_path, sql, &substituted)
+}
+
+/// `raw` and `substituted` have the same lines (placeholder values are
+/// single-line); lines are classified on `raw`, bodies taken from both.
+fn parse_sections(
+    _file_path: &Path,
+    raw: &str,
+    substituted: &str,
+) -> Result<Vec<MigrationSection>> {
     let mut sections = Vec::new();
-    let lines: Vec<&str> = sql.lines().collect();
+    let lines: Vec<&str> = raw.lines().collect();
+    let substituted_lines: Vec<&str> = substituted.lines().collect();
     let mut current_section: Option<SectionBuilder> = None;
     let mut current_sql = String::new();
+    let mut current_raw_sql = String::new();
+    // `-- pgmt:allow` lines outside any section header: they can only apply
+    // to the implicit `default` section of a header-less file (checked below).
+    let mut file_allow = Vec::new();
+    let mut file_allow_line = None;
+    // Likewise for assertions, except that a sectioned file must not silently
+    // drop them (checked below).
+    let mut file_assert_before = Vec::new();
+    let mut file_assert_after = Vec::new();
 
     for (line_num, line) in lines.iter().enumerate() {
-        if line.trim_start().starts_with("-- pgmt:section") {
+        if line.trim_start().starts_with(ALLOW_PREFIX) {
+            let rules = parse_allow_line(line);
+            match current_section.as_mut() {
+                Some(builder) => {
+                    if !builder.raw_header.is_empty() {
+                        builder.raw_header.push('\n');
+                    }
+                    builder.raw_header.push_str(line);
+                    builder.allow.extend(rules);
+                }
+                None => {
+                    file_allow_line.get_or_insert(line_num + 1);
+                    file_allow.extend(rules);
+                }
+            }
+        } else if let Some((prefix, is_before)) =
+            [(ASSERT_BEFORE_PREFIX, true), (ASSERT_AFTER_PREFIX, false)]
+                .into_iter()
+                .find(|(prefix, _)| is_directive(line, prefix))
+        {
+            // The query comes from the substituted line; the raw line is part
+            // of the header (and so of the checksum) like any other.
+            let query = substituted_lines[line_num].trim_start()[prefix.len()..].trim();
+            if query.is_empty() {
+                return Err(anyhow!(
+                    "{} at line {} has no query",
+                    prefix.trim_start_matches("-- pgmt:"),
+                    line_num + 1
+                ));
+            }
+            let assertion = SectionAssertion {
+                query: query.to_string(),
+                line: line_num + 1,
+            };
+            let (before, after) = match current_section.as_mut() {
+                Some(builder) => {
+                    if !builder.raw_header.is_empty() {
+                        builder.raw_header.push('\n');
+                    }
+                    builder.raw_header.push_str(line);
+                    (&mut builder.assert_before, &mut builder.assert_after)
+                }
+                None => (&mut file_assert_before, &mut file_assert_after),
+            };
+            if is_before {
+                before.push(assertion);
+            } else {
+                after.push(assertion);
+            }
+        } else if line.trim_start().starts_with("-- pgmt:section") {
             // Save previous section if exists
             if let Some(builder) = current_section.take() {
-                sections.push(builder.build(current_sql.clone())?);
+                sections.push(builder.build(&current_sql, &current_raw_sql)?);
                 current_sql.clear();
+                current_raw_sql.clear();
             }
 
             // Start new section
@@ -173,14 +308,36 @@ pub fn parse_migration_sections(_file_path: &Path, sql: &str) -> Result<Vec<Migr
             }
         } else {
             // Accumulate SQL
-            current_sql.push_str(line);
+            current_sql.push_str(substituted_lines[line_num]);
             current_sql.push('\n');
+            current_raw_sql.push_str(line);
+            current_raw_sql.push('\n');
         }
     }
 
     // Save final section
     if let Some(builder) = current_section {
-        sections.push(builder.build(current_sql)?);
+        sections.push(builder.build(&current_sql, &current_raw_sql)?);
+    }
+
+    if let Some(assertion) = file_assert_before.iter().chain(&file_assert_after).next()
+        && !sections.is_empty()
+    {
+        return Err(anyhow!(
+            "assertion at line {} comes before the first `-- pgmt:section` header; \
+             put it in the header of the section it guards",
+            assertion.line
+        ));
+    }
+
+    if let Some(line) = file_allow_line
+        && !sections.is_empty()
+    {
+        return Err(anyhow!(
+            "`-- pgmt:allow` at line {} comes before the first `-- pgmt:section` header; \
+             put it in the header of the section it applies to",
+            line
+        ));
     }
 
     // If no sections found, treat entire file as single section
@@ -192,10 +349,15 @@ pub fn parse_migration_sections(_file_path: &Path, sql: &str) -> Result<Vec<Migr
             timeout: Duration::from_secs(600), // 10 minute default
             lock_timeout: None,
             retry_config: None,
-            sql: sql.to_string(),
+            batch: None,
+            assert_before: file_assert_before,
+            assert_after: file_assert_after,
+            sql: substituted.to_string(),
+            raw_sql: (substituted != raw).then(|| raw.to_string()),
             raw_header: String::new(),
             module: None,
             remaps: None,
+            allow: file_allow,
             start_line: 1,
         });
     }
@@ -203,6 +365,33 @@ pub fn parse_migration_sections(_file_path: &Path, sql: &str) -> Result<Vec<Migr
     Ok(sections)
 }
 
+/// Prefix of a lint suppression line: `-- pgmt:allow rule-a, rule-b`.
+const ALLOW_PREFIX: &str = "-- pgmt:allow";
+
+/// Prefixes of section assertion lines: `-- pgmt:assert_before <query>`.
+const ASSERT_BEFORE_PREFIX: &str = "-- pgmt:assert_before";
+const ASSERT_AFTER_PREFIX: &str = "-- pgmt:assert_after";
+
+/// Whether `line` is the `prefix` directive (and not a longer word that
+/// starts with it).
+fn is_directive(line: &str, prefix: &str) -> bool {
+    line.trim_start()
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
+
+/// Rule IDs named on a `-- pgmt:allow` line, separated by commas and/or
+/// whitespace. Rule IDs are not validated here — the parser is shared with
+/// apply, which ignores them; `migrate lint` rejects unknown ones.
+fn parse_allow_line(line: &str) -> Vec<String> {
+    line.trim_start()
+        .trim_start_matches(ALLOW_PREFIX)
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 /// Parse section attributes from a line (supports multiple key-value pairs)
 fn parse_section_attribute(line: &str, builder: &mut SectionBuilder) -> Result<()> {
     let line = line.trim_start().trim_start_matches("-- pgmt:");
@@ -220,6 +409,8 @@ fn parse_section_attribute(line: &str, builder: &mut SectionBuilder) -> Result<(
             "retry_backoff" => builder.retry_backoff = Some(parse_backoff(&value)?),
             "on_lock_timeout" => builder.on_lock_timeout = Some(parse_lock_action(&value)?),
             "lock_timeout" => builder.lock_timeout = Some(parse_duration(&value)?),
+            "batch_sleep" => builder.batch_sleep = Some(parse_duration(&value)?),
+            "max_batches" => builder.max_batches = Some(value.parse()?),
             "module" => builder.module = Some(value),
             "remaps" => {
                 // Provenance-cut sections: a remap section is acquired from
@@ -320,6 +511,7 @@ fn parse_transaction_mode(s: &str) -> Result<TransactionMode> {
         "transactional" => Ok(TransactionMode::Transactional),
         "non-transactional" => Ok(TransactionMode::NonTransactional),
         "autocommit" => Ok(TransactionMode::Autocommit),
+        "batched" => Ok(TransactionMode::Batched),
         _ => Err(anyhow!("Unknown transaction mode: {}", s)),
     }
 }
@@ -403,8 +595,13 @@ struct SectionBuilder {
     retry_delay: Option<Duration>,
     retry_backoff: Option<BackoffStrategy>,
     on_lock_timeout: Option<LockTimeoutAction>,
+    batch_sleep: Option<Duration>,
+    max_batches: Option<u64>,
+    assert_before: Vec<SectionAssertion>,
+    assert_after: Vec<SectionAssertion>,
     module: Option<String>,
     remaps: Option<String>,
+    allow: Vec<String>,
 }
 
 impl SectionBuilder {
@@ -421,12 +618,17 @@ impl SectionBuilder {
             retry_delay: None,
             retry_backoff: None,
             on_lock_timeout: None,
+            batch_sleep: None,
+            max_batches: None,
+            assert_before: Vec::new(),
+            assert_after: Vec::new(),
             module: None,
             remaps: None,
+            allow: Vec::new(),
         }
     }
 
-    fn build(self, sql: String) -> Result<MigrationSection> {
+    fn build(self, sql: &str, raw_sql: &str) -> Result<MigrationSection> {
         let name = self.name.ok_or_else(|| {
             anyhow!(
                 "Section at line {} missing 'name' attribute",
@@ -454,16 +656,38 @@ impl SectionBuilder {
             None
         };
 
+        let batch = if mode == TransactionMode::Batched {
+            Some(BatchConfig {
+                sleep: self.batch_sleep.unwrap_or(Duration::ZERO),
+                max_batches: self.max_batches,
+            })
+        } else if self.batch_sleep.is_some() || self.max_batches.is_some() {
+            return Err(anyhow!(
+                "Section '{}' at line {} sets batch_sleep/max_batches but its mode is '{}'; \
+                 they only apply to mode=\"batched\"",
+                name,
+                self.start_line,
+                mode.as_str()
+            ));
+        } else {
+            None
+        };
+
         Ok(MigrationSection {
             name,
             description: self.description,
             module: self.module,
             remaps: self.remaps,
+            allow: self.allow,
             mode,
             timeout,
             lock_timeout: self.lock_timeout,
             retry_config,
+            batch,
+            assert_before: self.assert_before,
+            assert_after: self.assert_after,
             sql: sql.trim().to_string(),
+            raw_sql: (sql != raw_sql).then(|| raw_sql.trim().to_string()),
             raw_header: self.raw_header,
             start_line: self.start_line,
         })
@@ -629,6 +853,82 @@ ALTER TABLE users ALTER COLUMN col1 SET NOT NULL;
         assert_eq!(sections[0].timeout, Duration::from_secs(600));
     }
 
+    #[test]
+    fn test_parse_batched_section() {
+        let sql = r#"
+-- pgmt:section name="backfill" mode="batched"
+-- pgmt:  batch_sleep="500ms" max_batches="100"
+UPDATE users SET email_lower = lower(email)
+WHERE id IN (SELECT id FROM users WHERE email_lower IS NULL LIMIT 1000);
+"#;
+
+        let sections = parse_migration_sections(Path::new("test.sql"), sql).unwrap();
+        assert_eq!(sections[0].mode, TransactionMode::Batched);
+        assert_eq!(
+            sections[0].batch,
+            Some(BatchConfig {
+                sleep: Duration::from_millis(500),
+                max_batches: Some(100),
+            })
+        );
+
+        // Batch attributes on any other mode are a mistake, not ignored.
+        let sql = "-- pgmt:section name=\"s\" max_batches=\"5\"\nSELECT 1;\n";
+        let err = parse_migration_sections(Path::new("test.sql"), sql).unwrap_err();
+        assert!(err.to_string().contains("only apply to mode=\"batched\""));
+    }
+
+    #[test]
+    fn test_parse_section_assertions() {
+        let sql = r#"
+-- pgmt:section name="not_null"
+-- pgmt:assert_before SELECT count(*) = 0 FROM users WHERE email IS NULL
+-- pgmt:assert_before SELECT count(*) < ${max_rows} FROM users
+-- pgmt:assert_after SELECT attnotnull FROM pg_attribute WHERE attrelid = 'users'::regclass AND attname = 'email'
+ALTER TABLE users ALTER COLUMN email SET NOT NULL;
+"#;
+
+        let vars = Variables::new([("max_rows", "1000000")]);
+        let sections =
+            parse_migration_sections_with_variables(Path::new("test.sql"), sql, &vars).unwrap();
+        let section = &sections[0];
+        assert_eq!(
+            section.assert_before,
+            vec![
+                SectionAssertion {
+                    query: "SELECT count(*) = 0 FROM users WHERE email IS NULL".to_string(),
+                    line: 3,
+                },
+                SectionAssertion {
+                    query: "SELECT count(*) < 1000000 FROM users".to_string(),
+                    line: 4,
+                },
+            ]
+        );
+        assert_eq!(section.assert_after.len(), 1);
+        assert_eq!(
+            section.sql,
+            "ALTER TABLE users ALTER COLUMN email SET NOT NULL;"
+        );
+        // Assertion lines are header lines: checksummed as written.
+        assert!(section.raw_header.contains("${max_rows}"));
+
+        // A header-less file's assertions guard its default section...
+        let sql = "-- pgmt:assert_before SELECT true\nSELECT 1;\n";
+        let sections = parse_migration_sections(Path::new("test.sql"), sql).unwrap();
+        assert_eq!(sections[0].name, "default");
+        assert_eq!(sections[0].assert_before[0].query, "SELECT true");
+
+        // ...but in a sectioned file they must not be silently dropped.
+        let sql = "-- pgmt:assert_before SELECT true\n-- pgmt:section name=\"a\"\nSELECT 1;\n";
+        let err = parse_migration_sections(Path::new("test.sql"), sql).unwrap_err();
+        assert!(err.to_string().contains("before the first"), "{err}");
+
+        let err =
+            parse_migration_sections(Path::new("test.sql"), "-- pgmt:assert_after\n").unwrap_err();
+        assert!(err.to_string().contains("has no query"), "{err}");
+    }
+
     #[test]
     fn test_parse_section_with_description() {
         let sql = r#"
@@ -819,6 +1119,91 @@ CREATE TABLE z (id SERIAL PRIMARY KEY);
         assert!(err.contains("exactly one prior owner"), "{err}");
     }
 
+    #[test]
+    fn test_parse_allow_annotations() {
+        let sql = r#"
+-- pgmt:section name="cleanup"
+-- pgmt:allow drop-without-allow
+-- pgmt:allow missing-lock-timeout, rename-column
+DROP TABLE legacy_events;
+"#;
+
+        let sections = parse_migration_sections(Path::new("test.sql"), sql).unwrap();
+        assert_eq!(
+            sections[0].allow,
+            vec![
+                "drop-without-allow",
+                "missing-lock-timeout",
+                "rename-column"
+            ]
+        );
+        assert!(
+            sections[0]
+                .raw_header
+                .contains("-- pgmt:allow drop-without-allow")
+        );
+        assert_eq!(sections[0].sql, "DROP TABLE legacy_events;");
+    }
+
+    /// A header-less file has no header to carry `-- pgmt:allow`; file-level
+    /// lines apply to its implicit `default` section, whose body (and so its
+    /// checksum) is the whole file as before.
+    #[test]
+    fn test_parse_allow_in_headerless_file() {
+        let sql = "-- pgmt:allow drop-without-allow\nDROP TABLE legacy_events;\n";
+
+        let sections = parse_migration_sections(Path::new("test.sql"), sql).unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].name, "default");
+        assert_eq!(sections[0].allow, vec!["drop-without-allow"]);
+        assert_eq!(sections[0].checksum_content(), sql);
+
+        // In a sectioned file there is no default section to apply it to.
+        let sql = "-- pgmt:allow drop-without-allow\n-- pgmt:section name=\"a\"\nDROP TABLE t;\n";
+        let err = parse_migration_sections(Path::new("test.sql"), sql).unwrap_err();
+        assert!(err.to_string().contains("before the first"), "{err}");
+    }
+
+    /// Placeholders are substituted in the executed SQL only: checksums hash
+    /// the file as written, so it is valid in every environment.
+    #[test]
+    fn test_parse_with_variables_checksums_raw_text() {
+        let sql = r#"-- pgmt:section name="grants"
+GRANT SELECT ON users TO ${app_role};
+
+-- pgmt:section name="plain"
+SELECT 1;
+"#;
+        let dev = Variables::new([("app_role", "app")]);
+        let prod = Variables::new([("app_role", "app_rw")]);
+
+        let dev_sections =
+            parse_migration_sections_with_variables(Path::new("test.sql"), sql, &dev).unwrap();
+        let prod_sections =
+            parse_migration_sections_with_variables(Path::new("test.sql"), sql, &prod).unwrap();
+        let raw_sections = parse_migration_sections(Path::new("test.sql"), sql).unwrap();
+
+        assert_eq!(dev_sections[0].sql, "GRANT SELECT ON users TO app;");
+        assert_eq!(prod_sections[0].sql, "GRANT SELECT ON users TO app_rw;");
+        assert_eq!(
+            dev_sections[0].checksum_content(),
+            prod_sections[0].checksum_content()
+        );
+        assert_eq!(
+            dev_sections[0].checksum_content(),
+            raw_sections[0].checksum_content()
+        );
+        assert_eq!(dev_sections[1].raw_sql, None);
+
+        let err = parse_migration_sections_with_variables(
+            Path::new("test.sql"),
+            sql,
+            &Variables::new([("other", "x")]),
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("'${app_role}' at line 2"));
+    }
+
     #[test]
     fn test_parse_key_value_pairs() {
         use super::parse_key_value_pairs;
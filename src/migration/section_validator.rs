@@ -63,6 +63,17 @@ fn validate_section(section: &MigrationSection) -> Result<()> {
         }
     }
 
+    if section
+        .batch
+        .is_some_and(|batch| batch.max_batches == Some(0))
+    {
+        return Err(anyhow!(
+            "Section '{}' (line {}) has max_batches=\"0\" (must be positive)",
+            section.name,
+            section.start_line
+        ));
+    }
+
     // Validate SQL is not empty
     if section.sql.trim().is_empty() {
         return Err(anyhow!(
@@ -73,15 +84,14 @@ fn validate_section(section: &MigrationSection) -> Result<()> {
     }
 
     // Validate CONCURRENTLY operations are non-transactional
-    if section.sql.to_uppercase().contains("CONCURRENTLY")
-        && section.mode == TransactionMode::Transactional
-    {
+    if uses_concurrently_in_transaction(section) {
         return Err(anyhow!(
-            "Section '{}' (line {}) uses CONCURRENTLY but mode is 'transactional'. \
+            "Section '{}' (line {}) uses CONCURRENTLY but mode is '{}'. \
              CONCURRENTLY operations cannot run in transactions. \
              Use mode='non-transactional' instead.",
             section.name,
-            section.start_line
+            section.start_line,
+            section.mode.as_str()
         ));
     }
 
@@ -115,6 +125,17 @@ fn validate_section(section: &MigrationSection) -> Result<()> {
     Ok(())
 }
 
+/// Whether a section that runs in a transaction (transactional, or each batch
+/// of a batched one) mentions CONCURRENTLY, which PostgreSQL refuses inside a
+/// transaction block. Shared with `migrate lint`, which
+/// reports the same condition as a finding instead of an error.
+pub(crate) fn uses_concurrently_in_transaction(section: &MigrationSection) -> bool {
+    matches!(
+        section.mode,
+        TransactionMode::Transactional | TransactionMode::Batched
+    ) && section.sql.to_uppercase().contains("CONCURRENTLY")
+}
+
 /// Count case-insensitive occurrences of the literal "CONCURRENTLY" in `sql`.
 /// A lint heuristic only (see the call site): pgmt never parses user SQL, so
 /// this is a plain substring count, not a statement count.
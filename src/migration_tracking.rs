@@ -1,4 +1,5 @@
 pub mod advisory_lock;
+pub mod audit;
 pub mod section_tracking;
 pub mod store;
 
@@ -7,8 +8,9 @@ use anyhow::{Context, Result};
 use sqlx::PgPool;
 
 pub use advisory_lock::MigrationLock;
+pub use audit::AuditLog;
 pub use section_tracking::{ensure_section_tracking_table, initialize_sections};
-pub use store::TrackingStore;
+pub use store::{HistoryEntry, HistoryRecord, SectionDetail, TrackingRows, TrackingStore};
 
 /// Safely convert migration version from u64 to i64 for database storage
 /// Migration versions are Unix timestamps, which will exceed i64::MAX around year 2262
@@ -115,6 +117,40 @@ pub async fn ensure_tracking_table_exists(
     Ok(())
 }
 
+/// Create the repeatable-migration tracking table (`{name}_repeatable`) next
+/// to the main tracking table: one row per repeatable file, holding the
+/// checksum of the content last executed. `migrate apply` re-runs a file
+/// whenever its current checksum differs from the recorded one.
+pub async fn ensure_repeatable_tracking_table(
+    pool: &PgPool,
+    tracking_table: &TrackingTable,
+) -> Result<()> {
+    // Validates the identifiers; the suffixed name shares them.
+    format_tracking_table_name(tracking_table)?;
+    let repeatable_table = format!(
+        r#""{}"."{}_repeatable""#,
+        tracking_table.schema, tracking_table.name
+    );
+
+    sqlx::query(sqlx::AssertSqlSafe(format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS {} (
+            path TEXT PRIMARY KEY,
+            checksum TEXT NOT NULL,
+            applied_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+            applied_by TEXT DEFAULT CURRENT_USER,
+            duration_ms BIGINT
+        )
+        "#,
+        repeatable_table
+    )))
+    .execute(pool)
+    .await
+    .with_context(|| format!("Failed to create tracking table {}", repeatable_table))?;
+
+    Ok(())
+}
+
 /// Columns of a table's primary key, in index order. Empty if the table has
 /// no primary key. `qualified_name` must be a safely-quoted qualified name
 /// (from `format_tracking_table_name` or equivalent).
@@ -270,7 +306,7 @@ async fn migrate_tracking_table_schema(
 /// NOTHING`, so a resumed run preserves any rows a prior attempt left.
 ///
 /// `on_conflict_ignore` governs the MAIN-row insert only, and is the sole
-/// behavioural difference between the two public wrappers: a baseline
+/// behavioural difference between the two start wrappers: a baseline
 /// re-registers harmlessly (a resumed provision or a later module adoption
 /// touches the same version), so it ignores the conflict; a migration inserts
 /// exactly once.
@@ -301,6 +337,26 @@ async fn register_start(
     pool: &PgPool,
     tracking_table: &TrackingTable,
     params: RegisterStart<'_>,
+) -> Result<()> {
+    let kind = if params.is_baseline {
+        "baseline"
+    } else {
+        "migration"
+    };
+    let version = params.version;
+    let mut tx = pool.begin().await?;
+    register_in(&mut tx, tracking_table, params).await?;
+    tx.commit()
+        .await
+        .with_context(|| format!("Failed to register {} {} start", kind, version))?;
+    Ok(())
+}
+
+/// The statements of [`register_start`], on the caller's transaction.
+async fn register_in(
+    tx: &mut sqlx::PgConnection,
+    tracking_table: &TrackingTable,
+    params: RegisterStart<'_>,
 ) -> Result<()> {
     let RegisterStart {
         version,
@@ -320,7 +376,6 @@ async fn register_start(
         ""
     };
 
-    let mut tx = pool.begin().await?;
     sqlx::query(sqlx::AssertSqlSafe(format!(
         "INSERT INTO {} (version, description, checksum, is_baseline) VALUES ($1, $2, $3, $4){}",
         tracking_table_name, conflict
@@ -349,7 +404,7 @@ async fn register_start(
     // main row never commits without them.
     for (order, section) in satisfied {
         section_tracking::insert_satisfied_section(
-            &mut tx,
+            &mut *tx,
             &sections_table,
             version,
             is_baseline,
@@ -359,10 +414,6 @@ async fn register_start(
         .await?;
     }
 
-    tx.commit()
-        .await
-        .with_context(|| format!("Failed to register {} {} start", kind, version))?;
-
     Ok(())
 }
 
@@ -426,6 +477,36 @@ pub async fn register_migration_start(
     .await
 }
 
+/// Record a baseline or migration as already applied by another tool: the
+/// main row plus every section `satisfied`, on the caller's transaction so a
+/// whole adoption lands at once. Nothing runs — the caller has checked the
+/// target already holds what the file describes (`pgmt init --adopt-from`).
+/// See [`register_start`].
+pub async fn register_adopted(
+    tx: &mut sqlx::PgConnection,
+    tracking_table: &TrackingTable,
+    version: u64,
+    description: &str,
+    checksum: &str,
+    is_baseline: bool,
+    sections: &[(i32, crate::migration::section_parser::MigrationSection)],
+) -> Result<()> {
+    register_in(
+        tx,
+        tracking_table,
+        RegisterStart {
+            version,
+            description,
+            checksum,
+            sections: &[],
+            satisfied: sections,
+            is_baseline,
+            on_conflict_ignore: false,
+        },
+    )
+    .await
+}
+
 /// Calculate checksum for migration content
 pub fn calculate_checksum(content: &str) -> String {
     format!("{:x}", md5::compute(content))
@@ -0,0 +1,142 @@
+//! The opt-in audit log (`migration.audit: true`).
+//!
+//! The sections table keeps only each section's latest state. With auditing
+//! on, every attempt also gets a row in `{name}_history`: the SQL that ran, the
+//! session settings it ran under, who ran it from where (database role, client
+//! hostname, pgmt version, `--deploy-id`) and how it ended. `migrate resolve`
+//! actions are recorded there too, so `migrate history` can show a failed
+//! section that was later marked completed by hand.
+//!
+//! The SQL is stored after `${var}` substitution, exactly as it ran — keep
+//! secrets out of variables used in migrations when auditing is on.
+
+use crate::config::Config;
+use crate::config::types::TrackingTable;
+use crate::migration::section_parser::MigrationSection;
+use crate::migration_tracking::section_tracking::SectionStatus;
+use crate::migration_tracking::{HistoryRecord, TrackingStore};
+use anyhow::Result;
+use serde_json::json;
+use sqlx::PgPool;
+
+const PGMT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    tracking_table: TrackingTable,
+    deploy_id: Option<String>,
+    client_hostname: Option<String>,
+}
+
+impl AuditLog {
+    /// The audit log for this run, or `None` when `migration.audit` is off.
+    pub fn from_config(config: &Config, deploy_id: Option<String>) -> Option<Self> {
+        if !config.migration.audit {
+            if deploy_id.is_some() {
+                eprintln!(
+                    "Warning: --deploy-id is only recorded with `migration.audit: true`; \
+                     ignoring it"
+                );
+            }
+            return None;
+        }
+        Some(Self {
+            tracking_table: config.migration.tracking_table.clone(),
+            deploy_id,
+            client_hostname: client_hostname(),
+        })
+    }
+
+    pub async fn ensure_table(&self, pool: &PgPool) -> Result<()> {
+        self.store(pool)?.ensure_history_table().await
+    }
+
+    /// Record that `section` is about to run — a down section when
+    /// `rollback`; returns the history row id to pass to
+    /// [`Self::finish_attempt`].
+    pub async fn start_attempt(
+        &self,
+        pool: &PgPool,
+        version: u64,
+        is_baseline: bool,
+        section: &MigrationSection,
+        rollback: bool,
+    ) -> Result<i64> {
+        let settings = json!({
+            "mode": section.mode.as_str(),
+            "statement_timeout_ms": section.timeout.as_millis() as u64,
+            "lock_timeout_ms": section.lock_timeout.map(|t| t.as_millis() as u64),
+        })
+        .to_string();
+        let record = HistoryRecord {
+            sql: Some(&section.sql),
+            settings: Some(&settings),
+            ..self.record(&section.name)
+        };
+        self.store(pool)?
+            .record_attempt_start(
+                version,
+                is_baseline,
+                if rollback { "rollback" } else { "attempt" },
+                &record,
+            )
+            .await
+    }
+
+    pub async fn finish_attempt(&self, pool: &PgPool, id: i64, result: &Result<()>) -> Result<()> {
+        let (status, error) = match result {
+            Ok(()) => (SectionStatus::Completed, None),
+            Err(e) => (SectionStatus::Failed, Some(format!("{:#}", e))),
+        };
+        self.store(pool)?
+            .record_attempt_end(id, status, error.as_deref())
+            .await
+    }
+
+    /// Record a `migrate resolve` action on a section.
+    pub async fn record_resolution(
+        &self,
+        pool: &PgPool,
+        version: u64,
+        is_baseline: bool,
+        section_name: &str,
+        event: &str,
+        status: &str,
+    ) -> Result<()> {
+        let store = self.store(pool)?;
+        store.ensure_history_table().await?;
+        store
+            .record_resolution(
+                version,
+                is_baseline,
+                event,
+                status,
+                &self.record(section_name),
+            )
+            .await
+    }
+
+    fn store(&self, pool: &PgPool) -> Result<TrackingStore> {
+        TrackingStore::new(pool, &self.tracking_table)
+    }
+
+    fn record<'a>(&'a self, section_name: &'a str) -> HistoryRecord<'a> {
+        HistoryRecord {
+            section_name,
+            client_hostname: self.client_hostname.as_deref(),
+            pgmt_version: PGMT_VERSION,
+            deploy_id: self.deploy_id.as_deref(),
+            ..Default::default()
+        }
+    }
+}
+
+/// This machine's hostname: `$HOSTNAME` when the shell exports it, otherwise
+/// the kernel's (Linux) — enough to tell CI runners and pods apart.
+fn client_hostname() -> Option<String> {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
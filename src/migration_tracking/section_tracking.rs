@@ -760,6 +760,37 @@ pub async fn record_section_complete<'e>(
     Ok(())
 }
 
+/// Add one batch's row count to a batched section's running total and return
+/// the new total. Called inside the batch's own transaction, so the total
+/// commits with the rows it counts and stays exact across interruptions; a
+/// resumed run keeps adding to it.
+pub async fn record_batch_progress<'e>(
+    executor: impl sqlx::PgExecutor<'e>,
+    tracking_table: &TrackingTable,
+    migration_version: u64,
+    is_baseline: bool,
+    section_name: &str,
+    rows_affected: i64,
+) -> Result<i64> {
+    let sections_table = format_sections_table_name(tracking_table);
+
+    let total: i64 = sqlx::query_scalar(sqlx::AssertSqlSafe(format!(
+        "UPDATE {}
+         SET rows_affected = COALESCE(rows_affected, 0) + $1
+         WHERE migration_version = $2 AND is_baseline = $3 AND section_name = $4
+         RETURNING rows_affected",
+        sections_table
+    )))
+    .bind(rows_affected)
+    .bind(version_to_db(migration_version)?)
+    .bind(is_baseline)
+    .bind(section_name)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(total)
+}
+
 /// Record section failure
 pub async fn record_section_failed(
     pool: &PgPool,
@@ -13,6 +13,10 @@
 //!   row per section with its per-section lifecycle status.
 //! - `{name}_modules` — the module subscription: which modules this target
 //!   has established.
+//! - `{name}_repeatable` — the checksum each repeatable migration last ran
+//!   with.
+//! - `{name}_history` — only with `migration.audit`: one row per section
+//!   attempt or `migrate resolve` action, never updated once finished.
 //!
 //! The re-anchor consumption cursor is NOT a stored value: it is derived as
 //! the highest baseline version in the main table (provision-applied and
@@ -57,6 +61,10 @@ pub struct TrackingStore {
     sections: String,
     /// `"schema"."name_modules"` — the stored module subscription.
     modules: String,
+    /// `"schema"."name_repeatable"` — last-run checksums of repeatable files.
+    repeatable: String,
+    /// `"schema"."name_history"` — the opt-in audit log.
+    history: String,
 }
 
 impl TrackingStore {
@@ -76,6 +84,8 @@ impl TrackingStore {
             main,
             sections: suffixed("sections"),
             modules: suffixed("modules"),
+            repeatable: suffixed("repeatable"),
+            history: suffixed("history"),
         })
     }
 
@@ -96,6 +106,16 @@ impl TrackingStore {
         &self.sections
     }
 
+    /// `"schema"."name_repeatable"`.
+    pub fn repeatable_table(&self) -> &str {
+        &self.repeatable
+    }
+
+    /// `"schema"."name_history"`.
+    pub fn history_table(&self) -> &str {
+        &self.history
+    }
+
     /// THE covered-status predicate: a section whose objects are present here,
     /// whether it executed (`completed`) or was covered by an established
     /// source (`satisfied`). `col` is the (optionally alias-qualified) status
@@ -306,6 +326,48 @@ impl TrackingStore {
             .collect())
     }
 
+    /// Every recorded section row with its execution record, ordered by
+    /// coordinate and section order — the per-section detail of `migrate
+    /// status --format json`. Status is read-only and never evolves the
+    /// table, so the columns later pgmt versions added are read through
+    /// `to_jsonb` and come back `None` on an older table instead of failing.
+    pub async fn section_details(&self) -> Result<Vec<SectionDetail>> {
+        let rows = sqlx::query_as(sqlx::AssertSqlSafe(format!(
+            "SELECT migration_version, is_baseline, section_name, status,
+                    j ->> 'module' AS module,
+                    j ->> 'mode' AS mode,
+                    (j ->> 'attempts')::INT AS attempts,
+                    started_at::TEXT AS started_at,
+                    completed_at::TEXT AS completed_at,
+                    (j ->> 'duration_ms')::BIGINT AS duration_ms,
+                    (j ->> 'rows_affected')::BIGINT AS rows_affected,
+                    j ->> 'last_error' AS last_error,
+                    j ->> 'checksum' AS checksum
+             FROM {} s, to_jsonb(s) j
+             ORDER BY migration_version, is_baseline DESC, section_order",
+            self.sections
+        )))
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to read the section tracking rows")?;
+        Ok(rows)
+    }
+
+    /// The whole-file checksum stored for each recorded migration (not
+    /// baseline) version.
+    pub async fn migration_checksums(&self) -> Result<std::collections::BTreeMap<u64, String>> {
+        let rows: Vec<(i64, String)> = sqlx::query_as(sqlx::AssertSqlSafe(format!(
+            "SELECT version, checksum FROM {} WHERE NOT is_baseline",
+            self.main
+        )))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(v, checksum)| (version_from_db(v), checksum))
+            .collect())
+    }
+
     /// Every recorded section row as `(owning module literal, is_baseline,
     /// status)` — the input to the per-module status rollup. Reads the stored
     /// `module` column (authoritative), so `None` is a base section.
@@ -475,6 +537,221 @@ impl TrackingStore {
         Ok(())
     }
 
+    /// Forget a rolled-back migration: delete its section rows and its main
+    /// row in ONE transaction, so the version reads as never applied (pending)
+    /// and a later `migrate apply` re-registers it from scratch. Baseline rows
+    /// at the same version are untouched.
+    pub async fn remove_migration(&self, version: u64) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(sqlx::AssertSqlSafe(format!(
+            "DELETE FROM {} WHERE migration_version = $1 AND NOT is_baseline",
+            self.sections
+        )))
+        .bind(version_to_db(version)?)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(sqlx::AssertSqlSafe(format!(
+            "DELETE FROM {} WHERE version = $1 AND NOT is_baseline",
+            self.main
+        )))
+        .bind(version_to_db(version)?)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit()
+            .await
+            .with_context(|| format!("Failed to remove tracking rows for migration {version}"))?;
+        Ok(())
+    }
+
+    /// The checksum each repeatable migration last ran with, keyed by its path
+    /// relative to the repeatable directory.
+    pub async fn repeatable_checksums(&self) -> Result<std::collections::BTreeMap<String, String>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(sqlx::AssertSqlSafe(format!(
+            "SELECT path, checksum FROM {}",
+            self.repeatable
+        )))
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to read the repeatable migration checksums")?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Create the audit history table if absent. Only `migration.audit` runs
+    /// create it. The id is an identity column rather than a `BIGSERIAL`, so
+    /// no standalone sequence shows up in a drift diff.
+    pub async fn ensure_history_table(&self) -> Result<()> {
+        sqlx::query(sqlx::AssertSqlSafe(format!(
+            r#"CREATE TABLE IF NOT EXISTS {history} (
+                id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                migration_version BIGINT NOT NULL,
+                is_baseline BOOLEAN NOT NULL,
+                section_name TEXT NOT NULL,
+                event TEXT NOT NULL,
+                status TEXT NOT NULL,
+                attempt INT,
+                started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                finished_at TIMESTAMP WITH TIME ZONE,
+                duration_ms BIGINT,
+                rows_affected BIGINT,
+                error TEXT,
+                sql TEXT,
+                settings JSONB,
+                applied_by TEXT NOT NULL DEFAULT CURRENT_USER,
+                client_hostname TEXT,
+                pgmt_version TEXT,
+                deploy_id TEXT
+            )"#,
+            history = self.history
+        )))
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("Failed to create history table {}", self.history))?;
+
+        Ok(())
+    }
+
+    /// Open a history row for a section attempt that is about to run and
+    /// return its id. Committed on its own, so an attempt that never finishes
+    /// (a crash, a killed deploy) stays visible as `running`. `event` is
+    /// `attempt`, or `rollback` for a down section.
+    pub async fn record_attempt_start(
+        &self,
+        version: u64,
+        is_baseline: bool,
+        event: &str,
+        entry: &HistoryRecord<'_>,
+    ) -> Result<i64> {
+        let id = sqlx::query_scalar(sqlx::AssertSqlSafe(format!(
+            "INSERT INTO {} (migration_version, is_baseline, section_name, event, status,
+                             sql, settings, client_hostname, pgmt_version, deploy_id)
+             VALUES ($1, $2, $3, $4, 'running', $5, $6::JSONB, $7, $8, $9)
+             RETURNING id",
+            self.history
+        )))
+        .bind(version_to_db(version)?)
+        .bind(is_baseline)
+        .bind(entry.section_name)
+        .bind(event)
+        .bind(entry.sql)
+        .bind(entry.settings)
+        .bind(entry.client_hostname)
+        .bind(entry.pgmt_version)
+        .bind(entry.deploy_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to record the section attempt in the history table")?;
+        Ok(id)
+    }
+
+    /// Close the history row `id` with the attempt's outcome. For an
+    /// `attempt`, the attempt number, duration and row count are copied from
+    /// the section row, which the executor has just written; a `rollback` has
+    /// no section row of its own and leaves them empty.
+    pub async fn record_attempt_end(
+        &self,
+        id: i64,
+        status: SectionStatus,
+        error: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(sqlx::AssertSqlSafe(format!(
+            "UPDATE {history} h
+             SET status = $2, error = $3, finished_at = CURRENT_TIMESTAMP,
+                 attempt = s.attempts, duration_ms = s.duration_ms,
+                 rows_affected = s.rows_affected
+             FROM {history} cur
+             LEFT JOIN {sections} s
+               ON cur.event = 'attempt'
+              AND s.migration_version = cur.migration_version
+              AND s.is_baseline = cur.is_baseline
+              AND s.section_name = cur.section_name
+             WHERE h.id = $1 AND cur.id = h.id",
+            history = self.history,
+            sections = self.sections,
+        )))
+        .bind(id)
+        .bind(status.as_str())
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record the section outcome in the history table")?;
+        Ok(())
+    }
+
+    /// Record a `migrate resolve` action (`event`) that left the section in
+    /// `status`.
+    pub async fn record_resolution(
+        &self,
+        version: u64,
+        is_baseline: bool,
+        event: &str,
+        status: &str,
+        entry: &HistoryRecord<'_>,
+    ) -> Result<()> {
+        sqlx::query(sqlx::AssertSqlSafe(format!(
+            "INSERT INTO {} (migration_version, is_baseline, section_name, event, status,
+                             finished_at, client_hostname, pgmt_version, deploy_id)
+             VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP, $6, $7, $8)",
+            self.history
+        )))
+        .bind(version_to_db(version)?)
+        .bind(is_baseline)
+        .bind(entry.section_name)
+        .bind(event)
+        .bind(status)
+        .bind(entry.client_hostname)
+        .bind(entry.pgmt_version)
+        .bind(entry.deploy_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record the resolution in the history table")?;
+        Ok(())
+    }
+
+    /// The history rows, oldest first, optionally for one version.
+    pub async fn history(&self, version: Option<u64>) -> Result<Vec<HistoryEntry>> {
+        let version = version.map(version_to_db).transpose()?;
+        let rows = sqlx::query_as(sqlx::AssertSqlSafe(format!(
+            "SELECT migration_version, is_baseline, section_name, event, status, attempt,
+                    started_at::TEXT AS started_at,
+                    duration_ms, rows_affected, error, sql, settings::TEXT AS settings,
+                    applied_by, client_hostname, pgmt_version, deploy_id
+             FROM {}
+             WHERE $1::BIGINT IS NULL OR migration_version = $1
+             ORDER BY id",
+            self.history
+        )))
+        .bind(version)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to read the history table")?;
+        Ok(rows)
+    }
+
+    /// Record that the repeatable migration at `path` ran with `checksum`.
+    /// Runs on the caller's executor so the row commits with the file's SQL.
+    pub async fn record_repeatable_applied<'e>(
+        &self,
+        executor: impl sqlx::PgExecutor<'e>,
+        path: &str,
+        checksum: &str,
+        duration_ms: i64,
+    ) -> Result<()> {
+        sqlx::query(sqlx::AssertSqlSafe(format!(
+            "INSERT INTO {} (path, checksum, duration_ms) VALUES ($1, $2, $3)
+             ON CONFLICT (path) DO UPDATE
+             SET checksum = EXCLUDED.checksum, duration_ms = EXCLUDED.duration_ms,
+                 applied_at = CURRENT_TIMESTAMP, applied_by = CURRENT_USER",
+            self.repeatable
+        )))
+        .bind(path)
+        .bind(checksum)
+        .bind(duration_ms)
+        .execute(executor)
+        .await
+        .with_context(|| format!("Failed to record repeatable migration '{path}'"))?;
+        Ok(())
+    }
+
     /// Whether the subscription tables exist, without creating them (the
     /// read-only `migrate status` path must not evolve the schema).
     pub async fn subscription_tables_exist(&self) -> Result<bool> {
@@ -541,4 +818,121 @@ impl TrackingStore {
         .with_context(|| format!("Failed to unsubscribe module '{module}'"))?;
         Ok(())
     }
+
+    /// Every row of each tracking table that exists here, as JSON, so another
+    /// database can be given the same ledger with [`Self::import_rows`]
+    /// (`migrate apply --rehearse` copies the target's onto its branch).
+    /// Read-only: absent tables are `None`, not created.
+    pub async fn export_rows(&self) -> Result<TrackingRows> {
+        let export = async |table: &str| -> Result<Option<String>> {
+            let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+                .bind(table)
+                .fetch_one(&self.pool)
+                .await?;
+            if !exists {
+                return Ok(None);
+            }
+            let rows: String = sqlx::query_scalar(sqlx::AssertSqlSafe(format!(
+                "SELECT COALESCE(json_agg(t), '[]')::text FROM {table} t"
+            )))
+            .fetch_one(&self.pool)
+            .await
+            .with_context(|| format!("Failed to read {table}"))?;
+            Ok(Some(rows))
+        };
+        Ok(TrackingRows {
+            main: export(&self.main).await?,
+            sections: export(&self.sections).await?,
+            modules: export(&self.modules).await?,
+            repeatable: export(&self.repeatable).await?,
+        })
+    }
+
+    /// Insert rows produced by [`Self::export_rows`] on another database.
+    /// Every table present in `rows` must already exist here; columns are
+    /// matched by name, so a ledger written by an older pgmt imports cleanly.
+    pub async fn import_rows(&self, rows: &TrackingRows) -> Result<()> {
+        let tables = [
+            (&self.main, &rows.main),
+            (&self.sections, &rows.sections),
+            (&self.modules, &rows.modules),
+            (&self.repeatable, &rows.repeatable),
+        ];
+        let mut tx = self.pool.begin().await?;
+        for (table, rows) in tables {
+            let Some(rows) = rows else { continue };
+            sqlx::query(sqlx::AssertSqlSafe(format!(
+                "INSERT INTO {table} SELECT * FROM json_populate_recordset(NULL::{table}, $1::json)"
+            )))
+            .bind(rows)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to copy rows into {table}"))?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// One recorded section row with its execution record, from
+/// [`TrackingStore::section_details`]. Timestamps are PostgreSQL's text form.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SectionDetail {
+    pub migration_version: i64,
+    pub is_baseline: bool,
+    pub section_name: String,
+    pub status: String,
+    pub module: Option<String>,
+    pub mode: Option<String>,
+    pub attempts: Option<i32>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub rows_affected: Option<i64>,
+    pub last_error: Option<String>,
+    pub checksum: Option<String>,
+}
+
+/// What the audit log records about who ran something, and for a section
+/// attempt, what ran. See [`TrackingStore::record_attempt_start`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HistoryRecord<'a> {
+    pub section_name: &'a str,
+    pub sql: Option<&'a str>,
+    /// JSON text
+    pub settings: Option<&'a str>,
+    pub client_hostname: Option<&'a str>,
+    pub pgmt_version: &'a str,
+    pub deploy_id: Option<&'a str>,
+}
+
+/// One row of the audit history table, from [`TrackingStore::history`].
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct HistoryEntry {
+    pub migration_version: i64,
+    pub is_baseline: bool,
+    pub section_name: String,
+    pub event: String,
+    pub status: String,
+    pub attempt: Option<i32>,
+    pub started_at: String,
+    pub duration_ms: Option<i64>,
+    pub rows_affected: Option<i64>,
+    pub error: Option<String>,
+    pub sql: Option<String>,
+    pub settings: Option<String>,
+    pub applied_by: String,
+    pub client_hostname: Option<String>,
+    pub pgmt_version: Option<String>,
+    pub deploy_id: Option<String>,
+}
+
+/// A copy of a database's tracking tables, from [`TrackingStore::export_rows`]:
+/// each table's rows as a JSON array, or `None` where the table doesn't exist.
+#[derive(Debug, Clone, Default)]
+pub struct TrackingRows {
+    pub main: Option<String>,
+    pub sections: Option<String>,
+    pub modules: Option<String>,
+    pub repeatable: Option<String>,
 }
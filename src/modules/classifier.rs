@@ -206,10 +206,15 @@ mod tests {
             timeout: Duration::from_secs(30),
             lock_timeout: None,
             retry_config: None,
+            batch: None,
+            assert_before: Vec::new(),
+            assert_after: Vec::new(),
             sql: format!("-- {name}"),
+            raw_sql: None,
             raw_header: format!("-- pgmt:section name={name}"),
             module: module.map(str::to_string),
             remaps: remaps.map(str::to_string),
+            allow: Vec::new(),
             start_line: 1,
         }
     }
@@ -10,6 +10,7 @@ use crate::catalog::Catalog;
 use crate::catalog::file_dependencies::FileToObjectMapping;
 use crate::catalog::id::DbObjectId;
 use crate::config::Config;
+use crate::migrate::SEED_DATA_SECTION;
 use anyhow::Result;
 use std::collections::BTreeMap;
 
@@ -90,8 +91,12 @@ pub(crate) fn provenance_cut_baseline_sections(
                 }
                 _ => None,
             };
+            let same_kind =
+                |last: &Run| last.steps.last().map(MigrationStep::is_data) == Some(step.is_data());
             match runs.last_mut() {
-                Some(last) if last.module == own && last.remap == remap => last.steps.push(step),
+                Some(last) if last.module == own && last.remap == remap && same_kind(last) => {
+                    last.steps.push(step)
+                }
                 _ => runs.push(Run {
                     module: own.clone(),
                     remap,
@@ -117,11 +122,16 @@ pub(crate) fn provenance_cut_baseline_sections(
 }
 
 /// Assign section names in place: a module's first section is the module name
-/// (`default` for the base), later ones get `_2`, `_3`, … in file order.
+/// (`default` for the base), later ones get `_2`, `_3`, … in file order. The
+/// seed data section keeps its own name.
 pub(crate) fn assign_section_names(sections: &mut [StepSection]) {
     let mut name_counts: BTreeMap<String, usize> = BTreeMap::new();
     for section in sections.iter_mut() {
-        let base_name = section.module.as_deref().unwrap_or("default").to_string();
+        let base_name = if is_seed_data_section(section) {
+            SEED_DATA_SECTION.to_string()
+        } else {
+            section.module.as_deref().unwrap_or("default").to_string()
+        };
         let count = name_counts.entry(base_name.clone()).or_insert(0);
         *count += 1;
         section.name = if *count == 1 {
@@ -132,6 +142,10 @@ pub(crate) fn assign_section_names(sections: &mut [StepSection]) {
     }
 }
 
+fn is_seed_data_section(section: &StepSection) -> bool {
+    !section.steps.is_empty() && section.steps.iter().all(|step| step.is_data())
+}
+
 /// Section a diff into module-tagged sections. Attribute each step to its
 /// owning module, run THE planning pipeline once (`diff::plan_annotated`:
 /// diff → cascade → coalesce → annotate → module-affinity order), and cut the
@@ -230,6 +244,11 @@ pub fn sectionize_steps(
 
     let ordered = crate::diff::plan_annotated(old_catalog, new_catalog, &mut module_of)?;
 
+    // Seed data belongs to no module: it gets a base section of its own after
+    // everything else, which its dependencies (all schema steps) allow.
+    let (data, ordered): (Vec<_>, Vec<_>) =
+        ordered.into_iter().partition(|node| node.step.is_data());
+
     // Cut the (now maximally contiguous) order at module boundaries.
     let mut sections: Vec<StepSection> = Vec::new();
     for node in ordered {
@@ -244,6 +263,15 @@ pub fn sectionize_steps(
             }),
         }
     }
+    if !data.is_empty() {
+        sections.push(StepSection {
+            name: String::new(),
+            module: None,
+            remaps: None,
+            comment: None,
+            steps: data.into_iter().map(|node| node.step).collect(),
+        });
+    }
     assign_section_names(&mut sections);
 
     Ok(sections)
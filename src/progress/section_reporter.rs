@@ -45,6 +45,15 @@ impl SectionReporter {
         }
     }
 
+    pub fn batch(&self, number: u64, rows: u64, total: u64) {
+        println!(
+            "    Batch {}: {} rows ({} total)",
+            number,
+            rows,
+            style(total).cyan()
+        );
+    }
+
     pub fn retry(&self, _name: &str, attempt: u32, error: &anyhow::Error, delay: Duration) {
         let delay_str = format_duration(delay);
         println!(
@@ -107,6 +116,25 @@ impl SectionReporter {
         println!("{} Failed: {}", style("✗").red(), style(&err_str).red());
     }
 
+    pub fn complete_hook(&self, name: &str, duration: Duration) {
+        println!(
+            "  {} Hook {} ({})",
+            style("✓").green(),
+            name,
+            format_duration(duration)
+        );
+    }
+
+    pub fn fail_hook(&self, name: &str, error: &anyhow::Error) {
+        let err_str = error.to_string();
+        println!(
+            "  {} Hook {} failed: {}",
+            style("✗").red(),
+            name,
+            style(&err_str).red()
+        );
+    }
+
     pub fn migration_summary(&self, total_duration: Duration, _sections_completed: usize) {
         let duration_str = format_duration(total_duration);
         println!(
@@ -80,7 +80,10 @@ fn comment_keyword(target: &AttrTarget) -> &'static str {
         DbObjectId::Policy { .. } => "POLICY",
         DbObjectId::Extension { .. } => "EXTENSION",
         // Not used as comment targets; fall back to a neutral keyword.
-        DbObjectId::Grant { .. } | DbObjectId::Comment { .. } | DbObjectId::Column { .. } => "",
+        DbObjectId::Grant { .. }
+        | DbObjectId::Comment { .. }
+        | DbObjectId::Column { .. }
+        | DbObjectId::TableData { .. } => "",
     }
 }
 
@@ -160,9 +163,10 @@ fn comment_reference(target: &AttrTarget) -> String {
             quote_ident(schema),
             quote_ident(table)
         ),
-        DbObjectId::Grant { .. } | DbObjectId::Comment { .. } | DbObjectId::Column { .. } => {
-            String::new()
-        }
+        DbObjectId::Grant { .. }
+        | DbObjectId::Comment { .. }
+        | DbObjectId::Column { .. }
+        | DbObjectId::TableData { .. } => String::new(),
     }
 }
 
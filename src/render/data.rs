@@ -0,0 +1,195 @@
+//! SQL rendering for seed data operations
+
+use crate::catalog::id::DbObjectId;
+use crate::diff::operations::DataOperation;
+use crate::render::{RenderedSql, Safety, SqlRenderer, escape_string, quote_ident};
+
+impl SqlRenderer for DataOperation {
+    fn to_sql(&self) -> Vec<RenderedSql> {
+        let (schema, table) = self.table();
+        let relation = format!("{}.{}", quote_ident(schema), quote_ident(table));
+        match self {
+            DataOperation::Insert { columns, rows, .. } => {
+                let values: Vec<String> = rows
+                    .iter()
+                    .map(|row| format!("    ({})", literal_list(row)))
+                    .collect();
+                vec![RenderedSql::new(format!(
+                    "INSERT INTO {} ({}) VALUES\n{};",
+                    relation,
+                    ident_list(columns),
+                    values.join(",\n")
+                ))]
+            }
+            DataOperation::Update {
+                key_columns, rows, ..
+            } => rows
+                .iter()
+                .map(|row| {
+                    let set: Vec<String> = row
+                        .set
+                        .iter()
+                        .map(|(column, value)| {
+                            format!("{} = {}", quote_ident(column), literal(value))
+                        })
+                        .collect();
+                    RenderedSql::new(format!(
+                        "UPDATE {} SET {} WHERE {};",
+                        relation,
+                        set.join(", "),
+                        key_condition(key_columns, &row.key)
+                    ))
+                })
+                .collect(),
+            DataOperation::Delete {
+                key_columns, keys, ..
+            } => {
+                let condition = if key_columns.len() == 1 {
+                    let values: Vec<String> = keys.iter().map(|key| literal(&key[0])).collect();
+                    format!(
+                        "{} IN ({})",
+                        quote_ident(&key_columns[0]),
+                        values.join(", ")
+                    )
+                } else {
+                    let values: Vec<String> = keys
+                        .iter()
+                        .map(|key| format!("({})", literal_list(key)))
+                        .collect();
+                    format!("({}) IN ({})", ident_list(key_columns), values.join(", "))
+                };
+                vec![RenderedSql {
+                    sql: format!("DELETE FROM {} WHERE {};", relation, condition),
+                    safety: Safety::Destructive,
+                }]
+            }
+        }
+    }
+
+    fn db_object_id(&self) -> DbObjectId {
+        let (schema, table) = self.table();
+        DbObjectId::TableData {
+            schema: schema.to_string(),
+            table: table.to_string(),
+        }
+    }
+}
+
+fn literal(value: &Option<String>) -> String {
+    match value {
+        Some(value) => escape_string(value),
+        None => "NULL".to_string(),
+    }
+}
+
+fn literal_list(values: &[Option<String>]) -> String {
+    values.iter().map(literal).collect::<Vec<_>>().join(", ")
+}
+
+fn ident_list(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|column| quote_ident(column))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn key_condition(key_columns: &[String], key: &[Option<String>]) -> String {
+    key_columns
+        .iter()
+        .zip(key)
+        .map(|(column, value)| format!("{} = {}", quote_ident(column), literal(value)))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::operations::RowUpdate;
+
+    fn text(value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    #[test]
+    fn test_render_insert() {
+        let operation = DataOperation::Insert {
+            schema: "public".to_string(),
+            table: "countries".to_string(),
+            columns: vec!["code".to_string(), "name".to_string()],
+            rows: vec![
+                vec![text("FR"), text("France")],
+                vec![text("CI"), text("Côte d'Ivoire")],
+                vec![text("XX"), None],
+            ],
+        };
+        let rendered = operation.to_sql();
+        assert_eq!(rendered.len(), 1);
+        assert_eq!(
+            rendered[0].sql,
+            "INSERT INTO \"public\".\"countries\" (\"code\", \"name\") VALUES\n    \
+             ('FR', 'France'),\n    ('CI', 'Côte d''Ivoire'),\n    ('XX', NULL);"
+        );
+        assert_eq!(rendered[0].safety, Safety::Safe);
+    }
+
+    #[test]
+    fn test_render_update_one_statement_per_row() {
+        let operation = DataOperation::Update {
+            schema: "public".to_string(),
+            table: "plans".to_string(),
+            key_columns: vec!["id".to_string()],
+            rows: vec![
+                RowUpdate {
+                    key: vec![text("1")],
+                    set: vec![("price".to_string(), text("10"))],
+                },
+                RowUpdate {
+                    key: vec![text("2")],
+                    set: vec![
+                        ("name".to_string(), text("Pro")),
+                        ("price".to_string(), None),
+                    ],
+                },
+            ],
+        };
+        let rendered = operation.to_sql();
+        assert_eq!(rendered.len(), 2);
+        assert_eq!(
+            rendered[0].sql,
+            "UPDATE \"public\".\"plans\" SET \"price\" = '10' WHERE \"id\" = '1';"
+        );
+        assert_eq!(
+            rendered[1].sql,
+            "UPDATE \"public\".\"plans\" SET \"name\" = 'Pro', \"price\" = NULL WHERE \"id\" = '2';"
+        );
+    }
+
+    #[test]
+    fn test_render_delete_is_destructive() {
+        let single = DataOperation::Delete {
+            schema: "public".to_string(),
+            table: "plans".to_string(),
+            key_columns: vec!["id".to_string()],
+            keys: vec![vec![text("1")], vec![text("2")]],
+        };
+        let rendered = single.to_sql();
+        assert_eq!(
+            rendered[0].sql,
+            "DELETE FROM \"public\".\"plans\" WHERE \"id\" IN ('1', '2');"
+        );
+        assert_eq!(rendered[0].safety, Safety::Destructive);
+
+        let composite = DataOperation::Delete {
+            schema: "public".to_string(),
+            table: "rates".to_string(),
+            key_columns: vec!["region".to_string(), "tier".to_string()],
+            keys: vec![vec![text("eu"), text("1")]],
+        };
+        assert_eq!(
+            composite.to_sql()[0].sql,
+            "DELETE FROM \"public\".\"rates\" WHERE (\"region\", \"tier\") IN (('eu', '1'));"
+        );
+    }
+}
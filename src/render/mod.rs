@@ -3,6 +3,7 @@ pub mod cast;
 pub mod collation;
 pub mod comment;
 pub mod constraint;
+pub mod data;
 pub mod domain;
 pub mod extension;
 pub mod function;
@@ -77,6 +78,7 @@ impl SqlRenderer for MigrationStep {
             MigrationStep::Extension(op) => op.to_sql(),
             MigrationStep::Grant(op) => op.to_sql(),
             MigrationStep::Comment(op) => op.to_sql(),
+            MigrationStep::Data(op) => op.to_sql(),
         }
     }
 
@@ -100,6 +102,7 @@ impl SqlRenderer for MigrationStep {
             MigrationStep::Extension(op) => op.db_object_id(),
             MigrationStep::Grant(op) => op.db_object_id(),
             MigrationStep::Comment(op) => op.db_object_id(),
+            MigrationStep::Data(op) => op.db_object_id(),
         }
     }
 }
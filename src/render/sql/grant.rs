@@ -189,7 +189,8 @@ pub fn render_grant_object_clause(object: &DbObjectId) -> String {
         | DbObjectId::Collation { .. }
         | DbObjectId::Grant { .. }
         | DbObjectId::Comment { .. }
-        | DbObjectId::Column { .. } => {
+        | DbObjectId::Column { .. }
+        | DbObjectId::TableData { .. } => {
             unreachable!("not a grantable object kind: {object}")
         }
     }
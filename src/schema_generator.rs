@@ -256,6 +256,8 @@ impl SchemaGenerator {
             // object kind is carried in the target, exactly like a grant.
             MigrationStep::Comment(op) => self.determine_file_for_object_id(&op.target().object),
 
+            MigrationStep::Data(op) => self.determine_file_for_object_id(&op.db_object_id()),
+
             MigrationStep::Grant(op) => match self.extract_grant_target(op) {
                 GrantTarget::Table { schema, name } => {
                     let prefix = self.schema_path_prefix(&schema);
@@ -350,7 +352,8 @@ impl SchemaGenerator {
             DbObjectId::Constraint { schema, table, .. }
             | DbObjectId::Trigger { schema, table, .. }
             | DbObjectId::Policy { schema, table, .. }
-            | DbObjectId::Column { schema, table, .. } => {
+            | DbObjectId::Column { schema, table, .. }
+            | DbObjectId::TableData { schema, table } => {
                 format!("{}tables/{}.sql", self.schema_path_prefix(schema), table)
             }
             DbObjectId::Index { schema, name } => {
@@ -738,7 +741,8 @@ impl SchemaGenerator {
             | DbObjectId::Collation { .. }
             | DbObjectId::Grant { .. }
             | DbObjectId::Comment { .. }
-            | DbObjectId::Column { .. } => {
+            | DbObjectId::Column { .. }
+            | DbObjectId::TableData { .. } => {
                 unreachable!("not a grantable object kind: {:?}", target.object)
             }
         }
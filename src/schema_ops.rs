@@ -1,7 +1,7 @@
 use crate::catalog::Catalog;
 use crate::catalog::file_dependencies::FileToObjectMapping;
-use crate::config::Config;
 use crate::config::filter::ObjectFilter;
+use crate::config::{Config, PlaceholderRestorer, Variables};
 use crate::db::cleaner;
 use crate::db::error_context::SqlErrorContext;
 use crate::db::schema_processor::{SchemaProcessor, SchemaProcessorConfig};
@@ -18,7 +18,14 @@ use tracing::{debug, info};
 ///
 /// The file should use idempotent statements like `CREATE ROLE IF NOT EXISTS`
 /// to handle cases where roles may already exist.
-pub async fn apply_roles_file(pool: &PgPool, roles_file: &Path) -> Result<()> {
+///
+/// `${var}` placeholders are substituted first (role names are the classic
+/// per-environment difference).
+pub async fn apply_roles_file(
+    pool: &PgPool,
+    roles_file: &Path,
+    variables: &Variables,
+) -> Result<()> {
     if !roles_file.exists() {
         debug!("Roles file not found at {}, skipping", roles_file.display());
         return Ok(());
@@ -28,6 +35,9 @@ pub async fn apply_roles_file(pool: &PgPool, roles_file: &Path) -> Result<()> {
 
     let content = std::fs::read_to_string(roles_file)
         .with_context(|| format!("Failed to read roles file: {}", roles_file.display()))?;
+    let content = variables
+        .substitute(&content)
+        .with_context(|| format!("Failed to substitute variables in {}", roles_file.display()))?;
 
     // Execute the roles file using raw_sql which supports multiple statements
     // This allows for complex scripts with CREATE ROLE, DO blocks, etc.
@@ -58,7 +68,7 @@ fn truncate_for_log(s: &str) -> String {
 }
 
 /// Build the desired-state catalog on a shadow database: clean it, apply the
-/// roles file and schema files, and return the **managed** catalog (the shadow
+/// roles file, schema files and seed data files, and return the **managed** catalog (the shadow
 /// branch inherits image substrate, which is outside the managed universe).
 ///
 /// This is THE desired-state source. Every command that needs "what the schema
@@ -77,7 +87,7 @@ pub async fn build_desired_state(
 
 /// Clean the shadow database and apply the roles file, leaving it ready for
 /// schema files. (For docker/branch shadows the clean is a no-op.)
-async fn clean_shadow_for_schema(
+pub(crate) async fn clean_shadow_for_schema(
     config: &Config,
     root_dir: &Path,
     shadow_pool: &PgPool,
@@ -89,13 +99,14 @@ async fn clean_shadow_for_schema(
         .await
         .map_err(|e| anyhow::anyhow!("Failed to clean database: {}", e))?;
 
-    apply_roles_file(shadow_pool, &roles_file).await?;
+    apply_roles_file(shadow_pool, &roles_file, &config.variables).await?;
     Ok(())
 }
 
-/// Apply the schema files to an already-cleaned shadow and return the
-/// resulting **unfiltered** catalog plus the file→object mapping (callers
-/// apply the managed-universe filter if needed).
+/// Apply the schema files, then the seed data files, to an already-cleaned
+/// shadow and return the resulting **unfiltered** catalog (with the seeded
+/// rows) plus the file→object mapping (callers apply the managed-universe
+/// filter if needed).
 async fn apply_schema_files_to_shadow(
     config: &Config,
     root_dir: &Path,
@@ -103,12 +114,7 @@ async fn apply_schema_files_to_shadow(
 ) -> Result<(Catalog, FileToObjectMapping)> {
     let schema_dir = root_dir.join(&config.directories.schema);
 
-    let processor_config = SchemaProcessorConfig {
-        verbose: config.schema.verbose_file_processing,
-        clean_before_apply: false, // Already cleaned by clean_shadow_for_schema
-        objects: config.objects.clone(),
-    };
-    let processor = SchemaProcessor::new(shadow_pool.clone(), processor_config);
+    let processor = schema_processor(config, shadow_pool);
     let processed_schema = processor
         .process_schema_directory(&schema_dir)
         .await
@@ -126,7 +132,7 @@ async fn apply_schema_files_to_shadow(
         })?;
 
     let file_mapping = processed_schema.file_mapping.clone();
-    let catalog = if config.schema.augment_dependencies_from_files {
+    let mut catalog = if config.schema.augment_dependencies_from_files {
         processed_schema.with_file_dependencies_applied()
     } else {
         processed_schema.catalog
@@ -136,9 +142,25 @@ async fn apply_schema_files_to_shadow(
     validate_schema_applied(shadow_pool).await?;
     info!("✅ Schema validation completed");
 
+    catalog.data =
+        crate::seed_data::load_seed_files(config, root_dir, shadow_pool, &catalog).await?;
+    let catalog = crate::tenants::reload_for_planning(config, shadow_pool, catalog).await?;
+
     Ok((catalog, file_mapping))
 }
 
+/// The schema processor for `config`'s schema files, for a shadow that
+/// [`clean_shadow_for_schema`] has already prepared.
+pub(crate) fn schema_processor(config: &Config, shadow_pool: &PgPool) -> SchemaProcessor {
+    let processor_config = SchemaProcessorConfig {
+        verbose: config.schema.verbose_file_processing,
+        clean_before_apply: false, // Already cleaned by clean_shadow_for_schema
+        objects: config.objects.clone(),
+        variables: config.variables.clone(),
+    };
+    SchemaProcessor::new(shadow_pool.clone(), processor_config)
+}
+
 /// Connect to the given shadow database, build the desired state on it,
 /// and return the managed catalog. Convenience wrapper around
 /// [`build_desired_state`] for commands that don't hold a shadow pool.
@@ -176,6 +198,72 @@ pub async fn apply_current_schema_to_shadow_with_mapping(
     Ok((managed, mapping))
 }
 
+/// The [`PlaceholderRestorer`] for SQL generated in this project, built from
+/// everything a shadow can be built from (schema, roles, seed data, migration
+/// and baseline files) as written. Variables whose values can't be mapped back
+/// unambiguously are called out, since generated files will hardcode them.
+pub fn placeholder_restorer(config: &Config, root_dir: &Path) -> Result<PlaceholderRestorer> {
+    if !config.variables.is_enabled() {
+        return Ok(PlaceholderRestorer::default());
+    }
+
+    let mut sources = Vec::new();
+    let dirs = [
+        &config.directories.schema,
+        &config.directories.data,
+        &config.directories.migrations,
+        &config.directories.baselines,
+    ];
+    for dir in dirs {
+        read_project_files(&root_dir.join(dir), &mut sources)?;
+    }
+    let roles_file = root_dir.join(&config.directories.roles);
+    if roles_file.is_file() {
+        sources.push(std::fs::read_to_string(&roles_file)?);
+    }
+
+    let mut restorer = config
+        .variables
+        .placeholder_restorer(sources.iter().map(String::as_str));
+    if config.tenants.is_some() {
+        restorer = restorer.keeping(crate::tenants::TENANT_VARIABLE);
+    }
+    for name in restorer.skipped() {
+        println!(
+            "⚠️  Generated SQL keeps the value of ${{{}}} rather than the placeholder: another \
+             variable has the same value, or the value is also written literally in the project",
+            name
+        );
+    }
+    Ok(restorer)
+}
+
+/// Append the contents of every `.sql` and `.csv` file under `dir`.
+fn read_project_files(dir: &Path, out: &mut Vec<String>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            read_project_files(&path, out)?;
+        } else if matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("sql" | "csv")
+        ) {
+            out.push(
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read file: {}", path.display()))?,
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Validate that schema was applied correctly by checking basic connectivity and structure
 async fn validate_schema_applied(pool: &PgPool) -> Result<()> {
     // Basic connectivity test
@@ -0,0 +1,376 @@
+//! Seed data files: the `data/` directory of reference rows.
+//!
+//! Each file holds the rows of one table, named after it: `countries.csv` or
+//! `billing.plans.sql` (a bare name is in `public`). A CSV file starts with a
+//! header naming its columns; a SQL file holds `INSERT` statements into its
+//! table. Either way the columns written are the ones pgmt manages — the rest
+//! (a `created_at` default, say) are neither compared nor written — and rows
+//! are matched by the table's primary key.
+//!
+//! The files are loaded into the shadow after the schema files, so the
+//! desired-state catalog carries their rows (`Catalog::data`), and
+//! `diff::data` turns the difference into data steps.
+
+use crate::catalog::Catalog;
+use crate::catalog::data::{
+    self, SeededTable, TableData, TableName, order_by_references, references_between,
+};
+use crate::config::Config;
+use crate::db::error_context::SqlErrorContext;
+use crate::render::quote_ident;
+use anyhow::{Context, Result, anyhow, bail};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedFormat {
+    Csv,
+    Sql,
+}
+
+/// One file in the data directory
+#[derive(Debug, Clone)]
+pub struct SeedFile {
+    pub path: PathBuf,
+    pub format: SeedFormat,
+    pub table: SeededTable,
+}
+
+/// The seed files in `data_dir`, one per table. A missing directory has none.
+pub fn discover(data_dir: &Path) -> Result<Vec<SeedFile>> {
+    if !data_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(data_dir)
+        .with_context(|| format!("Failed to read data directory {}", data_dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    paths.sort();
+
+    let mut files: Vec<SeedFile> = Vec::new();
+    for path in paths {
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => SeedFormat::Csv,
+            Some("sql") => SeedFormat::Sql,
+            _ => continue,
+        };
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow!("Invalid seed file name: {}", path.display()))?;
+        let (schema, table) = match stem.split_once('.') {
+            Some((schema, table)) => (schema.to_string(), table.to_string()),
+            None => ("public".to_string(), stem.to_string()),
+        };
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read seed file {}", path.display()))?;
+        let columns = match format {
+            SeedFormat::Csv => Some(
+                csv_header(&content)
+                    .with_context(|| format!("Seed file {} has no header row", path.display()))?,
+            ),
+            SeedFormat::Sql => insert_columns(&content),
+        };
+
+        if let Some(other) = files
+            .iter()
+            .find(|f| f.table.schema == schema && f.table.table == table)
+        {
+            bail!(
+                "{} and {} both seed {}.{}; keep one file per table",
+                other.path.display(),
+                path.display(),
+                schema,
+                table
+            );
+        }
+        files.push(SeedFile {
+            path,
+            format,
+            table: SeededTable {
+                schema,
+                table,
+                columns,
+            },
+        });
+    }
+    Ok(files)
+}
+
+/// The tables `config`'s data directory seeds, for loading their rows from
+/// another database with `Catalog::with_data`.
+pub fn seeded_tables(config: &Config, root_dir: &Path) -> Result<Vec<SeededTable>> {
+    Ok(discover(&root_dir.join(&config.directories.data))?
+        .into_iter()
+        .map(|file| file.table)
+        .collect())
+}
+
+/// Load the seed files into a shadow that the schema files have been applied
+/// to, and return the rows they describe. `catalog` is the shadow's catalog:
+/// its foreign keys decide the load order.
+pub async fn load_seed_files(
+    config: &Config,
+    root_dir: &Path,
+    shadow_pool: &PgPool,
+    catalog: &Catalog,
+) -> Result<Vec<TableData>> {
+    load_seeds(config, root_dir, shadow_pool, catalog, |_| true).await
+}
+
+/// [`load_seed_files`] for a shadow that already holds the seed rows except
+/// in `recreated`, tables dropped and created again since: only their files
+/// load, and the rows of every seeded table are returned.
+pub async fn reload_seed_files(
+    config: &Config,
+    root_dir: &Path,
+    shadow_pool: &PgPool,
+    catalog: &Catalog,
+    recreated: &BTreeSet<TableName>,
+) -> Result<Vec<TableData>> {
+    load_seeds(config, root_dir, shadow_pool, catalog, |table| {
+        recreated.contains(table)
+    })
+    .await
+}
+
+async fn load_seeds(
+    config: &Config,
+    root_dir: &Path,
+    shadow_pool: &PgPool,
+    catalog: &Catalog,
+    needs_loading: impl Fn(&TableName) -> bool,
+) -> Result<Vec<TableData>> {
+    let files = discover(&root_dir.join(&config.directories.data))?;
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    info!("🌱 Loading {} seed data file(s)...", files.len());
+
+    let tables: BTreeSet<TableName> = files
+        .iter()
+        .map(|f| (f.table.schema.clone(), f.table.table.clone()))
+        .collect();
+    let references = references_between(&tables, &catalog.constraints);
+    let mut by_table: BTreeMap<TableName, &SeedFile> = files
+        .iter()
+        .map(|f| ((f.table.schema.clone(), f.table.table.clone()), f))
+        .collect();
+
+    for table in order_by_references(&tables, &references) {
+        let file = by_table.remove(&table).expect("every table has a file");
+        if catalog.find_table(&table.0, &table.1).is_none() {
+            bail!(
+                "Seed file {} is for table {}.{}, which the schema files don't create",
+                file.path.display(),
+                table.0,
+                table.1
+            );
+        }
+        if needs_loading(&table) {
+            load_file(config, shadow_pool, file).await?;
+        }
+    }
+
+    let seeded: Vec<SeededTable> = files.into_iter().map(|f| f.table).collect();
+    data::load(shadow_pool, &seeded).await
+}
+
+async fn load_file(config: &Config, pool: &PgPool, file: &SeedFile) -> Result<()> {
+    let content = std::fs::read_to_string(&file.path)
+        .with_context(|| format!("Failed to read seed file {}", file.path.display()))?;
+    let display = file.path.display().to_string();
+
+    match file.format {
+        SeedFormat::Csv => {
+            let columns = file.table.columns.as_deref().unwrap_or_default();
+            let statement = format!(
+                "COPY {}.{} ({}) FROM STDIN WITH (FORMAT csv, HEADER true)",
+                quote_ident(&file.table.schema),
+                quote_ident(&file.table.table),
+                columns
+                    .iter()
+                    .map(|c| quote_ident(c))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            let mut conn = pool.acquire().await?;
+            let mut copy = conn
+                .copy_in_raw(&statement)
+                .await
+                .with_context(|| format!("Failed to load seed file {}", display))?;
+            copy.send(content.into_bytes()).await?;
+            copy.finish()
+                .await
+                .with_context(|| format!("Failed to load seed file {}", display))?;
+        }
+        SeedFormat::Sql => {
+            let sql = config
+                .variables
+                .substitute(&content)
+                .with_context(|| format!("Failed to substitute variables in {}", display))?;
+            sqlx::raw_sql(sqlx::AssertSqlSafe(sql.clone()))
+                .execute(pool)
+                .await
+                .map_err(|e| {
+                    let ctx = SqlErrorContext::from_sqlx_error(&e, &sql);
+                    anyhow!("{}", ctx.format(&display, &sql))
+                })?;
+        }
+    }
+    Ok(())
+}
+
+/// The column names in a CSV file's header row.
+fn csv_header(content: &str) -> Option<Vec<String>> {
+    let line = content.lines().next()?.trim_end_matches('\r');
+    if line.trim().is_empty() {
+        return None;
+    }
+
+    let mut columns = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => columns.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    columns.push(field.trim().to_string());
+    Some(columns)
+}
+
+/// The columns a SQL seed file's `INSERT` statements name, or `None` if one
+/// of them names none (so writes every column).
+fn insert_columns(sql: &str) -> Option<Vec<String>> {
+    let lower = sql.to_ascii_lowercase();
+    let mut columns: Vec<String> = Vec::new();
+    let mut rest = 0;
+    while let Some(found) = lower[rest..].find("insert into") {
+        let start = rest + found + "insert into".len();
+        let after_name = skip_relation_name(sql, start);
+        let list = sql[after_name..].trim_start();
+        if !list.starts_with('(') {
+            return None;
+        }
+        let list_start = sql.len() - list.len() + 1;
+        let list_end = list_start + sql[list_start..].find(')')?;
+        for column in split_identifiers(&sql[list_start..list_end]) {
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+        rest = list_end;
+    }
+    Some(columns)
+}
+
+/// The byte offset just past the (possibly qualified, possibly quoted)
+/// relation name starting at `start`.
+fn skip_relation_name(sql: &str, start: usize) -> usize {
+    let bytes = sql.as_bytes();
+    let mut i = start;
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    let mut quoted = false;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => quoted = !quoted,
+            b'(' if !quoted => break,
+            b if b.is_ascii_whitespace() && !quoted => break,
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Split a column list into identifiers: quoted ones as written, the rest
+/// folded to lower case the way PostgreSQL does.
+fn split_identifiers(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| match name.strip_prefix('"') {
+            Some(quoted) => quoted.trim_end_matches('"').replace("\"\"", "\""),
+            None => name.to_lowercase(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_header() {
+        assert_eq!(
+            csv_header("code,name\nFR,France\n"),
+            Some(vec!["code".to_string(), "name".to_string()])
+        );
+        assert_eq!(
+            csv_header("\"code\", \"display \"\"name\"\"\"\r\n"),
+            Some(vec!["code".to_string(), "display \"name\"".to_string()])
+        );
+        assert_eq!(csv_header(""), None);
+    }
+
+    #[test]
+    fn test_insert_columns() {
+        assert_eq!(
+            insert_columns(
+                "INSERT INTO billing.plans (id, Name) VALUES (1, 'Free');\n\
+                 insert into \"billing\".\"plans\"(id, \"Price\") VALUES (2, 10);"
+            ),
+            Some(vec![
+                "id".to_string(),
+                "name".to_string(),
+                "Price".to_string()
+            ])
+        );
+        assert_eq!(
+            insert_columns("INSERT INTO plans VALUES (1, 'Free');"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_discover_names_tables_after_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("countries.csv"), "code,name\n").unwrap();
+        std::fs::write(
+            dir.path().join("billing.plans.sql"),
+            "INSERT INTO billing.plans (id) VALUES (1);",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "notes").unwrap();
+
+        let files = discover(dir.path()).unwrap();
+        let tables: Vec<(&str, &str, SeedFormat)> = files
+            .iter()
+            .map(|f| (f.table.schema.as_str(), f.table.table.as_str(), f.format))
+            .collect();
+        assert_eq!(
+            tables,
+            vec![
+                ("billing", "plans", SeedFormat::Sql),
+                ("public", "countries", SeedFormat::Csv)
+            ]
+        );
+
+        std::fs::write(dir.path().join("public.countries.sql"), "").unwrap();
+        assert!(discover(dir.path()).is_err());
+    }
+}
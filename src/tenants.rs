@@ -0,0 +1,248 @@
+//! Schema-per-tenant mode (`tenants:` in pgmt.yaml).
+//!
+//! Every tenant keeps an identical set of objects in its own schema. The
+//! schema files describe one of them, the *template* schema, under its
+//! literal name or as `${tenant}` (a variable tenant mode defines, set to the
+//! template), so dev and the shadow work on the template exactly as in an
+//! ordinary project. Tenant schemas (the ones matching `tenants.schemas`,
+//! other than the template) are outside the managed universe everywhere.
+//!
+//! Migrations are written once for every tenant: the commands that generate
+//! them plan from catalogs loaded with the template schema renamed to
+//! `${tenant}` (see [`load_for_planning`]), so the rendered SQL names the
+//! schema through the placeholder. The rename happens in the database, for the
+//! duration of the load, so it reaches every reference Postgres keeps by
+//! object rather than by name — defaults, view queries, column types — and
+//! leaves string function bodies as written.
+//!
+//! `migrate apply` then runs each migration once per tenant with `${tenant}`
+//! set to the tenant's schema, and records it in a tracking table inside that
+//! schema — so each tenant has its own history, lock and pending set, and a
+//! tenant created later catches up from the latest baseline or the first
+//! migration. Substitution changes only what is executed: checksums stay
+//! those of the files as written.
+
+use crate::catalog::Catalog;
+use crate::config::Config;
+use crate::config::filter::ObjectFilter;
+use crate::config::types::Tenants;
+use anyhow::{Result, bail};
+use sqlx::PgPool;
+
+/// The variable holding the tenant's schema: the template everywhere but
+/// `migrate apply`, which sets it to each tenant in turn.
+pub const TENANT_VARIABLE: &str = "tenant";
+
+/// The schema name generated SQL uses for the template: the `${tenant}`
+/// placeholder itself.
+pub fn placeholder_schema() -> String {
+    format!("${{{}}}", TENANT_VARIABLE)
+}
+
+/// A schema name that needs no quoting: lowercase letters, digits and
+/// underscores, not starting with a digit. Template and tenant names must be
+/// plain so `${tenant}` can be written bare as well as quoted.
+pub fn is_plain_schema_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && name.len() <= 63
+}
+
+/// The config `migrate apply` and `migrate status` use for one tenant:
+/// `${tenant}` set to `tenant`, and tracking inside the tenant's schema.
+pub fn tenant_config(config: &Config, tenant: &str) -> Config {
+    let mut tenant_config = config.clone();
+    if tenant_config.tenants.take().is_some() {
+        tenant_config.variables = tenant_config.variables.with_value(TENANT_VARIABLE, tenant);
+    }
+    tenant_config.migration.tracking_table.schema = tenant.to_string();
+    tenant_config
+}
+
+/// The config for a command that writes migration files: in tenant mode, the
+/// catalogs it plans from name the template `${tenant}`.
+pub fn for_generation(mut config: Config) -> Config {
+    if let Some(tenants) = config.tenants.as_mut() {
+        tenants.generating = true;
+    }
+    config
+}
+
+/// The template schema, when `config` is generating in tenant mode.
+fn generating_template(config: &Config) -> Option<&str> {
+    config
+        .tenants
+        .as_ref()
+        .filter(|tenants| tenants.generating)
+        .map(|tenants| tenants.template.as_str())
+}
+
+/// Load the managed catalog on a shadow for planning: with the template
+/// schema named `${tenant}` when generating in tenant mode.
+pub async fn load_for_planning(config: &Config, pool: &PgPool) -> Result<Catalog> {
+    let catalog = load_unfiltered_for_planning(config, pool).await?;
+    Ok(ObjectFilter::from_config(config).filter_catalog(catalog))
+}
+
+/// [`load_for_planning`] without the managed-universe filter.
+pub async fn load_unfiltered_for_planning(config: &Config, pool: &PgPool) -> Result<Catalog> {
+    match generating_template(config) {
+        Some(template) if schema_exists(pool, template).await? => {
+            Catalog::load_unfiltered_renamed(pool, template, &placeholder_schema()).await
+        }
+        _ => Catalog::load_unfiltered(pool).await,
+    }
+}
+
+/// [`load_for_planning`] for a catalog already loaded from `pool` under the
+/// template's name: reloaded with the template named `${tenant}`, keeping the
+/// dependencies added from file headers and the seed rows.
+pub async fn reload_for_planning(
+    config: &Config,
+    pool: &PgPool,
+    catalog: Catalog,
+) -> Result<Catalog> {
+    let Some(template) = generating_template(config) else {
+        return Ok(catalog);
+    };
+    if !schema_exists(pool, template).await? {
+        return Ok(catalog);
+    }
+    let placeholder = placeholder_schema();
+    let mut renamed = Catalog::load_unfiltered_renamed(pool, template, &placeholder).await?;
+    for (id, deps) in catalog.forward_deps {
+        let mut id = id;
+        id.rename_schema(template, &placeholder);
+        let existing = renamed.forward_deps.entry(id).or_default();
+        for mut dep in deps {
+            dep.rename_schema(template, &placeholder);
+            if !existing.contains(&dep) {
+                existing.push(dep);
+            }
+        }
+    }
+    renamed.rebuild_reverse_deps();
+    renamed.data = catalog.data;
+    name_seed_rows_for_planning(config, &mut renamed);
+    Ok(renamed)
+}
+
+/// Name the template `${tenant}` in seed rows loaded by table name, to match
+/// a catalog from [`load_for_planning`].
+pub fn name_seed_rows_for_planning(config: &Config, catalog: &mut Catalog) {
+    if let Some(template) = generating_template(config) {
+        for data in &mut catalog.data {
+            if data.schema == template {
+                data.schema = placeholder_schema();
+            }
+        }
+    }
+}
+
+pub async fn schema_exists(pool: &PgPool, schema: &str) -> Result<bool> {
+    Ok(
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_namespace WHERE nspname = $1)")
+            .bind(schema)
+            .fetch_one(pool)
+            .await?,
+    )
+}
+
+/// The tenant schemas on a database, sorted: the rows of `tenants.query` if
+/// set (which may name schemas that don't exist yet), otherwise every
+/// existing schema matching `tenants.schemas` other than the template.
+pub async fn list_tenant_schemas(pool: &PgPool, tenants: &Tenants) -> Result<Vec<String>> {
+    let pattern = glob::Pattern::new(&tenants.schemas)?;
+    let mut schemas: Vec<String> = match &tenants.query {
+        Some(query) => {
+            let listed: Vec<String> = sqlx::query_scalar(sqlx::AssertSqlSafe(query.as_str()))
+                .fetch_all(pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("tenants.query failed: {}", e))?;
+            for schema in &listed {
+                if !is_plain_schema_name(schema) {
+                    bail!(
+                        "tenants.query returned '{}', which is not a lowercase identifier",
+                        schema
+                    );
+                }
+                if *schema == tenants.template || !pattern.matches(schema) {
+                    bail!(
+                        "tenants.query returned '{}', which is not a tenant schema (tenants.schemas is '{}', the template '{}')",
+                        schema,
+                        tenants.schemas,
+                        tenants.template
+                    );
+                }
+            }
+            listed
+        }
+        None => {
+            let all: Vec<String> = sqlx::query_scalar("SELECT nspname::text FROM pg_namespace")
+                .fetch_all(pool)
+                .await?;
+            let mut matching = Vec::new();
+            for schema in all {
+                if schema == tenants.template || !pattern.matches(&schema) {
+                    continue;
+                }
+                if !is_plain_schema_name(&schema) {
+                    bail!(
+                        "schema '{}' matches tenants.schemas but is not a lowercase identifier",
+                        schema
+                    );
+                }
+                matching.push(schema);
+            }
+            matching
+        }
+    };
+    schemas.sort();
+    schemas.dedup();
+    Ok(schemas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::Tenants;
+
+    #[test]
+    fn test_plain_schema_names() {
+        assert!(is_plain_schema_name("tenant_template"));
+        assert!(is_plain_schema_name("_t1"));
+        assert!(!is_plain_schema_name("Tenant"));
+        assert!(!is_plain_schema_name("1tenant"));
+        assert!(!is_plain_schema_name("tenant-a"));
+        assert!(!is_plain_schema_name(""));
+    }
+
+    /// A tenant's config substitutes its schema for `${tenant}` and keeps the
+    /// rest of the SQL as written.
+    #[test]
+    fn test_tenant_config_substitutes_placeholder_only() {
+        let config = Config {
+            tenants: Some(Tenants {
+                template: "tenant_template".to_string(),
+                schemas: "tenant_*".to_string(),
+                query: None,
+                generating: false,
+            }),
+            variables: crate::config::types::Variables::new([(TENANT_VARIABLE, "tenant_template")]),
+            ..Default::default()
+        };
+        let tenant = tenant_config(&config, "tenant_acme");
+        assert!(tenant.tenants.is_none());
+        assert_eq!(tenant.migration.tracking_table.schema, "tenant_acme");
+        assert_eq!(
+            tenant
+                .variables
+                .substitute(r#"INSERT INTO "${tenant}".users (note) VALUES ('tenant_template');"#)
+                .unwrap(),
+            r#"INSERT INTO "tenant_acme".users (note) VALUES ('tenant_template');"#
+        );
+    }
+}
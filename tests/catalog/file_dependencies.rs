@@ -270,6 +270,7 @@ async fn test_each_file_owns_the_objects_it_creates() -> anyhow::Result<()> {
                 verbose: false,
                 clean_before_apply: false,
                 objects: Default::default(),
+                variables: Default::default(),
             },
         );
         let processed = processor.process_schema_directory(&schema).await?;
@@ -0,0 +1,93 @@
+//! `pgmt catalog snapshot`, and `diff` / `migrate diff` / `validate` comparing
+//! snapshot files without connecting to any database.
+use crate::helpers::cli::with_cli_helper;
+use anyhow::Result;
+use predicates::prelude::*;
+
+/// Snapshots of the schema files and of dev, taken while the databases are
+/// up, still diff once every URL in pgmt.yaml is unreachable.
+#[tokio::test]
+async fn test_snapshots_diff_without_a_database() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id SERIAL PRIMARY KEY, name TEXT);\n\
+             CREATE VIEW user_names AS SELECT name FROM users;",
+        )?;
+
+        helper
+            .command()
+            .args(["catalog", "snapshot", "--out", "snapshots/schema.json"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Wrote snapshot of schema files to snapshots/schema.json (1 tables, 1 views",
+            ));
+        helper
+            .command()
+            .args([
+                "catalog", "snapshot", "--source", "dev", "--out", "dev.json",
+            ])
+            .assert()
+            .success();
+        let snapshot: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+            helper.project_root.join("snapshots/schema.json"),
+        )?)?;
+        assert_eq!(snapshot["format"], 1);
+        assert_eq!(snapshot["source"], "schema files");
+        assert_eq!(snapshot["catalog"]["tables"][0]["name"], "users");
+
+        // No database from here on.
+        let config_path = helper.project_root.join("pgmt.yaml");
+        let config = std::fs::read_to_string(&config_path)?
+            .replace(
+                &helper.dev_database_url,
+                "postgres://nobody@127.0.0.1:1/none",
+            )
+            .replace(
+                &helper.shadow_database_url,
+                "postgres://nobody@127.0.0.1:1/none",
+            );
+        std::fs::write(&config_path, config)?;
+
+        helper
+            .command()
+            .args(["diff", "--from-snapshot", "dev.json"])
+            .args(["--to-snapshot", "snapshots/schema.json", "--format", "sql"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "CREATE TABLE \"public\".\"users\"",
+            ))
+            .stdout(predicate::str::contains(
+                "CREATE VIEW \"public\".\"user_names\"",
+            ));
+
+        helper
+            .command()
+            .args(["migrate", "diff", "--from-snapshot", "dev.json"])
+            .args(["--to-snapshot", "snapshots/schema.json"])
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains(
+                "Drift detected! Snapshot dev.json differs from snapshot snapshots/schema.json.",
+            ));
+
+        helper
+            .command()
+            .args(["validate", "--from-snapshot", "snapshots/schema.json"])
+            .args(["--to-snapshot", "snapshots/schema.json"])
+            .assert()
+            .success();
+        helper
+            .command()
+            .args(["validate", "--from-snapshot", "dev.json"])
+            .args(["--to-snapshot", "snapshots/schema.json"])
+            .assert()
+            .failure();
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,62 @@
+/// Tests for pgmt compare (one live database against another)
+use crate::helpers::cli::with_cli_helper;
+use anyhow::Result;
+use predicates::prelude::*;
+
+async fn execute(url: &str, sql: &str) -> Result<()> {
+    let pool = sqlx::PgPool::connect(url).await?;
+    sqlx::raw_sql(sqlx::AssertSqlSafe(sql))
+        .execute(&pool)
+        .await?;
+    pool.close().await;
+    Ok(())
+}
+
+/// Production has a column staging lacks: the report drops it going to
+/// staging, and --sync-sql adds it back.
+#[tokio::test]
+async fn test_compare_two_databases() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        let production = helper.dev_database_url.clone();
+        let staging = helper.shadow_database_url.clone();
+        execute(
+            &production,
+            "CREATE TABLE users (id SERIAL PRIMARY KEY, name TEXT, email TEXT)",
+        )
+        .await?;
+        execute(
+            &staging,
+            "CREATE TABLE users (id SERIAL PRIMARY KEY, name TEXT)",
+        )
+        .await?;
+
+        helper
+            .command()
+            .args(["compare", "--from-url", &production, "--to-url", &staging])
+            .args(["--format", "sql", "--sync-sql", "sync.sql"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("DROP COLUMN IF EXISTS \"email\""));
+
+        let sync = std::fs::read_to_string(helper.project_root.join("sync.sql"))?;
+        assert!(sync.contains("ADD COLUMN \"email\""), "{}", sync);
+        assert!(!sync.contains("DROP COLUMN"), "{}", sync);
+
+        helper
+            .command()
+            .args([
+                "compare",
+                "--from-url",
+                &production,
+                "--to-url",
+                &production,
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No differences found"));
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,219 @@
+//! Dev database snapshots: taken before destructive applies, restored by
+//! `pgmt apply --undo` and `pgmt dev snapshots restore`.
+use crate::helpers::cli::with_cli_helper;
+use anyhow::Result;
+use predicates::prelude::*;
+
+/// The dev database's database-wide settings and comment
+async fn database_properties(url: &str) -> Result<(Vec<String>, Option<String>)> {
+    let pool = sqlx::PgPool::connect(url).await?;
+    let properties = sqlx::query_as(
+        "SELECT coalesce((SELECT setconfig FROM pg_db_role_setting \
+         WHERE setdatabase = d.oid AND setrole = 0), '{}'), \
+         shobj_description(d.oid, 'pg_database') \
+         FROM pg_database d WHERE datname = current_database()",
+    )
+    .fetch_one(&pool)
+    .await;
+    pool.close().await;
+    Ok(properties?)
+}
+
+async fn count_users_with_email(url: &str) -> Result<i64> {
+    let pool = sqlx::PgPool::connect(url).await?;
+    let count = sqlx::query_scalar("SELECT count(email) FROM users")
+        .fetch_one(&pool)
+        .await;
+    pool.close().await;
+    Ok(count?)
+}
+
+#[tokio::test]
+async fn test_destructive_apply_snapshot_and_undo() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY, email TEXT);",
+        )?;
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success();
+
+        let pool = sqlx::PgPool::connect(&helper.dev_database_url).await?;
+        sqlx::query("INSERT INTO users VALUES (1, 'a@example.com'), (2, 'b@example.com')")
+            .execute(&pool)
+            .await?;
+        sqlx::query(
+            "DO $$ BEGIN \
+             EXECUTE format('ALTER DATABASE %I SET work_mem = ''7MB''', current_database()); \
+             EXECUTE format('COMMENT ON DATABASE %I IS ''dev db''', current_database()); \
+             END $$",
+        )
+        .execute(&pool)
+        .await?;
+        pool.close().await;
+
+        // Safe changes don't snapshot.
+        helper
+            .command()
+            .args(["dev", "snapshots", "list"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No snapshots of the dev database"));
+
+        // Dropping the column takes a snapshot first.
+        helper.write_schema_file("users.sql", "CREATE TABLE users (id INT PRIMARY KEY);")?;
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("📸 Snapshot pgmt_snap_"));
+        helper
+            .command()
+            .args(["dev", "snapshots", "list"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Found 1 snapshot(s)"))
+            .stdout(predicate::str::contains("DROP COLUMN"));
+
+        // Undo brings the column and its data back, and uses the snapshot up.
+        helper
+            .command()
+            .args(["apply", "--undo"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("⏪ Restored the dev database"));
+        assert_eq!(count_users_with_email(&helper.dev_database_url).await?, 2);
+        // The restored database keeps what the copy doesn't carry.
+        assert_eq!(
+            database_properties(&helper.dev_database_url).await?,
+            (vec!["work_mem=7MB".to_string()], Some("dev db".to_string()))
+        );
+        helper
+            .command()
+            .args(["apply", "--undo"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("No dev database snapshots"));
+
+        // Restoring by name keeps the snapshot.
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success();
+        let list = helper
+            .command()
+            .args(["dev", "snapshots", "list"])
+            .output()?;
+        let list = String::from_utf8(list.stdout)?;
+        let name = list
+            .split_whitespace()
+            .find(|word| word.starts_with("pgmt_snap_"))
+            .expect("a snapshot is listed")
+            .to_string();
+        helper
+            .command()
+            .args(["dev", "snapshots", "restore", &name])
+            .assert()
+            .success();
+        assert_eq!(count_users_with_email(&helper.dev_database_url).await?, 2);
+
+        helper
+            .command()
+            .args(["dev", "snapshots", "prune", "--keep", "0"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!("Dropped {}", name)));
+        helper
+            .command()
+            .args(["dev", "snapshots", "list"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No snapshots of the dev database"));
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_snapshot_retention_zero_disables_snapshots() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        let config = helper.project_root.join("pgmt.yaml");
+        let yaml = std::fs::read_to_string(&config)?;
+        std::fs::write(
+            &config,
+            format!("{}\ndev:\n  snapshot_retention: 0\n", yaml),
+        )?;
+
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY, email TEXT);",
+        )?;
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success();
+        helper.write_schema_file("users.sql", "CREATE TABLE users (id INT PRIMARY KEY);")?;
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("📸").not());
+        helper
+            .command()
+            .args(["dev", "snapshots", "list"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No snapshots of the dev database"));
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_failed_snapshot_warns_and_applies() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY, email TEXT);",
+        )?;
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success();
+
+        // Another session on the dev database keeps it from being copied.
+        let pool = sqlx::PgPool::connect(&helper.dev_database_url).await?;
+        let mut other = pool.acquire().await?;
+        helper.write_schema_file("users.sql", "CREATE TABLE users (id INT PRIMARY KEY);")?;
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Applying without a snapshot"));
+        let columns: i64 = sqlx::query_scalar(
+            "SELECT count(*) FROM information_schema.columns WHERE table_name = 'users'",
+        )
+        .fetch_one(&mut *other)
+        .await?;
+        assert_eq!(columns, 1);
+        drop(other);
+        pool.close().await;
+
+        Ok(())
+    })
+    .await
+}
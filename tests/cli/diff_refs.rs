@@ -0,0 +1,149 @@
+//! `pgmt diff --from-ref/--to-ref`: the DDL between the schema files at two
+//! git revisions, or between a revision and the working tree or a catalog
+//! snapshot.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::{Result, ensure};
+use predicates::prelude::*;
+use std::process::Command;
+
+fn git(helper: &CliTestHelper, args: &[&str]) -> Result<()> {
+    let status = Command::new("git")
+        .args(["-c", "user.name=pgmt", "-c", "user.email=pgmt@example.com"])
+        .args(args)
+        .current_dir(&helper.project_root)
+        .output()?;
+    ensure!(
+        status.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&status.stderr)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_diff_between_git_revisions() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        git(helper, &["init", "-q", "-b", "main"])?;
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "empty"])?;
+
+        helper.write_schema_file("users.sql", "CREATE TABLE users (id INT PRIMARY KEY);")?;
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "users"])?;
+
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY, email TEXT);",
+        )?;
+        git(helper, &["commit", "-qam", "email"])?;
+
+        // The revision before the schema directory existed is an empty schema.
+        helper
+            .command()
+            .args(["diff", "--from-ref", "HEAD~2", "--to-ref", "HEAD~1"])
+            .args(["--format", "sql"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "-- SQL to bring schema files at HEAD~2 in sync with schema files at HEAD~1",
+            ))
+            .stdout(predicate::str::contains(
+                "CREATE TABLE \"public\".\"users\"",
+            ));
+
+        helper
+            .command()
+            .args(["diff", "--from-ref", "HEAD~1", "--to-ref", "HEAD"])
+            .args(["--format", "sql"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("ADD COLUMN \"email\""))
+            .stdout(predicate::str::contains("CREATE TABLE").not());
+
+        // Without --to-ref the other side is the working tree.
+        helper
+            .command()
+            .args(["diff", "--from-ref", "HEAD"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No differences found"));
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY, email TEXT, name TEXT);",
+        )?;
+        helper
+            .command()
+            .args(["diff", "--from-ref", "HEAD", "--format", "sql"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("ADD COLUMN \"name\""));
+
+        // Or a catalog snapshot, taken here before the working tree moves on.
+        helper
+            .command()
+            .args(["catalog", "snapshot", "--out", "schema.json"])
+            .assert()
+            .success();
+        helper.write_schema_file("users.sql", "CREATE TABLE users (id INT PRIMARY KEY);")?;
+        helper
+            .command()
+            .args(["diff", "--from-ref", "HEAD", "--to-snapshot", "schema.json"])
+            .args(["--format", "sql"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains(
+                "in sync with snapshot schema.json",
+            ))
+            .stdout(predicate::str::contains("ADD COLUMN \"name\""))
+            .stdout(predicate::str::contains("DROP COLUMN").not());
+
+        helper
+            .command()
+            .args(["diff", "--from-ref", "no-such-branch"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Unknown git revision 'no-such-branch'",
+            ));
+
+        Ok(())
+    })
+    .await
+}
+
+/// Seed rows are part of each revision's side, so a changed row shows up.
+#[tokio::test]
+async fn test_diff_between_git_revisions_includes_seed_rows() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file(
+            "plans.sql",
+            "CREATE TABLE plans (id INT PRIMARY KEY, name TEXT NOT NULL);",
+        )?;
+        let data_dir = helper.project_root.join("data");
+        std::fs::create_dir_all(&data_dir)?;
+        std::fs::write(data_dir.join("plans.csv"), "id,name\n1,Free\n2,Pro\n")?;
+        git(helper, &["init", "-q", "-b", "main"])?;
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "plans"])?;
+
+        std::fs::write(
+            data_dir.join("plans.csv"),
+            "id,name\n1,Free\n2,Professional\n",
+        )?;
+        git(helper, &["commit", "-qam", "rename pro"])?;
+
+        helper
+            .command()
+            .args(["diff", "--from-ref", "HEAD~1", "--to-ref", "HEAD"])
+            .args(["--format", "sql"])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::contains("'Professional'"))
+            .stdout(predicate::str::contains("'Free'").not());
+        Ok(())
+    })
+    .await
+}
@@ -24,15 +24,27 @@ fn test_connection_flags_match_databases_each_command_uses() {
     let matrix: &[(&[&str], &[&str], &[&str])] = &[
         (&["apply"], &[DEV, SHADOW], &[TARGET]),
         (&["diff"], &[DEV, SHADOW], &[TARGET]),
+        (&["compare"], &[], &[DEV, SHADOW, TARGET]),
         (&["validate"], &[DEV, SHADOW], &[TARGET]),
+        (&["test"], &[SHADOW], &[DEV, TARGET]),
         (&["migrate", "new"], &[SHADOW], &[DEV, TARGET]),
         (&["migrate", "update"], &[SHADOW], &[DEV, TARGET]),
-        (&["migrate", "apply"], &[TARGET], &[DEV, SHADOW]),
-        (&["migrate", "status"], &[DEV, TARGET], &[SHADOW]),
-        (&["migrate", "validate"], &[SHADOW], &[DEV, TARGET]),
+        (&["migrate", "rebase"], &[SHADOW], &[DEV, TARGET]),
+        (&["migrate", "apply"], &[TARGET, SHADOW], &[DEV]),
+        (&["migrate", "rollback"], &[TARGET], &[DEV, SHADOW]),
+        (&["migrate", "status"], &[DEV, TARGET, SHADOW], &[]),
+        (&["migrate", "wait"], &[TARGET], &[DEV, SHADOW]),
+        (&["migrate", "history"], &[TARGET], &[DEV, SHADOW]),
+        (&["migrate", "validate"], &[SHADOW, TARGET], &[DEV]),
+        (&["migrate", "lint"], &[], &[DEV, SHADOW, TARGET]),
+        (&["migrate", "export"], &[], &[DEV, SHADOW, TARGET]),
         (&["migrate", "diff"], &[TARGET, SHADOW], &[DEV]),
         (&["migrate", "baseline"], &[SHADOW], &[DEV, TARGET]),
         (&["migrate", "resolve"], &[TARGET], &[DEV, SHADOW]),
+        (&["catalog", "snapshot"], &[DEV, SHADOW, TARGET], &[]),
+        (&["dev", "snapshots", "list"], &[DEV], &[SHADOW, TARGET]),
+        (&["dev", "snapshots", "restore"], &[DEV], &[SHADOW, TARGET]),
+        (&["dev", "snapshots", "prune"], &[DEV], &[SHADOW, TARGET]),
         (&["debug", "dependencies"], &[SHADOW], &[DEV, TARGET]),
     ];
 
@@ -0,0 +1,190 @@
+//! `pgmt init --adopt-from`: a database another tool migrated is recorded as
+//! applied up to the project's latest migration, without running anything.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+
+/// Baseline 1000 creates `users`; migration 2000 adds `email`.
+fn write_project(helper: &CliTestHelper) -> Result<()> {
+    helper.init_project()?;
+    helper.write_schema_file("users.sql", "CREATE TABLE users (id INT, email TEXT);")?;
+    std::fs::write(
+        helper.baselines_dir().join("baseline_1000.sql"),
+        "CREATE TABLE users (id INT);\n",
+    )?;
+    helper.write_migration_file("2000_email.sql", "ALTER TABLE users ADD COLUMN email TEXT;")?;
+    Ok(())
+}
+
+/// The database Flyway left behind, with `users` as given.
+async fn seed_flyway(helper: &CliTestHelper, users: &str, success: bool) -> Result<()> {
+    let pool = helper.connect_to_dev_db().await?;
+    sqlx::raw_sql(sqlx::AssertSqlSafe(format!(
+        "{users}
+         CREATE TABLE flyway_schema_history (
+             installed_rank INT PRIMARY KEY, version TEXT, description TEXT NOT NULL,
+             type TEXT NOT NULL, script TEXT NOT NULL, checksum INT, installed_by TEXT NOT NULL,
+             installed_on TIMESTAMP NOT NULL DEFAULT now(), execution_time INT NOT NULL,
+             success BOOLEAN NOT NULL);
+         INSERT INTO flyway_schema_history VALUES
+             (1, '1', 'users', 'SQL', 'V1__users.sql', 1, 'app', now(), 5, true),
+             (2, '2', 'email', 'SQL', 'V2__email.sql', 2, 'app', now(), 5, {success});"
+    )))
+    .execute(&pool)
+    .await?;
+    pool.close().await;
+    Ok(())
+}
+
+/// Adoption records the baseline and later migration as applied; apply then
+/// has nothing to do, and a second adoption is refused.
+#[tokio::test]
+async fn test_adopt_from_flyway() -> Result<()> {
+    with_cli_helper(async |helper| {
+        write_project(helper)?;
+        seed_flyway(helper, "CREATE TABLE users (id INT, email TEXT);", true).await?;
+
+        helper
+            .command()
+            .args(["init", "--adopt-from", "flyway"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Flyway history: 2 migration(s) applied, latest 2",
+            ))
+            .stdout(predicate::str::contains(
+                "Recorded baseline 1000 as applied",
+            ))
+            .stdout(predicate::str::contains(
+                "Recorded migration 2000 - email as applied",
+            ))
+            .stdout(predicate::str::contains("Adopted from Flyway"));
+
+        helper
+            .command()
+            .args(["migrate", "status", "--format", "json"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"state\": \"up_to_date\""));
+        helper
+            .command()
+            .args(["migrate", "apply"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Applied").not());
+
+        helper
+            .command()
+            .args(["init", "--adopt-from", "flyway"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("already tracked by pgmt"));
+
+        Ok(())
+    })
+    .await
+}
+
+/// A failed Flyway migration or a schema that differs from the files stops
+/// adoption before anything is recorded.
+#[tokio::test]
+async fn test_adopt_refuses_failed_or_drifted_database() -> Result<()> {
+    with_cli_helper(async |helper| {
+        write_project(helper)?;
+        seed_flyway(helper, "CREATE TABLE users (id INT);", false).await?;
+
+        helper
+            .command()
+            .args(["init", "--adopt-from", "flyway"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("1 failed migration(s)"));
+
+        let pool = helper.connect_to_dev_db().await?;
+        sqlx::query("UPDATE flyway_schema_history SET success = true")
+            .execute(&pool)
+            .await?;
+        pool.close().await;
+
+        helper
+            .command()
+            .args(["init", "--adopt-from", "flyway"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "the database differs from the schema files",
+            ));
+        assert!(
+            !helper
+                .table_exists_in_dev("public", "pgmt_migrations")
+                .await?
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+/// A database that matches the schema files but not the baseline plus the
+/// migrations after it (here the migration that adds `email` is missing) is
+/// refused: recording that history would make later replays wrong.
+#[tokio::test]
+async fn test_adopt_refuses_history_that_does_not_replay() -> Result<()> {
+    with_cli_helper(async |helper| {
+        write_project(helper)?;
+        std::fs::remove_file(helper.migrations_dir().join("2000_email.sql"))?;
+        seed_flyway(helper, "CREATE TABLE users (id INT, email TEXT);", true).await?;
+
+        helper
+            .command()
+            .args(["init", "--adopt-from", "flyway"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "baseline 1000 plus the migrations after it don't reproduce the database",
+            ));
+        assert!(
+            !helper
+                .table_exists_in_dev("public", "pgmt_migrations")
+                .await?
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+/// golang-migrate's dirty flag means a migration stopped part-way.
+#[tokio::test]
+async fn test_adopt_refuses_dirty_golang_migrate() -> Result<()> {
+    with_cli_helper(async |helper| {
+        write_project(helper)?;
+        let pool = helper.connect_to_dev_db().await?;
+        sqlx::raw_sql(
+            "CREATE TABLE users (id INT, email TEXT);
+             CREATE TABLE schema_migrations (version BIGINT PRIMARY KEY, dirty BOOLEAN NOT NULL);
+             INSERT INTO schema_migrations VALUES (2, true);",
+        )
+        .execute(&pool)
+        .await?;
+        pool.close().await;
+
+        helper
+            .command()
+            .args(["init", "--adopt-from", "golang-migrate"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("dirty at version 2"));
+
+        Ok(())
+    })
+    .await
+}
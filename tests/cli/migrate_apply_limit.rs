@@ -0,0 +1,131 @@
+//! `migrate apply --to/--count`: staged rollouts that stop at a chosen
+//! migration, with `migrate status` listing what remains.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+
+fn write_three_migrations(helper: &CliTestHelper) -> Result<()> {
+    helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+    helper.write_migration_file("V2000_posts.sql", "CREATE TABLE posts (id INT);")?;
+    helper.write_migration_file("V3000_tags.sql", "CREATE TABLE tags (id INT);")?;
+    Ok(())
+}
+
+/// `--to` applies through the named version and `--count` the next N; status
+/// lists the migrations still pending after each.
+#[tokio::test]
+async fn test_apply_to_and_count() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        write_three_migrations(helper)?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--to", "V1000"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Stopped before migration 2000"));
+        assert!(helper.table_exists_in_dev("public", "users").await?);
+        assert!(!helper.table_exists_in_dev("public", "posts").await?);
+
+        helper
+            .command()
+            .args(["migrate", "status"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Pending migrations:\n  2000 - posts\n  3000 - tags\n",
+            ));
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--count", "1"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Stopped before migration 3000"));
+        assert!(helper.table_exists_in_dev("public", "posts").await?);
+        assert!(!helper.table_exists_in_dev("public", "tags").await?);
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--count", "5"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Stopped before").not());
+        helper
+            .command()
+            .args(["migrate", "status"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Pending migrations: none"));
+
+        Ok(())
+    })
+    .await
+}
+
+/// An unknown `--to` version is an error, and `--to` with `--count` is
+/// rejected.
+#[tokio::test]
+async fn test_apply_limit_errors() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        write_three_migrations(helper)?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--to", "V9999"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Migration 'V9999' not found"));
+        helper
+            .command()
+            .args(["migrate", "apply", "--to", "V2000", "--count", "1"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("cannot be used with"));
+        assert!(!helper.table_exists_in_dev("public", "users").await?);
+
+        Ok(())
+    })
+    .await
+}
+
+/// A started-but-failed migration past the limit is called out rather than
+/// passed over.
+#[tokio::test]
+async fn test_apply_limit_reports_unfinished_migrations() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        helper.write_migration_file("V2000_broken.sql", "SELECT 1 / 0;")?;
+
+        helper
+            .command()
+            .args(["migrate", "apply"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure();
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--to", "V1000"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Migration 2000 (broken) is INCOMPLETE: 1 section(s) pending or failed",
+            ))
+            .stdout(predicate::str::contains("Stopped before migration 2000"));
+
+        Ok(())
+    })
+    .await
+}
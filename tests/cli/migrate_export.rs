@@ -0,0 +1,153 @@
+//! `migrate export`: the baseline and later migrations written in another
+//! tool's layout, with non-transactional sections kept out of a transaction.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+use std::path::Path;
+
+/// Baseline 1000, then 2000 with a transactional and a non-transactional
+/// section, then a plain 3000 with a down migration.
+fn write_history(helper: &CliTestHelper) -> Result<()> {
+    helper.init_project()?;
+    std::fs::write(
+        helper.baselines_dir().join("baseline_1000.sql"),
+        "CREATE TABLE users (id INT, email TEXT);\n",
+    )?;
+    helper.write_migration_file("0500_old.sql", "CREATE TABLE old (id INT);")?;
+    helper.write_migration_file(
+        "2000_email_index.sql",
+        "-- pgmt:section name=\"backfill\" lock_timeout=\"2s\"\n\
+         -- pgmt:assert_after SELECT count(*) = 0 FROM users WHERE email IS NULL\n\
+         UPDATE users SET email = '' WHERE email IS NULL;\n\n\
+         -- pgmt:section name=\"index\" mode=\"non-transactional\"\n\
+         CREATE INDEX CONCURRENTLY users_email ON users (email);\n",
+    )?;
+    helper.write_migration_file("3000_posts.sql", "CREATE TABLE posts (id INT);")?;
+    std::fs::create_dir_all(helper.migrations_dir().join("down"))?;
+    std::fs::write(
+        helper.migrations_dir().join("down/3000_posts.sql"),
+        "DROP TABLE posts;\n",
+    )?;
+    Ok(())
+}
+
+fn read(dir: &Path, name: &str) -> String {
+    std::fs::read_to_string(dir.join(name)).unwrap_or_else(|e| panic!("{}: {}", name, e))
+}
+
+/// Flyway: one script per migration, the split one with a `.sql.conf` and
+/// its own transactions; migrations the baseline covers are left out.
+#[tokio::test]
+async fn test_export_flyway() -> Result<()> {
+    with_cli_helper(async |helper| {
+        write_history(helper)?;
+        let out = helper.project_root.join("flyway");
+
+        helper
+            .command()
+            .args(["migrate", "export", "--format", "flyway", "--out"])
+            .arg(&out)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Exported 3 migration(s)"));
+
+        let mut files: Vec<String> = std::fs::read_dir(&out)?
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                "V1000__baseline.sql",
+                "V2000__email_index.sql",
+                "V2000__email_index.sql.conf",
+                "V3000__posts.sql",
+            ]
+        );
+        assert_eq!(
+            read(&out, "V2000__email_index.sql.conf"),
+            "executeInTransaction=false\n"
+        );
+
+        let split = read(&out, "V2000__email_index.sql");
+        let begin = split.find("BEGIN;").unwrap();
+        let set_lock = split.find("SET LOCAL lock_timeout = '2000';").unwrap();
+        let assertion = split.find("IF NOT (SELECT count(*) = 0").unwrap();
+        let commit = split.find("COMMIT;").unwrap();
+        let index = split.find("CREATE INDEX CONCURRENTLY").unwrap();
+        assert!(begin < set_lock && set_lock < assertion && assertion < commit && commit < index);
+        assert!(split.contains("SET lock_timeout TO DEFAULT;"));
+
+        let plain = read(&out, "V3000__posts.sql");
+        assert!(
+            !plain.contains("BEGIN;"),
+            "Flyway holds the transaction: {}",
+            plain
+        );
+        assert!(plain.contains("SET LOCAL statement_timeout"));
+
+        helper
+            .command()
+            .args(["migrate", "export", "--format", "flyway", "--out"])
+            .arg(&out)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("is not empty"));
+
+        Ok(())
+    })
+    .await
+}
+
+/// golang-migrate splits at the non-transactional section; sqitch and psql
+/// keep one script per migration.
+#[tokio::test]
+async fn test_export_golang_migrate_sqitch_psql() -> Result<()> {
+    with_cli_helper(async |helper| {
+        write_history(helper)?;
+        let root = &helper.project_root;
+        for format in ["golang-migrate", "sqitch", "psql"] {
+            helper
+                .command()
+                .args(["migrate", "export", "--format", format, "--out"])
+                .arg(root.join(format))
+                .assert()
+                .success();
+        }
+
+        let golang = root.join("golang-migrate");
+        assert!(read(&golang, "100000_baseline.up.sql").contains("CREATE TABLE users"));
+        assert!(read(&golang, "200000_email_index.up.sql").contains("UPDATE users"));
+        assert_eq!(
+            read(&golang, "200001_email_index.up.sql"),
+            "-- Exported by pgmt from 2000_email_index.sql\n\n-- section: index\n\
+             CREATE INDEX CONCURRENTLY users_email ON users (email);\n"
+        );
+        assert_eq!(
+            read(&golang, "300000_posts.down.sql"),
+            "DROP TABLE posts;\n"
+        );
+
+        let sqitch = root.join("sqitch");
+        let plan = read(&sqitch, "sqitch.plan");
+        assert!(plan.contains("v1000_baseline 1970-01-01T00:16:40Z pgmt <pgmt@localhost>"));
+        assert!(plan.find("v2000_email_index").unwrap() < plan.find("v3000_posts").unwrap());
+        assert!(
+            read(&sqitch, "deploy/v2000_email_index.sql").contains("CREATE INDEX CONCURRENTLY")
+        );
+        assert!(read(&sqitch, "revert/v3000_posts.sql").contains("DROP TABLE posts;"));
+
+        let psql = root.join("psql");
+        assert_eq!(
+            read(&psql, "deploy.sql"),
+            "\\set ON_ERROR_STOP on\n\\ir 1000_baseline.sql\n\\ir 2000_email_index.sql\n\
+             \\ir 3000_posts.sql\n"
+        );
+        let split = read(&psql, "2000_email_index.sql");
+        assert!(split.contains("SET statement_timeout = '600000';"));
+        assert!(split.contains("RESET statement_timeout;"));
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,152 @@
+//! `migrate apply --targets`: one run over several databases from the
+//! `targets:` inventory, canaries first, with a stop or continue policy.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+
+fn append_inventory(helper: &CliTestHelper, yaml: &str) -> Result<()> {
+    let path = helper.project_root.join("pgmt.yaml");
+    let config = std::fs::read_to_string(&path)?;
+    std::fs::write(&path, format!("{}\ntargets:\n{}", config, yaml))?;
+    Ok(())
+}
+
+async fn has_users(url: &str) -> Result<bool> {
+    let pool = sqlx::PgPool::connect(url).await?;
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass('public.users') IS NOT NULL")
+        .fetch_one(&pool)
+        .await?;
+    pool.close().await;
+    Ok(exists)
+}
+
+/// A group selects its targets only; the canary runs first and a URL can
+/// come from an environment variable.
+#[tokio::test]
+async fn test_apply_targets_by_group() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        let canary = helper.create_extra_database().await?;
+        let eu = helper.create_extra_database().await?;
+        let us = helper.create_extra_database().await?;
+        append_inventory(
+            helper,
+            &format!(
+                "  eu-canary:\n    url: {canary}\n    groups: [eu]\n    canary: true\n\
+                 \x20 eu-1:\n    url_env: FLEET_TEST_EU_1_URL\n    groups: [eu]\n\
+                 \x20 us-1:\n    url: {us}\n    groups: [us]\n"
+            ),
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--targets", "group:eu"])
+            .env("FLEET_TEST_EU_1_URL", &eu)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Applying migrations to 2 target(s): eu-canary (canary), eu-1",
+            ))
+            .stdout(predicate::str::contains("── eu-canary: ✅ applied"))
+            .stdout(predicate::str::contains(
+                "Fleet summary: 2 applied, 0 failed, 0 skipped",
+            ));
+        assert!(has_users(&canary).await?);
+        assert!(has_users(&eu).await?);
+        assert!(!has_users(&us).await?);
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--targets", "group:apac"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "no target in `targets:` has group 'apac'",
+            ));
+
+        Ok(())
+    })
+    .await
+}
+
+/// A broken target stops the run under `stop`, is reported alongside the
+/// others under `continue`, and as a canary keeps every other target from
+/// starting.
+#[tokio::test]
+async fn test_apply_targets_failure_policies() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        let broken = helper.create_extra_database().await?;
+        let pool = sqlx::PgPool::connect(&broken).await?;
+        sqlx::query("CREATE TABLE users (id INT)")
+            .execute(&pool)
+            .await?;
+        pool.close().await;
+        let ok = helper.create_extra_database().await?;
+        let config = std::fs::read_to_string(helper.project_root.join("pgmt.yaml"))?;
+        append_inventory(
+            helper,
+            &format!("  a-broken:\n    url: {broken}\n  b-ok:\n    url: {ok}\n"),
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--targets", "all", "--concurrency", "1"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("── a-broken: ❌ failed"))
+            .stdout(predicate::str::is_match(r"b-ok +skipped: an earlier target failed").unwrap())
+            .stderr(predicate::str::contains(
+                "Fleet apply incomplete: 1 of 2 target(s) failed, 1 skipped",
+            ));
+        assert!(!has_users(&ok).await?);
+
+        let output = helper
+            .command()
+            .args(["migrate", "apply", "--targets", "all", "--concurrency", "1"])
+            .args(["--on-failure", "continue", "--format", "json"])
+            .output()?;
+        assert!(!output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        assert_eq!(report["applied"], 1);
+        assert_eq!(report["failed"], 1);
+        assert_eq!(report["targets"][0]["name"], "a-broken");
+        assert_eq!(report["targets"][0]["status"], "failed");
+        assert!(
+            report["targets"][0]["error"]
+                .as_str()
+                .unwrap()
+                .contains("Migration 1000 failed")
+        );
+        assert_eq!(report["targets"][1]["status"], "applied");
+        assert!(has_users(&ok).await?);
+
+        // Both as canaries, the broken one first: the other never starts.
+        std::fs::write(helper.project_root.join("pgmt.yaml"), config)?;
+        append_inventory(
+            helper,
+            &format!(
+                "  a-broken:\n    url: {broken}\n    canary: true\n\
+                 \x20 b-ok:\n    url: {ok}\n    canary: true\n"
+            ),
+        )?;
+        helper
+            .command()
+            .args([
+                "migrate",
+                "apply",
+                "--targets",
+                "all",
+                "--on-failure",
+                "continue",
+            ])
+            .assert()
+            .failure()
+            .stdout(predicate::str::is_match(r"b-ok +skipped: canary a-broken failed").unwrap());
+
+        Ok(())
+    })
+    .await
+}
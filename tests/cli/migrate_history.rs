@@ -0,0 +1,111 @@
+//! `migration.audit` and `migrate history`: every section attempt and
+//! `migrate resolve` action, with what ran and who ran it.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+use std::fs;
+
+fn enable_audit(helper: &CliTestHelper) -> Result<()> {
+    let config_path = helper.project_root.join("pgmt.yaml");
+    let config =
+        fs::read_to_string(&config_path)?.replace("migration:\n", "migration:\n  audit: true\n");
+    fs::write(config_path, config)?;
+    Ok(())
+}
+
+/// A failed attempt, its resolution and a later successful apply all show up
+/// in the timeline, with the SQL, settings and deploy id.
+#[tokio::test]
+async fn test_history_records_attempts_and_resolutions() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        enable_audit(helper)?;
+        helper.write_migration_file(
+            "V1000_users.sql",
+            "-- pgmt:section name=\"create_users\" lock_timeout=\"2s\"\n\
+             CREATE TABLE users (id INT);\n\n\
+             -- pgmt:section name=\"fix_data\"\n\
+             UPDATE missing SET x = 1;\n",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--deploy-id", "release-42"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure();
+        helper
+            .command()
+            .args(["migrate", "resolve", "--mark-completed", "1000/fix_data"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        helper
+            .command()
+            .args(["migrate", "history", "--version", "V1000", "--sql"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("create_users attempt 1 completed"))
+            .stdout(predicate::str::contains("fix_data attempt 1 failed"))
+            .stdout(predicate::str::contains(
+                "relation \"missing\" does not exist",
+            ))
+            .stdout(predicate::str::contains(
+                "fix_data resolve --mark-completed completed",
+            ))
+            .stdout(predicate::str::contains("deploy release-42"))
+            .stdout(predicate::str::contains("\"lock_timeout_ms\": 2000"))
+            .stdout(predicate::str::contains("| CREATE TABLE users (id INT);"));
+
+        let pool = helper.connect_to_dev_db().await?;
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pgmt_migrations_history")
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(rows, 3);
+
+        helper
+            .command()
+            .args(["migrate", "history", "--version", "V2000"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "No history recorded for version 2000",
+            ));
+
+        Ok(())
+    })
+    .await
+}
+
+/// Without `migration.audit` nothing is recorded and history says how to
+/// turn it on.
+#[tokio::test]
+async fn test_history_without_audit() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        helper
+            .command()
+            .args([
+                "migrate",
+                "history",
+                "--target-url",
+                &helper.dev_database_url,
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("migration.audit: true"));
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,108 @@
+//! Lifecycle hooks: `hooks/*.sql` files and `migration.hooks` commands run by
+//! `migrate apply` around the run and after each section.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+use std::fs;
+
+const TWO_SECTIONS: &str = r#"-- pgmt:section name="create"
+CREATE TABLE users (id INT PRIMARY KEY);
+
+-- pgmt:section name="seed"
+INSERT INTO users VALUES (1);
+"#;
+
+/// Add `hooks_yaml` under the `migration:` block of the generated pgmt.yaml.
+fn configure_hooks(helper: &CliTestHelper, hooks_yaml: &str) -> Result<()> {
+    let config_path = helper.project_root.join("pgmt.yaml");
+    let config = fs::read_to_string(&config_path)?.replace(
+        "migration:\n",
+        &format!("migration:\n  hooks:\n{hooks_yaml}"),
+    );
+    fs::write(config_path, config)?;
+    Ok(())
+}
+
+/// SQL hooks run in order and read the version/section from `pgmt.*`
+/// settings; a command hook gets the same values as environment variables.
+#[tokio::test]
+async fn test_hooks_run_around_apply() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file("V1000_users.sql", TWO_SECTIONS)?;
+        let hooks_dir = helper.project_root.join("hooks");
+        fs::create_dir_all(&hooks_dir)?;
+        fs::write(
+            hooks_dir.join("before_migrate.sql"),
+            "CREATE TABLE IF NOT EXISTS hook_log (id SERIAL, entry TEXT);\n\
+             INSERT INTO hook_log (entry) VALUES (current_setting('pgmt.hook'));",
+        )?;
+        fs::write(
+            hooks_dir.join("after_each_section.sql"),
+            "INSERT INTO hook_log (entry) VALUES (current_setting('pgmt.migration_version') \
+             || ':' || current_setting('pgmt.section_name'));",
+        )?;
+        fs::write(
+            hooks_dir.join("after_migrate.sql"),
+            "INSERT INTO hook_log (entry) VALUES (current_setting('pgmt.hook'));",
+        )?;
+        configure_hooks(
+            helper,
+            "    after_each_section: 'echo \"$PGMT_MIGRATION_VERSION \
+             $PGMT_MIGRATION_DESCRIPTION $PGMT_SECTION_NAME\" >> sections.log'\n",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Hook before_migrate"))
+            .stdout(predicate::str::contains("Hook after_each_section"))
+            .stdout(predicate::str::contains("Hook after_migrate"));
+
+        let pool = helper.connect_to_dev_db().await?;
+        let entries: Vec<String> = sqlx::query_scalar("SELECT entry FROM hook_log ORDER BY id")
+            .fetch_all(&pool)
+            .await?;
+        assert_eq!(
+            entries,
+            [
+                "before_migrate",
+                "1000:create",
+                "1000:seed",
+                "after_migrate"
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(helper.project_root.join("sections.log"))?,
+            "1000 users create\n1000 users seed\n"
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+/// A failing `before_migrate` hook is reported and stops the apply before
+/// any migration runs.
+#[tokio::test]
+async fn test_failing_hook_stops_apply() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file("V1000_users.sql", TWO_SECTIONS)?;
+        configure_hooks(helper, "    before_migrate: 'exit 3'\n")?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("Hook before_migrate failed"))
+            .stderr(predicate::str::contains("before_migrate hook failed"));
+        assert!(!helper.table_exists_in_dev("public", "users").await?);
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,79 @@
+use crate::helpers::cli::with_cli_helper;
+use anyhow::Result;
+use predicates::prelude::*;
+use serde_json::Value;
+
+#[tokio::test]
+async fn test_migrate_lint_reports_findings_and_fails_on_errors() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file(
+            "V1000_drop_legacy.sql",
+            "DROP TABLE legacy_events;\nCREATE INDEX idx_users_email ON users (email);\n",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "lint"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("[drop-without-allow]"))
+            .stdout(predicate::str::contains("[index-not-concurrent]"));
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_migrate_lint_allow_annotation_suppresses_error() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file(
+            "V1000_drop_legacy.sql",
+            "-- pgmt:section name=\"drop_legacy\"\n\
+             -- pgmt:  lock_timeout=\"2s\"\n\
+             -- pgmt:allow drop-without-allow\n\
+             DROP TABLE legacy_events;\n",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "lint"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No lint findings"));
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_migrate_lint_sarif_format() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file(
+            "V1000_rename.sql",
+            "ALTER TABLE users RENAME COLUMN name TO full_name;\n",
+        )?;
+
+        let assert = helper
+            .command()
+            .args(["migrate", "lint", "--format", "sarif"])
+            .assert()
+            .success();
+        let sarif: Value = serde_json::from_slice(&assert.get_output().stdout)?;
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert!(results.iter().any(|r| r["ruleId"] == "rename-column"));
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "migrations/V1000_rename.sql"
+        );
+
+        Ok(())
+    })
+    .await
+}
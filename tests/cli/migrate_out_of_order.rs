@@ -0,0 +1,238 @@
+//! Out-of-order migrations: a pending migration older than the newest applied
+//! one is listed by `migrate status`, refused by `migrate apply` unless
+//! allowed, and checked by `migrate validate` when allowed.
+use crate::helpers::cli::with_cli_helper;
+use anyhow::Result;
+use predicates::prelude::*;
+
+/// A migration that lands below the newest applied version is flagged and
+/// refused, then applied with `--allow-out-of-order`.
+#[tokio::test]
+async fn test_apply_refuses_out_of_order_until_allowed() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        helper.write_migration_file("V3000_tags.sql", "CREATE TABLE tags (id INT);")?;
+        helper
+            .command()
+            .args(["migrate", "apply"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        // Merged from a branch after 3000 was applied here.
+        helper.write_migration_file("V2000_posts.sql", "CREATE TABLE posts (id INT);")?;
+
+        helper
+            .command()
+            .args(["migrate", "status"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "2000 - posts (OUT OF ORDER: older than the newest applied migration)",
+            ))
+            .stdout(predicate::str::contains("--allow-out-of-order"));
+
+        let output = helper
+            .command()
+            .args(["migrate", "status", "--format", "json"])
+            .args(["--target-url", &helper.dev_database_url])
+            .output()?;
+        let status: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let pending = status["migrations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|m| m["version"] == 2000)
+            .unwrap();
+        assert_eq!(pending["status"], "pending");
+        assert_eq!(pending["out_of_order"], true);
+
+        helper
+            .command()
+            .args(["migrate", "apply"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "older than the newest applied migration: 2000 (posts)",
+            ));
+        assert!(!helper.table_exists_in_dev("public", "posts").await?);
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--allow-out-of-order"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Applying 1 migration(s) out of order: 2000 (posts)",
+            ));
+        assert!(helper.table_exists_in_dev("public", "posts").await?);
+
+        helper
+            .command()
+            .args(["migrate", "status"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Pending migrations: none"));
+
+        Ok(())
+    })
+    .await
+}
+
+/// With out-of-order apply allowed, validate fails when a target's pending
+/// migration would land differently run after the later ones: an ADD COLUMN
+/// applied last puts the column at the end. Only targets with out-of-order
+/// migrations are replayed.
+#[tokio::test]
+async fn test_validate_checks_out_of_order_results() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT, email TEXT, name TEXT);",
+        )?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        helper.write_migration_file("V3000_name.sql", "ALTER TABLE users ADD COLUMN name TEXT;")?;
+        helper
+            .command()
+            .args(["migrate", "apply"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        // Merged from a branch after 3000 was applied to the target.
+        helper.write_migration_file(
+            "V2000_email.sql",
+            "ALTER TABLE users ADD COLUMN email TEXT;",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "validate"])
+            .assert()
+            .success();
+
+        // Without a target the order is unknown: warn that nothing was checked.
+        helper
+            .command()
+            .args(["migrate", "validate", "--allow-out-of-order"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Warning: migration.allow_out_of_order is on but no target database",
+            ));
+
+        helper
+            .command()
+            .args(["migrate", "validate", "--allow-out-of-order"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Checking 1 migration(s) applied out of order",
+            ))
+            .stderr(predicate::str::contains("applies 2000 (email)"));
+
+        helper
+            .command()
+            .args(["migrate", "validate", "--allow-out-of-order"])
+            .args(["--target-url", &helper.dev_database_url])
+            .args(["--ignore-migrations", "V2000"])
+            .assert()
+            .success();
+
+        Ok(())
+    })
+    .await
+}
+
+/// The target's order is its applied migrations, then every pending one in
+/// version order — including pending migrations newer than its latest, which
+/// may depend on the late one.
+#[tokio::test]
+async fn test_validate_replays_pending_migrations_after_the_late_one() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT, email TEXT NOT NULL, name TEXT);",
+        )?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        helper.write_migration_file("V3000_name.sql", "ALTER TABLE users ADD COLUMN name TEXT;")?;
+        helper
+            .command()
+            .args(["migrate", "apply"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        helper.write_migration_file(
+            "V2000_email.sql",
+            "ALTER TABLE users ADD COLUMN email TEXT;",
+        )?;
+        helper.write_migration_file(
+            "V4000_email_required.sql",
+            "ALTER TABLE users ALTER COLUMN email SET NOT NULL;",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "validate", "--allow-out-of-order"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("applies 2000 (email)"));
+
+        Ok(())
+    })
+    .await
+}
+
+/// A pending migration that fails when run after the newer applied ones
+/// fails validation rather than being skipped.
+#[tokio::test]
+async fn test_validate_fails_when_target_order_does_not_replay() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT, email TEXT);\nCREATE TABLE audit (id INT);",
+        )?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        helper.write_migration_file(
+            "V3000_email.sql",
+            "ALTER TABLE users ADD COLUMN email TEXT;",
+        )?;
+        helper
+            .command()
+            .args(["migrate", "apply"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        // Fine in version order (before 3000), fails after it.
+        helper.write_migration_file(
+            "V2000_audit.sql",
+            "CREATE TABLE audit (id INT);\nDO $$ BEGIN IF EXISTS (SELECT 1 FROM \
+             information_schema.columns WHERE table_name = 'users' AND column_name = 'email') \
+             THEN RAISE EXCEPTION 'email already exists'; END IF; END $$;",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "validate", "--allow-out-of-order"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("don't replay when 2000 (audit)"))
+            .stderr(predicate::str::contains("email already exists"));
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,279 @@
+//! `migrate rebase`: a branch's migrations, generated before main gained its
+//! own, move after main's and are regenerated from the merged schema files.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::{Result, ensure};
+use predicates::prelude::*;
+use std::process::Command;
+
+fn git(helper: &CliTestHelper, args: &[&str]) -> Result<()> {
+    let status = Command::new("git")
+        .args(["-c", "user.name=pgmt", "-c", "user.email=pgmt@example.com"])
+        .args(args)
+        .current_dir(&helper.project_root)
+        .output()?;
+    ensure!(
+        status.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&status.stderr)
+    );
+    Ok(())
+}
+
+/// main: users. Branch `feature` adds `users.email` and `posts` with
+/// `feature_migration`; meanwhile main adds the same `email` column in a later
+/// version. Leaves `feature` checked out with main merged in.
+fn diverge(helper: &CliTestHelper, feature_migration: &str) -> Result<()> {
+    helper.init_project()?;
+    git(helper, &["init", "-q", "-b", "main"])?;
+    helper.write_schema_file("users.sql", "CREATE TABLE users (id INT);")?;
+    helper.write_migration_file("1000000000_users.sql", "CREATE TABLE users (id INT);")?;
+    git(helper, &["add", "-A"])?;
+    git(helper, &["commit", "-qm", "users"])?;
+
+    git(helper, &["checkout", "-qb", "feature"])?;
+    helper.write_schema_file("users.sql", "CREATE TABLE users (id INT, email TEXT);")?;
+    helper.write_schema_file("posts.sql", "CREATE TABLE posts (id INT);")?;
+    helper.write_migration_file("1100000000_posts.sql", feature_migration)?;
+    git(helper, &["add", "-A"])?;
+    git(helper, &["commit", "-qm", "posts"])?;
+
+    git(helper, &["checkout", "-q", "main"])?;
+    helper.write_schema_file("users.sql", "CREATE TABLE users (id INT, email TEXT);")?;
+    helper.write_migration_file(
+        "1200000000_email.sql",
+        "ALTER TABLE users ADD COLUMN email TEXT;",
+    )?;
+    git(helper, &["add", "-A"])?;
+    git(helper, &["commit", "-qm", "email"])?;
+
+    git(helper, &["checkout", "-q", "feature"])?;
+    git(helper, &["merge", "-q", "--no-edit", "main"])?;
+    Ok(())
+}
+
+/// The branch migration moves after main's and loses the step main already
+/// took; a second rebase has nothing to do.
+#[tokio::test]
+async fn test_rebase_renumbers_and_drops_duplicate_steps() -> Result<()> {
+    with_cli_helper(async |helper| {
+        diverge(
+            helper,
+            "ALTER TABLE users ADD COLUMN email TEXT;\n\nCREATE TABLE posts (id INT);\n",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "rebase", "--dry-run"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1100000000_posts.sql -> "))
+            .stdout(predicate::str::contains("Dry run: no files changed"));
+        assert!(
+            helper
+                .migrations_dir()
+                .join("1100000000_posts.sql")
+                .exists()
+        );
+
+        helper
+            .command()
+            .args(["migrate", "rebase"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Rebase complete!"));
+
+        let files = helper.list_migration_files()?;
+        assert_eq!(files.len(), 3, "{:?}", files);
+        assert_eq!(files[0], "1000000000_users.sql");
+        assert_eq!(files[1], "1200000000_email.sql");
+        assert!(files[2].ends_with("_posts.sql") && files[2] != "1100000000_posts.sql");
+
+        let rebased = helper.read_migration_file(&files[2])?;
+        assert!(rebased.contains("posts"), "{}", rebased);
+        assert!(!rebased.contains("email"), "{}", rebased);
+
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "rebase"])?;
+        helper
+            .command()
+            .args(["migrate", "rebase"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("nothing to rebase"));
+
+        Ok(())
+    })
+    .await
+}
+
+/// Hand-written sections are kept as written; one that repeats main's step is
+/// called out rather than dropped.
+#[tokio::test]
+async fn test_rebase_keeps_handwritten_sections() -> Result<()> {
+    with_cli_helper(async |helper| {
+        diverge(
+            helper,
+            "-- pgmt:section name=\"email\"\n\
+             ALTER TABLE users ADD COLUMN email TEXT;\n\n\
+             -- pgmt:section name=\"posts\" lock_timeout=\"2s\"\n\
+             CREATE TABLE posts (id INT);\n",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "rebase"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Hand-written section 'email' no longer applies after main's migrations",
+            ));
+
+        let files = helper.list_migration_files()?;
+        let rebased = helper.read_migration_file(&files[2])?;
+        assert!(rebased.contains("-- pgmt:section name=\"email\""));
+        assert!(rebased.contains("-- pgmt:section name=\"posts\" lock_timeout=\"2s\""));
+        assert!(
+            !rebased.contains("rebased"),
+            "the sections cover the schema files, so nothing is regenerated: {}",
+            rebased
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+/// The regenerated steps are computed with the hand-written sections already
+/// run, so they go after them: a backfill reading a column still runs before
+/// the regenerated drop of that column, and the rebased history replays.
+#[tokio::test]
+async fn test_rebase_puts_regenerated_steps_after_handwritten_sections() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        git(helper, &["init", "-q", "-b", "main"])?;
+        helper.write_schema_file("users.sql", "CREATE TABLE users (id INT, legacy TEXT);")?;
+        helper.write_migration_file(
+            "1000000000_users.sql",
+            "CREATE TABLE users (id INT, legacy TEXT);",
+        )?;
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "users"])?;
+
+        git(helper, &["checkout", "-qb", "feature"])?;
+        helper.write_schema_file("users.sql", "CREATE TABLE users (id INT, email TEXT);")?;
+        helper.write_migration_file(
+            "1100000000_email.sql",
+            "-- pgmt:section name=\"backfill\"\n\
+             ALTER TABLE users ADD COLUMN email TEXT;\n\
+             UPDATE users SET email = legacy;\n",
+        )?;
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "email"])?;
+
+        git(helper, &["checkout", "-q", "main"])?;
+        helper.write_schema_file("posts.sql", "CREATE TABLE posts (id INT);")?;
+        helper.write_migration_file("1200000000_posts.sql", "CREATE TABLE posts (id INT);")?;
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "posts"])?;
+
+        git(helper, &["checkout", "-q", "feature"])?;
+        git(helper, &["merge", "-q", "--no-edit", "main"])?;
+
+        helper
+            .command()
+            .args(["migrate", "rebase"])
+            .assert()
+            .success();
+
+        let files = helper.list_migration_files()?;
+        let rebased = helper.read_migration_file(&files[2])?;
+        let backfill = rebased.find("name=\"backfill\"").expect("backfill kept");
+        let regenerated = rebased.find("name=\"rebased\"").expect("drop regenerated");
+        assert!(backfill < regenerated, "{}", rebased);
+        assert!(rebased[regenerated..].contains("legacy"), "{}", rebased);
+
+        helper
+            .command()
+            .args(["migrate", "validate"])
+            .assert()
+            .success();
+
+        Ok(())
+    })
+    .await
+}
+
+/// The `schema` and `seed_data` sections `migrate new` writes are regenerated,
+/// not kept as hand-written: main's `posts` drops out of the schema changes and
+/// the branch's seed row stays, in its own section.
+#[tokio::test]
+async fn test_rebase_regenerates_generated_seed_sections() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        git(helper, &["init", "-q", "-b", "main"])?;
+        let data_dir = helper.project_root.join("data");
+        std::fs::create_dir_all(&data_dir)?;
+        helper.write_schema_file(
+            "plans.sql",
+            "CREATE TABLE plans (id INT PRIMARY KEY, name TEXT NOT NULL);",
+        )?;
+        std::fs::write(data_dir.join("plans.csv"), "id,name\n1,Free\n")?;
+        helper.write_migration_file(
+            "1000000000_plans.sql",
+            "CREATE TABLE plans (id INT PRIMARY KEY, name TEXT NOT NULL);\n\
+             INSERT INTO plans VALUES (1, 'Free');",
+        )?;
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "plans"])?;
+
+        git(helper, &["checkout", "-qb", "feature"])?;
+        helper.write_schema_file("posts.sql", "CREATE TABLE posts (id INT);")?;
+        std::fs::write(data_dir.join("plans.csv"), "id,name\n1,Free\n2,Pro\n")?;
+        helper.write_migration_file(
+            "1100000000_pro.sql",
+            "-- pgmt:section name=\"schema\"\n\
+             CREATE TABLE \"public\".\"posts\" (\"id\" INTEGER);\n\n\
+             -- pgmt:section name=\"seed_data\"\n\
+             INSERT INTO \"public\".\"plans\" (\"id\", \"name\") VALUES\n    ('2', 'Pro');\n",
+        )?;
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "pro"])?;
+
+        git(helper, &["checkout", "-q", "main"])?;
+        helper.write_schema_file("posts.sql", "CREATE TABLE posts (id INT);")?;
+        helper.write_migration_file("1200000000_posts.sql", "CREATE TABLE posts (id INT);")?;
+        git(helper, &["add", "-A"])?;
+        git(helper, &["commit", "-qm", "posts"])?;
+
+        git(helper, &["checkout", "-q", "feature"])?;
+        git(helper, &["merge", "-q", "--no-edit", "main"])?;
+
+        helper
+            .command()
+            .args(["migrate", "rebase"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Hand-written section").not());
+
+        let files = helper.list_migration_files()?;
+        let rebased = helper.read_migration_file(&files[2])?;
+        assert!(!rebased.contains("posts"), "{}", rebased);
+        assert!(!rebased.contains("rebased"), "{}", rebased);
+        assert!(
+            rebased.starts_with("-- pgmt:section name=\"seed_data\""),
+            "{}",
+            rebased
+        );
+        assert!(rebased.contains("'Pro'"), "{}", rebased);
+
+        helper
+            .command()
+            .args(["migrate", "validate"])
+            .assert()
+            .success();
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,94 @@
+//! `migrate apply --rehearse`: pending migrations run on a schema-only branch
+//! of the target and the result is diffed against the schema files, without
+//! writing to the target.
+use crate::helpers::cli::with_cli_helper;
+use anyhow::Result;
+use predicates::prelude::*;
+
+const USERS_SCHEMA: &str = "CREATE TABLE users (id INT PRIMARY KEY, email TEXT);";
+
+/// A clean rehearsal reports each section and a matching schema, and the
+/// target keeps only what was applied before it.
+#[tokio::test]
+async fn test_rehearse_leaves_target_untouched() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file(
+            "V1000_users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY);",
+        )?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        helper.write_schema_file("users.sql", USERS_SCHEMA)?;
+        helper.write_migration_file(
+            "V2000_email.sql",
+            "-- pgmt:section name=\"add_email\"\nALTER TABLE users ADD COLUMN email TEXT;\n",
+        )?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--rehearse"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("add_email"))
+            .stdout(predicate::str::contains(
+                "Rehearsed schema matches the schema files",
+            ));
+
+        let pool = helper.connect_to_dev_db().await?;
+        let versions: Vec<i64> =
+            sqlx::query_scalar("SELECT version FROM pgmt_migrations ORDER BY version")
+                .fetch_all(&pool)
+                .await?;
+        assert_eq!(versions, [1000]);
+        let has_email: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM information_schema.columns \
+             WHERE table_name = 'users' AND column_name = 'email')",
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert!(!has_email);
+
+        Ok(())
+    })
+    .await
+}
+
+/// A failing migration and a result that doesn't match the schema files both
+/// fail the rehearsal.
+#[tokio::test]
+async fn test_rehearse_reports_failures_and_drift() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("users.sql", USERS_SCHEMA)?;
+        helper.write_migration_file(
+            "V1000_users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY);\nALTER TABLE users ADD COLUMN emial TEXT;",
+        )?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--rehearse"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("rehearsed schema differs"));
+
+        helper.write_migration_file("V2000_broken.sql", "ALTER TABLE missing ADD COLUMN x INT;")?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--rehearse"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Rehearsal failed"));
+
+        assert!(!helper.table_exists_in_dev("public", "users").await?);
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,92 @@
+use crate::helpers::cli::with_cli_helper;
+use anyhow::Result;
+use predicates::prelude::*;
+use std::fs;
+
+/// Repeatable migrations run after versioned migrations, in `-- require:`
+/// order, and re-run only when their checksum changes.
+#[tokio::test]
+async fn test_repeatable_migrations_rerun_on_change() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file(
+            "V1000_users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY, active BOOLEAN NOT NULL);",
+        )?;
+        let repeatable_dir = helper.project_root.join("repeatable");
+        fs::create_dir_all(&repeatable_dir)?;
+        // `a_` sorts first alphabetically, but requires the view file.
+        fs::write(
+            repeatable_dir.join("a_grants.sql"),
+            "-- require: views\nCOMMENT ON VIEW active_users IS 'v1';",
+        )?;
+        fs::write(
+            repeatable_dir.join("views.sql"),
+            "CREATE OR REPLACE VIEW active_users AS SELECT id FROM users WHERE active;",
+        )?;
+
+        helper
+            .command()
+            .args([
+                "migrate",
+                "status",
+                "--target-url",
+                &helper.dev_database_url,
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Repeatable migrations: 2 pending"))
+            .stdout(predicate::str::contains("views.sql (never run)"));
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Applying repeatable migrations"))
+            .stdout(predicate::str::contains("views.sql (ran"))
+            .stdout(predicate::str::contains("a_grants.sql (ran"));
+
+        // Nothing changed: nothing re-runs.
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Nothing to apply — up to date."));
+
+        fs::write(
+            repeatable_dir.join("a_grants.sql"),
+            "-- require: views\nCOMMENT ON VIEW active_users IS 'v2';",
+        )?;
+        helper
+            .command()
+            .args([
+                "migrate",
+                "status",
+                "--target-url",
+                &helper.dev_database_url,
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("a_grants.sql (changed)"));
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("a_grants.sql (re-ran"))
+            .stdout(predicate::str::contains("views.sql").not());
+
+        let pool = helper.connect_to_dev_db().await?;
+        let comment: Option<String> =
+            sqlx::query_scalar("SELECT obj_description('active_users'::regclass)")
+                .fetch_one(&pool)
+                .await?;
+        assert_eq!(comment.as_deref(), Some("v2"));
+        pool.close().await;
+
+        Ok(())
+    })
+    .await
+}
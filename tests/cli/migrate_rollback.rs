@@ -0,0 +1,252 @@
+use crate::helpers::cli::{next_version_tick, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+
+/// `migrate new --down` writes the reverse diff next to the migration, and
+/// `migrate update` keeps the down file paired with its migration:
+/// regenerated in place, and moved along when the migration is renumbered.
+#[tokio::test]
+async fn test_migrate_update_keeps_down_file_paired() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("users.sql", "CREATE TABLE users (id INT PRIMARY KEY);")?;
+        helper
+            .command()
+            .args(["migrate", "new", "add_users", "--down"])
+            .assert()
+            .success();
+        next_version_tick();
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY, email TEXT);",
+        )?;
+        helper
+            .command()
+            .args(["migrate", "new", "add_email", "--down"])
+            .assert()
+            .success();
+        let down_dir = helper.migrations_dir().join("down");
+        let read_down = |name: &str| std::fs::read_to_string(down_dir.join(name));
+
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY, name TEXT);",
+        )?;
+        helper
+            .command()
+            .args(["migrate", "update"])
+            .assert()
+            .success();
+        let migrations = helper.list_migration_files()?;
+        let down = read_down(&migrations[1])?;
+        assert!(down.contains("DROP COLUMN IF EXISTS \"name\""), "{}", down);
+        assert!(!down.contains("\"email\""), "{}", down);
+
+        // Renumbering the older migration moves its down file too.
+        let first = migrations[0].clone();
+        let version = first
+            .trim_start_matches('V')
+            .split('_')
+            .next()
+            .unwrap()
+            .to_string();
+        next_version_tick();
+        helper
+            .command()
+            .args(["migrate", "update", &version])
+            .assert()
+            .success();
+        let migrations = helper.list_migration_files()?;
+        assert!(!migrations.contains(&first));
+        assert!(!down_dir.join(&first).exists());
+        for migration in &migrations {
+            assert!(
+                down_dir.join(migration).exists(),
+                "no down file for {}",
+                migration
+            );
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// `migrate rollback` runs it and forgets the version in the tracking tables.
+#[tokio::test]
+async fn test_migrate_new_down_then_rollback() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("users.sql", "CREATE TABLE users (id INT PRIMARY KEY);")?;
+        helper
+            .command()
+            .args(["migrate", "new", "add_users", "--down"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Created down migration:"));
+
+        let migrations = helper.list_migration_files()?;
+        assert_eq!(migrations.len(), 1, "down files are not forward migrations");
+        let down_sql =
+            std::fs::read_to_string(helper.migrations_dir().join("down").join(&migrations[0]))?;
+        assert!(down_sql.contains("DROP TABLE"));
+        assert!(down_sql.contains("IRREVERSIBLE: discards data written since"));
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+        assert!(helper.table_exists_in_dev("public", "users").await?);
+
+        helper
+            .command()
+            .args([
+                "migrate",
+                "rollback",
+                "--target-url",
+                &helper.dev_database_url,
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Rolled back 1 migration(s)"));
+        assert!(!helper.table_exists_in_dev("public", "users").await?);
+
+        // The version is pending again: apply re-runs it.
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+        assert!(helper.table_exists_in_dev("public", "users").await?);
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_migrate_rollback_to_version_keeps_it_applied() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        std::fs::create_dir_all(helper.migrations_dir().join("down"))?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        helper.write_migration_file("down/V1000_users.sql", "DROP TABLE users;")?;
+        helper.write_migration_file("V2000_posts.sql", "CREATE TABLE posts (id INT);")?;
+        helper.write_migration_file("down/V2000_posts.sql", "DROP TABLE posts;")?;
+        helper.write_migration_file("V3000_tags.sql", "CREATE TABLE tags (id INT);")?;
+        helper.write_migration_file("down/V3000_tags.sql", "DROP TABLE tags;")?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        helper
+            .command()
+            .args([
+                "migrate",
+                "rollback",
+                "--to",
+                "V1000",
+                "--target-url",
+                &helper.dev_database_url,
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Rolled back 2 migration(s)"));
+
+        assert!(helper.table_exists_in_dev("public", "users").await?);
+        assert!(!helper.table_exists_in_dev("public", "posts").await?);
+        assert!(!helper.table_exists_in_dev("public", "tags").await?);
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_migrate_rollback_without_down_file_fails_before_changes() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        helper
+            .command()
+            .args([
+                "migrate",
+                "rollback",
+                "--target-url",
+                &helper.dev_database_url,
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("has no down migration"));
+        assert!(helper.table_exists_in_dev("public", "users").await?);
+
+        Ok(())
+    })
+    .await
+}
+
+/// Down sections run through the same executor as `apply`: a false
+/// `assert_before` stops the rollback before the section's SQL runs, the
+/// migration stays applied, and the attempt lands in the audit history.
+#[tokio::test]
+async fn test_migrate_rollback_honors_down_assertions() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        let config_path = helper.project_root.join("pgmt.yaml");
+        let config = std::fs::read_to_string(&config_path)?
+            .replace("migration:\n", "migration:\n  audit: true\n");
+        std::fs::write(config_path, config)?;
+        std::fs::create_dir_all(helper.migrations_dir().join("down"))?;
+        helper.write_migration_file(
+            "V1000_users.sql",
+            "CREATE TABLE users (id INT);\nINSERT INTO users VALUES (1);",
+        )?;
+        helper.write_migration_file(
+            "down/V1000_users.sql",
+            "-- pgmt:section name=\"drop_users\"\n\
+             -- pgmt:assert_before SELECT count(*) = 0 FROM users\n\
+             DROP TABLE users;\n",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+
+        for _ in 0..2 {
+            helper
+                .command()
+                .args(["migrate", "rollback"])
+                .args(["--target-url", &helper.dev_database_url])
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains(
+                    "assert_before at line 2 returned false",
+                ));
+        }
+        assert!(helper.table_exists_in_dev("public", "users").await?);
+
+        helper
+            .command()
+            .args(["migrate", "history", "--version", "V1000"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("drop_users rollback failed"));
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,121 @@
+//! `migrate status --format json` and the `--exit-code` deploy gate.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::Result;
+use pgmt::commands::migrate::status::TargetState;
+use serde_json::Value;
+
+/// Run status against dev with `--exit-code`, returning its exit code and the
+/// parsed JSON report.
+fn status_json(helper: &CliTestHelper) -> Result<(i32, Value)> {
+    let output = helper
+        .command()
+        .args(["migrate", "status", "--format", "json", "--exit-code"])
+        .args(["--target-url", &helper.dev_database_url])
+        .output()?;
+    let json = serde_json::from_slice(&output.stdout)?;
+    Ok((output.status.code().unwrap_or(-1), json))
+}
+
+/// Pending, applied, failed and modified-after-apply targets each get their
+/// own state and exit code, with per-section detail in the report.
+#[tokio::test]
+async fn test_status_json_states_and_exit_codes() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file(
+            "V1000_users.sql",
+            "-- pgmt:section name=\"create_users\"\nCREATE TABLE users (id INT);\n",
+        )?;
+
+        let (code, json) = status_json(helper)?;
+        assert_eq!(code, 10);
+        assert_eq!(json["state"], "pending");
+        assert_eq!(json["migrations"][0]["status"], "pending");
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+        let (code, json) = status_json(helper)?;
+        assert_eq!(code, 0);
+        assert_eq!(json["state"], "up_to_date");
+        let section = &json["migrations"][0]["sections"][0];
+        assert_eq!(section["name"], "create_users");
+        assert_eq!(section["status"], "completed");
+        assert!(section["duration_ms"].is_i64());
+
+        helper.write_migration_file(
+            "V2000_broken.sql",
+            "-- pgmt:section name=\"broken\"\nALTER TABLE missing ADD COLUMN x INT;\n",
+        )?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .failure();
+        let (code, json) = status_json(helper)?;
+        assert_eq!(code, 11);
+        assert_eq!(json["state"], "failed");
+        let failed = &json["migrations"][1];
+        assert_eq!(failed["status"], "failed");
+        assert_eq!(failed["sections"][0]["status"], "failed");
+        assert!(
+            failed["sections"][0]["last_error"]
+                .as_str()
+                .is_some_and(|e| e.contains("missing"))
+        );
+
+        helper.write_migration_file(
+            "V1000_users.sql",
+            "-- pgmt:section name=\"create_users\"\nCREATE TABLE users (id BIGINT);\n",
+        )?;
+        let (code, json) = status_json(helper)?;
+        assert_eq!(code, 12);
+        assert_eq!(json["state"], "checksum_mismatch");
+        assert_eq!(json["migrations"][0]["checksum_mismatch"], true);
+        assert_eq!(
+            json["migrations"][0]["sections"][0]["checksum_mismatch"],
+            true
+        );
+
+        // Without --exit-code the report is informational.
+        helper
+            .command()
+            .args([
+                "migrate",
+                "status",
+                "--target-url",
+                &helper.dev_database_url,
+            ])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("1000 - section(s) create_users"));
+
+        Ok(())
+    })
+    .await
+}
+
+/// `--exit-code`'s help lists the codes status actually exits with.
+#[test]
+fn test_exit_code_help_matches_states() {
+    let output = assert_cmd::cargo::cargo_bin_cmd!("pgmt")
+        .args(["migrate", "status", "--help"])
+        .output()
+        .unwrap();
+    let help = String::from_utf8(output.stdout).unwrap();
+    for (state, meaning) in [
+        (TargetState::Pending, "when migrations are pending"),
+        (TargetState::Failed, "when one failed"),
+        (TargetState::ChecksumMismatch, "on a checksum mismatch"),
+    ] {
+        let expected = format!("{} {}", state.exit_code(), meaning);
+        assert!(
+            help.contains(&expected),
+            "help lacks '{}':\n{}",
+            expected,
+            help
+        );
+    }
+}
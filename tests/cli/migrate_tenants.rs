@@ -0,0 +1,301 @@
+//! Schema-per-tenant mode: schema files name the template schema, generated
+//! migrations name it `${tenant}`; `migrate apply` runs them in every tenant
+//! schema with tracking per tenant, and `migrate status --tenants` reports
+//! laggards and drift.
+use crate::helpers::cli::{CliTestHelper, next_version_tick, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+
+/// The template under its literal name: Postgres follows the rename into
+/// the BEGIN ATOMIC body, which it stores parsed.
+const SCHEMA: &str = "CREATE SCHEMA tenant_template;
+CREATE TABLE tenant_template.users (id SERIAL PRIMARY KEY, name TEXT);
+CREATE FUNCTION tenant_template.user_count() RETURNS bigint LANGUAGE sql
+    BEGIN ATOMIC SELECT count(*) FROM tenant_template.users; END;
+";
+
+/// The template as `${tenant}`, which a string function body needs.
+const PLACEHOLDER_SCHEMA: &str = "CREATE SCHEMA ${tenant};
+CREATE TABLE ${tenant}.users (id SERIAL PRIMARY KEY, name TEXT);
+CREATE FUNCTION ${tenant}.user_count() RETURNS bigint LANGUAGE sql
+    AS $$ SELECT count(*) FROM ${tenant}.users $$;
+";
+
+fn enable_tenants(helper: &CliTestHelper) -> Result<()> {
+    let path = helper.project_root.join("pgmt.yaml");
+    let config = std::fs::read_to_string(&path)?;
+    std::fs::write(
+        &path,
+        format!(
+            "{}\ntenants:\n  template: tenant_template\n  schemas: \"tenant_*\"\n",
+            config
+        ),
+    )?;
+    Ok(())
+}
+
+async fn execute(url: &str, sql: &str) -> Result<()> {
+    let pool = sqlx::PgPool::connect(url).await?;
+    sqlx::raw_sql(sqlx::AssertSqlSafe(sql.to_string()))
+        .execute(&pool)
+        .await?;
+    pool.close().await;
+    Ok(())
+}
+
+async fn scalar_i64(url: &str, sql: &str) -> Result<i64> {
+    let pool = sqlx::PgPool::connect(url).await?;
+    let value: i64 = sqlx::query_scalar(sqlx::AssertSqlSafe(sql.to_string()))
+        .fetch_one(&pool)
+        .await?;
+    pool.close().await;
+    Ok(value)
+}
+
+/// One generated migration lands in every tenant schema — function bodies
+/// and sequence defaults included — each with its own tracking table; a
+/// tenant created later catches up on the next apply.
+#[tokio::test]
+async fn test_apply_to_every_tenant_schema() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        enable_tenants(helper)?;
+        helper.write_schema_file("tenant.sql", PLACEHOLDER_SCHEMA)?;
+        helper
+            .command()
+            .args(["migrate", "new", "tenant_tables"])
+            .assert()
+            .success();
+        let migrations = helper.list_migration_files()?;
+        let migration = helper.read_migration_file(&migrations[0])?;
+        assert!(migration.contains(r#"CREATE TABLE "${tenant}"."users""#));
+        assert!(migration.contains(r#"nextval('"${tenant}".users_id_seq'::regclass)"#));
+        assert!(migration.contains("FROM ${tenant}.users"));
+        assert!(!migration.contains("tenant_template"));
+
+        let target = helper.create_extra_database().await?;
+        execute(&target, "CREATE SCHEMA tenant_a; CREATE SCHEMA tenant_b;").await?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &target])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Applying migrations to 2 tenant schema(s) from template tenant_template: \
+                 tenant_a, tenant_b",
+            ));
+
+        execute(&target, "INSERT INTO tenant_b.users (name) VALUES ('x')").await?;
+        assert_eq!(
+            scalar_i64(&target, "SELECT tenant_a.user_count()").await?,
+            0
+        );
+        assert_eq!(
+            scalar_i64(&target, "SELECT tenant_b.user_count()").await?,
+            1
+        );
+        assert_eq!(
+            scalar_i64(
+                &target,
+                "SELECT count(*) FROM pg_namespace WHERE nspname = 'tenant_template'"
+            )
+            .await?,
+            0
+        );
+        assert_eq!(
+            scalar_i64(&target, "SELECT count(*) FROM tenant_a.pgmt_migrations").await?,
+            1
+        );
+
+        execute(&target, "CREATE SCHEMA tenant_c").await?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &target])
+            .assert()
+            .success();
+        assert_eq!(
+            scalar_i64(&target, "SELECT tenant_c.user_count()").await?,
+            0
+        );
+
+        helper
+            .command()
+            .args(["migrate", "rollback", "--target-url", &target])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "`migrate rollback` is not supported in schema-per-tenant mode",
+            ));
+        for args in [
+            vec!["migrate", "history"],
+            vec!["migrate", "wait", "--until", "1"],
+            vec!["migrate", "resolve", "--restamp", "1"],
+        ] {
+            helper
+                .command()
+                .args(&args)
+                .args(["--target-url", &target])
+                .assert()
+                .failure()
+                .stderr(predicate::str::contains(format!(
+                    "`{}` is not supported in schema-per-tenant mode",
+                    args[..2].join(" ")
+                )));
+        }
+        helper
+            .command()
+            .args(["migrate", "validate", "--target-url", &target])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "`migrate validate --target-url` is not supported in schema-per-tenant mode",
+            ));
+        helper
+            .command()
+            .args(["migrate", "export", "--format", "flyway", "--out", "export"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "`migrate export` is not supported in schema-per-tenant mode",
+            ));
+
+        Ok(())
+    })
+    .await
+}
+
+/// `status --tenants` names the tenant behind the newest migration and the
+/// up-to-date tenant whose objects were changed by hand.
+#[tokio::test]
+async fn test_status_tenants_reports_laggards_and_drift() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        enable_tenants(helper)?;
+        helper.write_schema_file("tenant.sql", SCHEMA)?;
+        helper
+            .command()
+            .args(["migrate", "new", "tenant_tables"])
+            .assert()
+            .success();
+
+        let target = helper.create_extra_database().await?;
+        execute(&target, "CREATE SCHEMA tenant_a; CREATE SCHEMA tenant_b;").await?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &target])
+            .assert()
+            .success();
+
+        // A second migration reaches only the tenants that exist now.
+        next_version_tick();
+        helper.write_schema_file(
+            "tenant.sql",
+            &SCHEMA.replace("name TEXT)", "name TEXT, email TEXT)"),
+        )?;
+        helper
+            .command()
+            .args(["migrate", "new", "add_email"])
+            .assert()
+            .success();
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &target])
+            .assert()
+            .success();
+        execute(
+            &target,
+            "CREATE SCHEMA tenant_c; ALTER TABLE tenant_b.users ADD COLUMN nickname TEXT;",
+        )
+        .await?;
+
+        helper
+            .command()
+            .args(["migrate", "status", "--target-url", &target])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("pgmt migrate status --tenants"));
+
+        helper
+            .command()
+            .args(["migrate", "status", "--tenants", "--target-url", &target])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "3 tenant schema(s) matching 'tenant_*' (template tenant_template)",
+            ))
+            .stdout(predicate::str::is_match(r"tenant_a +up_to_date .* no drift").unwrap())
+            .stdout(predicate::str::contains("Laggards: tenant_c (pending)"))
+            .stdout(predicate::str::contains(
+                "Drifted from the schema files:\n  tenant_b:\n    table tenant_b.users",
+            ));
+
+        let output = helper
+            .command()
+            .args(["migrate", "status", "--tenants", "--format", "json"])
+            .args(["--target-url", &target, "--exit-code"])
+            .output()?;
+        assert_eq!(output.status.code(), Some(10));
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        assert_eq!(report["state"], "pending");
+        assert_eq!(report["tenants"][0]["schema"], "tenant_a");
+        assert_eq!(report["tenants"][0]["drift"], serde_json::json!([]));
+        assert_eq!(report["tenants"][1]["drift"][0], "table tenant_b.users");
+        assert_eq!(report["tenants"][2]["pending"], 2);
+        assert!(report["tenants"][2]["drift"].is_null());
+
+        Ok(())
+    })
+    .await
+}
+
+/// With a baseline committed, a tenant with no history starts from it rather
+/// than being refused as unprovisioned, and the baseline names the template
+/// `${tenant}` too.
+#[tokio::test]
+async fn test_apply_starts_new_tenants_from_the_baseline() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        enable_tenants(helper)?;
+        helper.write_schema_file("tenant.sql", SCHEMA)?;
+        helper
+            .command()
+            .args(["migrate", "new", "tenant_tables"])
+            .assert()
+            .success();
+        helper
+            .command()
+            .args(["migrate", "baseline"])
+            .assert()
+            .success();
+        let baselines = helper.list_baseline_files()?;
+        let baseline = helper.read_baseline_file(&baselines[0])?;
+        assert!(baseline.contains(r#"CREATE TABLE "${tenant}"."users""#));
+        assert!(!baseline.contains("tenant_template"));
+
+        let target = helper.create_extra_database().await?;
+        execute(&target, "CREATE SCHEMA tenant_a").await?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &target])
+            .assert()
+            .success();
+        execute(&target, "INSERT INTO tenant_a.users (name) VALUES ('x')").await?;
+        assert_eq!(
+            scalar_i64(&target, "SELECT tenant_a.user_count()").await?,
+            1
+        );
+
+        helper
+            .command()
+            .args(["migrate", "provision", "--target-url", &target])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "`migrate apply` starts each new tenant from the latest baseline",
+            ));
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,71 @@
+//! `migrate wait --until`: block until the target reaches a schema version.
+use crate::helpers::cli::with_cli_helper;
+use anyhow::Result;
+use predicates::prelude::*;
+
+/// A waiter started before the deploy returns once the apply lands; a target
+/// that never gets there times out.
+#[tokio::test]
+async fn test_wait_until_version() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file("V1000_users.sql", "CREATE TABLE users (id INT);")?;
+        helper.write_migration_file("V2000_posts.sql", "CREATE TABLE posts (id INT);")?;
+
+        helper
+            .command()
+            .args(["migrate", "wait", "--until", "V1000", "--timeout", "1s"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Timed out after 1s waiting for schema version 1000",
+            ));
+
+        let mut waiter = std::process::Command::new(assert_cmd::cargo::cargo_bin!("pgmt"))
+            .current_dir(&helper.project_root)
+            .args(["migrate", "wait", "--until", "V2000", "--timeout", "60s"])
+            .args(["--target-url", &helper.dev_database_url])
+            .stdout(std::process::Stdio::null())
+            .spawn()?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+        assert!(waiter.wait()?.success());
+
+        // A newer migration doesn't hold back an older version.
+        helper.write_migration_file("V3000_broken.sql", "ALTER TABLE missing ADD x INT;")?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .failure();
+        helper
+            .command()
+            .args(["migrate", "wait", "--until", "V2000", "--timeout", "1s"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Schema version 2000 reached"));
+        helper
+            .command()
+            .args(["migrate", "wait", "--until", "V3000", "--timeout", "1s"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure();
+
+        // A late-merged migration below the version holds it back.
+        helper.write_migration_file("V1500_late.sql", "CREATE TABLE late (id INT);")?;
+        helper
+            .command()
+            .args(["migrate", "wait", "--until", "V2000", "--timeout", "1s"])
+            .args(["--target-url", &helper.dev_database_url])
+            .assert()
+            .failure();
+
+        Ok(())
+    })
+    .await
+}
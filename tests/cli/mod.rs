@@ -29,24 +29,46 @@ pub mod baseline_commands;
 pub mod baseline_extension_ordering;
 pub mod baseline_sections;
 pub mod basic_interactive;
+pub mod catalog_snapshot;
+pub mod compare;
 pub mod connection_precedence;
 pub mod debug_commands;
+pub mod dev_snapshots;
 pub mod diff;
+pub mod diff_refs;
 pub mod error_handling;
 pub mod help_matrix;
 pub mod incomplete_baseline_guard;
+pub mod init_adopt;
 pub mod init_interactive;
+pub mod migrate_apply_limit;
 pub mod migrate_end_to_end;
+pub mod migrate_export;
+pub mod migrate_fleet;
+pub mod migrate_history;
+pub mod migrate_hooks;
+pub mod migrate_lint;
 pub mod migrate_new;
+pub mod migrate_out_of_order;
+pub mod migrate_rebase;
 pub mod migrate_reconstruction;
+pub mod migrate_rehearse;
+pub mod migrate_repeatable;
 pub mod migrate_resolve;
+pub mod migrate_rollback;
 pub mod migrate_status;
+pub mod migrate_status_json;
+pub mod migrate_tenants;
 pub mod migrate_validate_json;
+pub mod migrate_wait;
 pub mod modules_crossing;
 pub mod modules_deploy;
 pub mod modules_generation;
 pub mod nontransactional_sections;
 pub mod roles_file;
 pub mod section_checksums;
+pub mod seed_data;
 pub mod substrate;
+pub mod test_command;
+pub mod variables;
 pub mod watermark_warning;
@@ -0,0 +1,210 @@
+//! Seed data files: rows in `data/` diffed by primary key into `migrate new`
+//! (in a `seed_data` section) and synced to the dev database by `apply`.
+use crate::helpers::cli::{CliTestHelper, next_version_tick, with_cli_helper};
+use anyhow::Result;
+
+const SCHEMA: &str = "CREATE TABLE plans (id INT PRIMARY KEY, name TEXT NOT NULL);\n\
+                      CREATE TABLE features (\n    \
+                          code TEXT PRIMARY KEY,\n    \
+                          plan_id INT NOT NULL REFERENCES plans(id),\n    \
+                          created_at TIMESTAMPTZ NOT NULL DEFAULT now()\n\
+                      );";
+
+fn write_data_file(helper: &CliTestHelper, filename: &str, content: &str) -> Result<()> {
+    let data_dir = helper.project_root.join("data");
+    std::fs::create_dir_all(&data_dir)?;
+    std::fs::write(data_dir.join(filename), content)?;
+    Ok(())
+}
+
+fn latest_migration(helper: &CliTestHelper) -> Result<String> {
+    let migrations = helper.list_migration_files()?;
+    helper.read_migration_file(migrations.last().expect("a migration"))
+}
+
+async fn plan_names(helper: &CliTestHelper) -> Result<Vec<(i32, String)>> {
+    let pool = helper.connect_to_dev_db().await?;
+    let rows = sqlx::query_as("SELECT id, name FROM plans ORDER BY id")
+        .fetch_all(&pool)
+        .await?;
+    pool.close().await;
+    Ok(rows)
+}
+
+/// The first migration inserts every seed row, referenced tables first; the
+/// next one carries only the row differences.
+#[tokio::test]
+async fn test_migrate_new_diffs_seed_rows() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("tables.sql", SCHEMA)?;
+        write_data_file(helper, "plans.csv", "id,name\n1,Free\n2,Pro\n3,Legacy\n")?;
+        write_data_file(
+            helper,
+            "public.features.sql",
+            "INSERT INTO features (code, plan_id) VALUES ('export', 2), ('sso', 2);",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "new", "initial"])
+            .assert()
+            .success();
+        let first = latest_migration(helper)?;
+        let seed_section = first
+            .find("-- pgmt:section name=\"seed_data\"")
+            .expect("a seed_data section");
+        assert!(first.find("CREATE TABLE").unwrap() < seed_section);
+        let plans_insert = first.find("INSERT INTO \"public\".\"plans\"").unwrap();
+        let features_insert = first.find("INSERT INTO \"public\".\"features\"").unwrap();
+        assert!(seed_section < plans_insert && plans_insert < features_insert);
+        assert!(first.contains("('3', 'Legacy')"));
+        // Unmanaged columns are left to their defaults.
+        assert!(!first.contains("created_at\", "));
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+        assert_eq!(
+            plan_names(helper).await?,
+            vec![
+                (1, "Free".to_string()),
+                (2, "Pro".to_string()),
+                (3, "Legacy".to_string())
+            ]
+        );
+
+        next_version_tick();
+        write_data_file(
+            helper,
+            "plans.csv",
+            "id,name\n1,Free\n2,Professional\n4,Team\n",
+        )?;
+        helper
+            .command()
+            .args(["migrate", "new", "rename_plans"])
+            .assert()
+            .success();
+        let second = latest_migration(helper)?;
+        assert!(!second.contains("CREATE TABLE"));
+        assert!(second.contains("DELETE FROM \"public\".\"plans\" WHERE \"id\" IN ('3');"));
+        assert!(second.contains("('4', 'Team')"));
+        assert!(second.contains(
+            "UPDATE \"public\".\"plans\" SET \"name\" = 'Professional' WHERE \"id\" = '2';"
+        ));
+        assert!(!second.contains("'Free'"));
+        assert!(!second.contains("features"));
+        Ok(())
+    })
+    .await
+}
+
+/// `apply` writes the seed rows to the dev database and leaves it alone once
+/// they match.
+#[tokio::test]
+async fn test_apply_syncs_seed_rows() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("tables.sql", SCHEMA)?;
+        write_data_file(helper, "plans.csv", "id,name\n1,Free\n2,Pro\n")?;
+
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success();
+        assert_eq!(
+            plan_names(helper).await?,
+            vec![(1, "Free".to_string()), (2, "Pro".to_string())]
+        );
+
+        write_data_file(helper, "plans.csv", "id,name\n2,Professional\n")?;
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success();
+        assert_eq!(
+            plan_names(helper).await?,
+            vec![(2, "Professional".to_string())]
+        );
+
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("No schema changes detected"));
+        Ok(())
+    })
+    .await
+}
+
+/// Rows compare the same whatever the dev database's session settings make
+/// of their text form.
+#[tokio::test]
+async fn test_apply_seed_rows_independent_of_session_settings() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file(
+            "tables.sql",
+            "CREATE TABLE events (\n    \
+                 id INT PRIMARY KEY,\n    \
+                 at TIMESTAMPTZ NOT NULL,\n    \
+                 duration INTERVAL NOT NULL,\n    \
+                 payload BYTEA NOT NULL\n\
+             );",
+        )?;
+        write_data_file(
+            helper,
+            "events.csv",
+            "id,at,duration,payload\n1,2024-03-10 12:00:00+00,1 day 02:00:00,\\x6869\n",
+        )?;
+
+        let pool = helper.connect_to_dev_db().await?;
+        sqlx::query(
+            "DO $$ BEGIN \
+             EXECUTE format('ALTER DATABASE %I SET IntervalStyle = ''sql_standard''', current_database()); \
+             EXECUTE format('ALTER DATABASE %I SET bytea_output = ''escape''', current_database()); \
+             END $$",
+        )
+        .execute(&pool)
+        .await?;
+        pool.close().await;
+
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success();
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("No schema changes detected"));
+        Ok(())
+    })
+    .await
+}
+
+/// A seed file for a table the schema doesn't define is an error.
+#[tokio::test]
+async fn test_seed_file_for_unknown_table_fails() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("tables.sql", SCHEMA)?;
+        write_data_file(helper, "countries.csv", "code,name\nFR,France\n")?;
+
+        helper
+            .command()
+            .args(["migrate", "new", "initial"])
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("public.countries"));
+        Ok(())
+    })
+    .await
+}
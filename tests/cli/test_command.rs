@@ -0,0 +1,206 @@
+//! `pgmt test`: SQL test files run on a fresh shadow, each in a rolled-back
+//! transaction, reported as human, JSON or JUnit output.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+
+const SCHEMA: &str = "CREATE TABLE users (id INT PRIMARY KEY, email TEXT NOT NULL);\n\
+                      CREATE FUNCTION normalize_email(email TEXT) RETURNS TEXT\n    \
+                          LANGUAGE sql IMMUTABLE AS $$ SELECT lower(trim(email)) $$;";
+
+fn write_test_file(helper: &CliTestHelper, filename: &str, content: &str) -> Result<()> {
+    let tests_dir = helper.project_root.join("tests");
+    std::fs::create_dir_all(&tests_dir)?;
+    std::fs::write(tests_dir.join(filename), content)?;
+    Ok(())
+}
+
+/// Passing tests report their assertions, and each file's writes are rolled
+/// back before the next one runs.
+#[tokio::test]
+async fn test_passing_tests_roll_back() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("users.sql", SCHEMA)?;
+        write_test_file(
+            helper,
+            "a_insert.sql",
+            "INSERT INTO users VALUES (1, 'a@example.com');\n\
+             SELECT assert_equals((SELECT count(*) FROM users), 1);\n\
+             SELECT assert_equals(normalize_email('  A@Example.COM '), 'a@example.com', 'lowercases');\n\
+             SELECT assert_raises('INSERT INTO users VALUES (1, ''dup'')', '23505');",
+        )?;
+        write_test_file(
+            helper,
+            "b_empty.sql",
+            "SELECT pgmt_test.assert_true(NOT EXISTS (SELECT 1 FROM users), 'rolled back');",
+        )?;
+
+        helper
+            .command()
+            .args(["test"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "✅ tests/a_insert.sql (3 assertion(s)",
+            ))
+            .stdout(predicate::str::contains("✅ tests/b_empty.sql (1 assertion(s)"))
+            .stdout(predicate::str::contains("2 passed, 0 failed"));
+        Ok(())
+    })
+    .await
+}
+
+/// A failed assertion fails its test with the description and values; the
+/// other tests still run and the command exits non-zero.
+#[tokio::test]
+async fn test_failed_assertion_is_reported() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("users.sql", SCHEMA)?;
+        write_test_file(
+            helper,
+            "normalize.sql",
+            "SELECT assert_equals(normalize_email('A@B.C '), 'A@B.C', 'keeps case');",
+        )?;
+        write_test_file(helper, "ok.sql", "SELECT assert_true(true);")?;
+
+        helper
+            .command()
+            .args(["test"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("❌ tests/normalize.sql"))
+            .stdout(predicate::str::contains("assertion failed: keeps case"))
+            .stdout(predicate::str::contains("expected 'A@B.C', got 'a@b.c'"))
+            .stdout(predicate::str::contains("✅ tests/ok.sql"))
+            .stderr(predicate::str::contains("1 of 2 test(s) failed"));
+        Ok(())
+    })
+    .await
+}
+
+/// A file that would end the transaction it runs in is an error and doesn't
+/// run, so it can't leave rows behind; pgTAP's trailing ROLLBACK is fine.
+#[tokio::test]
+async fn test_transaction_control_is_rejected() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("users.sql", SCHEMA)?;
+        write_test_file(
+            helper,
+            "a_commit.sql",
+            "INSERT INTO users VALUES (1, 'a@example.com');\n\
+             COMMIT;\n\
+             SELECT assert_true(true);",
+        )?;
+        write_test_file(
+            helper,
+            "b_empty.sql",
+            "BEGIN;\n\
+             SAVEPOINT s;\n\
+             INSERT INTO users VALUES (2, 'b@example.com');\n\
+             ROLLBACK TO SAVEPOINT s;\n\
+             SELECT assert_true(NOT EXISTS (SELECT 1 FROM users), 'nothing committed');\n\
+             ROLLBACK;",
+        )?;
+
+        helper
+            .command()
+            .args(["test"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains(
+                "COMMIT would end the transaction the test runs in",
+            ))
+            .stdout(predicate::str::contains("✅ tests/b_empty.sql"))
+            .stderr(predicate::str::contains("1 of 2 test(s) failed"));
+        Ok(())
+    })
+    .await
+}
+
+/// JSON output distinguishes failed assertions from other errors.
+#[tokio::test]
+async fn test_json_output() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("users.sql", SCHEMA)?;
+        write_test_file(helper, "broken.sql", "SELECT * FROM no_such_table;")?;
+        write_test_file(helper, "failing.sql", "SELECT assert_false(1 = 1, 'math');")?;
+        write_test_file(helper, "passing.sql", "SELECT assert_not_equals(1, 2);")?;
+
+        let output = helper
+            .command()
+            .args(["test", "--format", "json"])
+            .output()?;
+        assert!(!output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        assert_eq!(report["passed"], 1);
+        assert_eq!(report["failed"], 2);
+        let tests = report["tests"].as_array().unwrap();
+        assert_eq!(tests[0]["name"], "broken");
+        assert_eq!(tests[0]["status"], "error");
+        assert_eq!(tests[0]["failure"]["line"], 1);
+        assert_eq!(tests[1]["status"], "failed");
+        assert_eq!(tests[1]["failure"]["message"], "assertion failed: math");
+        assert_eq!(tests[2]["status"], "passed");
+        assert_eq!(tests[2]["assertions"], 1);
+        Ok(())
+    })
+    .await
+}
+
+/// JUnit output has a test case per file; pgTAP-style `not ok` rows fail
+/// their test.
+#[tokio::test]
+async fn test_junit_output_with_tap_rows() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_schema_file("users.sql", SCHEMA)?;
+        write_test_file(
+            helper,
+            "tap.sql",
+            "SELECT 'ok 1 - first';\n\
+             SELECT E'not ok 2 - second\\n# Failed test 2: \"second\"';",
+        )?;
+        write_test_file(helper, "plain.sql", "SELECT assert_true(true);")?;
+
+        helper
+            .command()
+            .args(["test", "--format", "junit"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::starts_with(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+            ))
+            .stdout(predicate::str::contains(
+                "<testsuites name=\"pgmt\" tests=\"2\" failures=\"1\" errors=\"0\"",
+            ))
+            .stdout(predicate::str::contains(
+                "<testcase name=\"plain\" classname=\"pgmt\" file=\"tests/plain.sql\" assertions=\"1\"",
+            ))
+            .stdout(predicate::str::contains(
+                "<failure message=\"1 pgTAP assertion(s) failed\">",
+            ))
+            .stdout(predicate::str::contains("not ok 2 - second"));
+        Ok(())
+    })
+    .await
+}
+
+/// A project without tests has nothing to run.
+#[tokio::test]
+async fn test_no_tests() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper
+            .command()
+            .args(["test"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("No test files"));
+        Ok(())
+    })
+    .await
+}
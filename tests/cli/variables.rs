@@ -0,0 +1,204 @@
+//! `${var}` placeholders: values from pgmt.yaml (per environment), `PGMT_VAR_*`
+//! and `--var`, substituted into what runs while checksums keep hashing the
+//! files as written.
+use crate::helpers::cli::{CliTestHelper, with_cli_helper};
+use anyhow::Result;
+use predicates::prelude::*;
+
+const VARIABLES_YAML: &str = r#"
+variables:
+  audience: dev
+
+environments:
+  prod:
+    variables:
+      audience: prod
+"#;
+
+fn append_variables(helper: &CliTestHelper) -> Result<()> {
+    let config_path = helper.project_root.join("pgmt.yaml");
+    let mut config = std::fs::read_to_string(&config_path)?;
+    config.push_str(VARIABLES_YAML);
+    std::fs::write(config_path, config)?;
+    Ok(())
+}
+
+/// A migration runs with the selected environment's values, and applying the
+/// same file with different values is not a checksum mismatch.
+#[tokio::test]
+async fn test_migration_placeholders_per_environment() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        append_variables(helper)?;
+        helper.write_migration_file(
+            "V1000_users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY);\n\
+             COMMENT ON TABLE users IS 'for ${audience}';",
+        )?;
+
+        helper
+            .command()
+            .args([
+                "migrate",
+                "apply",
+                "--target-url",
+                &helper.dev_database_url,
+                "--env",
+                "prod",
+            ])
+            .assert()
+            .success();
+        assert_eq!(
+            helper.get_table_comment_in_dev("public", "users").await?,
+            Some("for prod".to_string())
+        );
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .args(["--var", "audience=staging"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Nothing to apply"));
+
+        helper.write_migration_file(
+            "V2000_more.sql",
+            "COMMENT ON TABLE users IS '${undefined_thing}';",
+        )?;
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "undefined variable '${undefined_thing}' at line 1",
+            ));
+
+        helper
+            .command()
+            .args([
+                "migrate",
+                "status",
+                "--target-url",
+                &helper.dev_database_url,
+            ])
+            .args(["--env", "staging"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "environment 'staging' is not declared",
+            ));
+
+        Ok(())
+    })
+    .await
+}
+
+/// Schema files and the roles file are substituted before they are applied
+/// to the shadow; `PGMT_VAR_*` overrides pgmt.yaml and `--var` overrides both.
+#[tokio::test]
+async fn test_schema_placeholders() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        append_variables(helper)?;
+        helper.write_roles_file(
+            "DO $$ BEGIN CREATE ROLE test_app_user; \
+             EXCEPTION WHEN duplicate_object OR unique_violation THEN NULL; END $$;\n\
+             COMMENT ON ROLE test_app_user IS '${audience}';",
+        )?;
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY);\n\
+             COMMENT ON TABLE users IS 'for ${audience}';",
+        )?;
+
+        helper
+            .command()
+            .args(["apply", "--force"])
+            .env("PGMT_VAR_AUDIENCE", "env")
+            .assert()
+            .success();
+        assert_eq!(
+            helper.get_table_comment_in_dev("public", "users").await?,
+            Some("for env".to_string())
+        );
+
+        helper
+            .command()
+            .args(["apply", "--force", "--var", "audience=cli"])
+            .env("PGMT_VAR_AUDIENCE", "env")
+            .assert()
+            .success();
+        assert_eq!(
+            helper.get_table_comment_in_dev("public", "users").await?,
+            Some("for cli".to_string())
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+/// `migrate new` writes the placeholder, not the value it was generated with,
+/// so the migration applies with each environment's value.
+#[tokio::test]
+async fn test_migrate_new_keeps_placeholders() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        append_variables(helper)?;
+        helper.write_schema_file(
+            "users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY);\n\
+             COMMENT ON TABLE users IS 'for ${audience}';",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "new", "users"])
+            .assert()
+            .success();
+        let files = helper.list_migration_files()?;
+        let migration = helper.read_migration_file(&files[0])?;
+        assert!(migration.contains("'for ${audience}'"), "{migration}");
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .args(["--env", "prod"])
+            .assert()
+            .success();
+        assert_eq!(
+            helper.get_table_comment_in_dev("public", "users").await?,
+            Some("for prod".to_string())
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+/// A project that defines no variables runs `${...}` as plain SQL.
+#[tokio::test]
+async fn test_placeholders_without_variables_are_plain_sql() -> Result<()> {
+    with_cli_helper(async |helper| {
+        helper.init_project()?;
+        helper.write_migration_file(
+            "V1000_users.sql",
+            "CREATE TABLE users (id INT PRIMARY KEY);\n\
+             COMMENT ON TABLE users IS 'template ${name}';",
+        )?;
+
+        helper
+            .command()
+            .args(["migrate", "apply", "--target-url", &helper.dev_database_url])
+            .assert()
+            .success();
+        assert_eq!(
+            helper.get_table_comment_in_dev("public", "users").await?,
+            Some("template ${name}".to_string())
+        );
+
+        Ok(())
+    })
+    .await
+}
@@ -56,11 +56,20 @@ fn test_apply_command_signature() {
                 migrations_dir: None,
                 baselines_dir: None,
                 roles_file: None,
+                repeatable_dir: None,
+                hooks_dir: None,
+                data_dir: None,
+                tests_dir: None,
             }),
             objects: None,
             migration: None,
             schema: None,
             docker: None,
+            dev: None,
+            variables: None,
+            environments: None,
+            targets: None,
+            tenants: None,
         };
 
         let config = ConfigBuilder::new()
@@ -110,11 +119,20 @@ async fn test_apply_command_error_handling() -> Result<()> {
             migrations_dir: None,
             baselines_dir: None,
             roles_file: None,
+            repeatable_dir: None,
+            hooks_dir: None,
+            data_dir: None,
+            tests_dir: None,
         }),
         objects: None,
         migration: None,
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     let config = ConfigBuilder::new()
@@ -162,11 +180,20 @@ fn test_apply_command_configuration_requirements() -> Result<()> {
             migrations_dir: None,
             baselines_dir: None,
             roles_file: None,
+            repeatable_dir: None,
+            hooks_dir: None,
+            data_dir: None,
+            tests_dir: None,
         }),
         objects: None,
         migration: None,
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     // Verify config and connections can be resolved successfully
@@ -189,6 +189,7 @@ CREATE TABLE users (
             1,
             &sections,
             "V001__test_migration.sql",
+            None,
         )
         .await;
 
@@ -142,6 +142,11 @@ async fn test_shadow_database_config_docker() -> Result<()> {
         migration: None,
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     // Test configuration building (without actually creating containers)
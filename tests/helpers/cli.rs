@@ -86,6 +86,24 @@ impl CliTestHelper {
         // Best-effort cleanup with timeout
         let cleanup_future = async move {
             if let Ok(pool) = sqlx::PgPool::connect(&base_url).await {
+                // Snapshots destructive applies took of the dev database
+                let snapshots: Vec<String> = sqlx::query_scalar(
+                    "SELECT datname::text FROM pg_database \
+                     WHERE shobj_description(oid, 'pg_database') \
+                     LIKE '%\"pgmt_snapshot_of\":\"' || $1 || '\"%'",
+                )
+                .bind(&dev_db_name)
+                .fetch_all(&pool)
+                .await
+                .unwrap_or_default();
+                for snapshot in snapshots {
+                    let _ = sqlx::query(sqlx::AssertSqlSafe(format!(
+                        "DROP DATABASE IF EXISTS \"{}\" WITH (FORCE)",
+                        snapshot
+                    )))
+                    .execute(&pool)
+                    .await;
+                }
                 let _ = sqlx::query(sqlx::AssertSqlSafe(format!(
                     "DROP DATABASE IF EXISTS \"{}\" WITH (FORCE)",
                     dev_db_name
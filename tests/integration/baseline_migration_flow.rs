@@ -391,6 +391,7 @@ async fn test_apply_baseline_to_target_creates_objects() -> Result<()> {
             1,
             &sections,
             "baseline_1.sql",
+            None,
         )
         .await?;
 
@@ -432,6 +433,7 @@ async fn test_apply_baseline_to_target_is_atomic() -> Result<()> {
             1,
             &sections,
             "bad.sql",
+            None,
         )
         .await;
         assert!(result.is_err(), "duplicate table should fail the baseline");
@@ -0,0 +1,177 @@
+//! Integration tests for the warm shadow behind `apply --watch`: a save
+//! re-runs only the files it affects, and the resulting desired state matches
+//! a full build of the same files.
+
+use crate::helpers::harness::with_test_db;
+use anyhow::Result;
+use pgmt::commands::apply::incremental::{Rebuild, WarmShadow};
+use pgmt::config::Config;
+use pgmt::config::types::{ShadowDatabase, ShadowResetMode};
+use pgmt::diff::plan;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const TABLES: &[&str] = &[
+    "posts", "tags", "audit", "settings", "sessions", "likes", "follows", "media", "events",
+    "reports",
+];
+
+async fn assert_matches_full_build(
+    warm: &WarmShadow<'_>,
+    config: &Config,
+    root: &Path,
+    shadow: &ShadowDatabase,
+) {
+    let incremental = warm.desired_state().unwrap();
+    let full = pgmt::schema_ops::apply_current_schema_to_shadow(config, root, shadow)
+        .await
+        .unwrap();
+    let steps = plan(&full, &incremental).unwrap();
+    assert!(
+        steps.is_empty(),
+        "incremental state differs from a full build: {:?}",
+        steps
+    );
+}
+
+#[tokio::test]
+async fn test_warm_shadow_reruns_affected_files() -> Result<()> {
+    let _shadow_guard = crate::helpers::shadow_guard().await;
+    with_test_db(async |db| {
+        let source_db = format!("pgmt_src_{}", uuid::Uuid::new_v4().simple());
+        db.execute(&format!("CREATE DATABASE \"{}\"", source_db))
+            .await;
+        let base = db.url();
+        let source_url = format!("{}/{}", &base[..base.rfind('/').unwrap()], source_db);
+        let shadow = ShadowDatabase::Url {
+            url: source_url,
+            reset: ShadowResetMode::Branch,
+        };
+
+        let project = TempDir::new().unwrap();
+        let root = project.path();
+        let schema_dir = root.join("schema");
+        fs::create_dir_all(&schema_dir).unwrap();
+        let write = |name: &str, sql: &str| fs::write(schema_dir.join(name), sql).unwrap();
+        write("app.sql", "CREATE SCHEMA app;");
+        write(
+            "users.sql",
+            "-- require: app.sql\nCREATE TABLE app.users (id int PRIMARY KEY, name text);",
+        );
+        write(
+            "user_names.sql",
+            "-- require: users.sql\nCREATE VIEW app.user_names AS SELECT name FROM app.users;",
+        );
+        for table in TABLES {
+            write(
+                &format!("{}.sql", table),
+                &format!(
+                    "-- require: app.sql\nCREATE TABLE app.{} (id int PRIMARY KEY);",
+                    table
+                ),
+            );
+        }
+        write(
+            "comments.sql",
+            "-- require: posts.sql\nCOMMENT ON TABLE app.posts IS 'Posts';",
+        );
+        write(
+            "post_titles.sql",
+            "-- require: posts.sql\nALTER TABLE app.posts ADD COLUMN title text;",
+        );
+        let data_dir = root.join("data");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("app.tags.csv"), "id\n1\n2\n").unwrap();
+
+        let config = Config::default();
+        let mut warm = WarmShadow::new(&config, root, &shadow);
+        assert_eq!(warm.update().await.unwrap(), Rebuild::Initial);
+        assert_eq!(warm.update().await.unwrap(), Rebuild::Unchanged);
+
+        // A view nothing depends on re-runs alone.
+        write(
+            "user_names.sql",
+            "-- require: users.sql\nCREATE VIEW app.user_names AS SELECT id, name FROM app.users;",
+        );
+        assert_eq!(
+            warm.update().await.unwrap(),
+            Rebuild::Incremental(vec!["user_names.sql".to_string()])
+        );
+        assert_matches_full_build(&warm, &config, root, &shadow).await;
+
+        // Changing the table re-runs the view that reads it too.
+        write(
+            "users.sql",
+            "-- require: app.sql\nCREATE TABLE app.users (id int PRIMARY KEY, name text, email text);",
+        );
+        assert_eq!(
+            warm.update().await.unwrap(),
+            Rebuild::Incremental(vec![
+                "user_names.sql".to_string(),
+                "users.sql".to_string()
+            ])
+        );
+        assert_matches_full_build(&warm, &config, root, &shadow).await;
+
+        // A file that changes another file's table re-runs that file, and so
+        // every other file changing the table.
+        write(
+            "comments.sql",
+            "-- require: posts.sql\nCOMMENT ON TABLE app.posts IS 'Blog posts';",
+        );
+        let posts_files = Rebuild::Incremental(vec![
+            "comments.sql".to_string(),
+            "post_titles.sql".to_string(),
+            "posts.sql".to_string(),
+        ]);
+        assert_eq!(warm.update().await.unwrap(), posts_files);
+        assert_matches_full_build(&warm, &config, root, &shadow).await;
+
+        // Dropping the ALTER takes the column away with it.
+        write(
+            "post_titles.sql",
+            "-- require: posts.sql\nCREATE INDEX posts_id ON app.posts (id);",
+        );
+        assert_eq!(warm.update().await.unwrap(), posts_files);
+        assert_matches_full_build(&warm, &config, root, &shadow).await;
+
+        // A recreated table gets its seed rows back.
+        write(
+            "tags.sql",
+            "-- require: app.sql\nCREATE TABLE app.tags (id int PRIMARY KEY, label text);",
+        );
+        assert_eq!(
+            warm.update().await.unwrap(),
+            Rebuild::Incremental(vec!["tags.sql".to_string()])
+        );
+        assert_eq!(warm.desired_state().unwrap().data[0].rows.len(), 2);
+        assert_matches_full_build(&warm, &config, root, &shadow).await;
+
+        // Seed files are loaded whole.
+        fs::write(data_dir.join("app.tags.csv"), "id\n1\n2\n3\n").unwrap();
+        assert!(matches!(warm.update().await.unwrap(), Rebuild::Full(_)));
+        assert_matches_full_build(&warm, &config, root, &shadow).await;
+
+        // What a statement changes must be told from its text.
+        write(
+            "comments.sql",
+            "-- require: posts.sql\nDO $$ BEGIN PERFORM 1; END $$;",
+        );
+        assert!(matches!(warm.update().await.unwrap(), Rebuild::Full(_)));
+
+        // Touching the schema reaches every file.
+        write("app.sql", "CREATE SCHEMA app;\n");
+        assert!(matches!(warm.update().await.unwrap(), Rebuild::Full(_)));
+
+        warm.close().await.unwrap();
+        db.execute(&format!(
+            "DROP DATABASE IF EXISTS \"{}\" WITH (FORCE)",
+            source_db
+        ))
+        .await;
+    })
+    .await;
+
+    Ok(())
+}
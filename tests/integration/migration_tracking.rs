@@ -215,10 +215,15 @@ fn remap_section(name: &str, module: &str, source: &str) -> MigrationSection {
         timeout: Duration::from_secs(30),
         lock_timeout: None,
         retry_config: None,
+        batch: None,
+        assert_before: Vec::new(),
+        assert_after: Vec::new(),
         sql: format!("-- {name}"),
+        raw_sql: None,
         raw_header: format!("-- pgmt:section name={name} module={module} remaps={source}"),
         module: Some(module.to_string()),
         remaps: Some(source.to_string()),
+        allow: Vec::new(),
         start_line: 1,
     }
 }
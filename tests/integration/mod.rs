@@ -2,6 +2,7 @@ pub mod baseline_migration_flow;
 pub mod branch_reset;
 /// Integration tests for pgmt
 /// Tests that verify end-to-end functionality across multiple components
+pub mod incremental_shadow;
 pub mod init;
 pub mod migrate_new_branch_shadow;
 pub mod migration_tracking;
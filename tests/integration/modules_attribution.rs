@@ -66,6 +66,7 @@ async fn process(
             verbose: false,
             clean_before_apply: false,
             objects: config.objects.clone(),
+            variables: Default::default(),
         },
     );
     processor
@@ -265,6 +265,154 @@ CREATE INDEX CONCURRENTLY idx_customers_email ON customers(email);
     .await
 }
 
+/// A batched section commits one batch at a time until a batch affects no
+/// rows; hitting `max_batches` fails with the progress kept, and the next run
+/// continues the running `rows_affected` total.
+#[tokio::test]
+async fn test_batched_section_resumes_after_max_batches() -> Result<()> {
+    with_test_db(async |db| {
+        db.execute("CREATE TABLE items (id INTEGER PRIMARY KEY, done BOOLEAN NOT NULL)")
+            .await;
+        db.execute("INSERT INTO items SELECT g, false FROM generate_series(1, 5) g")
+            .await;
+
+        let migration_sql = r#"
+-- pgmt:section name="backfill" mode="batched" max_batches="2" batch_sleep="10ms"
+UPDATE items SET done = true
+WHERE id IN (SELECT id FROM items WHERE NOT done ORDER BY id LIMIT 2);
+"#;
+
+        let sections = parse_migration_sections(Path::new("test.sql"), migration_sql)?;
+        validate_sections(&sections)?;
+
+        let tracking_table = TrackingTable::default();
+        ensure_section_tracking_table(db.pool(), &tracking_table).await?;
+        initialize_sections(db.pool(), &tracking_table, 1, false, &ordered(&sections)).await?;
+
+        let rows_affected = async || -> Result<Option<i64>> {
+            Ok(sqlx::query_scalar(
+                "SELECT rows_affected FROM public.pgmt_migrations_sections \
+                 WHERE section_name = 'backfill'",
+            )
+            .fetch_one(db.pool())
+            .await?)
+        };
+
+        let mut executor = SectionExecutor::new(
+            db.pool().clone(),
+            tracking_table.clone(),
+            SectionReporter::new(sections.len(), false),
+            ExecutionMode::Production,
+            false,
+        );
+
+        // Two batches of 2 rows, then the cap.
+        let err = executor.execute_section(1, &sections[0]).await.unwrap_err();
+        assert!(err.to_string().contains("max_batches=2"), "{err}");
+        assert_eq!(
+            get_section_status(db.pool(), &tracking_table, 1, false, "backfill").await?,
+            Some(SectionStatus::Failed)
+        );
+        assert_eq!(rows_affected().await?, Some(4));
+
+        // The rerun picks up the last row, then sees an empty batch.
+        executor.execute_section(1, &sections[0]).await?;
+        assert_eq!(
+            get_section_status(db.pool(), &tracking_table, 1, false, "backfill").await?,
+            Some(SectionStatus::Completed)
+        );
+        assert_eq!(rows_affected().await?, Some(5));
+
+        let remaining: i64 = sqlx::query_scalar("SELECT count(*) FROM items WHERE NOT done")
+            .fetch_one(db.pool())
+            .await?;
+        assert_eq!(remaining, 0);
+
+        Ok(())
+    })
+    .await
+}
+
+/// A false precondition fails the section without running its body and
+/// records why in `last_error`; a false postcondition rolls a transactional
+/// section back.
+#[tokio::test]
+async fn test_section_assertions() -> Result<()> {
+    with_test_db(async |db| {
+        db.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT)")
+            .await;
+        db.execute("INSERT INTO users VALUES (1, NULL)").await;
+
+        let migration_sql = r#"
+-- pgmt:section name="not_null"
+-- pgmt:assert_before SELECT count(*) = 0 FROM users WHERE email IS NULL
+ALTER TABLE users ALTER COLUMN email SET NOT NULL;
+
+-- pgmt:section name="seed"
+-- pgmt:assert_after SELECT count(*) = 1 FROM users
+INSERT INTO users VALUES (2, 'b@example.com');
+"#;
+
+        let sections = parse_migration_sections(Path::new("test.sql"), migration_sql)?;
+        validate_sections(&sections)?;
+
+        let tracking_table = TrackingTable::default();
+        ensure_section_tracking_table(db.pool(), &tracking_table).await?;
+        initialize_sections(db.pool(), &tracking_table, 1, false, &ordered(&sections)).await?;
+
+        let mut executor = SectionExecutor::new(
+            db.pool().clone(),
+            tracking_table.clone(),
+            SectionReporter::new(sections.len(), false),
+            ExecutionMode::Production,
+            false,
+        );
+        let last_error = async |name: &str| -> Result<Option<String>> {
+            Ok(sqlx::query_scalar(
+                "SELECT last_error FROM public.pgmt_migrations_sections WHERE section_name = $1",
+            )
+            .bind(name)
+            .fetch_one(db.pool())
+            .await?)
+        };
+
+        let err = executor.execute_section(1, &sections[0]).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("assert_before at line 3 returned false"),
+            "{err}"
+        );
+        assert_eq!(
+            get_section_status(db.pool(), &tracking_table, 1, false, "not_null").await?,
+            Some(SectionStatus::Failed)
+        );
+        assert!(
+            last_error("not_null")
+                .await?
+                .unwrap()
+                .contains("was not run")
+        );
+        let not_null: bool = sqlx::query_scalar(
+            "SELECT attnotnull FROM pg_attribute \
+             WHERE attrelid = 'users'::regclass AND attname = 'email'",
+        )
+        .fetch_one(db.pool())
+        .await?;
+        assert!(!not_null, "the body must not have run");
+
+        let err = executor.execute_section(1, &sections[1]).await.unwrap_err();
+        assert!(err.to_string().contains("assert_after at line 7"), "{err}");
+        assert!(last_error("seed").await?.unwrap().contains("rolled back"));
+        let count: i64 = sqlx::query_scalar("SELECT count(*) FROM users")
+            .fetch_one(db.pool())
+            .await?;
+        assert_eq!(count, 1);
+
+        Ok(())
+    })
+    .await
+}
+
 #[tokio::test]
 async fn test_multiple_sections_with_different_modes() -> Result<()> {
     with_test_db(async |db| {
@@ -39,6 +39,11 @@ mod config_integration_tests {
             migration: None,
             schema: None,
             docker: None,
+            dev: None,
+            variables: None,
+            environments: None,
+            targets: None,
+            tenants: None,
         };
 
         // A CLI flag beats the file; absent flags fall back to the file
@@ -91,6 +96,11 @@ mod config_integration_tests {
                 migration: None,
                 schema: None,
                 docker: None,
+                dev: None,
+                variables: None,
+                environments: None,
+                targets: None,
+                tenants: None,
             };
 
             let shadow = ShadowUrlArgs::default().resolve(&config_input).unwrap();
@@ -145,6 +155,11 @@ mod config_integration_tests {
             migration: None,
             schema: None,
             docker: None,
+            dev: None,
+            variables: None,
+            environments: None,
+            targets: None,
+            tenants: None,
         };
 
         let shadow = ShadowUrlArgs::default().resolve(&config_input)?;
@@ -183,6 +198,11 @@ mod config_integration_tests {
             migration: None,
             schema: None,
             docker: None,
+            dev: None,
+            variables: None,
+            environments: None,
+            targets: None,
+            tenants: None,
         };
 
         let config = ConfigBuilder::new().with_file(config_input).resolve()?;
@@ -265,6 +285,11 @@ mod config_integration_tests {
             migration: None, // Use defaults
             schema: None,
             docker: None, // Use defaults
+            dev: None,
+            variables: None,
+            environments: None,
+            targets: None,
+            tenants: None,
         };
 
         let dev = DevUrlArgs::default().resolve(&partial_config)?;
@@ -352,6 +377,11 @@ migration:
             migration: None,
             schema: None,
             docker: None,
+            dev: None,
+            variables: None,
+            environments: None,
+            targets: None,
+            tenants: None,
         };
 
         let dev = DevUrlArgs::default().resolve(&minimal_config)?;
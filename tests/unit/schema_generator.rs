@@ -63,6 +63,7 @@ fn test_schema_generator_basic() {
         policies: vec![],
         extensions: vec![],
         grants: vec![],
+        data: vec![],
         forward_deps: BTreeMap::new(),
         reverse_deps: BTreeMap::new(),
     };
@@ -152,6 +153,7 @@ fn test_file_organization_by_object_type() {
         policies: vec![],
         extensions: vec![],
         grants: vec![],
+        data: vec![],
         forward_deps,
         reverse_deps: BTreeMap::new(),
     };
@@ -224,6 +226,7 @@ fn test_config_filtering() {
         policies: vec![],
         extensions: vec![],
         grants: vec![],
+        data: vec![],
         forward_deps: BTreeMap::new(),
         reverse_deps: BTreeMap::new(),
     };
@@ -325,6 +328,7 @@ fn test_no_self_referential_dependencies() {
         policies: vec![],
         extensions: vec![],
         grants: vec![],
+        data: vec![],
         forward_deps,
         reverse_deps: BTreeMap::new(),
     };
@@ -447,6 +451,7 @@ fn test_gist_index_rendering() {
         policies: vec![],
         extensions: vec![],
         grants: vec![],
+        data: vec![],
         forward_deps: BTreeMap::new(),
         reverse_deps: BTreeMap::new(),
     };
@@ -519,6 +524,7 @@ fn test_detailed_config_variations() {
         policies: vec![],
         extensions: vec![],
         grants: vec![],
+        data: vec![],
         forward_deps: BTreeMap::new(),
         reverse_deps: BTreeMap::new(),
     };
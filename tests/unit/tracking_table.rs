@@ -26,9 +26,18 @@ fn test_tracking_table_configuration_parsing() -> Result<()> {
             }),
             column_order: None,
             filename_prefix: None,
+            lint: None,
+            hooks: None,
+            audit: None,
+            allow_out_of_order: None,
         }),
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     let config = ConfigBuilder::new().with_file(config_input).resolve()?;
@@ -57,9 +66,18 @@ fn test_tracking_table_partial_configuration() -> Result<()> {
             }),
             column_order: None,
             filename_prefix: None,
+            lint: None,
+            hooks: None,
+            audit: None,
+            allow_out_of_order: None,
         }),
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     let config = ConfigBuilder::new().with_file(config_input).resolve()?;
@@ -81,6 +99,11 @@ fn test_tracking_table_fallback_to_defaults() -> Result<()> {
         migration: None, // No migration config at all
         schema: None,
         docker: None,
+        dev: None,
+        variables: None,
+        environments: None,
+        targets: None,
+        tenants: None,
     };
 
     let config = ConfigBuilder::new().with_file(config_input).resolve()?;
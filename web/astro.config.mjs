@@ -66,6 +66,8 @@ export default defineConfig({
               label: 'Roles & Permissions',
               link: '/docs/guides/roles-and-permissions',
             },
+            { label: 'Seed Data', link: '/docs/guides/seed-data' },
+            { label: 'Testing', link: '/docs/guides/testing' },
           ],
         },
         {
@@ -88,6 +90,10 @@ export default defineConfig({
               label: 'Modules',
               link: '/docs/guides/modules',
             },
+            {
+              label: 'Schema-per-Tenant',
+              link: '/docs/guides/tenants',
+            },
           ],
         },
         {